@@ -294,6 +294,9 @@ fn cmd_search(
         as_of_frame: None,
         as_of_ts: None,
         no_sketch: false,
+        mmr_lambda: None,
+        facets: None,
+        highlight: None,
     };
 
     let response = mem.search(request)?;
@@ -97,6 +97,12 @@ fn main() -> Result<()> {
         as_of_frame: None,
         as_of_ts: None,
         no_sketch: false,
+        mmr_lambda: None,
+        facets: None,
+        highlight: None,
+        auto_correct: false,
+        rerank: false,
+        expand: None,
     };
     let response = mem.search(request)?;
     println!("   Query: 'memvid'");
@@ -126,6 +132,12 @@ fn main() -> Result<()> {
         as_of_frame: None,
         as_of_ts: None,
         no_sketch: false,
+        mmr_lambda: None,
+        facets: None,
+        highlight: None,
+        auto_correct: false,
+        rerank: false,
+        expand: None,
     };
     let response = mem.search(request)?;
     println!("   Query: 'documentation' (scope: mv2://docs/)");
@@ -26,7 +26,10 @@ use std::collections::HashSet;
 use std::path::{Path, PathBuf};
 use std::time::Duration;
 
-use crate::{MemvidError, Result, types::FrameId};
+use crate::{
+    MemvidError, Result,
+    types::{DistanceMetric, FrameId},
+};
 
 // ============================================================================
 // Configuration Constants
@@ -234,6 +237,8 @@ pub struct ClipIndexArtifact {
 #[derive(Debug, Clone)]
 pub struct ClipIndex {
     documents: Vec<ClipDocument>,
+    /// Ranking metric; not part of the encoding, supplied from the manifest on load
+    metric: DistanceMetric,
 }
 
 impl ClipIndex {
@@ -241,9 +246,23 @@ impl ClipIndex {
     pub fn new() -> Self {
         Self {
             documents: Vec::new(),
+            metric: DistanceMetric::default(),
         }
     }
 
+    /// Rank with `metric` instead of the default L2
+    #[must_use]
+    pub fn with_metric(mut self, metric: DistanceMetric) -> Self {
+        self.metric = metric;
+        self
+    }
+
+    /// Distance metric used to rank this index
+    #[must_use]
+    pub fn metric(&self) -> DistanceMetric {
+        self.metric
+    }
+
     /// Add a document with its CLIP embedding
     pub fn add_document<I>(&mut self, frame_id: FrameId, page: Option<u32>, embedding: I)
     where
@@ -282,10 +301,13 @@ impl ClipIndex {
             "decoded CLIP index"
         );
 
-        Ok(Self { documents })
+        Ok(Self {
+            documents,
+            metric: DistanceMetric::default(),
+        })
     }
 
-    /// Search for similar embeddings using L2 distance
+    /// Search for similar embeddings using the index metric
     pub fn search(&self, query: &[f32], limit: usize) -> Vec<ClipSearchHit> {
         if query.is_empty() {
             return Vec::new();
@@ -295,11 +317,12 @@ impl ClipIndex {
             .documents
             .iter()
             .map(|doc| {
-                let distance = l2_distance(query, &doc.embedding);
+                let distance = self.metric.distance(query, &doc.embedding);
                 ClipSearchHit {
                     frame_id: doc.frame_id,
                     page: doc.page,
                     distance,
+                    similarity: self.metric.similarity(distance),
                 }
             })
             .collect();
@@ -370,17 +393,10 @@ pub struct ClipSearchHit {
     pub frame_id: FrameId,
     /// Optional page number (for PDFs)
     pub page: Option<u32>,
-    /// L2 distance to query (lower is more similar)
+    /// Distance to query under the index metric (lower is more similar)
     pub distance: f32,
-}
-
-/// L2 (Euclidean) distance between two vectors
-fn l2_distance(a: &[f32], b: &[f32]) -> f32 {
-    a.iter()
-        .zip(b.iter())
-        .map(|(x, y)| (x - y).powi(2))
-        .sum::<f32>()
-        .sqrt()
+    /// Similarity derived from `distance` (higher is more similar)
+    pub similarity: f32,
 }
 
 // ============================================================================
@@ -1540,6 +1556,9 @@ pub struct ClipIndexManifest {
     pub checksum: [u8; 32],
     /// Model name used to generate embeddings
     pub model_name: String,
+    /// Distance metric used for search (default: L2 for backward compatibility)
+    #[serde(default)]
+    pub metric: DistanceMetric,
 }
 
 // ============================================================================
@@ -1589,13 +1608,24 @@ mod tests {
 
     #[test]
     fn l2_distance_calculation() {
-        let d = l2_distance(&[0.0, 0.0], &[3.0, 4.0]);
+        let d = DistanceMetric::L2.distance(&[0.0, 0.0], &[3.0, 4.0]);
         assert!((d - 5.0).abs() < 1e-6);
 
-        let d = l2_distance(&[1.0, 1.0, 1.0], &[1.0, 1.0, 1.0]);
+        let d = DistanceMetric::L2.distance(&[1.0, 1.0, 1.0], &[1.0, 1.0, 1.0]);
         assert!(d.abs() < 1e-6);
     }
 
+    #[test]
+    fn clip_index_cosine_metric() {
+        let mut index = ClipIndex::new().with_metric(DistanceMetric::Cosine);
+        index.add_document(1, None, vec![5.0, 0.0, 0.0]);
+        index.add_document(2, None, vec![0.5, 0.5, 0.0]);
+
+        let hits = index.search(&[1.0, 0.0, 0.0], 2);
+        assert_eq!(hits[0].frame_id, 1);
+        assert!((hits[0].similarity - 1.0).abs() < 1e-6);
+    }
+
     #[test]
     fn image_info_filtering() {
         // Tiny image - should skip
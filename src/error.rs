@@ -112,6 +112,21 @@ pub enum MemvidError {
     #[error("Vector dimension mismatch (expected {expected}, got {actual})")]
     VecDimensionMismatch { expected: u32, actual: usize },
 
+    #[error("Vector index already uses the {expected} metric (requested {actual})")]
+    VecMetricMismatch {
+        expected: crate::types::DistanceMetric,
+        actual: crate::types::DistanceMetric,
+    },
+
+    #[error("Vector space '{name}' is not defined")]
+    VecSpaceNotFound { name: String },
+
+    #[error("Invalid vector space '{name}': {reason}")]
+    InvalidVecSpace { name: String, reason: &'static str },
+
+    #[error("Re-embedding job error: {reason}")]
+    ReembedJob { reason: &'static str },
+
     #[error("Auxiliary file detected: {path:?}")]
     AuxiliaryFileDetected { path: PathBuf },
 
@@ -172,6 +187,14 @@ pub enum MemvidError {
     #[error("Invalid search cursor: {reason}")]
     InvalidCursor { reason: &'static str },
 
+    #[error(
+        "Search cursor is stale: issued at vacuum generation {cursor_generation}, memory is now at {current_generation}"
+    )]
+    StaleCursor {
+        cursor_generation: u64,
+        current_generation: u64,
+    },
+
     #[error("Invalid frame {frame_id}: {reason}")]
     InvalidFrame {
         frame_id: crate::types::FrameId,
@@ -325,6 +325,12 @@ pub fn hybrid_search(memvid: &mut Memvid, plan: &QueryPlan) -> Result<Vec<Hybrid
                 as_of_frame: None,
                 as_of_ts: None,
                 no_sketch: false,
+                mmr_lambda: None,
+                facets: None,
+                highlight: None,
+                auto_correct: false,
+                rerank: false,
+                expand: None,
             };
             let response = memvid.search(request)?;
             Ok(response
@@ -389,6 +395,12 @@ pub fn hybrid_search(memvid: &mut Memvid, plan: &QueryPlan) -> Result<Vec<Hybrid
                     as_of_frame: None,
                     as_of_ts: None,
                     no_sketch: false,
+                    mmr_lambda: None,
+                    facets: None,
+                    highlight: None,
+                    auto_correct: false,
+                    rerank: false,
+                    expand: None,
                 };
                 let response = memvid.search(request)?;
                 return Ok(response
@@ -155,7 +155,7 @@ impl LexIndex {
         if query_tokens.is_empty() {
             return Vec::new();
         }
-        let mut matches = self.compute_matches(&query_tokens, None, None);
+        let mut matches = self.compute_matches(&query_tokens, &[], None, None);
         matches.truncate(limit);
         matches
             .into_iter()
@@ -179,13 +179,16 @@ impl LexIndex {
         self.documents.retain(|doc| doc.frame_id != frame_id);
     }
 
+    /// Sections containing every query token verbatim and, for each fuzzy term,
+    /// a word within its edit distance.
     pub(crate) fn compute_matches(
         &self,
         query_tokens: &[String],
+        fuzzy_terms: &[(String, u8)],
         uri_filter: Option<&str>,
         scope_filter: Option<&str>,
     ) -> Vec<LexMatch> {
-        if query_tokens.is_empty() {
+        if query_tokens.is_empty() && fuzzy_terms.is_empty() {
             return Vec::new();
         }
 
@@ -215,7 +218,7 @@ impl LexIndex {
 
                 let mut occurrences: Vec<(usize, usize)> = Vec::new();
 
-                if query_tokens.len() == 1 {
+                if query_tokens.len() == 1 && fuzzy_terms.is_empty() {
                     let needle = &query_tokens[0];
                     if needle.is_empty() {
                         continue;
@@ -249,6 +252,14 @@ impl LexIndex {
                             break;
                         }
                     }
+                    for (word, distance) in fuzzy_terms {
+                        if !all_present {
+                            break;
+                        }
+                        let before = all_occurrences.len();
+                        all_occurrences.extend(fuzzy_occurrences(haystack, word, *distance));
+                        all_present = all_occurrences.len() > before;
+                    }
                     if !all_present {
                         continue;
                     }
@@ -281,6 +292,25 @@ impl LexIndex {
     }
 }
 
+/// Byte spans of the words in `haystack` within `distance` edits of `word`.
+fn fuzzy_occurrences(haystack: &str, word: &str, distance: u8) -> Vec<(usize, usize)> {
+    let mut spans = Vec::new();
+    let mut start = None;
+    for (idx, ch) in haystack
+        .char_indices()
+        .chain(std::iter::once((haystack.len(), ' ')))
+    {
+        if ch.is_alphanumeric() {
+            start.get_or_insert(idx);
+        } else if let Some(word_start) = start.take() {
+            if crate::text::edit_distance(&haystack[word_start..idx], word) <= distance as usize {
+                spans.push((word_start, idx));
+            }
+        }
+    }
+    spans
+}
+
 fn uri_matches(candidate: Option<&str>, expected: &str) -> bool {
     let Some(uri) = candidate else {
         return false;
@@ -401,6 +431,11 @@ pub(crate) struct LexMatch {
 fn tokenize(input: &str) -> Vec<String> {
     input
         .split(|c: char| !is_token_char(c))
+        .flat_map(|token| {
+            crate::text::cjk_segments(token)
+                .into_iter()
+                .map(move |(start, end)| &token[start..end])
+        })
         .filter_map(|token| {
             if token.chars().any(|ch| ch.is_alphanumeric()) {
                 Some(token.to_lowercase())
@@ -692,4 +727,50 @@ mod tests {
         let tokens = tokenize("N&M EXPRESS LLC @ 2024");
         assert_eq!(tokens, vec!["n&m", "express", "llc", "2024"]);
     }
+
+    #[test]
+    fn tokenizer_segments_cjk_into_bigrams() {
+        let tokens = tokenize("東京タワー Tokyo");
+        assert_eq!(tokens, vec!["東京", "京タ", "タワ", "ワー", "tokyo"]);
+    }
+
+    #[test]
+    fn search_matches_word_inside_cjk_sentence() {
+        let mut builder = LexIndexBuilder::new();
+        builder.add_document(
+            1,
+            "mv2://ja",
+            None,
+            "私は東京タワーに行きました",
+            &HashMap::new(),
+        );
+        let artifact = builder.finish().expect("finish");
+        let index = LexIndex::decode(&artifact.bytes).expect("decode");
+        let hits = index.search("東京タワー", 10);
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].frame_id, 1);
+    }
+
+    #[test]
+    fn compute_matches_accepts_fuzzy_terms() {
+        let mut builder = LexIndexBuilder::new();
+        builder.add_document(1, "mv2://a", None, "memvid stores frames", &HashMap::new());
+        builder.add_document(2, "mv2://b", None, "unrelated content", &HashMap::new());
+        let artifact = builder.finish().expect("finish");
+        let index = LexIndex::decode(&artifact.bytes).expect("decode");
+
+        let fuzzy = vec![("memvdi".to_string(), 1)];
+        let hits = index.compute_matches(&[], &fuzzy, None, None);
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].frame_id, 1);
+        assert_eq!(hits[0].occurrences, vec![(0, 6)]);
+
+        let hits = index.compute_matches(&["frames".to_string()], &fuzzy, None, None);
+        assert_eq!(hits.len(), 1);
+        assert!(
+            index
+                .compute_matches(&["content".to_string()], &fuzzy, None, None)
+                .is_empty()
+        );
+    }
 }
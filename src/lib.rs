@@ -30,7 +30,10 @@ pub mod text;
 mod toc;
 pub mod types;
 pub mod vec;
+pub mod vec_hnsw;
+pub mod vec_mmap;
 pub mod vec_pq;
+pub mod vec_quant;
 
 // Triplet extraction module for automatic SPO extraction during ingestion
 pub mod triplet;
@@ -93,8 +96,8 @@ pub use io::wal::{EmbeddedWal, WalRecord, WalStats};
 pub use lex::{LexIndex, LexIndexArtifact, LexIndexBuilder, LexSearchHit};
 pub use lock::FileLock;
 pub use memvid::{
-    BlobReader, EnrichmentHandle, EnrichmentStats, LockSettings, Memvid, OpenReadOptions,
-    SketchCandidate, SketchSearchOptions, SketchSearchStats,
+    BlobReader, CreateOptions, EnrichmentHandle, EnrichmentStats, LockSettings, Memvid,
+    OpenReadOptions, ReembedProgress, SketchCandidate, SketchSearchOptions, SketchSearchStats,
     mutation::{CommitMode, CommitOptions},
     start_enrichment_worker, start_enrichment_worker_with_embeddings,
 };
@@ -120,21 +123,26 @@ pub use types::{
     TemporalMentionKind, TemporalTrack, TemporalTrackManifest,
 };
 pub use types::{
-    AskCitation, AskMode, AskRequest, AskResponse, AskRetriever, AskStats, AudioSegmentMetadata,
-    AuditOptions, AuditReport, CanonicalEncoding, DOCTOR_PLAN_VERSION, DocAudioMetadata,
-    DocExifMetadata, DocGpsMetadata, DocMetadata, DoctorActionDetail, DoctorActionKind,
-    DoctorActionPlan, DoctorActionReport, DoctorActionStatus, DoctorFinding, DoctorFindingCode,
-    DoctorMetrics, DoctorOptions, DoctorPhaseDuration, DoctorPhaseKind, DoctorPhasePlan,
-    DoctorPhaseReport, DoctorPhaseStatus, DoctorPlan, DoctorReport, DoctorSeverity, DoctorStatus,
-    EmbeddingIdentity, EmbeddingIdentityCount, EmbeddingIdentitySummary, Frame, FrameId, FrameRole,
-    FrameStatus, Header, IndexManifests, LexIndexManifest, LexSegmentDescriptor,
-    MEMVID_EMBEDDING_DIMENSION_KEY, MEMVID_EMBEDDING_MODEL_KEY, MEMVID_EMBEDDING_NORMALIZED_KEY,
-    MEMVID_EMBEDDING_PROVIDER_KEY, MediaManifest, MemvidHandle, Open, PutOptions,
-    PutOptionsBuilder, Sealed, SearchEngineKind, SearchHit, SearchHitMetadata, SearchParams,
-    SearchRequest, SearchResponse, SegmentCatalog, SegmentCommon, SegmentCompression, SegmentMeta,
-    SegmentSpan, SourceSpan, Stats, TextChunkManifest, TextChunkRange, Ticket, TicketRef, Tier,
-    TimeIndexManifest, TimeSegmentDescriptor, TimelineEntry, TimelineQuery, TimelineQueryBuilder,
-    Toc, VecEmbedder, VecIndexManifest, VecSegmentDescriptor, VectorCompression, VerificationCheck,
+    ApproxTokenCounter, AskCitation, AskMode, AskQueryAttribution, AskQuerySource, AskRequest,
+    AskResponse, AskRetriever, AskStats, AudioSegmentMetadata, AuditOptions, AuditReport,
+    CanonicalEncoding, ChunkExpansion, ChunkWindow, DOCTOR_PLAN_VERSION, DateInterval,
+    DistanceMetric, DocAudioMetadata, DocExifMetadata, DocGpsMetadata, DocMetadata,
+    DoctorActionDetail, DoctorActionKind, DoctorActionPlan, DoctorActionReport, DoctorActionStatus,
+    DoctorFinding, DoctorFindingCode, DoctorMetrics, DoctorOptions, DoctorPhaseDuration,
+    DoctorPhaseKind, DoctorPhasePlan, DoctorPhaseReport, DoctorPhaseStatus, DoctorPlan,
+    DoctorReport, DoctorSeverity, DoctorStatus, EmbeddingIdentity, EmbeddingIdentityCount,
+    EmbeddingIdentitySummary, FacetBucket, FacetField, FacetRequest, FacetResult, Frame, FrameId,
+    FrameRole, FrameStatus, FusionStrategy, Header, HighlightFragment, HighlightOptions,
+    HybridRetriever, IndexManifests, LexAnalyzerConfig, LexIndexManifest, LexLanguage,
+    LexSegmentDescriptor, MEMVID_EMBEDDING_DIMENSION_KEY, MEMVID_EMBEDDING_MODEL_KEY,
+    MEMVID_EMBEDDING_NORMALIZED_KEY, MEMVID_EMBEDDING_PROVIDER_KEY, MediaManifest, MemvidHandle,
+    Open, PutOptions, PutOptionsBuilder, QueryRewriter, ReembedJobManifest, RetrieverScore, Sealed,
+    SearchEngineKind, SearchHit, SearchHitMetadata, SearchHitRerank, SearchParams, SearchRequest,
+    SearchResponse, SegmentCatalog, SegmentCommon, SegmentCompression, SegmentMeta, SegmentSpan,
+    SourceSpan, SpellingSuggestion, Stats, TextChunkManifest, TextChunkRange, Ticket, TicketRef,
+    Tier, TimeIndexManifest, TimeSegmentDescriptor, TimelineEntry, TimelinePage, TimelineQuery,
+    TimelineQueryBuilder, Toc, TokenCounter, VecEmbedder, VecIndexManifest, VecSearchFilter,
+    VecSegmentDescriptor, VecSpaceManifest, VectorCompression, VerificationCheck,
     VerificationReport, VerificationStatus,
 };
 // Memory card types for structured memory extraction and storage
@@ -185,13 +193,18 @@ pub use types::{
 pub use types::reranker::{
     Reranker, RerankerConfig, RerankerDocument, RerankerKind, RerankerResult,
 };
+// Answer synthesizers for ask
+pub use types::{AnswerSynthesizer, ExtractiveSynthesizer, SynthesizedAnswer};
 #[cfg(feature = "parallel_segments")]
 pub use types::{IndexSegmentRef, SegmentKind, SegmentStats};
 pub use vec::{VecIndex, VecIndexArtifact, VecSearchHit};
+pub use vec_hnsw::{HNSW_DEFAULT_EF_SEARCH, HNSW_MIN_VECTORS, HnswGraph, HnswParams, VectorStore};
+pub use vec_mmap::{MappedVecIndex, VECTOR_SEGMENT_ALIGN};
 pub use vec_pq::{
     CompressionStats, ProductQuantizer, QuantizedVecIndex, QuantizedVecIndexArtifact,
     QuantizedVecIndexBuilder,
 };
+pub use vec_quant::{ScalarKind, ScalarQuantizedIndex, ScalarQuantizer};
 // CLIP visual embeddings - types always available for serde compatibility
 pub use clip::{
     CLIP_MODELS, ClipConfig, ClipDocument, ClipEmbeddingProvider, ClipError, ClipIndex,
@@ -324,6 +337,7 @@ impl Memvid {
         if self.read_only {
             self.lock.upgrade_to_exclusive()?;
             self.read_only = false;
+            self.detach_mapped_vec_indexes();
         }
         Ok(())
     }
@@ -381,6 +395,16 @@ pub(crate) fn decode_canonical_bytes(
     }
 }
 
+/// Convert a count, rank or position to `f32` for scoring.
+///
+/// Values above 2^24 round to the nearest representable float, which only blurs scores
+/// that are already indistinguishable at that scale. Scoring code goes through this
+/// helper instead of allowing the cast at each call site.
+#[allow(clippy::cast_precision_loss)]
+pub(crate) fn count_to_f32(value: usize) -> f32 {
+    value as f32
+}
+
 pub(crate) fn default_uri(frame_id: FrameId) -> String {
     format!("mv2://frames/{frame_id}")
 }
@@ -553,6 +577,7 @@ mod tests {
                     reverse: false,
                     #[cfg(feature = "temporal_track")]
                     temporal: None,
+                    cursor: None,
                 })
                 .expect("timeline limit");
             assert_eq!(limited.len(), 1);
@@ -566,6 +591,7 @@ mod tests {
                     reverse: true,
                     #[cfg(feature = "temporal_track")]
                     temporal: None,
+                    cursor: None,
                 })
                 .expect("timeline reverse");
             assert_eq!(reversed.len(), 1);
@@ -598,6 +624,12 @@ mod tests {
                 as_of_frame: None,
                 as_of_ts: None,
                 no_sketch: false,
+                mmr_lambda: None,
+                facets: None,
+                highlight: None,
+                auto_correct: false,
+                rerank: false,
+                expand: None,
             };
             let response = mem.search(request).expect("search");
             assert_eq!(response.hits.len(), 1);
@@ -617,6 +649,12 @@ mod tests {
                 as_of_frame: None,
                 as_of_ts: None,
                 no_sketch: false,
+                mmr_lambda: None,
+                facets: None,
+                highlight: None,
+                auto_correct: false,
+                rerank: false,
+                expand: None,
             };
             let response = reopened.search(request).expect("search reopened");
             assert_eq!(response.hits.len(), 1);
@@ -688,6 +726,12 @@ mod tests {
                     as_of_frame: None,
                     as_of_ts: None,
                     no_sketch: false,
+                    mmr_lambda: None,
+                    facets: None,
+                    highlight: None,
+                    auto_correct: false,
+                    rerank: false,
+                    expand: None,
                 })
                 .expect("search");
 
@@ -749,6 +793,12 @@ mod tests {
                     as_of_frame: None,
                     as_of_ts: None,
                     no_sketch: false,
+                    mmr_lambda: None,
+                    facets: None,
+                    highlight: None,
+                    auto_correct: false,
+                    rerank: false,
+                    expand: None,
                 })
                 .expect("search");
 
@@ -833,6 +883,12 @@ mod tests {
                     as_of_frame: None,
                     as_of_ts: None,
                     no_sketch: false,
+                    mmr_lambda: None,
+                    facets: None,
+                    highlight: None,
+                    auto_correct: false,
+                    rerank: false,
+                    expand: None,
                 })
                 .expect("uri search");
             assert_eq!(uri_response.engine, SearchEngineKind::Tantivy);
@@ -856,6 +912,12 @@ mod tests {
                     as_of_frame: None,
                     as_of_ts: None,
                     no_sketch: false,
+                    mmr_lambda: None,
+                    facets: None,
+                    highlight: None,
+                    auto_correct: false,
+                    rerank: false,
+                    expand: None,
                 })
                 .expect("scope search");
             assert_eq!(scope_response.engine, SearchEngineKind::Tantivy);
@@ -909,6 +971,12 @@ mod tests {
                     as_of_frame: None,
                     as_of_ts: None,
                     no_sketch: false,
+                    mmr_lambda: None,
+                    facets: None,
+                    highlight: None,
+                    auto_correct: false,
+                    rerank: false,
+                    expand: None,
                 })
                 .expect("page one");
             assert_eq!(first_page.engine, SearchEngineKind::Tantivy);
@@ -932,6 +1000,12 @@ mod tests {
                     as_of_frame: None,
                     as_of_ts: None,
                     no_sketch: false,
+                    mmr_lambda: None,
+                    facets: None,
+                    highlight: None,
+                    auto_correct: false,
+                    rerank: false,
+                    expand: None,
                 })
                 .expect("page two");
             assert_eq!(second_page.engine, SearchEngineKind::Tantivy);
@@ -973,6 +1047,12 @@ mod tests {
                     as_of_frame: None,
                     as_of_ts: None,
                     no_sketch: false,
+                    mmr_lambda: None,
+                    facets: None,
+                    highlight: None,
+                    auto_correct: false,
+                    rerank: false,
+                    expand: None,
                 })
                 .expect("search with tantivy");
 
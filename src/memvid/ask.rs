@@ -1,16 +1,29 @@
 use std::borrow::Cow;
 use std::collections::{BTreeSet, HashMap, HashSet};
 use std::num::NonZeroU64;
+#[cfg(feature = "lex")]
+use std::sync::Arc;
 use std::time::Instant;
 
+#[cfg(feature = "lex")]
+use crate::memvid::context_budget::validate_context_budget;
 use crate::memvid::lifecycle::Memvid;
+#[cfg(feature = "lex")]
+use crate::memvid::search::fusion::{
+    CandidateList, attribute_merged_ranks, fuse_hits, validate_fusion, variant_lists,
+};
 use crate::memvid::search::helpers::{build_context, reorder_hits_by_token_matches};
+#[cfg(feature = "lex")]
+use crate::memvid::search::mmr::validate_mmr_lambda;
+#[cfg(feature = "lex")]
+use crate::types::ExtractiveSynthesizer;
 #[cfg(feature = "temporal_track")]
 use crate::types::TemporalFilter;
 use crate::types::{
-    AskCitation, AskContextFragment, AskContextFragmentKind, AskMode, AskRequest, AskResponse,
-    AskRetriever, AskStats, SearchEngineKind, SearchHit, SearchParams, SearchRequest,
-    SearchResponse, TimelineQueryBuilder,
+    AnswerSynthesizer, AskCitation, AskContextFragment, AskContextFragmentKind, AskMode,
+    AskQueryAttribution, AskQuerySource, AskRequest, AskResponse, AskRetriever, AskStats, FrameId,
+    HybridRetriever, QueryRewriter, SearchEngineKind, SearchHit, SearchParams, SearchRequest,
+    SearchResponse, TimelineQueryBuilder, VecSearchFilter,
 };
 use crate::{MemvidError, Result, VecEmbedder};
 
@@ -18,13 +31,30 @@ const RRF_K: f32 = 60.0;
 
 #[cfg(feature = "lex")]
 impl Memvid {
-    pub fn ask<E>(&mut self, request: AskRequest, embedder: Option<&E>) -> Result<AskResponse>
+    /// Retrieve context for `request.question` and answer it.
+    ///
+    /// `synthesizer` turns the retrieved context into the answer; without one the
+    /// deterministic [`ExtractiveSynthesizer`] is used.
+    pub fn ask<E>(
+        &mut self,
+        request: AskRequest,
+        embedder: Option<&E>,
+        synthesizer: Option<&dyn AnswerSynthesizer>,
+    ) -> Result<AskResponse>
     where
         E: VecEmbedder + ?Sized,
     {
         if !self.lex_enabled {
             return Err(MemvidError::LexNotEnabled);
         }
+        if let Some(lambda) = request.mmr_lambda {
+            validate_mmr_lambda(lambda)?;
+        }
+        validate_fusion(&request.fusion)?;
+        validate_context_budget(request.context_budget_tokens)?;
+        if request.rerank {
+            self.reranker_candidates()?;
+        }
 
         let total_start = Instant::now();
         let lexical_query = sanitize_question_for_lexical(&request.question);
@@ -103,6 +133,12 @@ impl Memvid {
             // Disable sketch pre-filter for ask queries - accuracy is more important than speed
             // SimHash can filter out semantically relevant documents that use different wording
             no_sketch: true,
+            mmr_lambda: None,
+            facets: None,
+            highlight: None,
+            auto_correct: false,
+            rerank: false,
+            expand: None,
         };
 
         // Pre-compute the query embedding once so we can reuse it for vector recall and semantic re-rank
@@ -125,7 +161,25 @@ impl Memvid {
         let mut lex_fallback_used = false;
         let mut timeline_fallback_used = false;
         if retrieval.hits.is_empty() {
-            if !primary_tokens.is_empty() {
+            // Spelling correction: rerun with the indexed spellings of unknown words.
+            if let Some(corrected) = retrieval.corrected_query.clone() {
+                let mut corrected_request = search_request.clone();
+                corrected_request.query.clone_from(&corrected);
+                let mut corrected_response = self.search(corrected_request)?;
+                self.filter_hits_in_time_range(
+                    &mut corrected_response.hits,
+                    request.start,
+                    request.end,
+                    &mut corrected_response.total_hits,
+                )?;
+                if !corrected_response.hits.is_empty() {
+                    corrected_response.suggestions = std::mem::take(&mut retrieval.suggestions);
+                    corrected_response.corrected_query = Some(corrected.clone());
+                    retrieval = corrected_response;
+                    search_request.query = corrected;
+                }
+            }
+            if retrieval.hits.is_empty() && !primary_tokens.is_empty() {
                 if let Some(or_query) = build_disjunctive_query(&primary_tokens) {
                     if or_query != search_request.query {
                         let mut or_request = search_request.clone();
@@ -204,8 +258,11 @@ impl Memvid {
             }
         }
 
-        // Build multiple candidate lists (lexical variants + vector) and fuse with RRF.
-        let mut candidate_lists: Vec<Vec<SearchHit>> = Vec::new();
+        // Build multiple candidate lists (lexical variants + vector) and fuse them.
+        let mut candidate_lists: Vec<CandidateList> = Vec::new();
+        // Lexical variants of the question (base, OR-expanded, corrections); see
+        // `variant_lists` for how they are fused.
+        let mut lexical_lists: Vec<Vec<SearchHit>> = Vec::new();
         let mut vector_used = false;
 
         // For analytical questions, use timeline directly (skip RRF mixing with search)
@@ -228,12 +285,12 @@ impl Memvid {
                     );
                     // For analytical questions, use ONLY timeline hits (with full text)
                     // Skip mixing with search hits which have truncated text
-                    candidate_lists.push(timeline_response.hits);
+                    lexical_lists.push(timeline_response.hits);
                 }
             }
         } else {
             // For non-analytical questions, add search hits to candidate list
-            candidate_lists.push(retrieval.hits.clone());
+            lexical_lists.push(retrieval.hits.clone());
         }
 
         // OR-expanded lexical query even when base has hits to widen recall.
@@ -251,7 +308,7 @@ impl Memvid {
                         &mut or_response.total_hits,
                     )?;
                     if !or_response.hits.is_empty() {
-                        candidate_lists.push(or_response.hits);
+                        lexical_lists.push(or_response.hits);
                     }
                 }
             }
@@ -265,7 +322,7 @@ impl Memvid {
                     effective_top_k.max(24).min(64),
                 )?;
                 if !vec_hits.is_empty() {
-                    candidate_lists.push(vec_hits);
+                    candidate_lists.push((HybridRetriever::Semantic, vec_hits));
                     vector_used = true;
                 }
             }
@@ -287,13 +344,67 @@ impl Memvid {
                         "found {} potential corrections for question",
                         correction_response.hits.len()
                     );
-                    candidate_lists.push(correction_response.hits);
+                    lexical_lists.push(correction_response.hits);
                 }
             }
         }
+        let (lexical_candidates, merged_lexical) =
+            variant_lists(HybridRetriever::Lexical, lexical_lists, &request.fusion);
+        candidate_lists.splice(0..0, lexical_candidates);
+
+        // Multi-query: run every reformulation through both retrievers and fuse their
+        // lists with the question's own.
+        let mut query_frames: Vec<(AskQueryAttribution, HashSet<FrameId>)> = Vec::new();
+        if request.mode == AskMode::MultiQuery {
+            query_frames.push(query_attribution(
+                request.question.clone(),
+                AskQuerySource::Question,
+                &candidate_lists,
+            ));
+            for (query, source) in self.multi_query_reformulations(&request, &primary_tokens)? {
+                let mut lists: Vec<CandidateList> = Vec::new();
+                let lexical = sanitize_question_for_lexical(&query);
+                let tokens: Vec<String> = lexical
+                    .split_whitespace()
+                    .map(str::to_ascii_lowercase)
+                    .collect();
+                let lexical_queries = std::iter::once(lexical.clone())
+                    .chain(build_disjunctive_query(&tokens).filter(|or_query| *or_query != lexical))
+                    .filter(|lexical_query| !lexical_query.is_empty());
+                for lexical_query in lexical_queries {
+                    let mut query_request = search_request.clone();
+                    query_request.query = lexical_query;
+                    let mut query_response = self.search(query_request)?;
+                    self.filter_hits_in_time_range(
+                        &mut query_response.hits,
+                        request.start,
+                        request.end,
+                        &mut query_response.total_hits,
+                    )?;
+                    if !query_response.hits.is_empty() {
+                        lists.push((HybridRetriever::Lexical, query_response.hits));
+                        break;
+                    }
+                }
+                if let Some(embedder) = embedder.filter(|_| self.vec_enabled) {
+                    let embedding = embedder.embed_query(&query)?;
+                    let vec_hits =
+                        vector_hits(self, &embedding, &request, effective_top_k.max(24).min(64))?;
+                    if !vec_hits.is_empty() {
+                        lists.push((HybridRetriever::Semantic, vec_hits));
+                        vector_used = true;
+                    }
+                }
+                query_frames.push(query_attribution(query, source, &lists));
+                candidate_lists.extend(lists);
+            }
+        }
 
-        // Fuse all candidates with RRF and rebuild retrieval.
-        if let Some(fused) = fuse_hits_rrf(candidate_lists, effective_top_k.max(24)) {
+        // Fuse all candidates and rebuild retrieval.
+        if let Some(mut fused) =
+            fuse_hits(candidate_lists, &request.fusion, effective_top_k.max(24))
+        {
+            attribute_merged_ranks(&mut fused, HybridRetriever::Lexical, &merged_lexical);
             retrieval.hits = fused;
             retrieval.total_hits = retrieval.hits.len();
             if vector_used {
@@ -360,34 +471,39 @@ impl Memvid {
             )?;
         }
 
+        let rerank_ms = if request.rerank {
+            let rerank_start = Instant::now();
+            self.apply_reranker(&request.question, &mut retrieval.hits)?;
+            retrieval.total_hits = retrieval.hits.len();
+            rerank_start.elapsed().as_millis()
+        } else {
+            0
+        };
+
+        // Diversify the candidate pool; corrections that survive are still promoted below.
+        if let Some(lambda) = request.mmr_lambda {
+            let target = effective_top_k.min(retrieval.hits.len());
+            self.apply_mmr(
+                &mut retrieval.hits,
+                lambda,
+                target,
+                request.vec_space.as_deref(),
+            )?;
+            retrieval.total_hits = retrieval.hits.len();
+        }
+
         // Apply correction boost AFTER all other reranking - corrections should have final priority
         // This ensures user corrections override all other ranking signals
         promote_corrections(self, &mut retrieval.hits)?;
 
         retrieval.context = build_context(&retrieval.hits);
 
-        let (answer, citations, synthesis_ms) = if request.context_only {
-            (None, Vec::new(), 0)
-        } else {
-            let synth_start = Instant::now();
-            let citations = build_citations(&retrieval.hits, &semantic_scores);
-            let answer = synthesize_answer(&request.question, &retrieval.hits, &citations);
-            let synth_ms = synth_start.elapsed().as_millis();
-            (answer, citations, synth_ms)
-        };
-
         let retriever = determine_retriever(
             request.mode,
             semantics_applied,
             lex_fallback_used,
             timeline_fallback_used,
         );
-        let stats = AskStats {
-            retrieval_ms,
-            synthesis_ms,
-            latency_ms: total_start.elapsed().as_millis(),
-        };
-
         let context_fragments: Vec<AskContextFragment> = retrieval
             .hits
             .iter()
@@ -409,6 +525,47 @@ impl Memvid {
                     .and_then(|metadata| metadata.temporal.clone()),
             })
             .collect();
+        let context_fragments = match request.context_budget_tokens {
+            Some(budget) => self.pack_context_fragments(context_fragments, budget),
+            None => context_fragments,
+        };
+
+        let queries: Vec<AskQueryAttribution> = query_frames
+            .into_iter()
+            .map(|(mut attribution, frames)| {
+                attribution.fragments = context_fragments
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, fragment)| frames.contains(&fragment.frame_id))
+                    .map(|(idx, _)| idx + 1)
+                    .collect();
+                attribution
+            })
+            .collect();
+
+        let (answer, answer_citations, citations, synthesis_ms) = if request.context_only {
+            (None, Vec::new(), Vec::new(), 0)
+        } else {
+            let synth_start = Instant::now();
+            let citations = build_citations(&context_fragments, &semantic_scores);
+            let extractive = ExtractiveSynthesizer::default();
+            let synthesizer = synthesizer.unwrap_or(&extractive);
+            let (answer, mut answer_citations) =
+                match synthesizer.synthesize(&request.question, &context_fragments)? {
+                    Some(synthesized) => (Some(synthesized.text), synthesized.citations),
+                    None => (None, Vec::new()),
+                };
+            // Fragments and citations share 1-based indices; drop any out of range.
+            answer_citations.retain(|index| (1..=citations.len()).contains(index));
+            let synth_ms = synth_start.elapsed().as_millis();
+            (answer, answer_citations, citations, synth_ms)
+        };
+        let stats = AskStats {
+            retrieval_ms,
+            rerank_ms,
+            synthesis_ms,
+            latency_ms: total_start.elapsed().as_millis(),
+        };
 
         Ok(AskResponse {
             question: request.question,
@@ -417,12 +574,56 @@ impl Memvid {
             context_only: request.context_only,
             retrieval,
             answer,
+            answer_citations,
             citations,
             context_fragments,
+            queries,
             stats,
         })
     }
 
+    /// Register the rewriter that supplies reformulations for [`AskMode::MultiQuery`].
+    pub fn set_query_rewriter(&mut self, rewriter: Arc<dyn QueryRewriter>) {
+        self.query_rewriter = Some(rewriter);
+    }
+
+    /// Remove the registered query rewriter.
+    pub fn clear_query_rewriter(&mut self) {
+        self.query_rewriter = None;
+    }
+
+    /// Distinct reformulations for a multi-query ask: the caller's, then the
+    /// rewriter's, falling back to term expansions when neither supplies any.
+    fn multi_query_reformulations(
+        &self,
+        request: &AskRequest,
+        primary_tokens: &[String],
+    ) -> Result<Vec<(String, AskQuerySource)>> {
+        let mut seen: HashSet<String> = HashSet::new();
+        seen.insert(request.question.trim().to_lowercase());
+        let mut queries: Vec<(String, AskQuerySource)> = Vec::new();
+        let mut push = |query: &str, source: AskQuerySource| {
+            let query = query.trim();
+            if !query.is_empty() && seen.insert(query.to_lowercase()) {
+                queries.push((query.to_string(), source));
+            }
+        };
+        for query in &request.queries {
+            push(query, AskQuerySource::Caller);
+        }
+        if let Some(rewriter) = self.query_rewriter.as_ref() {
+            for query in rewriter.rewrite(&request.question)? {
+                push(&query, AskQuerySource::Rewriter);
+            }
+        }
+        if request.queries.is_empty() && self.query_rewriter.is_none() {
+            for query in build_expanded_queries(primary_tokens) {
+                push(&query, AskQuerySource::Expansion);
+            }
+        }
+        Ok(queries)
+    }
+
     fn filter_hits_in_time_range(
         &mut self,
         hits: &mut Vec<SearchHit>,
@@ -492,19 +693,26 @@ impl Memvid {
         }
         let query_embedding = query_embedding_cow.as_ref();
         let expected_dimension = embedder.embedding_dimension();
-        let stored_dimension = self
-            .toc
-            .indexes
-            .vec
-            .as_ref()
-            .map(|manifest| manifest.dimension)
-            .filter(|dim| *dim > 0)
-            .or_else(|| {
-                self.vec_index
-                    .as_ref()
-                    .and_then(|index| index.entries().next().map(|(_, emb)| emb.len() as u32))
-            })
-            .unwrap_or(0);
+        let space = request.vec_space.as_deref();
+        let (stored_dimension, metric) = if let Some(space) = space {
+            let manifest = &self.vec_space_manifest(space)?.index;
+            (manifest.dimension, manifest.metric)
+        } else {
+            let dimension = self
+                .toc
+                .indexes
+                .vec
+                .as_ref()
+                .map(|manifest| manifest.dimension)
+                .filter(|dim| *dim > 0)
+                .or_else(|| {
+                    self.vec_index
+                        .as_ref()
+                        .and_then(|index| index.entries().next().map(|(_, emb)| emb.len() as u32))
+                })
+                .unwrap_or(0);
+            (dimension, self.vec_metric)
+        };
         if stored_dimension > 0 && query_embedding.len() as u32 != stored_dimension {
             return Err(MemvidError::VecDimensionMismatch {
                 expected: stored_dimension,
@@ -514,9 +722,13 @@ impl Memvid {
 
         let mut semantic_scores: HashMap<u64, f32> = HashMap::new();
         for hit in hits.iter() {
-            if let Some(embedding) = self.frame_embedding(hit.frame_id)? {
+            let embedding = match space {
+                Some(space) => self.frame_space_embedding(space, hit.frame_id)?,
+                None => self.frame_embedding(hit.frame_id)?,
+            };
+            if let Some(embedding) = embedding {
                 if expected_dimension == 0 || embedding.len() == expected_dimension {
-                    let score = cosine_similarity(&query_embedding, &embedding);
+                    let score = metric.similarity(metric.distance(query_embedding, &embedding));
                     semantic_scores.insert(hit.frame_id, score);
                 }
             }
@@ -552,6 +764,9 @@ impl Memvid {
                 context: String::new(),
                 next_cursor: None,
                 engine: SearchEngineKind::LexFallback,
+                facets: Vec::new(),
+                suggestions: Vec::new(),
+                corrected_query: None,
                 elapsed_ms,
                 params: SearchParams {
                     top_k: request.top_k,
@@ -622,6 +837,12 @@ impl Memvid {
                 text: frame_text.clone(),
                 chunk_text: Some(frame_text.clone()),
                 metadata: None,
+                retriever_scores: Vec::new(),
+                highlights: Vec::new(),
+                chunk_highlights: Vec::new(),
+                fragments: Vec::new(),
+                rerank: None,
+                expanded: None,
             });
         }
 
@@ -635,6 +856,9 @@ impl Memvid {
             context,
             next_cursor: None,
             engine: SearchEngineKind::LexFallback, // Mark as fallback
+            facets: Vec::new(),
+            suggestions: Vec::new(),
+            corrected_query: None,
             elapsed_ms,
             params: SearchParams {
                 top_k: request.top_k,
@@ -647,7 +871,12 @@ impl Memvid {
 
 #[cfg(not(feature = "lex"))]
 impl Memvid {
-    pub fn ask<E>(&mut self, _request: AskRequest, _embedder: Option<&E>) -> Result<AskResponse>
+    pub fn ask<E>(
+        &mut self,
+        _request: AskRequest,
+        _embedder: Option<&E>,
+        _synthesizer: Option<&dyn AnswerSynthesizer>,
+    ) -> Result<AskResponse>
     where
         E: VecEmbedder + ?Sized,
     {
@@ -677,7 +906,7 @@ fn determine_retriever(
                 AskRetriever::LexFallback
             }
         }
-        AskMode::Hybrid => {
+        AskMode::Hybrid | AskMode::MultiQuery => {
             if semantics_applied {
                 AskRetriever::Hybrid
             } else if lex_fallback_used {
@@ -712,7 +941,7 @@ fn reorder_hits_with_semantic_scores(
             let semantic_score = semantic_scores.get(&hit.frame_id).copied().unwrap_or(0.0);
             let combined = match mode {
                 AskMode::Sem => semantic_score,
-                AskMode::Hybrid => {
+                AskMode::Hybrid | AskMode::MultiQuery => {
                     let lexical_rrf = 1.0 / (RRF_K + lexical_rank as f32);
                     let semantic_rrf = semantic_rank
                         .get(&hit.frame_id)
@@ -744,70 +973,54 @@ fn reorder_hits_with_semantic_scores(
     *hits = reordered;
 }
 
-fn build_citations(hits: &[SearchHit], semantic_scores: &HashMap<u64, f32>) -> Vec<AskCitation> {
-    hits.iter()
+fn build_citations(
+    fragments: &[AskContextFragment],
+    semantic_scores: &HashMap<u64, f32>,
+) -> Vec<AskCitation> {
+    fragments
+        .iter()
         .enumerate()
-        .map(|(idx, hit)| AskCitation {
+        .map(|(idx, fragment)| AskCitation {
             index: idx + 1,
-            frame_id: hit.frame_id,
-            uri: hit.uri.clone(),
-            chunk_range: hit.chunk_range.or(Some(hit.range)),
-            score: semantic_scores.get(&hit.frame_id).copied().or(hit.score),
+            frame_id: fragment.frame_id,
+            uri: fragment.uri.clone(),
+            chunk_range: fragment.chunk_range.or(fragment.range),
+            score: semantic_scores
+                .get(&fragment.frame_id)
+                .copied()
+                .or(fragment.score),
         })
         .collect()
 }
 
-fn synthesize_answer(
-    question: &str,
-    hits: &[SearchHit],
-    citations: &[AskCitation],
-) -> Option<String> {
-    if hits.is_empty() {
-        return None;
-    }
-
-    let mut segments = Vec::new();
-    for citation in citations.iter().take(3) {
-        if let Some(hit) = hits
+/// Attribution for a query with its hit counts and the frames its lists retrieved.
+#[cfg(feature = "lex")]
+fn query_attribution(
+    query: String,
+    source: AskQuerySource,
+    lists: &[CandidateList],
+) -> (AskQueryAttribution, HashSet<FrameId>) {
+    // Distinct frames, since one retriever may contribute several variant lists.
+    let count = |retriever: HybridRetriever| {
+        lists
             .iter()
-            .find(|candidate| candidate.frame_id == citation.frame_id)
-        {
-            let snippet = hit.text.trim();
-            if snippet.is_empty() {
-                continue;
-            }
-            let sanitized = snippet.split_whitespace().collect::<Vec<_>>().join(" ");
-            if sanitized.is_empty() {
-                continue;
-            }
-            segments.push(format!("{} [{}]", sanitized, citation.index));
-        }
-    }
-
-    if segments.is_empty() {
-        return Some(format!(
-            "No direct synthesis available for '{question}'. Review the top contexts manually.",
-        ));
-    }
-
-    Some(segments.join(" "))
-}
-
-fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
-    let mut dot = 0.0f32;
-    let mut sum_a = 0.0f32;
-    let mut sum_b = 0.0f32;
-    for (x, y) in a.iter().zip(b.iter()) {
-        dot += x * y;
-        sum_a += x * x;
-        sum_b += y * y;
-    }
-
-    if sum_a <= f32::EPSILON || sum_b <= f32::EPSILON {
-        0.0
-    } else {
-        dot / (sum_a.sqrt() * sum_b.sqrt())
-    }
+            .filter(|(list_retriever, _)| *list_retriever == retriever)
+            .flat_map(|(_, hits)| hits.iter().map(|hit| hit.frame_id))
+            .collect::<HashSet<_>>()
+            .len()
+    };
+    let frames = lists
+        .iter()
+        .flat_map(|(_, hits)| hits.iter().map(|hit| hit.frame_id))
+        .collect();
+    let attribution = AskQueryAttribution {
+        query,
+        source,
+        lexical_hits: count(HybridRetriever::Lexical),
+        semantic_hits: count(HybridRetriever::Semantic),
+        fragments: Vec::new(),
+    };
+    (attribution, frames)
 }
 
 fn lexical_fallback_query(question: &str) -> Option<String> {
@@ -1323,6 +1536,8 @@ fn vector_hits(
         return Ok(Vec::new());
     }
 
+    let filter = VecSearchFilter::from(request);
+
     // Use adaptive retrieval if configured
     if let Some(ref adaptive_config) = request.adaptive {
         if adaptive_config.enabled {
@@ -1331,7 +1546,7 @@ fn vector_hits(
                 query_embedding,
                 adaptive_config.clone(),
                 request.snippet_chars,
-                request.scope.as_deref(),
+                &filter,
             )?;
             tracing::debug!(
                 "adaptive retrieval: {} -> {} results ({})",
@@ -1348,66 +1563,12 @@ fn vector_hits(
         query_embedding,
         limit,
         request.snippet_chars,
-        request.scope.as_deref(),
+        &filter,
     )?;
 
     Ok(vec_response.hits)
 }
 
-/// Fuse multiple hit lists using Reciprocal Rank Fusion.
-fn fuse_hits_rrf(mut lists: Vec<Vec<SearchHit>>, target: usize) -> Option<Vec<SearchHit>> {
-    if lists.is_empty() {
-        return None;
-    }
-    lists.retain(|list| !list.is_empty());
-    if lists.is_empty() {
-        return None;
-    }
-
-    let mut fused: HashMap<u64, (f32, SearchHit)> = HashMap::new();
-
-    for list in &lists {
-        for (idx, hit) in list.iter().enumerate() {
-            let rank = idx + 1;
-            let contribution = 1.0 / (RRF_K + rank as f32);
-            let entry = fused
-                .entry(hit.frame_id)
-                .or_insert_with(|| (0.0, hit.clone()));
-
-            // Keep the hit with more matches or earlier rank as the representative.
-            if hit.matches > entry.1.matches
-                || (hit.matches == entry.1.matches && rank < entry.1.rank)
-            {
-                entry.1 = hit.clone();
-            }
-            entry.0 += contribution;
-        }
-    }
-
-    let mut combined: Vec<(u64, f32, SearchHit)> = fused
-        .into_iter()
-        .map(|(id, (score, hit))| (id, score, hit))
-        .collect();
-
-    combined.sort_by(|a, b| {
-        b.1.partial_cmp(&a.1)
-            .unwrap_or(std::cmp::Ordering::Equal)
-            .then(a.2.rank.cmp(&b.2.rank))
-    });
-
-    let mut result = Vec::new();
-    for (_id, score, mut hit) in combined.into_iter().take(target.max(1)) {
-        hit.score = Some(score);
-        result.push(hit);
-    }
-
-    for (idx, hit) in result.iter_mut().enumerate() {
-        hit.rank = idx + 1;
-    }
-
-    Some(result)
-}
-
 /// Promote corrections to the top of the hit list.
 /// Corrections are user-provided facts that should override older information.
 /// The MOST RECENT correction takes priority (sorted by timestamp, newest first).
@@ -1546,6 +1707,43 @@ fn promote_temporal_extremes(
 #[cfg(test)]
 mod tests {
     use super::{build_disjunctive_query, lexical_fallback_query, sanitize_question_for_lexical};
+    #[cfg(feature = "lex")]
+    use crate::types::{
+        AnswerSynthesizer, AskContextFragment, AskContextFragmentKind, AskMode, AskQuerySource,
+        AskRequest, FusionStrategy, QueryRewriter, SynthesizedAnswer, TokenCounter,
+    };
+    #[cfg(feature = "lex")]
+    use crate::{
+        Memvid, PutOptions, Reranker, RerankerConfig, RerankerDocument, RerankerResult, Result,
+        VecEmbedder, run_serial_test,
+    };
+
+    /// Cites every fragment and echoes the question, recording what it was given.
+    #[cfg(feature = "lex")]
+    struct MockSynthesizer {
+        seen: std::cell::RefCell<Vec<String>>,
+    }
+
+    #[cfg(feature = "lex")]
+    impl AnswerSynthesizer for MockSynthesizer {
+        fn kind(&self) -> &str {
+            "mock"
+        }
+
+        fn synthesize(
+            &self,
+            question: &str,
+            fragments: &[AskContextFragment],
+        ) -> Result<Option<SynthesizedAnswer>> {
+            self.seen
+                .borrow_mut()
+                .extend(fragments.iter().map(|fragment| fragment.uri.clone()));
+            Ok(Some(SynthesizedAnswer {
+                text: format!("mock answer to {question}"),
+                citations: (1..=fragments.len() + 1).rev().collect(),
+            }))
+        }
+    }
 
     #[test]
     fn sanitize_question_strips_trailing_punctuation() {
@@ -1587,4 +1785,347 @@ mod tests {
         let query = build_disjunctive_query(&tokens).expect("query");
         assert_eq!(query, "checksum OR header");
     }
+
+    /// Prefers documents mentioning "march", dropping the rest.
+    #[cfg(feature = "lex")]
+    struct MarchReranker;
+
+    #[cfg(feature = "lex")]
+    impl Reranker for MarchReranker {
+        fn kind(&self) -> &str {
+            "march"
+        }
+
+        fn rerank(
+            &self,
+            _query: &str,
+            documents: &[RerankerDocument],
+            _top_k: usize,
+        ) -> Result<Vec<RerankerResult>> {
+            Ok(documents
+                .iter()
+                .filter(|doc| doc.text.to_lowercase().contains("march"))
+                .enumerate()
+                .map(|(idx, doc)| RerankerResult {
+                    id: doc.id,
+                    score: 1.0,
+                    original_rank: 0,
+                    new_rank: idx + 1,
+                })
+                .collect())
+        }
+    }
+
+    #[cfg(feature = "lex")]
+    fn budget_request() -> AskRequest {
+        AskRequest {
+            question: "budget review schedule".to_string(),
+            top_k: 5,
+            snippet_chars: 200,
+            uri: None,
+            scope: None,
+            cursor: None,
+            start: None,
+            end: None,
+            #[cfg(feature = "temporal_track")]
+            temporal: None,
+            context_only: true,
+            mode: AskMode::Lex,
+            as_of_frame: None,
+            as_of_ts: None,
+            adaptive: None,
+            vec_space: None,
+            mmr_lambda: None,
+            fusion: FusionStrategy::default(),
+            rerank: true,
+            queries: Vec::new(),
+            context_budget_tokens: None,
+        }
+    }
+
+    #[cfg(feature = "lex")]
+    #[test]
+    fn ask_applies_registered_reranker() {
+        run_serial_test(|| {
+            let dir = tempfile::tempdir().expect("tmp");
+            let path = dir.path().join("ask-rerank.mv2");
+
+            let mut mem = Memvid::create(&path).expect("create");
+            mem.enable_lex().expect("enable lex");
+            for (uri, text) in [
+                (
+                    "mv2://ops/q1",
+                    "Budget review schedule for the budget team.",
+                ),
+                ("mv2://ops/q2", "The budget review is due in March."),
+            ] {
+                let options = PutOptions::builder().uri(uri).build();
+                mem.put_bytes_with_options(text.as_bytes(), options)
+                    .expect("put");
+            }
+            mem.commit().expect("commit");
+
+            assert!(
+                mem.ask::<dyn VecEmbedder>(budget_request(), None, None)
+                    .is_err()
+            );
+
+            mem.set_reranker(
+                std::sync::Arc::new(MarchReranker),
+                RerankerConfig::default(),
+            );
+            let response = mem
+                .ask::<dyn VecEmbedder>(budget_request(), None, None)
+                .expect("ask");
+            let uris: Vec<&str> = response
+                .context_fragments
+                .iter()
+                .map(|fragment| fragment.uri.as_str())
+                .collect();
+            assert_eq!(uris, vec!["mv2://ops/q2"]);
+            let rerank = response.retrieval.hits[0].rerank.expect("rerank");
+            assert_eq!(rerank.new_rank, 1);
+        });
+    }
+
+    #[cfg(feature = "lex")]
+    #[test]
+    fn ask_uses_supplied_synthesizer() {
+        run_serial_test(|| {
+            let dir = tempfile::tempdir().expect("tmp");
+            let path = dir.path().join("ask.mv2");
+
+            let mut mem = Memvid::create(&path).expect("create");
+            mem.enable_lex().expect("enable lex");
+            for (uri, text) in [
+                ("mv2://geo/lava", "Lava fields cover the northern slope."),
+                ("mv2://ops/budget", "The budget review is due in March."),
+            ] {
+                let options = PutOptions::builder().uri(uri).build();
+                mem.put_bytes_with_options(text.as_bytes(), options)
+                    .expect("put");
+            }
+            mem.commit().expect("commit");
+
+            let request = AskRequest {
+                question: "When is the budget review?".to_string(),
+                top_k: 5,
+                snippet_chars: 200,
+                uri: None,
+                scope: None,
+                cursor: None,
+                start: None,
+                end: None,
+                #[cfg(feature = "temporal_track")]
+                temporal: None,
+                context_only: false,
+                mode: AskMode::Lex,
+                as_of_frame: None,
+                as_of_ts: None,
+                adaptive: None,
+                vec_space: None,
+                mmr_lambda: None,
+                fusion: FusionStrategy::default(),
+                rerank: false,
+                queries: Vec::new(),
+                context_budget_tokens: None,
+            };
+
+            let default = mem
+                .ask::<dyn VecEmbedder>(request.clone(), None, None)
+                .expect("ask");
+            assert_eq!(
+                default.answer.as_deref(),
+                Some("The budget review is due in March. [1]")
+            );
+            assert_eq!(default.answer_citations, vec![1]);
+
+            let mock = MockSynthesizer {
+                seen: std::cell::RefCell::new(Vec::new()),
+            };
+            let response = mem
+                .ask::<dyn VecEmbedder>(request, None, Some(&mock))
+                .expect("ask with mock");
+            assert_eq!(
+                response.answer.as_deref(),
+                Some("mock answer to When is the budget review?")
+            );
+            let fragment_uris: Vec<String> = response
+                .context_fragments
+                .iter()
+                .map(|fragment| fragment.uri.clone())
+                .collect();
+            assert_eq!(*mock.seen.borrow(), fragment_uris);
+            // The out-of-range index is dropped.
+            let expected: Vec<usize> = (1..=fragment_uris.len()).rev().collect();
+            assert_eq!(response.answer_citations, expected);
+        });
+    }
+
+    #[cfg(feature = "lex")]
+    struct WordCounter;
+
+    #[cfg(feature = "lex")]
+    impl TokenCounter for WordCounter {
+        fn count_tokens(&self, text: &str) -> usize {
+            text.split_whitespace().count()
+        }
+    }
+
+    #[cfg(feature = "lex")]
+    #[test]
+    fn ask_packs_context_within_token_budget() {
+        run_serial_test(|| {
+            let dir = tempfile::tempdir().expect("tmp");
+            let path = dir.path().join("ask.mv2");
+
+            let mut mem = Memvid::create(&path).expect("create");
+            mem.enable_lex().expect("enable lex");
+            mem.set_token_counter(std::sync::Arc::new(WordCounter));
+            let text: String = (0..6)
+                .map(|section| {
+                    let topic = if section == 3 { "zeppelin" } else { "harbour" };
+                    format!("Section {section} covers the {topic}. ")
+                        + &"Plain filler words pad this section out. ".repeat(30)
+                })
+                .collect();
+            let options = PutOptions::builder().uri("mv2://docs/long").build();
+            mem.put_bytes_with_options(text.as_bytes(), options)
+                .expect("put");
+            mem.commit().expect("commit");
+
+            let mut request = budget_request();
+            request.question = "zeppelin".to_string();
+            request.top_k = 1;
+            request.context_only = false;
+            request.rerank = false;
+
+            let unbudgeted = mem
+                .ask::<dyn VecEmbedder>(request.clone(), None, None)
+                .expect("ask");
+            let hit = unbudgeted.context_fragments[0].clone();
+            let hit_range = hit.chunk_range.expect("chunk range");
+            let hit_words = WordCounter.count_tokens(&hit.text);
+
+            request.context_budget_tokens = Some(hit_words * 3);
+            let response = mem
+                .ask::<dyn VecEmbedder>(request.clone(), None, None)
+                .expect("budgeted ask");
+            let packed: usize = response
+                .context_fragments
+                .iter()
+                .map(|fragment| WordCounter.count_tokens(&fragment.text))
+                .sum();
+            assert!(packed <= hit_words * 3);
+            let expanded = &response.context_fragments[0];
+            assert_eq!(expanded.kind, Some(AskContextFragmentKind::Full));
+            assert!(expanded.text.contains(&hit.text));
+            let range = expanded.chunk_range.expect("expanded range");
+            assert!(range.0 < hit_range.0 && range.1 > hit_range.1);
+            assert_eq!(response.citations[0].chunk_range, Some(range));
+
+            request.context_budget_tokens = Some(20);
+            let trimmed = mem
+                .ask::<dyn VecEmbedder>(request.clone(), None, None)
+                .expect("trimmed ask");
+            assert_eq!(trimmed.context_fragments.len(), 1);
+            let summary = &trimmed.context_fragments[0];
+            assert_eq!(summary.kind, Some(AskContextFragmentKind::Summary));
+            assert!(WordCounter.count_tokens(&summary.text) <= 20);
+            assert!(hit.text.starts_with(&summary.text));
+
+            request.context_budget_tokens = Some(0);
+            assert!(mem.ask::<dyn VecEmbedder>(request, None, None).is_err());
+        });
+    }
+
+    #[cfg(feature = "lex")]
+    struct FixedRewriter(&'static str);
+
+    #[cfg(feature = "lex")]
+    impl QueryRewriter for FixedRewriter {
+        fn kind(&self) -> &str {
+            "fixed"
+        }
+
+        fn rewrite(&self, _question: &str) -> Result<Vec<String>> {
+            Ok(vec![self.0.to_string()])
+        }
+    }
+
+    #[cfg(feature = "lex")]
+    #[test]
+    fn ask_multi_query_fuses_reformulations_with_attribution() {
+        run_serial_test(|| {
+            let dir = tempfile::tempdir().expect("tmp");
+            let path = dir.path().join("ask.mv2");
+
+            let mut mem = Memvid::create(&path).expect("create");
+            mem.enable_lex().expect("enable lex");
+            for (uri, text) in [
+                ("mv2://geo/lava", "Lava fields cover the northern slope."),
+                ("mv2://ops/budget", "The budget review is due in March."),
+                (
+                    "mv2://ops/retro",
+                    "Quarterly retrospective notes live in the wiki.",
+                ),
+            ] {
+                let options = PutOptions::builder().uri(uri).build();
+                mem.put_bytes_with_options(text.as_bytes(), options)
+                    .expect("put");
+            }
+            mem.commit().expect("commit");
+            mem.set_query_rewriter(std::sync::Arc::new(FixedRewriter("lava slope")));
+
+            let mut request = budget_request();
+            request.question = "When is the budget review?".to_string();
+            request.mode = AskMode::MultiQuery;
+            request.rerank = false;
+            request.queries = vec![
+                "retrospective notes".to_string(),
+                "when is the budget review?".to_string(),
+            ];
+            let response = mem
+                .ask::<dyn VecEmbedder>(request, None, None)
+                .expect("ask");
+
+            assert_eq!(response.mode, AskMode::MultiQuery);
+            let queries: Vec<(&str, AskQuerySource)> = response
+                .queries
+                .iter()
+                .map(|attribution| (attribution.query.as_str(), attribution.source))
+                .collect();
+            assert_eq!(
+                queries,
+                vec![
+                    ("When is the budget review?", AskQuerySource::Question),
+                    ("retrospective notes", AskQuerySource::Caller),
+                    ("lava slope", AskQuerySource::Rewriter),
+                ]
+            );
+            for (attribution, uri) in response.queries.iter().zip([
+                "mv2://ops/budget",
+                "mv2://ops/retro",
+                "mv2://geo/lava",
+            ]) {
+                assert!(attribution.lexical_hits > 0);
+                assert_eq!(attribution.semantic_hits, 0);
+                let uris: Vec<&str> = attribution
+                    .fragments
+                    .iter()
+                    .map(|index| response.context_fragments[index - 1].uri.as_str())
+                    .collect();
+                assert!(uris.contains(&uri), "{uri} missing from {uris:?}");
+            }
+
+            mem.clear_query_rewriter();
+            let mut request = budget_request();
+            request.mode = AskMode::Hybrid;
+            request.rerank = false;
+            let hybrid = mem
+                .ask::<dyn VecEmbedder>(request, None, None)
+                .expect("hybrid ask");
+            assert!(hybrid.queries.is_empty());
+        });
+    }
 }
@@ -8,6 +8,7 @@ use std::time::{SystemTime, UNIX_EPOCH};
 use crate::memvid::lifecycle::Memvid;
 use crate::types::ask::{AskMode, AskRequest};
 use crate::types::audit::{AuditOptions, AuditReport, SourceSpan};
+use crate::types::search::FusionStrategy;
 use crate::{Result, VecEmbedder};
 
 /// Default top-k for audit queries.
@@ -71,9 +72,15 @@ impl Memvid {
             as_of_frame: None,
             as_of_ts: None,
             adaptive: None,
+            vec_space: None,
+            mmr_lambda: None,
+            fusion: FusionStrategy::default(),
+            rerank: false,
+            queries: Vec::new(),
+            context_budget_tokens: None,
         };
 
-        let response = self.ask(request, embedder)?;
+        let response = self.ask(request, embedder, None)?;
 
         // Build source spans from the response
         let mut sources: Vec<SourceSpan> = Vec::new();
@@ -234,7 +241,7 @@ impl From<AskMode> for crate::AskRetriever {
         match mode {
             AskMode::Lex => crate::AskRetriever::Lex,
             AskMode::Sem => crate::AskRetriever::Semantic,
-            AskMode::Hybrid => crate::AskRetriever::Hybrid,
+            AskMode::Hybrid | AskMode::MultiQuery => crate::AskRetriever::Hybrid,
         }
     }
 }
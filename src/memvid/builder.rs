@@ -280,6 +280,7 @@ impl Memvid {
                 bytes_length: 0,
                 checksum: empty_checksum,
                 compression_mode: self.vec_compression.clone(),
+                metric: self.vec_metric,
             });
         }
         if let Some(manifest) = self.toc.indexes.vec.as_mut() {
@@ -464,7 +465,13 @@ mod tests {
 
             let mut reopened = Memvid::open_read_only(&path)?;
             let err = reopened
-                .vec_search_with_embedding("hello", &vec![0.0f32; 384], 5, 240, None)
+                .vec_search_with_embedding(
+                    "hello",
+                    &vec![0.0f32; 384],
+                    5,
+                    240,
+                    &crate::types::VecSearchFilter::default(),
+                )
                 .unwrap_err();
             match err {
                 MemvidError::VecDimensionMismatch { expected, actual } => {
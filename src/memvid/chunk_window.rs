@@ -0,0 +1,215 @@
+//! Parent-document and neighbouring-chunk windows around retrieved chunks.
+//!
+//! Chunked documents are a `Document` frame carrying a `TextChunkManifest` plus one
+//! `DocumentChunk` frame per chunk linked through `parent_id`. Hit ranges are byte
+//! offsets into the parent's concatenated chunk text, so a window is resolved by
+//! locating the chunk that contains the range start and widening from there.
+//! Frames that are not part of a chunked document behave as a single chunk.
+
+use crate::memvid::frame::DocumentChunkSpan;
+use crate::memvid::lifecycle::Memvid;
+use crate::structure::detect_structure;
+use crate::types::{ChunkExpansion, ChunkWindow, ElementData, FrameId, FrameRole, SearchHit};
+use crate::{MemvidError, Result};
+
+/// Ordered chunks of a frame's document, with the anchor chunk and the byte offset
+/// a section is resolved around.
+struct ResolvedChunks {
+    parent_id: FrameId,
+    spans: Vec<DocumentChunkSpan>,
+    anchor: usize,
+    anchor_offset: usize,
+}
+
+impl Memvid {
+    /// The chunk of `frame_id` containing `chunk_range` plus up to `before` and
+    /// `after` neighbouring chunks of the same document.
+    ///
+    /// `chunk_range` is a byte range in the document's text, such as a hit's
+    /// `range` or `chunk_range`; without one the frame's own chunk is used.
+    pub fn chunk_window(
+        &mut self,
+        frame_id: FrameId,
+        chunk_range: Option<(usize, usize)>,
+        before: usize,
+        after: usize,
+    ) -> Result<ChunkWindow> {
+        let resolved = self.resolve_document_chunks(frame_id, chunk_range)?;
+        let first = resolved.anchor.saturating_sub(before);
+        let last = resolved
+            .anchor
+            .saturating_add(after)
+            .min(resolved.spans.len() - 1);
+        let text: String = resolved.spans[first..=last]
+            .iter()
+            .map(|span| span.text.as_str())
+            .collect();
+        Ok(ChunkWindow {
+            parent_id: resolved.parent_id,
+            range: (resolved.spans[first].start, resolved.spans[last].end),
+            first_chunk: first,
+            last_chunk: last,
+            chunk_count: resolved.spans.len(),
+            text,
+        })
+    }
+
+    /// The markdown section of `frame_id`'s document that encloses the middle of
+    /// `chunk_range` (the frame's own chunk when `None`).
+    ///
+    /// The section runs from the nearest heading at or before that point to the next
+    /// heading of the same or a higher level; without headings it is the whole
+    /// document.
+    pub fn chunk_section(
+        &mut self,
+        frame_id: FrameId,
+        chunk_range: Option<(usize, usize)>,
+    ) -> Result<ChunkWindow> {
+        let resolved = self.resolve_document_chunks(frame_id, chunk_range)?;
+        let document: String = resolved
+            .spans
+            .iter()
+            .map(|span| span.text.as_str())
+            .collect();
+        let (start, end) = section_bounds(&document, resolved.anchor_offset);
+        let overlapping = |span: &DocumentChunkSpan| span.start < end && start < span.end;
+        let first = resolved
+            .spans
+            .iter()
+            .position(overlapping)
+            .unwrap_or(resolved.anchor);
+        let last = resolved
+            .spans
+            .iter()
+            .rposition(overlapping)
+            .unwrap_or(resolved.anchor);
+        Ok(ChunkWindow {
+            parent_id: resolved.parent_id,
+            range: (start, end),
+            first_chunk: first,
+            last_chunk: last,
+            chunk_count: resolved.spans.len(),
+            text: document[start..end].to_string(),
+        })
+    }
+
+    /// Attach the requested window to every hit.
+    pub(crate) fn expand_hits(
+        &mut self,
+        hits: &mut [SearchHit],
+        expansion: ChunkExpansion,
+    ) -> Result<()> {
+        for hit in hits {
+            // The snippet range sits inside the chunk and keeps sections anchored on the match.
+            let range = Some(hit.range);
+            let window = match expansion {
+                ChunkExpansion::Adjacent { before, after } => {
+                    self.chunk_window(hit.frame_id, range, before, after)?
+                }
+                ChunkExpansion::Section => self.chunk_section(hit.frame_id, range)?,
+            };
+            hit.expanded = Some(window);
+        }
+        Ok(())
+    }
+
+    fn resolve_document_chunks(
+        &mut self,
+        frame_id: FrameId,
+        chunk_range: Option<(usize, usize)>,
+    ) -> Result<ResolvedChunks> {
+        let frame = self.frame_by_id(frame_id)?;
+        let chunked = self.document_chunk_spans(frame_id).map(|spans| {
+            let parent_id = match frame.role {
+                FrameRole::DocumentChunk => frame.parent_id.unwrap_or(frame_id),
+                _ => frame_id,
+            };
+            (parent_id, spans)
+        });
+        let (parent_id, spans) = match chunked {
+            Some(chunked) => chunked,
+            None => {
+                let info = self.resolve_chunk_context(&frame)?;
+                let span = DocumentChunkSpan {
+                    start: info.start,
+                    end: info.end,
+                    text: info.text,
+                };
+                (frame_id, vec![span])
+            }
+        };
+
+        let anchor = match chunk_range {
+            Some((start, _)) => spans
+                .iter()
+                .position(|span| span.start <= start && start < span.end)
+                .ok_or(MemvidError::InvalidFrame {
+                    frame_id,
+                    reason: "chunk range outside document",
+                })?,
+            None if frame.role == FrameRole::DocumentChunk => frame
+                .chunk_index
+                .map(|index| index as usize)
+                .filter(|index| *index < spans.len())
+                .unwrap_or(0),
+            None => 0,
+        };
+        let anchor_offset = match chunk_range {
+            Some((start, end)) => start + end.saturating_sub(start) / 2,
+            None => spans[anchor].start,
+        };
+        Ok(ResolvedChunks {
+            parent_id,
+            spans,
+            anchor,
+            anchor_offset,
+        })
+    }
+}
+
+/// Byte range of the markdown section of `document` that contains `offset`.
+fn section_bounds(document: &str, offset: usize) -> (usize, usize) {
+    let headings: Vec<(usize, u8)> = detect_structure(document)
+        .elements
+        .iter()
+        .filter_map(|element| match &element.data {
+            ElementData::Heading(heading) => Some((element.char_start, heading.level)),
+            _ => None,
+        })
+        .filter(|(start, _)| document.is_char_boundary(*start))
+        .collect();
+
+    let opening = headings.iter().rev().find(|(start, _)| *start <= offset);
+    let start = opening.map_or(0, |(start, _)| *start);
+    let end = headings
+        .iter()
+        .find(|(heading_start, level)| {
+            *heading_start > offset && opening.is_none_or(|(_, open)| level <= open)
+        })
+        .map_or(document.len(), |(start, _)| *start);
+    (start, end)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn section_bounds_follow_heading_levels() {
+        let document = "Intro line\n# Guide\nOverview\n## Setup\nInstall it\n## Usage\nRun it\n# Appendix\nNotes\n";
+        let setup = document.find("Install").unwrap();
+        let (start, end) = section_bounds(document, setup);
+        assert_eq!(&document[start..end], "## Setup\nInstall it\n");
+
+        let overview = document.find("Overview").unwrap();
+        let (start, end) = section_bounds(document, overview);
+        assert_eq!(
+            &document[start..end],
+            "# Guide\nOverview\n## Setup\nInstall it\n## Usage\nRun it\n"
+        );
+
+        let (start, end) = section_bounds(document, 0);
+        assert_eq!(&document[start..end], "Intro line\n");
+        assert_eq!(section_bounds("No headings here.", 4), (0, 17));
+    }
+}
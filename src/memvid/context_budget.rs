@@ -0,0 +1,280 @@
+//! Token-budgeted packing of `ask` context fragments.
+//!
+//! Fragments are considered best score first. Each one that fits the remaining
+//! budget is kept whole; the first one that does not is trimmed at a word boundary
+//! to the remaining budget and marked [`AskContextFragmentKind::Summary`].
+//! Fragments whose span overlaps one already packed from the same document are
+//! dropped. Leftover budget then grows whole-chunk fragments, best first, with the
+//! neighbouring chunks of their parent document (alternating after and before)
+//! until the next neighbour no longer fits. Packed fragments keep their retrieval
+//! order.
+
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::memvid::frame::DocumentChunkSpan;
+use crate::memvid::lifecycle::Memvid;
+use crate::types::{
+    ApproxTokenCounter, AskContextFragment, AskContextFragmentKind, FrameId, FrameRole,
+    TokenCounter,
+};
+use crate::{MemvidError, Result};
+
+/// Smallest remaining budget worth filling with a trimmed fragment.
+const MIN_SUMMARY_TOKENS: usize = 16;
+
+impl Memvid {
+    /// Register the token counter used to budget `ask` context.
+    pub fn set_token_counter(&mut self, counter: Arc<dyn TokenCounter>) {
+        self.token_counter = Some(counter);
+    }
+
+    /// Revert to the approximate token counter.
+    pub fn clear_token_counter(&mut self) {
+        self.token_counter = None;
+    }
+
+    /// Pack `fragments` into `budget` tokens as described in the module docs.
+    pub(crate) fn pack_context_fragments(
+        &mut self,
+        fragments: Vec<AskContextFragment>,
+        budget: usize,
+    ) -> Vec<AskContextFragment> {
+        let counter = self
+            .token_counter
+            .clone()
+            .unwrap_or_else(|| Arc::new(ApproxTokenCounter));
+
+        let mut order: Vec<usize> = (0..fragments.len()).collect();
+        order.sort_by(|a, b| score_order(&fragments[*a], &fragments[*b]));
+
+        let mut remaining = budget;
+        let mut covered: Vec<(FrameId, (usize, usize))> = Vec::new();
+        // (retrieval position, document, fragment), best score first
+        let mut packed: Vec<(usize, FrameId, AskContextFragment)> = Vec::new();
+        let mut slots: Vec<Option<AskContextFragment>> = fragments.into_iter().map(Some).collect();
+        for position in order {
+            if remaining == 0 {
+                break;
+            }
+            let Some(mut fragment) = slots[position].take() else {
+                continue;
+            };
+            let document = self.fragment_document(fragment.frame_id);
+            let span = fragment.chunk_range.or(fragment.range);
+            if span.is_some_and(|span| is_covered(&covered, document, span)) {
+                continue;
+            }
+            let tokens = counter.count_tokens(&fragment.text);
+            if tokens <= remaining {
+                remaining -= tokens;
+                fragment.kind = Some(AskContextFragmentKind::Full);
+            } else if remaining >= MIN_SUMMARY_TOKENS {
+                let trimmed = trim_to_tokens(&fragment.text, remaining, counter.as_ref());
+                if trimmed.is_empty() {
+                    continue;
+                }
+                remaining = remaining.saturating_sub(counter.count_tokens(&trimmed));
+                fragment.text = trimmed;
+                fragment.kind = Some(AskContextFragmentKind::Summary);
+            } else {
+                continue;
+            }
+            if let Some(span) = span {
+                covered.push((document, span));
+            }
+            packed.push((position, document, fragment));
+        }
+
+        let mut chunks: HashMap<FrameId, Option<Vec<DocumentChunkSpan>>> = HashMap::new();
+        for (_, document, fragment) in &mut packed {
+            if remaining == 0 {
+                break;
+            }
+            if fragment.kind != Some(AskContextFragmentKind::Full) {
+                continue;
+            }
+            let Some(range) = fragment.chunk_range else {
+                continue;
+            };
+            let frame_id = fragment.frame_id;
+            let spans = chunks
+                .entry(*document)
+                .or_insert_with(|| self.document_chunk_spans(frame_id));
+            let Some(spans) = spans.as_deref() else {
+                continue;
+            };
+            let Some(start) = spans
+                .iter()
+                .position(|span| (span.start, span.end) == range)
+            else {
+                continue;
+            };
+
+            let (mut lo, mut hi) = (start, start);
+            loop {
+                let mut grew = false;
+                for after in [true, false] {
+                    let next = if after {
+                        Some(hi + 1).filter(|next| *next < spans.len())
+                    } else {
+                        lo.checked_sub(1)
+                    };
+                    let Some(next) = next else {
+                        continue;
+                    };
+                    let neighbour = &spans[next];
+                    let neighbour_span = (neighbour.start, neighbour.end);
+                    if is_covered(&covered, *document, neighbour_span) {
+                        continue;
+                    }
+                    let tokens = counter.count_tokens(&neighbour.text);
+                    if tokens > remaining {
+                        continue;
+                    }
+                    remaining -= tokens;
+                    if after {
+                        fragment.text.push_str(&neighbour.text);
+                        hi = next;
+                    } else {
+                        fragment.text.insert_str(0, &neighbour.text);
+                        lo = next;
+                    }
+                    covered.push((*document, neighbour_span));
+                    grew = true;
+                }
+                if !grew {
+                    break;
+                }
+            }
+            fragment.chunk_range = Some((spans[lo].start, spans[hi].end));
+        }
+
+        packed.sort_by_key(|(position, ..)| *position);
+        packed
+            .into_iter()
+            .map(|(_, _, fragment)| fragment)
+            .collect()
+    }
+
+    /// Frame whose coordinates a fragment's ranges use: the parent for document chunks.
+    fn fragment_document(&self, frame_id: FrameId) -> FrameId {
+        self.toc
+            .frames
+            .get(frame_id as usize)
+            .filter(|frame| frame.role == FrameRole::DocumentChunk)
+            .and_then(|frame| frame.parent_id)
+            .unwrap_or(frame_id)
+    }
+}
+
+pub(crate) fn validate_context_budget(budget: Option<usize>) -> Result<()> {
+    if budget == Some(0) {
+        return Err(MemvidError::InvalidQuery {
+            reason: "context budget must be greater than zero tokens".into(),
+        });
+    }
+    Ok(())
+}
+
+/// Higher scores first; unscored fragments after scored ones, in rank order.
+fn score_order(a: &AskContextFragment, b: &AskContextFragment) -> Ordering {
+    match (a.score, b.score) {
+        (Some(left), Some(right)) => right.partial_cmp(&left).unwrap_or(Ordering::Equal),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    }
+    .then(a.rank.cmp(&b.rank))
+}
+
+fn is_covered(
+    covered: &[(FrameId, (usize, usize))],
+    document: FrameId,
+    span: (usize, usize),
+) -> bool {
+    covered
+        .iter()
+        .any(|(other, (start, end))| *other == document && span.0 < *end && *start < span.1)
+}
+
+/// Longest prefix of `text` within `budget` tokens, cut at whitespace when possible.
+fn trim_to_tokens(text: &str, budget: usize, counter: &dyn TokenCounter) -> String {
+    let longest_fitting = |cuts: &[usize]| {
+        let fits = cuts.partition_point(|cut| counter.count_tokens(&text[..*cut]) <= budget);
+        fits.checked_sub(1).map(|idx| text[..cuts[idx]].trim_end())
+    };
+    let word_cuts: Vec<usize> = text
+        .char_indices()
+        .filter(|(_, ch)| ch.is_whitespace())
+        .map(|(idx, _)| idx)
+        .collect();
+    if let Some(prefix) = longest_fitting(&word_cuts).filter(|prefix| !prefix.is_empty()) {
+        return prefix.to_string();
+    }
+    let char_cuts: Vec<usize> = text.char_indices().skip(1).map(|(idx, _)| idx).collect();
+    longest_fitting(&char_cuts).unwrap_or_default().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct WordCounter;
+
+    impl TokenCounter for WordCounter {
+        fn count_tokens(&self, text: &str) -> usize {
+            text.split_whitespace().count()
+        }
+    }
+
+    fn fragment(rank: usize, score: Option<f32>) -> AskContextFragment {
+        AskContextFragment {
+            rank,
+            frame_id: rank as u64,
+            uri: format!("mv2://doc/{rank}"),
+            title: None,
+            score,
+            matches: 0,
+            range: None,
+            chunk_range: None,
+            text: String::new(),
+            kind: None,
+            #[cfg(feature = "temporal_track")]
+            temporal: None,
+        }
+    }
+
+    #[test]
+    fn trim_cuts_at_word_boundaries() {
+        let text = "one two three four five";
+        assert_eq!(trim_to_tokens(text, 3, &WordCounter), "one two three");
+        assert_eq!(trim_to_tokens(text, 0, &WordCounter), "");
+        assert_eq!(
+            trim_to_tokens("abcdefghijkl", 2, &ApproxTokenCounter),
+            "abcdefgh"
+        );
+    }
+
+    #[test]
+    fn score_order_prefers_scored_then_rank() {
+        let mut fragments = vec![
+            fragment(1, None),
+            fragment(2, Some(0.2)),
+            fragment(3, Some(0.9)),
+            fragment(4, None),
+        ];
+        fragments.sort_by(score_order);
+        let ranks: Vec<usize> = fragments.iter().map(|fragment| fragment.rank).collect();
+        assert_eq!(ranks, vec![3, 2, 1, 4]);
+    }
+
+    #[test]
+    fn overlap_detection_is_per_document() {
+        let covered = vec![(1, (10, 20))];
+        assert!(is_covered(&covered, 1, (15, 30)));
+        assert!(!is_covered(&covered, 1, (20, 30)));
+        assert!(!is_covered(&covered, 2, (15, 30)));
+    }
+}
@@ -20,6 +20,8 @@ use crate::types::{Header, Toc};
 
 #[cfg(feature = "lex")]
 use crate::lex::LexIndex;
+#[cfg(feature = "lex")]
+use crate::types::{SKETCH_TRACK_VERSION, SketchTrackHeader};
 use crate::vec::VecIndex;
 
 #[derive(Default)]
@@ -457,6 +459,8 @@ impl DoctorPlanner {
 
         self.inspect_time_index(&mut probe, &mut file);
         self.inspect_lex_index(&mut probe, &mut file);
+        #[cfg(feature = "lex")]
+        Self::inspect_sketch_track(&mut probe, &mut file);
         self.inspect_vec_index(&mut probe, &mut file);
 
         Ok(probe)
@@ -545,7 +549,33 @@ impl DoctorPlanner {
                 "doctor: detected Tantivy-based lex index with {} segments, skipping old validation",
                 toc.indexes.lex_segments.len()
             );
-            // Tantivy index is valid, no further validation needed
+            for (field, description) in [
+                ("title", "frame titles"),
+                ("meta", "extra metadata"),
+                ("words", "unstemmed words for spelling suggestions"),
+            ] {
+                if let Some(false) =
+                    tantivy_snapshot_has_field(file, &toc.indexes.lex_segments, field)
+                {
+                    probe.index.needs_lex = true;
+                    probe.findings.push(DoctorFinding::warning(
+                        DoctorFindingCode::TantivySnapshotOutdated,
+                        format!("tantivy snapshot does not index {description}"),
+                    ));
+                    break;
+                }
+            }
+            #[cfg(feature = "lex")]
+            if !probe.index.needs_lex
+                && tantivy_snapshot_tokenizer(file, &toc.indexes.lex_segments, "content")
+                    .is_some_and(|tokenizer| tokenizer != crate::search::DEFAULT_TOKENIZER)
+            {
+                probe.index.needs_lex = true;
+                probe.findings.push(DoctorFinding::warning(
+                    DoctorFindingCode::TantivySnapshotOutdated,
+                    "tantivy snapshot predates CJK bigram segmentation".to_string(),
+                ));
+            }
             return;
         }
 
@@ -635,6 +665,34 @@ impl DoctorPlanner {
         }
     }
 
+    /// Sketches are regenerated from the indexed text, so an outdated track is
+    /// repaired by the lex rebuild.
+    #[cfg(feature = "lex")]
+    fn inspect_sketch_track(probe: &mut PlanProbe, file: &mut std::fs::File) {
+        let Some(manifest) = probe.toc.as_ref().and_then(|toc| toc.sketch_track.clone()) else {
+            return;
+        };
+        let mut buf = [0u8; SketchTrackHeader::SIZE];
+        if file.seek(SeekFrom::Start(manifest.bytes_offset)).is_err()
+            || file.read_exact(&mut buf).is_err()
+        {
+            return;
+        }
+        let Ok(header) = SketchTrackHeader::from_bytes(&buf) else {
+            return;
+        };
+        if header.version < SKETCH_TRACK_VERSION {
+            probe.index.needs_lex = true;
+            probe.findings.push(DoctorFinding::warning(
+                DoctorFindingCode::SketchTrackOutdated,
+                format!(
+                    "sketch track version {} predates CJK bigram terms",
+                    header.version
+                ),
+            ));
+        }
+    }
+
     fn inspect_vec_index(&self, probe: &mut PlanProbe, file: &mut std::fs::File) {
         let Some(toc) = probe.toc.as_ref() else {
             return;
@@ -796,6 +854,57 @@ impl DoctorPlanner {
     }
 }
 
+/// Reads the embedded Tantivy `meta.json` and reports whether its schema has `field`.
+/// Returns `None` when the snapshot metadata cannot be read.
+fn tantivy_snapshot_has_field(
+    file: &mut std::fs::File,
+    segments: &[crate::types::LexSegmentManifest],
+    field: &str,
+) -> Option<bool> {
+    let schema = tantivy_snapshot_schema(file, segments)?;
+    Some(
+        schema
+            .iter()
+            .any(|entry| entry.get("name").and_then(|name| name.as_str()) == Some(field)),
+    )
+}
+
+/// Tokenizer the embedded Tantivy snapshot indexes `field` with, if readable.
+#[cfg(feature = "lex")]
+fn tantivy_snapshot_tokenizer(
+    file: &mut std::fs::File,
+    segments: &[crate::types::LexSegmentManifest],
+    field: &str,
+) -> Option<String> {
+    let schema = tantivy_snapshot_schema(file, segments)?;
+    let entry = schema
+        .iter()
+        .find(|entry| entry.get("name").and_then(|name| name.as_str()) == Some(field))?;
+    let tokenizer = entry.pointer("/options/indexing/tokenizer")?.as_str()?;
+    Some(tokenizer.to_string())
+}
+
+/// Field entries of the embedded Tantivy `meta.json` schema.
+fn tantivy_snapshot_schema(
+    file: &mut std::fs::File,
+    segments: &[crate::types::LexSegmentManifest],
+) -> Option<Vec<serde_json::Value>> {
+    let meta = segments
+        .iter()
+        .find(|segment| segment.path == "meta.json")?;
+    if meta.bytes_length > crate::MAX_INDEX_BYTES {
+        return None;
+    }
+    let mut buf = vec![0u8; usize::try_from(meta.bytes_length).ok()?];
+    file.seek(SeekFrom::Start(meta.bytes_offset)).ok()?;
+    file.read_exact(&mut buf).ok()?;
+    let mut meta: serde_json::Value = serde_json::from_slice(&buf).ok()?;
+    match meta.get_mut("schema")?.take() {
+        serde_json::Value::Array(schema) => Some(schema),
+        _ => None,
+    }
+}
+
 struct DoctorExecutor {
     path: PathBuf,
     plan: DoctorPlan,
@@ -1408,6 +1517,14 @@ impl DoctorExecutor {
 
         println!("doctor: rebuild_indexes start");
         mem.rebuild_indexes(&[])?;
+        // rebuild_indexes rewrites the region the sketch track may live in and leaves
+        // persisting it to the commit path.
+        if mem.has_sketches() {
+            mem.persist_sketch_track()?;
+            mem.rewrite_toc_footer()?;
+            mem.header.toc_checksum = mem.toc.toc_checksum;
+            crate::persist_header(&mut mem.file, &mem.header)?;
+        }
         println!("doctor: rebuild_indexes done");
 
         // Preserve footer_offset that was just set by rebuild_indexes
@@ -1637,3 +1754,49 @@ impl DoctorExecutor {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::LexSegmentManifest;
+
+    #[test]
+    fn detects_tantivy_schema_fields() {
+        let mut file = tempfile::tempfile().expect("tempfile");
+        let old_meta = br#"{"schema":[{"name":"content","type":"text","options":{"indexing":{"tokenizer":"memvid_default"}}}]}"#;
+        let new_meta = br#"{"schema":[{"name":"content","type":"text","options":{"indexing":{"tokenizer":"memvid_text"}}},{"name":"title","type":"text"}]}"#;
+        file.write_all(old_meta).expect("write");
+        file.write_all(new_meta).expect("write");
+        let segment = |offset: usize, len: usize| LexSegmentManifest {
+            path: "meta.json".to_string(),
+            bytes_offset: offset as u64,
+            bytes_length: len as u64,
+            checksum: [0u8; 32],
+        };
+
+        let old = [segment(0, old_meta.len())];
+        assert_eq!(
+            tantivy_snapshot_has_field(&mut file, &old, "title"),
+            Some(false)
+        );
+        let new = [segment(old_meta.len(), new_meta.len())];
+        assert_eq!(
+            tantivy_snapshot_has_field(&mut file, &new, "title"),
+            Some(true)
+        );
+        assert_eq!(tantivy_snapshot_has_field(&mut file, &[], "title"), None);
+
+        #[cfg(feature = "lex")]
+        {
+            assert_eq!(
+                tantivy_snapshot_tokenizer(&mut file, &old, "content").as_deref(),
+                Some("memvid_default")
+            );
+            assert_eq!(
+                tantivy_snapshot_tokenizer(&mut file, &new, "content").as_deref(),
+                Some(crate::search::DEFAULT_TOKENIZER)
+            );
+            assert_eq!(tantivy_snapshot_tokenizer(&mut file, &new, "title"), None);
+        }
+    }
+}
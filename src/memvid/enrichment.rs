@@ -467,32 +467,47 @@ impl Memvid {
 
         let count = embeddings.len();
 
-        // Build new vector index with existing + new embeddings
-        let mut builder = VecIndexBuilder::new();
-
-        // Add existing embeddings from current index
-        if let Some(ref vec_index) = self.vec_index {
-            for (frame_id, embedding) in vec_index.entries() {
-                // Skip if we're replacing this frame's embedding
-                if !embeddings.iter().any(|(id, _)| *id == frame_id) {
-                    builder.add_document(frame_id, embedding.to_vec());
+        // Switch to int8/binary codes if requested; PQ and scalar quantized indexes
+        // encode the new embeddings with their existing parameters
+        let extended =
+            if let Some(requantized) = self.requantize_vec_index(|_| true, &embeddings)? {
+                Some(requantized)
+            } else if let Some(ref vec_index) = self.vec_index {
+                vec_index.extend_compressed(|_| true, &embeddings)?
+            } else {
+                None
+            };
+        let (artifact, new_index) = match extended {
+            Some(extended) => extended,
+            None => {
+                // Build new vector index with existing + new embeddings
+                // Add existing embeddings from current index, skipping frames being replaced
+                let mut builder = match self.vec_index {
+                    Some(ref vec_index) => VecIndexBuilder::from_index(vec_index, |frame_id| {
+                        !embeddings.iter().any(|(id, _)| *id == frame_id)
+                    }),
+                    None => VecIndexBuilder::new(),
                 }
-            }
-        }
+                .with_metric(self.vec_metric);
 
-        // Add new embeddings
-        for (frame_id, embedding) in embeddings {
-            builder.add_document(frame_id, embedding);
-        }
+                // Add new embeddings
+                for (frame_id, embedding) in embeddings {
+                    builder.add_document(frame_id, embedding);
+                }
 
-        // Finish building the index
-        let artifact = builder.finish()?;
+                // Finish building the index
+                let artifact = builder.finish()?;
+                let new_index =
+                    crate::vec::VecIndex::decode(&artifact.bytes)?.with_metric(self.vec_metric);
+                (artifact, new_index)
+            }
+        };
         if artifact.vector_count == 0 {
             return Ok(0);
         }
 
-        // Decode and store the new index
-        let new_index = crate::vec::VecIndex::decode(&artifact.bytes)?;
+        // Store the new index
+        let compression_mode = new_index.compression();
         self.vec_index = Some(new_index);
 
         // Update TOC with new manifest
@@ -502,7 +517,8 @@ impl Memvid {
             bytes_offset: 0, // Will be set during commit
             bytes_length: artifact.bytes.len() as u64,
             checksum: artifact.checksum,
-            compression_mode: crate::types::VectorCompression::None,
+            compression_mode,
+            metric: self.vec_metric,
         });
 
         self.dirty = true;
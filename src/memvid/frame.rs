@@ -14,6 +14,14 @@ pub(crate) struct ChunkInfo {
     pub text: String,
 }
 
+/// One chunk of a chunked document, with its byte range in the parent's canonical text.
+#[derive(Debug, Clone)]
+pub(crate) struct DocumentChunkSpan {
+    pub start: usize,
+    pub end: usize,
+    pub text: String,
+}
+
 /// Streaming reader over canonical frame bytes. For binary payloads (e.g., video) the reader
 /// clones the underlying file handle to avoid disturbing `Memvid`'s primary cursor.
 pub struct BlobReader {
@@ -332,6 +340,83 @@ impl Memvid {
         self.frame_canonical_text(frame)
     }
 
+    /// The first `max_chars` characters of [`Self::frame_content`], decoding only as much
+    /// of the payload as they need. Chunked documents read their chunks in order.
+    pub(crate) fn frame_text_prefix(&mut self, frame: &Frame, max_chars: usize) -> Result<String> {
+        if frame.role == FrameRole::Document && frame.chunk_manifest.is_some() {
+            let mut children = self.document_chunk_frames(frame.id);
+            children.sort_by_key(|child| (child.chunk_index.unwrap_or(u32::MAX), child.id));
+            let mut text = String::new();
+            let mut remaining = max_chars;
+            for child in children {
+                if remaining == 0 {
+                    break;
+                }
+                let chunk = self.payload_text_prefix(&child, remaining)?;
+                remaining -= chunk.chars().count();
+                text.push_str(&chunk);
+            }
+            return Ok(text);
+        }
+        if let Some(search) = &frame.search_text {
+            return Ok(search.chars().take(max_chars).collect());
+        }
+        let binary = frame
+            .metadata
+            .as_ref()
+            .and_then(|meta| meta.mime.as_deref())
+            .is_some_and(|mime| !mime_is_text(mime));
+        if binary {
+            let logical = frame.canonical_length.unwrap_or(frame.payload_length);
+            return Ok(Self::render_binary_summary(
+                usize::try_from(logical).unwrap_or(usize::MAX),
+            ));
+        }
+        self.payload_text_prefix(frame, max_chars)
+    }
+
+    /// Decode at most `max_chars` characters from the start of a frame's own payload.
+    fn payload_text_prefix(&mut self, frame: &Frame, max_chars: usize) -> Result<String> {
+        if frame.payload_length == 0 {
+            return Ok(String::new());
+        }
+        // A UTF-8 character takes at most four bytes.
+        let limit = max_chars.saturating_mul(4) as u64;
+        self.validate_frame_bounds(frame)?;
+        self.file.seek(SeekFrom::Start(frame.payload_offset))?;
+        let mut bytes = Vec::new();
+        match frame.canonical_encoding {
+            CanonicalEncoding::Plain => {
+                (&mut self.file)
+                    .take(limit.min(frame.payload_length))
+                    .read_to_end(&mut bytes)?;
+            }
+            CanonicalEncoding::Zstd => {
+                let payload = (&mut self.file).take(frame.payload_length);
+                zstd::stream::read::Decoder::new(payload)
+                    .and_then(|decoder| decoder.take(limit).read_to_end(&mut bytes))
+                    .map_err(|_| MemvidError::InvalidFrame {
+                        frame_id: frame.id,
+                        reason: "failed to decode canonical payload",
+                    })?;
+            }
+        }
+        let text = match std::str::from_utf8(&bytes) {
+            Ok(text) => text,
+            // The byte limit may split the last character.
+            Err(err) if err.error_len().is_none() => {
+                std::str::from_utf8(&bytes[..err.valid_up_to()]).unwrap_or_default()
+            }
+            Err(_) => {
+                let logical = frame.canonical_length.unwrap_or(frame.payload_length);
+                return Ok(Self::render_binary_summary(
+                    usize::try_from(logical).unwrap_or(usize::MAX),
+                ));
+            }
+        };
+        Ok(text.chars().take(max_chars).collect())
+    }
+
     pub fn frame_embedding(&mut self, frame_id: FrameId) -> Result<Option<Vec<f32>>> {
         if !self.vec_enabled {
             return Ok(None);
@@ -340,7 +425,7 @@ impl Memvid {
         Ok(self.vec_index.as_ref().and_then(|index| {
             index
                 .embedding_for(frame_id)
-                .map(|embedding| embedding.to_vec())
+                .map(std::borrow::Cow::into_owned)
         }))
     }
 
@@ -482,6 +567,40 @@ impl Memvid {
         frames
     }
 
+    /// Ordered chunks of the document that `frame_id` belongs to.
+    ///
+    /// Returns `None` when the frame is neither a chunked document nor one of its
+    /// chunks, or when the chunks cannot be read back.
+    pub(crate) fn document_chunk_spans(
+        &mut self,
+        frame_id: FrameId,
+    ) -> Option<Vec<DocumentChunkSpan>> {
+        let frame = self.toc.frames.get(frame_id as usize).cloned()?;
+        let parent = match frame.role {
+            FrameRole::Document => frame,
+            FrameRole::DocumentChunk => self.toc.frames.get(frame.parent_id? as usize).cloned()?,
+            FrameRole::ExtractedImage => return None,
+        };
+        if parent.chunk_manifest.is_none() {
+            return None;
+        }
+        let payloads = self.document_chunk_payloads(&parent).ok()?;
+        let mut offset = 0usize;
+        let spans = payloads
+            .into_iter()
+            .map(|(_, bytes)| {
+                let start = offset;
+                offset += bytes.len();
+                DocumentChunkSpan {
+                    start,
+                    end: offset,
+                    text: String::from_utf8_lossy(&bytes).into_owned(),
+                }
+            })
+            .collect();
+        Some(spans)
+    }
+
     pub(crate) fn resolve_chunk_context(&mut self, frame: &Frame) -> Result<ChunkInfo> {
         match frame.role {
             FrameRole::Document => {
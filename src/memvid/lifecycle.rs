@@ -6,6 +6,7 @@
 //! - Validate TOC/footer layout, recover the latest valid footer when needed.
 //! - Wire up index state (lex/vector/time) without mutating payload bytes.
 
+use std::collections::HashMap;
 use std::convert::TryInto;
 use std::fs::{File, OpenOptions};
 use std::io::{Read, Seek, SeekFrom};
@@ -21,16 +22,20 @@ use crate::io::header::HeaderCodec;
 use crate::io::manifest_wal::ManifestWal;
 use crate::io::wal::EmbeddedWal;
 use crate::lock::{FileLock, LockMode};
+use crate::memvid::reembed::ReembedTally;
 #[cfg(feature = "lex")]
 use crate::search::{EmbeddedLexStorage, TantivyEngine};
-#[cfg(feature = "temporal_track")]
-use crate::types::FrameId;
 #[cfg(feature = "parallel_segments")]
 use crate::types::IndexSegmentRef;
+#[cfg(feature = "lex")]
+use crate::types::reranker::{Reranker, RerankerConfig};
 use crate::types::{
-    FrameStatus, Header, IndexManifests, LogicMesh, MemoriesTrack, SchemaRegistry, SegmentCatalog,
-    SketchTrack, TicketRef, Tier, Toc, VectorCompression,
+    DistanceMetric, FrameId, FrameStatus, Header, IndexManifests, LexAnalyzerConfig, LogicMesh,
+    MemoriesTrack, SchemaRegistry, SegmentCatalog, SketchTrack, TicketRef, Tier, Toc,
+    VectorCompression,
 };
+#[cfg(feature = "lex")]
+use crate::types::{QueryRewriter, TokenCounter};
 #[cfg(feature = "temporal_track")]
 use crate::{TemporalTrack, temporal_track_read};
 use crate::{lex::LexIndex, vec::VecIndex};
@@ -61,21 +66,47 @@ pub struct Memvid {
     pub(crate) generation: u64,
     pub(crate) lock_settings: LockSettings,
     pub(crate) lex_enabled: bool,
+    /// Analyzer chain for the Tantivy index, persisted in `IndexManifests::lex_analyzer`.
+    pub(crate) lex_analyzer: LexAnalyzerConfig,
     pub(crate) lex_index: Option<LexIndex>,
     #[cfg(feature = "lex")]
     #[allow(dead_code)]
     pub(crate) lex_storage: Arc<RwLock<EmbeddedLexStorage>>,
     pub(crate) vec_enabled: bool,
     pub(crate) vec_compression: VectorCompression,
+    /// HNSW beam width used by vector search when the index carries a graph.
+    pub(crate) vec_ef_search: usize,
+    /// Distance metric for the text vector index, mirrored in `VecIndexManifest`.
+    pub(crate) vec_metric: DistanceMetric,
     pub(crate) vec_index: Option<VecIndex>,
     /// CLIP visual embeddings index (separate from vec due to different dimensions)
     pub(crate) clip_enabled: bool,
     pub(crate) clip_index: Option<crate::clip::ClipIndex>,
+    /// Distance metric for the CLIP index, mirrored in `ClipIndexManifest`.
+    pub(crate) clip_metric: DistanceMetric,
+    /// Loaded indexes for the named vector spaces in `toc.indexes.vec_spaces`.
+    pub(crate) vec_space_indexes: HashMap<String, VecIndex>,
+    /// Embeddings applied from the WAL but not yet folded into their named space.
+    pub(crate) pending_vec_space_docs: HashMap<String, Vec<(FrameId, Vec<f32>)>>,
+    /// Re-embedding batches appended to the WAL but not yet applied.
+    pub(crate) reembed_tally: ReembedTally,
     pub(crate) dirty: bool,
     #[cfg(feature = "lex")]
     pub(crate) tantivy: Option<TantivyEngine>,
     #[cfg(feature = "lex")]
     pub(crate) tantivy_dirty: bool,
+    /// Score multiplier for title matches, applied to every Tantivy engine we build.
+    #[cfg(feature = "lex")]
+    pub(crate) lex_title_boost: f32,
+    /// Reranker applied by searches and asks that request it.
+    #[cfg(feature = "lex")]
+    pub(crate) reranker: Option<(Arc<dyn Reranker>, RerankerConfig)>,
+    /// Token counter used to budget `ask` context; the approximate counter when unset.
+    #[cfg(feature = "lex")]
+    pub(crate) token_counter: Option<Arc<dyn TokenCounter>>,
+    /// Rewriter supplying reformulations for multi-query asks.
+    #[cfg(feature = "lex")]
+    pub(crate) query_rewriter: Option<Arc<dyn QueryRewriter>>,
     #[cfg(feature = "temporal_track")]
     pub(crate) temporal_track: Option<TemporalTrack>,
     #[cfg(feature = "parallel_segments")]
@@ -104,6 +135,13 @@ pub struct OpenReadOptions {
     pub allow_repair: bool,
 }
 
+/// Settings fixed when a new `.mv2` memory is created.
+#[derive(Debug, Clone, Default)]
+pub struct CreateOptions {
+    /// Analyzer chain for the lexical index; persisted and reused on every open.
+    pub lex_analyzer: LexAnalyzerConfig,
+}
+
 #[derive(Debug, Clone)]
 pub struct LockSettings {
     pub timeout_ms: u64,
@@ -137,6 +175,11 @@ impl Memvid {
     /// Create a new, empty `.mv2` file with an embedded WAL and empty TOC.
     /// The file is locked exclusively for the lifetime of the handle.
     pub fn create<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::create_with_options(path, CreateOptions::default())
+    }
+
+    /// Create a new, empty `.mv2` file with the given creation settings.
+    pub fn create_with_options<P: AsRef<Path>>(path: P, options: CreateOptions) -> Result<Self> {
         let path_ref = path.as_ref();
         ensure_single_file(path_ref)?;
 
@@ -190,19 +233,34 @@ impl Memvid {
             generation: 0,
             lock_settings: LockSettings::default(),
             lex_enabled: cfg!(feature = "lex"), // Enable by default if feature is enabled
+            lex_analyzer: options.lex_analyzer,
             lex_index: None,
             #[cfg(feature = "lex")]
             lex_storage,
             vec_enabled: cfg!(feature = "vec"), // Enable by default if feature is enabled
             vec_compression: VectorCompression::None,
+            vec_ef_search: crate::vec_hnsw::HNSW_DEFAULT_EF_SEARCH,
+            vec_metric: DistanceMetric::default(),
             vec_index: None,
             clip_enabled: cfg!(feature = "clip"), // Enable by default if feature is enabled
             clip_index: None,
+            clip_metric: DistanceMetric::default(),
+            vec_space_indexes: HashMap::new(),
+            pending_vec_space_docs: HashMap::new(),
+            reembed_tally: ReembedTally::default(),
             dirty: false,
             #[cfg(feature = "lex")]
             tantivy: None,
             #[cfg(feature = "lex")]
             tantivy_dirty: false,
+            #[cfg(feature = "lex")]
+            lex_title_boost: crate::search::DEFAULT_TITLE_BOOST,
+            #[cfg(feature = "lex")]
+            reranker: None,
+            #[cfg(feature = "lex")]
+            token_counter: None,
+            #[cfg(feature = "lex")]
+            query_rewriter: None,
             #[cfg(feature = "temporal_track")]
             temporal_track: None,
             #[cfg(feature = "parallel_segments")]
@@ -225,6 +283,9 @@ impl Memvid {
         memvid.load_manifest_segments(manifest_wal_entries);
 
         memvid.bootstrap_segment_catalog();
+        if memvid.lex_analyzer != LexAnalyzerConfig::default() {
+            memvid.toc.indexes.lex_analyzer = Some(memvid.lex_analyzer.clone());
+        }
 
         // Create empty manifests for enabled indexes so they persist across open/close
         let empty_offset = memvid.data_end;
@@ -251,6 +312,7 @@ impl Memvid {
                 bytes_length: 0,
                 checksum: empty_checksum,
                 compression_mode: memvid.vec_compression.clone(),
+                metric: memvid.vec_metric,
             });
         }
 
@@ -261,6 +323,11 @@ impl Memvid {
         Ok(memvid)
     }
 
+    /// Analyzer chain used by the lexical index of this memory.
+    pub fn lex_analyzer(&self) -> &LexAnalyzerConfig {
+        &self.lex_analyzer
+    }
+
     pub fn lock_settings(&self) -> &LockSettings {
         &self.lock_settings
     }
@@ -271,6 +338,10 @@ impl Memvid {
 
     /// Set the vector compression mode for this memory
     /// Must be called before ingesting documents with embeddings
+    ///
+    /// `Int8` and `Binary` re-encode the vector index on the next commit and apply at any
+    /// vector count; `Pq96` only applies once a segment holds enough vectors to train its codebook.
+    /// The mode is recorded in the vec manifest and restored on open.
     pub fn set_vector_compression(&mut self, compression: VectorCompression) {
         self.vec_compression = compression;
     }
@@ -280,6 +351,134 @@ impl Memvid {
         &self.vec_compression
     }
 
+    /// Compression statistics for the loaded vector index, or `None` when it stores
+    /// full-precision vectors.
+    pub fn vector_compression_stats(&mut self) -> Result<Option<crate::vec_pq::CompressionStats>> {
+        self.ensure_vec_index()?;
+        Ok(self
+            .vec_index
+            .as_ref()
+            .and_then(crate::vec::VecIndex::compression_stats))
+    }
+
+    /// Set the distance metric for the text vector index.
+    ///
+    /// The metric is recorded in the manifest when the index is created and can only change
+    /// while the index holds no vectors.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`MemvidError::VecMetricMismatch`] if vectors were already indexed under a
+    /// different metric.
+    pub fn set_vector_metric(&mut self, metric: DistanceMetric) -> Result<()> {
+        if metric == self.vec_metric {
+            return Ok(());
+        }
+        let populated = self
+            .toc
+            .indexes
+            .vec
+            .as_ref()
+            .is_some_and(|manifest| manifest.vector_count > 0)
+            || !self.toc.segment_catalog.vec_segments.is_empty();
+        if populated {
+            return Err(MemvidError::VecMetricMismatch {
+                expected: self.vec_metric,
+                actual: metric,
+            });
+        }
+        self.vec_metric = metric;
+        if let Some(manifest) = self.toc.indexes.vec.as_mut() {
+            manifest.metric = metric;
+            self.dirty = true;
+        }
+        Ok(())
+    }
+
+    /// Get the distance metric of the text vector index
+    #[must_use]
+    pub fn vector_metric(&self) -> DistanceMetric {
+        self.vec_metric
+    }
+
+    /// Set the distance metric for the CLIP index (same rules as [`Self::set_vector_metric`]).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`MemvidError::VecMetricMismatch`] if CLIP embeddings were already indexed
+    /// under a different metric.
+    pub fn set_clip_metric(&mut self, metric: DistanceMetric) -> Result<()> {
+        if metric == self.clip_metric {
+            return Ok(());
+        }
+        let populated = self
+            .toc
+            .indexes
+            .clip
+            .as_ref()
+            .is_some_and(|manifest| manifest.vector_count > 0)
+            || self
+                .clip_index
+                .as_ref()
+                .is_some_and(|index| !index.is_empty());
+        if populated {
+            return Err(MemvidError::VecMetricMismatch {
+                expected: self.clip_metric,
+                actual: metric,
+            });
+        }
+        self.clip_metric = metric;
+        if let Some(manifest) = self.toc.indexes.clip.as_mut() {
+            manifest.metric = metric;
+            self.dirty = true;
+        }
+        Ok(())
+    }
+
+    /// Get the distance metric of the CLIP index
+    #[must_use]
+    pub fn clip_metric(&self) -> DistanceMetric {
+        self.clip_metric
+    }
+
+    /// Restore the metrics and vector compression recorded in the manifests when opening
+    /// an existing file.
+    fn load_index_metrics(&mut self) {
+        self.vec_metric = self
+            .toc
+            .indexes
+            .vec
+            .as_ref()
+            .map(|manifest| manifest.metric)
+            .unwrap_or_default();
+        self.vec_compression = self
+            .toc
+            .indexes
+            .vec
+            .as_ref()
+            .map(|manifest| manifest.compression_mode.clone())
+            .unwrap_or_default();
+        self.clip_metric = self
+            .toc
+            .indexes
+            .clip
+            .as_ref()
+            .map(|manifest| manifest.metric)
+            .unwrap_or_default();
+    }
+
+    /// Set the HNSW `ef_search` beam width used by vector search.
+    /// Higher values improve recall at the cost of latency; ignored for brute-force indexes.
+    pub fn set_vec_ef_search(&mut self, ef_search: usize) {
+        self.vec_ef_search = ef_search.max(1);
+    }
+
+    /// Get the current HNSW `ef_search` beam width
+    #[must_use]
+    pub fn vec_ef_search(&self) -> usize {
+        self.vec_ef_search
+    }
+
     /// Predict the next frame ID that would be assigned to a new insert.
     ///
     /// Frame IDs are dense indices into `toc.frames`. When a memory is mutable, inserts are first
@@ -339,6 +538,7 @@ impl Memvid {
         ensure_non_overlapping_frames(&toc, file_len)?;
 
         let wal = EmbeddedWal::open(&file, &header)?;
+        let lex_analyzer = toc.indexes.lex_analyzer.clone().unwrap_or_default();
         #[cfg(feature = "lex")]
         let lex_storage = Arc::new(RwLock::new(EmbeddedLexStorage::from_manifest(
             toc.indexes.lex.as_ref(),
@@ -365,19 +565,34 @@ impl Memvid {
             generation,
             lock_settings: LockSettings::default(),
             lex_enabled: false,
+            lex_analyzer,
             lex_index: None,
             #[cfg(feature = "lex")]
             lex_storage,
             vec_enabled: false,
             vec_compression: VectorCompression::None,
+            vec_ef_search: crate::vec_hnsw::HNSW_DEFAULT_EF_SEARCH,
+            vec_metric: DistanceMetric::default(),
             vec_index: None,
             clip_enabled: false,
             clip_index: None,
+            clip_metric: DistanceMetric::default(),
+            vec_space_indexes: HashMap::new(),
+            pending_vec_space_docs: HashMap::new(),
+            reembed_tally: ReembedTally::default(),
             dirty: false,
             #[cfg(feature = "lex")]
             tantivy: None,
             #[cfg(feature = "lex")]
             tantivy_dirty: false,
+            #[cfg(feature = "lex")]
+            lex_title_boost: crate::search::DEFAULT_TITLE_BOOST,
+            #[cfg(feature = "lex")]
+            reranker: None,
+            #[cfg(feature = "lex")]
+            token_counter: None,
+            #[cfg(feature = "lex")]
+            query_rewriter: None,
             #[cfg(feature = "temporal_track")]
             temporal_track: None,
             #[cfg(feature = "parallel_segments")]
@@ -404,6 +619,7 @@ impl Memvid {
         }
         memvid.vec_enabled =
             memvid.toc.indexes.vec.is_some() || !memvid.toc.segment_catalog.vec_segments.is_empty();
+        memvid.load_index_metrics();
         if memvid.vec_enabled {
             memvid.load_vec_index_from_manifest()?;
         }
@@ -411,6 +627,7 @@ impl Memvid {
         if memvid.clip_enabled {
             memvid.load_clip_index_from_manifest()?;
         }
+        memvid.load_vec_space_indexes();
         memvid.recover_wal()?;
         #[cfg(feature = "parallel_segments")]
         memvid.load_manifest_segments(manifest_wal_entries);
@@ -474,6 +691,7 @@ impl Memvid {
         let lock = FileLock::acquire_with_mode(&file, LockMode::Shared)?;
         let wal = EmbeddedWal::open_read_only(&file, &header)?;
 
+        let lex_analyzer = toc.indexes.lex_analyzer.clone().unwrap_or_default();
         #[cfg(feature = "lex")]
         let lex_storage = Arc::new(RwLock::new(EmbeddedLexStorage::from_manifest(
             toc.indexes.lex.as_ref(),
@@ -493,19 +711,34 @@ impl Memvid {
             generation,
             lock_settings: LockSettings::default(),
             lex_enabled: false,
+            lex_analyzer,
             lex_index: None,
             #[cfg(feature = "lex")]
             lex_storage,
             vec_enabled: false,
             vec_compression: VectorCompression::None,
+            vec_ef_search: crate::vec_hnsw::HNSW_DEFAULT_EF_SEARCH,
+            vec_metric: DistanceMetric::default(),
             vec_index: None,
             clip_enabled: false,
             clip_index: None,
+            clip_metric: DistanceMetric::default(),
+            vec_space_indexes: HashMap::new(),
+            pending_vec_space_docs: HashMap::new(),
+            reembed_tally: ReembedTally::default(),
             dirty: false,
             #[cfg(feature = "lex")]
             tantivy: None,
             #[cfg(feature = "lex")]
             tantivy_dirty: false,
+            #[cfg(feature = "lex")]
+            lex_title_boost: crate::search::DEFAULT_TITLE_BOOST,
+            #[cfg(feature = "lex")]
+            reranker: None,
+            #[cfg(feature = "lex")]
+            token_counter: None,
+            #[cfg(feature = "lex")]
+            query_rewriter: None,
             #[cfg(feature = "temporal_track")]
             temporal_track: None,
             #[cfg(feature = "parallel_segments")]
@@ -531,6 +764,7 @@ impl Memvid {
 
         memvid.vec_enabled =
             memvid.toc.indexes.vec.is_some() || !memvid.toc.segment_catalog.vec_segments.is_empty();
+        memvid.load_index_metrics();
         if memvid.vec_enabled {
             memvid.load_vec_index_from_manifest()?;
         }
@@ -538,6 +772,7 @@ impl Memvid {
         if memvid.clip_enabled {
             memvid.load_clip_index_from_manifest()?;
         }
+        memvid.load_vec_space_indexes();
         // Load memories track, Logic-Mesh, and sketch track if present
         memvid.load_memories_track()?;
         memvid.load_logic_mesh()?;
@@ -1113,6 +1348,7 @@ pub(crate) fn empty_toc() -> Toc {
         memory_binding: None,
         replay_manifest: None,
         enrichment_queue: crate::types::EnrichmentQueueManifest::default(),
+        vacuum_generation: 0,
         merkle_root: [0u8; 32],
         toc_checksum: [0u8; 32],
     }
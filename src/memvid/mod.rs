@@ -4,7 +4,10 @@ pub mod ask;
 pub mod audit;
 #[cfg(feature = "parallel_segments")]
 pub mod builder;
+mod chunk_window;
 pub mod chunks;
+#[cfg(feature = "lex")]
+mod context_budget;
 pub mod doctor;
 pub mod enrichment;
 pub mod frame;
@@ -16,6 +19,7 @@ pub mod mesh;
 pub mod mutation;
 #[cfg(feature = "parallel_segments")]
 pub mod planner;
+pub mod reembed;
 #[cfg(feature = "replay")]
 pub mod replay_ops;
 pub mod search;
@@ -23,6 +27,7 @@ mod segments;
 pub mod sketch;
 pub mod ticket;
 pub mod timeline;
+mod vec_spaces;
 #[cfg(feature = "parallel_segments")]
 pub mod workers;
 
@@ -33,5 +38,6 @@ pub use enrichment::{
     start_enrichment_worker_with_embeddings,
 };
 pub use frame::BlobReader;
-pub use lifecycle::{LockSettings, Memvid, OpenReadOptions};
+pub use lifecycle::{CreateOptions, LockSettings, Memvid, OpenReadOptions};
+pub use reembed::ReembedProgress;
 pub use sketch::{SketchCandidate, SketchSearchOptions, SketchSearchStats};
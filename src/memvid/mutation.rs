@@ -44,6 +44,7 @@ use crate::footer::CommitFooter;
 use crate::io::wal::{EmbeddedWal, WalRecord};
 use crate::memvid::chunks::{plan_document_chunks, plan_text_chunks};
 use crate::memvid::lifecycle::{Memvid, prepare_toc_bytes};
+use crate::memvid::reembed::ReembedWalBatch;
 use crate::reader::{
     DocumentFormat, DocumentReader, PassthroughReader, ReaderDiagnostics, ReaderHint, ReaderOutput,
     ReaderRegistry,
@@ -719,6 +720,11 @@ impl Memvid {
                 vec.bytes_offset += delta;
             }
         }
+        for space in &mut self.toc.indexes.vec_spaces {
+            if space.index.bytes_offset != 0 {
+                space.index.bytes_offset += delta;
+            }
+        }
         if let Some(time_index) = self.toc.time_index.as_mut() {
             if time_index.bytes_offset != 0 {
                 time_index.bytes_offset += delta;
@@ -819,6 +825,11 @@ impl Memvid {
             }
         }
 
+        // Persist named vector spaces if they have staged embeddings and weren't rebuilt
+        if !indexes_rebuilt {
+            self.persist_vec_spaces()?;
+        }
+
         // Persist memories track if it has cards and wasn't already persisted by rebuild_indexes
         if !indexes_rebuilt && self.memories_track.card_count() > 0 {
             self.persist_memories_track()?;
@@ -879,8 +890,10 @@ impl Memvid {
                 inserted_time_entries = delta.inserted_time_entries.len(),
                 "parallel commit applied delta"
             );
-            // Try to use parallel segment builder first
-            let used_parallel = self.publish_parallel_delta(&delta, opts)?;
+            // Try to use parallel segment builder first. A finished re-embedding job takes
+            // the serial rebuild, which swaps in and persists the new default index.
+            let used_parallel =
+                !self.reembed_ready() && self.publish_parallel_delta(&delta, opts)?;
             tracing::info!(
                 "parallel_commit: used_parallel={}, lex_enabled={}",
                 used_parallel,
@@ -1021,6 +1034,9 @@ impl Memvid {
             }
         }
 
+        // Persist named vector spaces with staged embeddings
+        self.persist_vec_spaces()?;
+
         // Persist memories track if it has cards
         if self.memories_track.card_count() > 0 {
             self.persist_memories_track()?;
@@ -1062,7 +1078,10 @@ impl Memvid {
             return Ok(());
         }
         let delta = self.apply_records(records)?;
-        if !delta.is_empty() {
+        // Replayed named-space embeddings (e.g. re-embedding batches) must reach disk
+        // before the WAL is checkpointed.
+        let rebuild = !delta.is_empty() || self.vec_spaces_pending();
+        if rebuild {
             tracing::debug!(
                 inserted_frames = delta.inserted_frames.len(),
                 inserted_embeddings = delta.inserted_embeddings.len(),
@@ -1075,7 +1094,7 @@ impl Memvid {
         }
         self.wal.record_checkpoint(&mut self.header)?;
         crate::persist_header(&mut self.file, &self.header)?;
-        if !delta.is_empty() {
+        if rebuild {
             // rebuild_indexes already flushed Tantivy, so nothing further to do.
         } else if self.tantivy_index_pending() {
             self.flush_tantivy()?;
@@ -1099,6 +1118,7 @@ impl Memvid {
         // all data including index segments.
         let mut data_cursor = self.data_end;
         let mut sequence_to_frame: HashMap<u64, FrameId> = HashMap::new();
+        let mut reembed_applied = false;
 
         if !records.is_empty() {
             self.file.seek(SeekFrom::Start(data_cursor))?;
@@ -1110,6 +1130,13 @@ impl Memvid {
                         self.apply_lex_wal(batch)?;
                         continue;
                     }
+                    #[cfg(not(feature = "lex"))]
+                    WalEntry::Lex => continue,
+                    WalEntry::Reembed(batch) => {
+                        self.apply_reembed_wal(batch);
+                        reembed_applied = true;
+                        continue;
+                    }
                 };
 
                 match entry.op {
@@ -1314,9 +1341,16 @@ impl Memvid {
                         }
 
                         if let Some(embedding) = entry.embedding.take() {
-                            delta
-                                .inserted_embeddings
-                                .push((frame_id, embedding.clone()));
+                            if let Some(space) = entry.vec_space.take() {
+                                self.pending_vec_space_docs
+                                    .entry(space)
+                                    .or_default()
+                                    .push((frame_id, embedding));
+                            } else {
+                                delta
+                                    .inserted_embeddings
+                                    .push((frame_id, embedding.clone()));
+                            }
                         }
 
                         if entry.role == FrameRole::Document {
@@ -1399,6 +1433,12 @@ impl Memvid {
             }
         }
 
+        // A finished re-embedding job is swapped in by rebuild_indexes, which also rewrites
+        // the embedding identity of every frame.
+        if reembed_applied && self.reembed_ready() {
+            delta.mutated_frames = true;
+        }
+
         // Index rebuild now happens once per commit (Option A) instead of incremental append.
         // See commit_from_records() for where rebuild_indexes() is invoked.
         Ok(delta)
@@ -1770,6 +1810,7 @@ impl Memvid {
                 bytes_length: 0,
                 checksum: empty_checksum,
                 compression_mode: self.vec_compression.clone(),
+                metric: self.vec_metric,
             });
         }
         if let Some(manifest) = self.toc.indexes.vec.as_mut() {
@@ -1904,6 +1945,14 @@ impl Memvid {
             return Ok(());
         }
 
+        // A finished re-embedding job replaces the default vector index before it is
+        // rebuilt below.
+        self.swap_reembed_index()?;
+
+        // Named spaces are rebuilt up front: their current bytes live in the region
+        // rewritten below.
+        let vec_space_artifacts = self.build_vec_space_artifacts()?;
+
         let payload_end = self.payload_region_end();
         self.data_end = payload_end;
         // Don't truncate if footer_offset is higher - there may be replay segments
@@ -2009,17 +2058,25 @@ impl Memvid {
         }
 
         if let Some((artifact, index)) = self.build_vec_artifact(new_vec_docs)? {
-            let vec_offset = footer_offset;
-            self.file.seek(SeekFrom::Start(vec_offset))?;
+            // Aligned so read-only opens can borrow the vectors straight from a map.
+            let padding = crate::vec_mmap::alignment_padding(footer_offset);
+            self.file.seek(SeekFrom::Start(footer_offset))?;
+            self.file.write_all(padding)?;
+            let vec_offset = footer_offset + padding.len() as u64;
             self.file.write_all(&artifact.bytes)?;
-            footer_offset += artifact.bytes.len() as u64;
+            footer_offset = vec_offset + artifact.bytes.len() as u64;
             self.toc.indexes.vec = Some(VecIndexManifest {
                 vector_count: artifact.vector_count,
                 dimension: artifact.dimension,
                 bytes_offset: vec_offset,
                 bytes_length: artifact.bytes.len() as u64,
                 checksum: artifact.checksum,
-                compression_mode: self.vec_compression.clone(),
+                // Full-precision indexes keep the requested mode (PQ needs enough vectors).
+                compression_mode: match index.compression() {
+                    crate::types::VectorCompression::None => self.vec_compression.clone(),
+                    encoded => encoded,
+                },
+                metric: self.vec_metric,
             });
             self.vec_index = Some(index);
         } else {
@@ -2046,6 +2103,7 @@ impl Memvid {
                         dimension: artifact.dimension,
                         checksum: artifact.checksum,
                         model_name: crate::clip::default_model_info().name.to_string(),
+                        metric: self.clip_metric,
                     });
                     tracing::info!(
                         "rebuild_indexes: persisted CLIP index with {} vectors at offset {}",
@@ -2058,6 +2116,8 @@ impl Memvid {
             self.toc.indexes.clip = None;
         }
 
+        footer_offset = self.write_vec_space_artifacts(vec_space_artifacts, footer_offset)?;
+
         // Persist memories track if it has cards
         if self.memories_track.card_count() > 0 {
             let memories_offset = footer_offset;
@@ -2238,6 +2298,7 @@ impl Memvid {
             dimension: artifact.dimension,
             checksum: artifact.checksum,
             model_name: crate::clip::default_model_info().name.to_string(),
+            metric: self.clip_metric,
         });
 
         tracing::info!(
@@ -2299,7 +2360,7 @@ impl Memvid {
     ///
     /// This is used when the sketch track has been modified (e.g., after
     /// running `sketch build`).
-    fn persist_sketch_track(&mut self) -> Result<()> {
+    pub(crate) fn persist_sketch_track(&mut self) -> Result<()> {
         if self.sketch_track.is_empty() {
             self.toc.sketch_track = None;
             return Ok(());
@@ -2357,6 +2418,12 @@ impl Memvid {
         result
     }
 
+    pub(crate) fn append_reembed_batch(&mut self, batch: &ReembedWalBatch) -> Result<()> {
+        let payload = encode_to_vec(WalEntry::Reembed(batch.clone()), wal_config())?;
+        self.append_wal_entry(&payload)?;
+        Ok(())
+    }
+
     #[cfg(feature = "lex")]
     fn append_lex_batch(&mut self, batch: &LexWalBatch) -> Result<()> {
         let payload = encode_to_vec(&WalEntry::Lex(batch.clone()), wal_config())?;
@@ -2503,6 +2570,9 @@ impl Memvid {
         if let Some(index) = self.vec_index.as_mut() {
             index.remove(frame_id);
         }
+        for index in self.vec_space_indexes.values_mut() {
+            index.remove(frame_id);
+        }
         Ok(())
     }
 
@@ -2796,6 +2866,8 @@ impl Memvid {
         }
 
         self.data_end = cursor;
+        // Rebuilt indexes rescore every frame, so earlier search cursors no longer line up.
+        self.toc.vacuum_generation = self.toc.vacuum_generation.wrapping_add(1);
 
         self.toc.segments.clear();
         self.toc.indexes.lex_segments.clear();
@@ -3032,6 +3104,7 @@ impl Memvid {
             source_sha256: None,
             source_path: None,
             enrichment_state: crate::types::EnrichmentState::default(),
+            vec_space: None,
         };
         tombstone.kind = frame.kind.clone();
         tombstone.track = frame.track.clone();
@@ -3119,7 +3192,17 @@ impl Memvid {
             dim
         };
 
-        if let Some(incoming_dimension) = incoming_dimension {
+        if let Some(space) = options.vec_space.as_deref() {
+            let expected = self.vec_space_manifest(space)?.index.dimension;
+            if let Some(incoming_dimension) = incoming_dimension {
+                if incoming_dimension != expected {
+                    return Err(MemvidError::VecDimensionMismatch {
+                        expected,
+                        actual: incoming_dimension as usize,
+                    });
+                }
+            }
+        } else if let Some(incoming_dimension) = incoming_dimension {
             // Embeddings imply vector search should be enabled.
             if !self.vec_enabled {
                 self.enable_vec()?;
@@ -3489,6 +3572,7 @@ impl Memvid {
                     source_path: None,
                     // Chunks are already extracted, so mark as Enriched
                     enrichment_state: crate::types::EnrichmentState::Enriched,
+                    vec_space: options.vec_space.clone(),
                 });
             }
         }
@@ -3569,6 +3653,7 @@ impl Memvid {
             source_sha256,
             source_path: source_path_value,
             enrichment_state,
+            vec_space: options.vec_space.clone(),
         };
 
         let parent_bytes = encode_to_vec(&WalEntry::Frame(entry), wal_config())?;
@@ -3721,6 +3806,11 @@ enum WalEntry {
     Frame(WalEntryData),
     #[cfg(feature = "lex")]
     Lex(LexWalBatch),
+    /// Keeps the `Lex` tag reserved without the lex feature so that later variants
+    /// encode with the same discriminant in every build.
+    #[cfg(not(feature = "lex"))]
+    Lex,
+    Reembed(ReembedWalBatch),
 }
 
 fn decode_wal_entry(bytes: &[u8]) -> Result<WalEntry> {
@@ -3785,6 +3875,9 @@ pub(crate) struct WalEntryData {
     /// Enrichment state for progressive ingestion.
     #[serde(default)]
     pub(crate) enrichment_state: crate::types::EnrichmentState,
+    /// Named vector space for `embedding` (`None` is the default index).
+    #[serde(default)]
+    pub(crate) vec_space: Option<String>,
 }
 
 pub(crate) fn prepare_canonical_payload(
@@ -0,0 +1,399 @@
+//! Re-embedding jobs: migrate a memory to a new embedding model without downtime.
+//!
+//! A job walks every frame in id order, embeds its search text with the new provider and
+//! stages the vectors in a dedicated named vector space, so the default index keeps
+//! serving queries meanwhile. Each batch is appended to the WAL before it is reported
+//! done, so a crash loses no finished work: recovery replays the batches and the job
+//! resumes from the recorded cursor. Once every frame has been visited, the next commit
+//! swaps the staged space in as the default vector index.
+
+use std::collections::HashSet;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::memvid::lifecycle::Memvid;
+use crate::types::{
+    DistanceMetric, EmbeddingIdentity, EmbeddingProvider, FrameId, FrameRole, FrameStatus,
+    ReembedJobManifest,
+};
+use crate::vec::{VecIndex, VecIndexBuilder};
+use crate::vec_quant::ScalarKind;
+use crate::{MemvidError, Result};
+
+/// Snapshot of a running re-embedding job.
+#[derive(Debug, Clone)]
+pub struct ReembedProgress {
+    /// Named vector space staging the new vectors.
+    pub space: String,
+    /// Identity of the provider producing the new vectors.
+    pub identity: EmbeddingIdentity,
+    /// Next frame to visit.
+    pub next_frame_id: FrameId,
+    /// Frames in the memory when the snapshot was taken.
+    pub total_frames: u64,
+    /// Frames embedded so far.
+    pub embedded_frames: u64,
+    /// Frames skipped so far (deleted, images or without text).
+    pub skipped_frames: u64,
+    /// Batches appended to the WAL but not yet committed.
+    pub uncommitted_batches: usize,
+}
+
+impl ReembedProgress {
+    /// True once every frame has been visited; the next commit swaps the new index in.
+    #[must_use]
+    pub fn is_complete(&self) -> bool {
+        self.next_frame_id >= self.total_frames
+    }
+}
+
+/// Work appended to the WAL but not yet applied to the job manifest.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct ReembedTally {
+    next_frame_id: FrameId,
+    embedded_frames: u64,
+    skipped_frames: u64,
+    batches: usize,
+}
+
+/// WAL payload for one re-embedding batch.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct ReembedWalBatch {
+    pub(crate) next_frame_id: FrameId,
+    pub(crate) skipped_frames: u64,
+    pub(crate) timestamp: i64,
+    pub(crate) embeddings: Vec<(FrameId, Vec<f32>)>,
+}
+
+fn now_secs() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| i64::try_from(d.as_secs()).unwrap_or(i64::MAX))
+        .unwrap_or(0)
+}
+
+impl Memvid {
+    /// Start migrating the memory to the model behind `provider`.
+    ///
+    /// New vectors are staged in the space `reembed:<model>`, created with the provider's
+    /// dimension and `metric`. The job is recorded in the TOC on the next commit; drive it
+    /// with [`Self::run_reembed_batch`] or [`Self::run_reembed`].
+    pub fn start_reembed<P>(
+        &mut self,
+        provider: &P,
+        metric: DistanceMetric,
+    ) -> Result<ReembedProgress>
+    where
+        P: EmbeddingProvider + ?Sized,
+    {
+        self.ensure_writable()?;
+        if self.toc.indexes.reembed.is_some() {
+            return Err(MemvidError::ReembedJob {
+                reason: "a re-embedding job is already running",
+            });
+        }
+        let identity = EmbeddingIdentity::from_provider(provider);
+        let space = format!("reembed:{}", provider.model().trim());
+        if self.toc.indexes.vec_space(&space).is_some() {
+            return Err(MemvidError::InvalidVecSpace {
+                name: space,
+                reason: "staging space already exists",
+            });
+        }
+        self.create_vec_space(
+            &space,
+            identity.dimension.unwrap_or(0),
+            metric,
+            Some(identity.clone()),
+        )?;
+
+        let now = now_secs();
+        let job = ReembedJobManifest {
+            space,
+            identity,
+            next_frame_id: 0,
+            embedded_frames: 0,
+            skipped_frames: 0,
+            started_at: now,
+            updated_at: now,
+        };
+        let progress = self.reembed_progress_for(&job);
+        self.toc.indexes.reembed = Some(job);
+        self.reembed_tally = ReembedTally::default();
+        self.dirty = true;
+        Ok(progress)
+    }
+
+    /// Progress of the running re-embedding job, including uncommitted batches.
+    #[must_use]
+    pub fn reembed_progress(&self) -> Option<ReembedProgress> {
+        self.toc
+            .indexes
+            .reembed
+            .as_ref()
+            .map(|job| self.reembed_progress_for(job))
+    }
+
+    /// Embed up to `batch_size` more frames and append them to the WAL.
+    ///
+    /// Frames that are deleted, extracted images or have no text are skipped. A batch is
+    /// durable once this returns; commit to fold it into the staging space. `provider`
+    /// must be the one the job was started with.
+    pub fn run_reembed_batch<P>(
+        &mut self,
+        provider: &P,
+        batch_size: usize,
+    ) -> Result<ReembedProgress>
+    where
+        P: EmbeddingProvider + ?Sized,
+    {
+        self.ensure_writable()?;
+        let job = self
+            .toc
+            .indexes
+            .reembed
+            .clone()
+            .ok_or(MemvidError::ReembedJob {
+                reason: "no re-embedding job is running",
+            })?;
+        if EmbeddingIdentity::from_provider(provider) != job.identity {
+            return Err(MemvidError::ReembedJob {
+                reason: "provider does not match the running job",
+            });
+        }
+        let dimension = self.vec_space_manifest(&job.space)?.index.dimension;
+
+        let total = self.toc.frames.len() as u64;
+        let mut cursor = self.reembed_tally.next_frame_id.max(job.next_frame_id);
+        let mut skipped = 0u64;
+        let mut pending: Vec<(FrameId, String)> = Vec::new();
+        while cursor < total && pending.len() < batch_size.max(1) {
+            let Some(frame) = usize::try_from(cursor)
+                .ok()
+                .and_then(|index| self.toc.frames.get(index))
+                .cloned()
+            else {
+                break;
+            };
+            cursor += 1;
+            if frame.status != FrameStatus::Active || frame.role == FrameRole::ExtractedImage {
+                skipped += 1;
+                continue;
+            }
+            match self.frame_search_text(&frame) {
+                Ok(text) if !text.trim().is_empty() => pending.push((frame.id, text)),
+                Ok(_) => skipped += 1,
+                Err(err) => {
+                    tracing::warn!(
+                        frame_id = frame.id,
+                        "skipping frame during re-embedding: {err}"
+                    );
+                    skipped += 1;
+                }
+            }
+        }
+
+        let texts: Vec<&str> = pending.iter().map(|(_, text)| text.as_str()).collect();
+        let vectors = if texts.is_empty() {
+            Vec::new()
+        } else {
+            provider.embed_batch(&texts)?
+        };
+        if vectors.len() != pending.len() {
+            return Err(MemvidError::EmbeddingFailed {
+                reason: "provider returned a different number of embeddings than inputs".into(),
+            });
+        }
+        if let Some(vector) = vectors
+            .iter()
+            .find(|vector| u32::try_from(vector.len()).ok() != Some(dimension))
+        {
+            return Err(MemvidError::VecDimensionMismatch {
+                expected: dimension,
+                actual: vector.len(),
+            });
+        }
+
+        let batch = ReembedWalBatch {
+            next_frame_id: cursor,
+            skipped_frames: skipped,
+            timestamp: now_secs(),
+            embeddings: pending
+                .into_iter()
+                .map(|(frame_id, _)| frame_id)
+                .zip(vectors)
+                .collect(),
+        };
+        self.append_reembed_batch(&batch)?;
+        self.reembed_tally.next_frame_id = cursor;
+        self.reembed_tally.embedded_frames += batch.embeddings.len() as u64;
+        self.reembed_tally.skipped_frames += skipped;
+        self.reembed_tally.batches += 1;
+        self.dirty = true;
+        Ok(self.reembed_progress_for(&job))
+    }
+
+    /// Run the job until every frame has been visited, then commit to swap the new index
+    /// in. Returns the progress of the finished job.
+    ///
+    /// All batches stay in the WAL until the final commit; callers that want periodic
+    /// checkpoints can interleave [`Self::run_reembed_batch`] with their own commits.
+    pub fn run_reembed<P>(&mut self, provider: &P, batch_size: usize) -> Result<ReembedProgress>
+    where
+        P: EmbeddingProvider + ?Sized,
+    {
+        let mut progress = self.run_reembed_batch(provider, batch_size)?;
+        while !progress.is_complete() {
+            progress = self.run_reembed_batch(provider, batch_size)?;
+        }
+        self.commit()?;
+        Ok(progress)
+    }
+
+    /// Abandon the running job and drop its staging space. The default index is untouched.
+    pub fn cancel_reembed(&mut self) -> Result<()> {
+        self.ensure_writable()?;
+        let job = self
+            .toc
+            .indexes
+            .reembed
+            .take()
+            .ok_or(MemvidError::ReembedJob {
+                reason: "no re-embedding job is running",
+            })?;
+        self.toc
+            .indexes
+            .vec_spaces
+            .retain(|space| space.name != job.space);
+        self.vec_space_indexes.remove(&job.space);
+        self.pending_vec_space_docs.remove(&job.space);
+        self.reembed_tally = ReembedTally::default();
+        self.dirty = true;
+        Ok(())
+    }
+
+    fn reembed_progress_for(&self, job: &ReembedJobManifest) -> ReembedProgress {
+        let tally = self.reembed_tally;
+        ReembedProgress {
+            space: job.space.clone(),
+            identity: job.identity.clone(),
+            next_frame_id: tally.next_frame_id.max(job.next_frame_id),
+            total_frames: self.toc.frames.len() as u64,
+            embedded_frames: job.embedded_frames + tally.embedded_frames,
+            skipped_frames: job.skipped_frames + tally.skipped_frames,
+            uncommitted_batches: tally.batches,
+        }
+    }
+
+    /// Apply a batch replayed from the WAL: advance the job and stage its vectors.
+    /// Batches for a cancelled job are ignored.
+    pub(crate) fn apply_reembed_wal(&mut self, batch: ReembedWalBatch) {
+        let Some(job) = self.toc.indexes.reembed.as_mut() else {
+            return;
+        };
+        let embedded = batch.embeddings.len() as u64;
+        job.next_frame_id = job.next_frame_id.max(batch.next_frame_id);
+        job.embedded_frames += embedded;
+        job.skipped_frames += batch.skipped_frames;
+        job.updated_at = batch.timestamp;
+
+        let tally = &mut self.reembed_tally;
+        tally.embedded_frames = tally.embedded_frames.saturating_sub(embedded);
+        tally.skipped_frames = tally.skipped_frames.saturating_sub(batch.skipped_frames);
+        tally.batches = tally.batches.saturating_sub(1);
+
+        self.pending_vec_space_docs
+            .entry(job.space.clone())
+            .or_default()
+            .extend(batch.embeddings);
+    }
+
+    /// True when the running job has visited every frame.
+    pub(crate) fn reembed_ready(&self) -> bool {
+        self.toc
+            .indexes
+            .reembed
+            .as_ref()
+            .is_some_and(|job| job.next_frame_id >= self.toc.frames.len() as u64)
+    }
+
+    /// Replace the default vector index with the finished job's staging space.
+    ///
+    /// Called at the start of `rebuild_indexes`, which then persists the swapped index.
+    /// Frames embedded by the job get the new identity in their `extra_metadata`; the
+    /// rest lose any stale identity since their old vectors are dropped.
+    pub(crate) fn swap_reembed_index(&mut self) -> Result<()> {
+        if !self.reembed_ready() {
+            return Ok(());
+        }
+        let Some(job) = self.toc.indexes.reembed.take() else {
+            return Ok(());
+        };
+        self.reembed_tally = ReembedTally::default();
+        let Some(position) = self
+            .toc
+            .indexes
+            .vec_spaces
+            .iter()
+            .position(|space| space.name == job.space)
+        else {
+            tracing::warn!(space = %job.space, "re-embedding staging space missing; job dropped");
+            return Ok(());
+        };
+
+        self.ensure_vec_space_index(&job.space)?;
+        let manifest = self.toc.indexes.vec_spaces.remove(position);
+        let metric = manifest.index.metric;
+        let staged = self.vec_space_indexes.remove(&job.space);
+        let pending = self
+            .pending_vec_space_docs
+            .remove(&job.space)
+            .unwrap_or_default();
+        // The new vectors take the default index's configured encoding.
+        let (artifact, index) =
+            if let Some(kind) = ScalarKind::from_compression(&self.vec_compression) {
+                VecIndex::build_scalar_quantized(
+                    staged.as_ref(),
+                    kind,
+                    |frame_id| self.frame_is_active(frame_id),
+                    &pending,
+                    metric,
+                )?
+            } else {
+                let mut builder = match staged.as_ref() {
+                    Some(index) => VecIndexBuilder::from_index(index, |frame_id| {
+                        self.frame_is_active(frame_id)
+                    }),
+                    None => VecIndexBuilder::new(),
+                }
+                .with_metric(metric);
+                for (frame_id, embedding) in pending {
+                    builder.add_document(frame_id, embedding);
+                }
+                let artifact = builder.finish()?;
+                let index = VecIndex::decode(&artifact.bytes)?.with_metric(metric);
+                (artifact, index)
+            };
+
+        let embedded: HashSet<FrameId> = index.entries().map(|(frame_id, _)| frame_id).collect();
+        for frame in &mut self.toc.frames {
+            if embedded.contains(&frame.id) {
+                job.identity.write_extra_metadata(&mut frame.extra_metadata);
+            } else {
+                EmbeddingIdentity::clear_extra_metadata(&mut frame.extra_metadata);
+            }
+        }
+
+        tracing::info!(
+            space = %job.space,
+            vectors = artifact.vector_count,
+            "re-embedding complete; swapping in new vector index"
+        );
+        self.vec_index = Some(index);
+        self.vec_metric = metric;
+        self.vec_enabled = true;
+        self.toc.segment_catalog.vec_segments.clear();
+        Ok(())
+    }
+}
@@ -7,11 +7,13 @@ use std::io::{Read, Seek, SeekFrom, Write};
 #[cfg(feature = "lex")]
 use tempfile::TempDir;
 
+use std::collections::HashSet;
+
 use crate::memvid::lifecycle::Memvid;
 use crate::types::{
     AdaptiveConfig, AdaptiveResult, AdaptiveStats, EmbeddingQualityStats, Frame, FrameId,
-    FrameStatus, SearchHit, TimelineEntry, TimelineQuery, VecSegmentDescriptor,
-    compute_embedding_quality, find_adaptive_cutoff,
+    FrameStatus, SearchHit, TimelineEntry, TimelinePage, TimelineQuery, VecSearchFilter,
+    VecSegmentDescriptor, compute_embedding_quality, find_adaptive_cutoff,
 };
 use crate::{LexSearchHit, MemvidError, Result, VecSearchHit};
 
@@ -87,6 +89,7 @@ impl Memvid {
                 bytes_length: 0,
                 checksum: empty_checksum,
                 compression_mode: self.vec_compression.clone(),
+                metric: self.vec_metric,
             });
         }
 
@@ -94,10 +97,50 @@ impl Memvid {
         Ok(())
     }
 
+    /// Nearest-neighbour search over the vector index.
+    ///
+    /// Graph-backed indexes use the beam width from [`Self::set_vec_ef_search`].
     pub fn search_vec(&mut self, query: &[f32], limit: usize) -> Result<Vec<VecSearchHit>> {
+        self.search_vec_filtered(query, limit, &VecSearchFilter::default())
+    }
+
+    /// Nearest-neighbour search restricted to frames matching `filter`.
+    ///
+    /// The filter is evaluated while the index is scanned, so up to `limit` matching
+    /// hits are returned even when most of the nearest neighbours are out of scope.
+    pub fn search_vec_filtered(
+        &mut self,
+        query: &[f32],
+        limit: usize,
+        filter: &VecSearchFilter,
+    ) -> Result<Vec<VecSearchHit>> {
         if !self.vec_enabled {
             return Err(MemvidError::VecNotEnabled);
         }
+        let space = filter.space.as_deref();
+        self.prepare_vec_search(space, query.len())?;
+        let allowed = self.vec_filter_allowed_ids(filter)?;
+        let index = self.vec_search_index(space)?;
+        let accept = |frame_id| self.vec_filter_accepts(filter, allowed.as_ref(), frame_id);
+        Ok(index.search_filtered(query, limit, self.vec_ef_search, &accept))
+    }
+
+    /// Validate the query dimension against the selected index (a named space, or the
+    /// default index when `space` is `None`) and make sure that index is loaded.
+    fn prepare_vec_search(&mut self, space: Option<&str>, query_len: usize) -> Result<()> {
+        if let Some(space) = space {
+            let expected = self.vec_space_manifest(space)?.index.dimension;
+            if u32::try_from(query_len).ok() != Some(expected) {
+                return Err(MemvidError::VecDimensionMismatch {
+                    expected,
+                    actual: query_len,
+                });
+            }
+            return self.ensure_vec_space_index(space);
+        }
+
+        // Validate embedding dimension BEFORE searching to prevent silent wrong results.
+        // For segment-only memories, dimension may only be discoverable after loading segments.
         let mut ensured_vec_index = false;
         let expected_dim = match self.effective_vec_index_dimension()? {
             Some(dim) => dim,
@@ -110,18 +153,64 @@ impl Memvid {
                     .unwrap_or(0)
             }
         };
-        if expected_dim > 0 && query.len() as u32 != expected_dim {
+        if expected_dim > 0 && query_len as u32 != expected_dim {
             return Err(MemvidError::VecDimensionMismatch {
                 expected: expected_dim,
-                actual: query.len(),
+                actual: query_len,
             });
         }
 
         if !ensured_vec_index {
             self.ensure_vec_index()?;
         }
-        let index = self.vec_index.as_ref().ok_or(MemvidError::VecNotEnabled)?;
-        Ok(index.search(query, limit))
+        Ok(())
+    }
+
+    fn vec_search_index(&self, space: Option<&str>) -> Result<&crate::vec::VecIndex> {
+        match space {
+            Some(space) => {
+                self.vec_space_indexes
+                    .get(space)
+                    .ok_or_else(|| MemvidError::VecSpaceNotFound {
+                        name: space.to_string(),
+                    })
+            }
+            None => self.vec_index.as_ref().ok_or(MemvidError::VecNotEnabled),
+        }
+    }
+
+    /// Resolve the parts of a vector filter that need more than the frame record
+    /// (currently the temporal track) into an allow-list of frame ids.
+    pub(super) fn vec_filter_allowed_ids(
+        &mut self,
+        filter: &VecSearchFilter,
+    ) -> Result<Option<HashSet<FrameId>>> {
+        #[cfg(feature = "temporal_track")]
+        if let Some(temporal) = filter.temporal.as_ref() {
+            let ids = super::frame_ids_for_temporal_filter(self, temporal)?;
+            return Ok(ids.map(|ids| ids.into_iter().collect()));
+        }
+        #[cfg(not(feature = "temporal_track"))]
+        let _ = filter;
+        Ok(None)
+    }
+
+    pub(super) fn vec_filter_accepts(
+        &self,
+        filter: &VecSearchFilter,
+        allowed: Option<&HashSet<FrameId>>,
+        frame_id: FrameId,
+    ) -> bool {
+        if allowed.is_some_and(|ids| !ids.contains(&frame_id)) {
+            return false;
+        }
+        usize::try_from(frame_id)
+            .ok()
+            .and_then(|idx| self.toc.frames.get(idx))
+            .is_some_and(|frame| {
+                frame.status == FrameStatus::Active
+                    && (filter.is_empty() || filter.matches_frame(frame))
+            })
     }
 
     /// Enable CLIP visual embeddings index.
@@ -147,6 +236,7 @@ impl Memvid {
                 dimension: crate::clip::MOBILECLIP_DIMS,
                 checksum: empty_checksum,
                 model_name: "mobileclip-s2".to_string(),
+                metric: self.clip_metric,
             });
         }
 
@@ -177,7 +267,7 @@ impl Memvid {
 
         // Initialize clip index if needed
         if self.clip_index.is_none() {
-            self.clip_index = Some(crate::clip::ClipIndex::new());
+            self.clip_index = Some(crate::clip::ClipIndex::new().with_metric(self.clip_metric));
         }
 
         // Add the document to the index
@@ -228,13 +318,15 @@ impl Memvid {
 
     /// Perform pure vector search using a pre-computed query embedding.
     /// This searches the entire vector index directly, like Chroma does.
+    ///
+    /// `filter` is applied during the index scan rather than to the returned hits.
     pub fn vec_search_with_embedding(
         &mut self,
         query: &str,
         query_embedding: &[f32],
         top_k: usize,
         snippet_chars: usize,
-        scope: Option<&str>,
+        filter: &VecSearchFilter,
     ) -> Result<crate::types::SearchResponse> {
         use super::helpers::{build_context, timestamp_to_rfc3339};
         use crate::types::{
@@ -246,38 +338,18 @@ impl Memvid {
             return Err(MemvidError::VecNotEnabled);
         }
 
-        // Validate embedding dimension BEFORE searching to prevent silent wrong results.
-        // For segment-only memories, dimension may only be discoverable after loading segments.
-        let mut ensured_vec_index = false;
-        let expected_dim = match self.effective_vec_index_dimension()? {
-            Some(dim) => dim,
-            None => {
-                self.ensure_vec_index()?;
-                ensured_vec_index = true;
-                self.vec_index
-                    .as_ref()
-                    .and_then(|index| index.entries().next().map(|(_, emb)| emb.len() as u32))
-                    .unwrap_or(0)
-            }
-        };
-        if expected_dim > 0 && query_embedding.len() as u32 != expected_dim {
-            return Err(MemvidError::VecDimensionMismatch {
-                expected: expected_dim,
-                actual: query_embedding.len(),
-            });
-        }
+        let space = filter.space.as_deref();
+        self.prepare_vec_search(space, query_embedding.len())?;
 
         let start_time = Instant::now();
 
-        // Ensure vector index is loaded
-        if !ensured_vec_index {
-            self.ensure_vec_index()?;
-        }
-
-        let vec_index = self.vec_index.as_ref().ok_or(MemvidError::VecNotEnabled)?;
+        let allowed = self.vec_filter_allowed_ids(filter)?;
+        let vec_index = self.vec_search_index(space)?;
 
-        // Do pure vector search over entire index
-        let vec_hits = vec_index.search(query_embedding, top_k * 2);
+        // Pure vector search over the entire index, filtered while scanning
+        let accept = |frame_id| self.vec_filter_accepts(filter, allowed.as_ref(), frame_id);
+        let vec_hits =
+            vec_index.search_filtered(query_embedding, top_k * 2, self.vec_ef_search, &accept);
 
         if vec_hits.is_empty() {
             let elapsed_ms = start_time.elapsed().as_millis();
@@ -294,6 +366,9 @@ impl Memvid {
                 context: build_context(&[]),
                 next_cursor: None,
                 engine: SearchEngineKind::Hybrid,
+                facets: Vec::new(),
+                suggestions: Vec::new(),
+                corrected_query: None,
             });
         }
 
@@ -302,20 +377,11 @@ impl Memvid {
         let snippet_limit = snippet_chars.max(80);
 
         for vec_hit in vec_hits {
-            // Apply scope filter if provided
             let frame = match self.toc.frames.get(vec_hit.frame_id as usize) {
                 Some(f) => f.clone(),
                 None => continue,
             };
 
-            if let Some(scope_prefix) = scope {
-                let default_uri = crate::default_uri(frame.id);
-                let uri = frame.uri.as_ref().unwrap_or(&default_uri);
-                if !uri.starts_with(scope_prefix) {
-                    continue;
-                }
-            }
-
             // Get frame content for snippet
             let content = match self.frame_content(&frame) {
                 Ok(c) => c,
@@ -334,9 +400,8 @@ impl Memvid {
                 .clone()
                 .or_else(|| crate::infer_title_from_uri(&uri));
 
-            // VecIndex returns distance (lower is better), convert back to similarity (higher is better)
-            // distance = 1.0 - similarity, so similarity = 1.0 - distance
-            let similarity_score = 1.0 - vec_hit.distance;
+            // Similarity under the index metric (higher is better)
+            let similarity_score = vec_hit.similarity;
 
             let metadata = SearchHitMetadata {
                 matches: 1,
@@ -362,6 +427,12 @@ impl Memvid {
                 chunk_text: Some(snippet),
                 score: Some(similarity_score),
                 metadata: Some(metadata),
+                retriever_scores: Vec::new(),
+                highlights: Vec::new(),
+                chunk_highlights: Vec::new(),
+                fragments: Vec::new(),
+                rerank: None,
+                expanded: None,
             });
 
             if hits.len() >= top_k {
@@ -389,6 +460,9 @@ impl Memvid {
             context,
             next_cursor: None,
             engine: SearchEngineKind::Hybrid,
+            facets: Vec::new(),
+            suggestions: Vec::new(),
+            corrected_query: None,
         })
     }
 
@@ -404,12 +478,12 @@ impl Memvid {
     /// * `query_embedding` - Pre-computed embedding vector for the query
     /// * `config` - Adaptive retrieval configuration
     /// * `snippet_chars` - Maximum characters for result snippets
-    /// * `scope` - Optional URI prefix filter
+    /// * `filter` - Metadata filter applied during the vector scan
     ///
     /// # Example
     /// ```ignore
     /// let config = AdaptiveConfig::with_relative_threshold(0.6);
-    /// let result = memvid.search_adaptive("query", &embedding, config, 200, None)?;
+    /// let result = memvid.search_adaptive("query", &embedding, config, 200, &VecSearchFilter::default())?;
     /// println!("Returned {} of {} results", result.stats.returned, result.stats.total_considered);
     /// ```
     pub fn search_adaptive(
@@ -418,7 +492,7 @@ impl Memvid {
         query_embedding: &[f32],
         config: AdaptiveConfig,
         snippet_chars: usize,
-        scope: Option<&str>,
+        filter: &VecSearchFilter,
     ) -> Result<AdaptiveResult<SearchHit>> {
         use std::time::Instant;
 
@@ -429,7 +503,7 @@ impl Memvid {
                 query_embedding,
                 config.max_results,
                 snippet_chars,
-                scope,
+                filter,
             )?;
             return Ok(AdaptiveResult {
                 results: response.hits,
@@ -453,7 +527,7 @@ impl Memvid {
             query_embedding,
             config.max_results,
             snippet_chars,
-            scope,
+            filter,
         )?;
 
         if response.hits.is_empty() {
@@ -592,6 +666,24 @@ impl Memvid {
     }
 
     pub fn timeline(&mut self, query: TimelineQuery) -> Result<Vec<TimelineEntry>> {
+        Ok(self.timeline_page(query)?.entries)
+    }
+
+    /// Like [`Memvid::timeline`], but also returns a cursor for the next page.
+    ///
+    /// Pass it back through [`TimelineQuery::cursor`]; frames committed in between are
+    /// left out of later pages.
+    ///
+    /// # Errors
+    ///
+    /// [`MemvidError::StaleCursor`] for cursors issued before a `vacuum`, and
+    /// [`MemvidError::InvalidCursor`] for cursors from a different query.
+    pub fn timeline_page(&mut self, query: TimelineQuery) -> Result<TimelinePage> {
+        let pager = super::cursor::Pager::new(
+            self,
+            query.cursor.as_deref(),
+            super::cursor::timeline_query_hash(&query),
+        )?;
         let TimelineQuery {
             limit,
             since,
@@ -599,12 +691,14 @@ impl Memvid {
             reverse,
             #[cfg(feature = "temporal_track")]
             temporal,
+            cursor: _,
         } = query;
 
         #[cfg(feature = "temporal_track")]
         {
             crate::memvid::timeline::build_timeline(
                 self,
+                &pager,
                 limit,
                 since,
                 until,
@@ -614,7 +708,7 @@ impl Memvid {
         }
         #[cfg(not(feature = "temporal_track"))]
         {
-            crate::memvid::timeline::build_timeline(self, limit, since, until, reverse)
+            crate::memvid::timeline::build_timeline(self, &pager, limit, since, until, reverse)
         }
     }
 }
@@ -712,6 +806,21 @@ impl Memvid {
         Ok(dir)
     }
 
+    /// Set the score multiplier for free-text matches in frame titles (default `2.0`).
+    /// Snapshots built before titles were indexed need a doctor lex rebuild to benefit.
+    pub fn set_lex_title_boost(&mut self, boost: f32) {
+        self.lex_title_boost = boost.max(0.0);
+        if let Some(engine) = self.tantivy.as_mut() {
+            engine.set_title_boost(self.lex_title_boost);
+        }
+    }
+
+    /// Get the current title score multiplier.
+    #[must_use]
+    pub fn lex_title_boost(&self) -> f32 {
+        self.lex_title_boost
+    }
+
     pub(crate) fn init_tantivy(&mut self) -> Result<()> {
         if !self.lex_enabled {
             self.tantivy = None;
@@ -750,7 +859,7 @@ impl Memvid {
             Some(segments) => {
                 match self
                     .materialize_tantivy_segments(&segments)
-                    .and_then(TantivyEngine::open_from_dir)
+                    .and_then(|dir| TantivyEngine::open_from_dir(dir, &self.lex_analyzer))
                 {
                     Ok(engine) => engine,
                     Err(err) => {
@@ -758,11 +867,11 @@ impl Memvid {
                             "failed to open embedded Tantivy index: {}, rebuilding",
                             err
                         );
-                        TantivyEngine::create()?
+                        TantivyEngine::create(&self.lex_analyzer)?
                     }
                 }
             }
-            None => TantivyEngine::create()?,
+            None => TantivyEngine::create(&self.lex_analyzer)?,
         };
 
         // Use consolidated helper for expected doc count
@@ -798,6 +907,7 @@ impl Memvid {
             rebuilt = self.rebuild_tantivy_engine(&mut engine)?;
         }
 
+        engine.set_title_boost(self.lex_title_boost);
         self.tantivy_dirty = rebuilt;
         self.tantivy = Some(engine);
 
@@ -985,6 +1095,10 @@ impl Memvid {
             prepared_docs.push((frame, text));
         }
 
+        // Sketches are generated from the indexed text, so they are rebuilt with it.
+        let variant = self.sketch_track.variant;
+        self.sketch_track = crate::types::SketchTrack::new(variant);
+
         if prepared_docs.is_empty() {
             engine.reset()?;
             engine.commit()?;
@@ -994,6 +1108,8 @@ impl Memvid {
         engine.reset()?;
         for (frame, text) in &prepared_docs {
             engine.add_frame(frame, text)?;
+            self.sketch_track
+                .insert(crate::types::generate_sketch(frame.id, text, variant, None));
         }
         engine.commit()?;
         Ok(true)
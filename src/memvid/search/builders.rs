@@ -1,10 +1,14 @@
 use std::collections::HashMap;
 use std::io::{Read, Seek, SeekFrom};
 use std::panic::{AssertUnwindSafe, catch_unwind};
+use std::sync::Arc;
+
+use memmap2::Mmap;
 
 use crate::lex::{LexIndex, LexIndexArtifact, LexIndexBuilder};
 use crate::memvid::lifecycle::Memvid;
 use crate::types::{Frame, FrameId, FrameStatus, VectorCompression};
+use crate::vec_quant::ScalarKind;
 use crate::{MemvidError, Result, VecIndex, VecIndexArtifact};
 
 impl Memvid {
@@ -48,22 +52,58 @@ impl Memvid {
         if !self.vec_enabled {
             return Ok(None);
         }
-        let mut builder = VecIndexBuilder::new();
-        if let Some(index) = self.vec_index.as_ref() {
-            for (frame_id, embedding) in index.entries() {
-                if self.frame_is_active(frame_id) {
-                    builder.add_document(frame_id, embedding.to_vec());
-                }
+        if let Some(quantized) =
+            self.requantize_vec_index(|frame_id| self.frame_is_active(frame_id), new_docs)?
+        {
+            return Ok(Some(quantized));
+        }
+        if let Some(extended) = self.vec_index.as_ref().map_or(Ok(None), |index| {
+            index.extend_compressed(|frame_id| self.frame_is_active(frame_id), new_docs)
+        })? {
+            return Ok(Some(extended));
+        }
+        let mut builder = match self.vec_index.as_ref() {
+            Some(index) => {
+                VecIndexBuilder::from_index(index, |frame_id| self.frame_is_active(frame_id))
             }
+            None => VecIndexBuilder::new(),
         }
+        .with_metric(self.vec_metric);
         for (frame_id, embedding) in new_docs {
             builder.add_document(*frame_id, embedding.clone());
         }
         let artifact = builder.finish()?;
-        let index = VecIndex::decode(&artifact.bytes)?;
+        let index = VecIndex::decode(&artifact.bytes)?.with_metric(self.vec_metric);
         Ok(Some((artifact, index)))
     }
 
+    /// Re-encode the vector index when int8 or binary compression is requested but the
+    /// current index uses a different encoding. Returns `None` when no switch is needed.
+    pub(crate) fn requantize_vec_index(
+        &self,
+        keep: impl Fn(FrameId) -> bool,
+        new_docs: &[(FrameId, Vec<f32>)],
+    ) -> Result<Option<(VecIndexArtifact, VecIndex)>> {
+        let Some(kind) = ScalarKind::from_compression(&self.vec_compression) else {
+            return Ok(None);
+        };
+        if self
+            .vec_index
+            .as_ref()
+            .is_some_and(|index| index.compression() == self.vec_compression)
+        {
+            return Ok(None);
+        }
+        VecIndex::build_scalar_quantized(
+            self.vec_index.as_ref(),
+            kind,
+            keep,
+            new_docs,
+            self.vec_metric,
+        )
+        .map(Some)
+    }
+
     pub(crate) fn ensure_lex_index(&mut self) -> Result<()> {
         if self.lex_index.is_some() {
             return Ok(());
@@ -124,23 +164,20 @@ impl Memvid {
 
     pub(crate) fn load_vec_index_from_manifest(&mut self) -> Result<()> {
         if let Some(manifest) = &self.toc.indexes.vec {
+            let metric = manifest.metric;
             // Empty manifest (placeholder for enabled but not yet populated index)
             if manifest.bytes_length == 0 {
                 self.vec_index = None;
                 return Ok(());
             }
 
-            let bytes = match self.read_range(manifest.bytes_offset, manifest.bytes_length) {
-                Ok(bytes) => bytes,
-                Err(_) => {
-                    self.vec_index = None;
-                    // Don't disable vec if loading fails - keep it enabled
-                    // self.vec_enabled = false;
-                    return Ok(());
-                }
-            };
-            match catch_unwind(AssertUnwindSafe(|| VecIndex::decode(&bytes))) {
-                Ok(Ok(index)) => self.vec_index = Some(index),
+            let (offset, length) = (manifest.bytes_offset, manifest.bytes_length);
+            let compression = manifest.compression_mode.clone();
+            // Don't disable vec if loading fails - keep it enabled
+            match catch_unwind(AssertUnwindSafe(|| {
+                self.load_vec_index_range(offset, length, compression)
+            })) {
+                Ok(Ok(index)) => self.vec_index = Some(index.with_metric(metric)),
                 Ok(Err(_)) | Err(_) => {
                     self.vec_index = None;
                     // Don't disable vec if decoding fails - keep it enabled
@@ -160,6 +197,7 @@ impl Memvid {
         use crate::clip::ClipIndex;
 
         if let Some(manifest) = &self.toc.indexes.clip {
+            let metric = manifest.metric;
             // Empty manifest (placeholder for enabled but not yet populated index)
             if manifest.bytes_length == 0 {
                 self.clip_index = None;
@@ -174,7 +212,7 @@ impl Memvid {
                 }
             };
             match catch_unwind(AssertUnwindSafe(|| ClipIndex::decode(&bytes))) {
-                Ok(Ok(index)) => self.clip_index = Some(index),
+                Ok(Ok(index)) => self.clip_index = Some(index.with_metric(metric)),
                 Ok(Err(_)) | Err(_) => {
                     self.clip_index = None;
                 }
@@ -185,6 +223,37 @@ impl Memvid {
         Ok(())
     }
 
+    /// Load the vector index stored at `offset..offset + length`.
+    ///
+    /// Read-only handles map the file so fixed-stride segments are searched in place.
+    /// Writable handles decode into memory, because later payload writes may reuse the
+    /// index region; upgrading a shared handle copies mapped indexes in the same way.
+    pub(crate) fn load_vec_index_range(
+        &mut self,
+        offset: u64,
+        length: u64,
+        compression: VectorCompression,
+    ) -> Result<VecIndex> {
+        if self.read_only {
+            // SAFETY: read-only handles hold a shared lock, so writers can't modify the
+            // file while the map is alive.
+            let map = unsafe { Mmap::map(&self.file)? };
+            return VecIndex::map(Arc::new(map), offset, length, compression);
+        }
+        let bytes = self.read_range(offset, length)?;
+        VecIndex::decode_with_compression(&bytes, compression)
+    }
+
+    /// Copy mapped vector indexes into memory when a read-only handle becomes writable,
+    /// before payload writes can reuse the regions they read from.
+    pub(crate) fn detach_mapped_vec_indexes(&mut self) {
+        self.vec_index = self.vec_index.take().map(VecIndex::into_owned);
+        self.vec_space_indexes = std::mem::take(&mut self.vec_space_indexes)
+            .into_iter()
+            .map(|(name, index)| (name, index.into_owned()))
+            .collect();
+    }
+
     pub fn read_range(&mut self, offset: u64, length: u64) -> Result<Vec<u8>> {
         let file_len = self.file.metadata()?.len();
         let end = offset.checked_add(length).ok_or(MemvidError::InvalidToc {
@@ -211,7 +280,12 @@ impl Memvid {
     fn build_vec_index_from_segments(&mut self) -> Result<()> {
         use crate::vec::VecIndexBuilder;
 
-        let mut builder = VecIndexBuilder::new();
+        // Merged in memory on every open, read-only ones included, so skip graph
+        // construction here. The next full rebuild seeds from this index, builds the
+        // graph once and persists it.
+        let mut builder = VecIndexBuilder::new()
+            .without_hnsw()
+            .with_metric(self.vec_metric);
 
         // Clone segments to avoid borrow checker issues
         let segments = self.toc.segment_catalog.vec_segments.clone();
@@ -247,7 +321,7 @@ impl Memvid {
                 Ok(segment_index) => {
                     for (frame_id, embedding) in segment_index.entries() {
                         if self.frame_is_active(frame_id) {
-                            builder.add_document(frame_id, embedding.to_vec());
+                            builder.add_document(frame_id, embedding.into_owned());
                         }
                     }
                 }
@@ -264,7 +338,8 @@ impl Memvid {
         let artifact = builder.finish()?;
         if artifact.vector_count > 0 {
             let index =
-                VecIndex::decode_with_compression(&artifact.bytes, VectorCompression::None)?;
+                VecIndex::decode_with_compression(&artifact.bytes, VectorCompression::None)?
+                    .with_metric(self.vec_metric);
             self.vec_index = Some(index);
         }
 
@@ -298,3 +373,111 @@ impl Memvid {
 }
 
 use crate::vec::VecIndexBuilder;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn mapped_index_survives_upgrading_to_writes() {
+        crate::run_serial_test(|| {
+            let dir = tempfile::tempdir().expect("tmp");
+            let path = dir.path().join("mapped.mv2");
+            let mut mem = Memvid::create(&path).expect("create");
+            mem.enable_vec().expect("vec");
+            for i in 0..30u8 {
+                mem.put_with_embedding(b"doc", vec![1.0, f32::from(i) * 0.1, 0.5])
+                    .expect("put");
+            }
+            mem.commit().expect("commit");
+            drop(mem);
+
+            // A shared handle maps the index straight from the file.
+            let mut mem = Memvid::open(&path).expect("open");
+            mem.downgrade_to_shared().expect("downgrade");
+            mem.vec_index = None;
+            mem.ensure_vec_index().expect("load");
+            assert!(matches!(mem.vec_index, Some(VecIndex::Mapped(_))));
+            let query = [1.0, 0.4, 0.5];
+            let expected = mem.search_vec(&query, 5).expect("search");
+
+            mem.put_with_embedding(b"late", vec![-1.0, 0.0, 0.0])
+                .expect("put");
+            // Stand in for a later write reusing the region the index was mapped from.
+            let (offset, length) = mem
+                .toc
+                .indexes
+                .vec
+                .as_ref()
+                .map(|manifest| (manifest.bytes_offset, manifest.bytes_length))
+                .expect("manifest");
+            mem.file.seek(SeekFrom::Start(offset)).expect("seek");
+            mem.file
+                .write_all(&vec![0; usize::try_from(length).expect("length")])
+                .expect("write");
+            assert_eq!(mem.search_vec(&query, 5).expect("search"), expected);
+            mem.commit().expect("commit");
+            assert_eq!(mem.search_vec(&query, 5).expect("search"), expected);
+            let hits = mem.search_vec(&[-1.0, 0.0, 0.0], 1).expect("search");
+            assert_eq!(hits[0].frame_id, 30);
+            drop(mem);
+
+            let mut mem = Memvid::open_read_only(&path).expect("reopen");
+            assert_eq!(mem.search_vec(&query, 5).expect("search"), expected);
+        });
+    }
+
+    #[test]
+    fn segment_merge_stays_flat_until_rebuild() {
+        crate::run_serial_test(|| {
+            let dir = tempfile::tempdir().expect("tmp");
+            let path = dir.path().join("segments.mv2");
+            let mut mem = Memvid::create(&path).expect("create");
+            mem.enable_vec().expect("vec");
+            let embed = |id: FrameId| {
+                let x = crate::count_to_f32(usize::try_from(id).expect("id"));
+                vec![x.sin(), x.cos(), (x * 0.5).sin()]
+            };
+            // One long document chunks into enough frames for a graph.
+            let text: String = (0..1_500)
+                .map(|i| format!("Paragraph {i} {}\n\n", "lorem ipsum dolor ".repeat(50)))
+                .collect();
+            mem.put_bytes(text.as_bytes()).expect("put");
+            mem.commit().expect("commit");
+            let count = mem.toc.frames.len() as FrameId;
+            assert!(count as usize > crate::vec_hnsw::HNSW_MIN_VECTORS);
+
+            // Store the vectors in a single flat segment, as parallel commits leave them.
+            let embeddings: Vec<(FrameId, Vec<f32>)> =
+                (0..count).map(|id| (id, embed(id))).collect();
+            let artifact = mem
+                .build_vec_segment_from_embeddings(&embeddings)
+                .expect("segment")
+                .expect("artifact");
+            let segment_id = mem.toc.segment_catalog.next_segment_id;
+            let descriptor = mem
+                .append_vec_segment(&artifact, segment_id)
+                .expect("append");
+            mem.toc.segment_catalog.vec_segments.push(descriptor);
+            mem.toc.segment_catalog.next_segment_id = segment_id + 1;
+            mem.toc.indexes.vec = None;
+            mem.vec_index = None;
+
+            mem.ensure_vec_index().expect("merge");
+            assert!(matches!(mem.vec_index, Some(VecIndex::Uncompressed { .. })));
+
+            mem.put_with_embedding(b"late", vec![0.0, 0.0, -1.0])
+                .expect("put");
+            mem.commit().expect("commit");
+            assert!(mem.toc.segment_catalog.vec_segments.is_empty());
+            assert!(matches!(mem.vec_index, Some(VecIndex::Graph { .. })));
+            drop(mem);
+
+            let mut mem = Memvid::open(&path).expect("reopen");
+            assert!(matches!(mem.vec_index, Some(VecIndex::Graph { .. })));
+            let hits = mem.search_vec(&embed(7), 1).expect("search");
+            assert_eq!(hits[0].frame_id, 7);
+        });
+    }
+}
@@ -0,0 +1,396 @@
+//! Opaque pagination cursors shared by search and timeline queries.
+//!
+//! A cursor pins the vacuum generation, a hash of the query and the frame count seen by
+//! the first page, plus the last entry handed out. Frames committed after the first page
+//! are ignored unless they update a frame the first page could see, and the next page
+//! resumes right after that last entry, so paging stays consistent while the memory
+//! grows. Vacuum rescores everything, so its cursors go stale.
+
+use std::collections::HashSet;
+
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use bincode::serde::{decode_from_slice, encode_to_vec};
+use serde::{Deserialize, Serialize};
+
+use crate::memvid::lifecycle::Memvid;
+use crate::types::{FrameId, SearchRequest, TimelineQuery};
+use crate::{MemvidError, Result};
+
+const CURSOR_VERSION: u8 = 1;
+
+/// Position of one result in a page sequence.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub(crate) enum CursorAnchor {
+    /// Search snippet, ordered by score (descending), then frame id, then slice.
+    Hit {
+        score: f32,
+        frame_id: FrameId,
+        slice: usize,
+    },
+    /// Timeline entry, ordered by timestamp, then frame id (both reversed if `reverse`).
+    Timeline {
+        timestamp: i64,
+        frame_id: FrameId,
+        reverse: bool,
+    },
+    /// Search snippet from hits reordered after retrieval (MMR, reranking). No key
+    /// orders these, so when the entry is gone the next page starts at the offset.
+    Reordered { frame_id: FrameId, slice: usize },
+}
+
+impl CursorAnchor {
+    fn same_entry(&self, other: &Self) -> bool {
+        match (self, other) {
+            (
+                Self::Hit {
+                    frame_id, slice, ..
+                },
+                Self::Hit {
+                    frame_id: other_frame,
+                    slice: other_slice,
+                    ..
+                },
+            )
+            | (
+                Self::Reordered { frame_id, slice },
+                Self::Reordered {
+                    frame_id: other_frame,
+                    slice: other_slice,
+                },
+            ) => frame_id == other_frame && slice == other_slice,
+            (
+                Self::Timeline { frame_id, .. },
+                Self::Timeline {
+                    frame_id: other_frame,
+                    ..
+                },
+            ) => frame_id == other_frame,
+            _ => false,
+        }
+    }
+
+    /// Whether `self` sorts strictly after `last` in page order.
+    fn follows(&self, last: &Self) -> bool {
+        match (*self, *last) {
+            (
+                Self::Hit {
+                    score,
+                    frame_id,
+                    slice,
+                },
+                Self::Hit {
+                    score: last_score,
+                    frame_id: last_frame,
+                    slice: last_slice,
+                },
+            ) => last_score
+                .total_cmp(&score)
+                .then_with(|| frame_id.cmp(&last_frame))
+                .then_with(|| slice.cmp(&last_slice))
+                .is_gt(),
+            (
+                Self::Timeline {
+                    timestamp,
+                    frame_id,
+                    reverse,
+                },
+                Self::Timeline {
+                    timestamp: last_ts,
+                    frame_id: last_frame,
+                    ..
+                },
+            ) => {
+                let order = (timestamp, frame_id).cmp(&(last_ts, last_frame));
+                if reverse {
+                    order.is_lt()
+                } else {
+                    order.is_gt()
+                }
+            }
+            _ => false,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CursorToken {
+    version: u8,
+    generation: u64,
+    query_hash: [u8; 16],
+    frame_limit: u64,
+    offset: usize,
+    last: Option<CursorAnchor>,
+}
+
+/// Validated paging state for one request, created from the caller's cursor (if any).
+#[derive(Debug)]
+pub(crate) struct Pager {
+    generation: u64,
+    query_hash: [u8; 16],
+    frame_limit: u64,
+    /// Frames past `frame_limit` that update (or chunk an update of) an earlier frame.
+    updates: HashSet<FrameId>,
+    resume: Option<(usize, Option<CursorAnchor>)>,
+}
+
+impl Pager {
+    /// Decodes `token` against the current memory; `None` or blank starts at page one.
+    pub(crate) fn new(memvid: &Memvid, token: Option<&str>, query_hash: [u8; 16]) -> Result<Self> {
+        let generation = memvid.toc.vacuum_generation;
+        let frame_count = memvid.toc.frames.len() as u64;
+        let Some(token) = token.map(str::trim).filter(|token| !token.is_empty()) else {
+            return Ok(Self {
+                generation,
+                query_hash,
+                frame_limit: frame_count,
+                updates: HashSet::new(),
+                resume: None,
+            });
+        };
+        let cursor = decode_token(token)?;
+        if cursor.generation != generation {
+            return Err(MemvidError::StaleCursor {
+                cursor_generation: cursor.generation,
+                current_generation: generation,
+            });
+        }
+        if cursor.query_hash != query_hash {
+            return Err(MemvidError::InvalidCursor {
+                reason: "cursor was issued for a different query",
+            });
+        }
+        if cursor.frame_limit > frame_count {
+            return Err(MemvidError::InvalidCursor {
+                reason: "cursor refers to frames this memory does not have",
+            });
+        }
+        Ok(Self {
+            generation,
+            query_hash,
+            frame_limit: cursor.frame_limit,
+            updates: later_updates(memvid, cursor.frame_limit),
+            resume: Some((cursor.offset, cursor.last)),
+        })
+    }
+
+    /// Frames committed after the first page are left out of later pages, except
+    /// updates that replace a frame the first page could see.
+    pub(crate) fn admits(&self, frame_id: FrameId) -> bool {
+        frame_id < self.frame_limit || self.updates.contains(&frame_id)
+    }
+
+    /// Number of entries earlier pages handed out; useful to size candidate pools.
+    pub(crate) fn offset_hint(&self) -> usize {
+        self.resume.map_or(0, |(offset, _)| offset)
+    }
+
+    /// Index in `anchors` (page order) at which this page starts.
+    pub(crate) fn start(&self, anchors: &[CursorAnchor]) -> usize {
+        let Some((offset, last)) = self.resume else {
+            return 0;
+        };
+        let Some(last) = last else {
+            return offset.min(anchors.len());
+        };
+        if let Some(position) = anchors.iter().position(|anchor| anchor.same_entry(&last)) {
+            return position + 1;
+        }
+        if matches!(last, CursorAnchor::Reordered { .. }) {
+            return offset.min(anchors.len());
+        }
+        anchors
+            .iter()
+            .position(|anchor| anchor.follows(&last))
+            .unwrap_or(anchors.len())
+    }
+
+    /// Cursor for the page following `consumed` entries, the last of which was `last`.
+    pub(crate) fn next(&self, consumed: usize, last: Option<CursorAnchor>) -> Result<String> {
+        encode_token(&CursorToken {
+            version: CURSOR_VERSION,
+            generation: self.generation,
+            query_hash: self.query_hash,
+            frame_limit: self.frame_limit,
+            offset: consumed,
+            last,
+        })
+    }
+}
+
+/// Frames at or past `frame_limit` that supersede a frame below it, directly or through
+/// earlier updates, plus the chunks of those frames.
+fn later_updates(memvid: &Memvid, frame_limit: FrameId) -> HashSet<FrameId> {
+    let mut updates = HashSet::new();
+    // Successors and chunks always follow the frame they refer to.
+    for frame in memvid
+        .toc
+        .frames
+        .iter()
+        .filter(|frame| frame.id >= frame_limit)
+    {
+        let replaces_earlier = frame
+            .supersedes
+            .is_some_and(|id| id < frame_limit || updates.contains(&id))
+            || frame.parent_id.is_some_and(|id| updates.contains(&id));
+        if replaces_earlier {
+            updates.insert(frame.id);
+        }
+    }
+    updates
+}
+
+/// Re-issue a cursor issued for the query hashed as `from` for the one hashed as `to`.
+pub(crate) fn rebind_cursor(token: &str, from: [u8; 16], to: [u8; 16]) -> Result<String> {
+    let mut cursor = decode_token(token.trim())?;
+    if cursor.query_hash != from {
+        return Err(MemvidError::InvalidCursor {
+            reason: "cursor was issued for a different query",
+        });
+    }
+    cursor.query_hash = to;
+    encode_token(&cursor)
+}
+
+fn encode_token(token: &CursorToken) -> Result<String> {
+    let bytes = encode_to_vec(token, bincode::config::standard())?;
+    Ok(URL_SAFE_NO_PAD.encode(bytes))
+}
+
+fn decode_token(token: &str) -> Result<CursorToken> {
+    let malformed = MemvidError::InvalidCursor {
+        reason: "cursor is malformed",
+    };
+    let Ok(bytes) = URL_SAFE_NO_PAD.decode(token) else {
+        return Err(malformed);
+    };
+    match decode_from_slice::<CursorToken, _>(&bytes, bincode::config::standard()) {
+        Ok((cursor, read)) if read == bytes.len() && cursor.version == CURSOR_VERSION => Ok(cursor),
+        _ => Err(malformed),
+    }
+}
+
+/// Hash of everything that shapes a search's result order; paging fields are ignored.
+pub(crate) fn search_query_hash(request: &SearchRequest) -> [u8; 16] {
+    let mut normalized = request.clone();
+    normalized.cursor = None;
+    normalized.facets = None;
+    normalized.highlight = None;
+    normalized.auto_correct = false;
+    digest(&normalized)
+}
+
+/// Hash of the timeline filters; the page size and cursor are ignored.
+pub(crate) fn timeline_query_hash(query: &TimelineQuery) -> [u8; 16] {
+    let mut normalized = query.clone();
+    normalized.limit = None;
+    normalized.cursor = None;
+    digest(&normalized)
+}
+
+fn digest<T: Serialize>(value: &T) -> [u8; 16] {
+    let bytes = serde_json::to_vec(value).unwrap_or_default();
+    let hash = blake3::hash(&bytes);
+    let mut out = [0u8; 16];
+    out.copy_from_slice(&hash.as_bytes()[..16]);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pager(resume: Option<(usize, Option<CursorAnchor>)>) -> Pager {
+        Pager {
+            generation: 3,
+            query_hash: [7; 16],
+            frame_limit: 10,
+            updates: HashSet::new(),
+            resume,
+        }
+    }
+
+    fn hit(score: f32, frame_id: FrameId, slice: usize) -> CursorAnchor {
+        CursorAnchor::Hit {
+            score,
+            frame_id,
+            slice,
+        }
+    }
+
+    #[test]
+    fn token_roundtrips() {
+        let token = pager(None).next(4, Some(hit(1.5, 2, 0))).expect("encode");
+        assert!(
+            token
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        );
+        let decoded = decode_token(&token).expect("decode");
+        assert_eq!(decoded.generation, 3);
+        assert_eq!(decoded.frame_limit, 10);
+        assert_eq!(decoded.offset, 4);
+        assert_eq!(decoded.last, Some(hit(1.5, 2, 0)));
+        assert!(matches!(
+            decode_token("12"),
+            Err(MemvidError::InvalidCursor { .. })
+        ));
+    }
+
+    #[test]
+    fn resumes_after_last_entry_even_when_results_shift() {
+        let anchors = [
+            hit(3.0, 1, 0),
+            hit(2.0, 4, 0),
+            hit(2.0, 4, 1),
+            hit(1.0, 2, 0),
+        ];
+        let resumed = pager(Some((2, Some(hit(2.0, 4, 0)))));
+        assert_eq!(resumed.start(&anchors), 2);
+
+        // A new, higher-scoring entry pushed everything down by one.
+        let shifted = [
+            hit(5.0, 9, 0),
+            hit(3.0, 1, 0),
+            hit(2.0, 4, 0),
+            hit(2.0, 4, 1),
+        ];
+        assert_eq!(resumed.start(&shifted), 3);
+
+        // The last entry vanished: continue with the first entry ordered after it.
+        let gone = [
+            hit(3.0, 1, 0),
+            hit(2.0, 3, 0),
+            hit(2.0, 5, 0),
+            hit(1.0, 2, 0),
+        ];
+        assert_eq!(resumed.start(&gone), 2);
+    }
+
+    #[test]
+    fn reordered_anchors_fall_back_to_the_offset() {
+        let reordered = |frame_id| CursorAnchor::Reordered { frame_id, slice: 0 };
+        let anchors = [reordered(4), reordered(1), reordered(7), reordered(2)];
+        assert_eq!(pager(Some((2, Some(reordered(7))))).start(&anchors), 3);
+        // The last entry vanished: no score orders the list, so resume by position.
+        assert_eq!(pager(Some((2, Some(reordered(9))))).start(&anchors), 2);
+    }
+
+    #[test]
+    fn timeline_anchors_respect_direction() {
+        let last = CursorAnchor::Timeline {
+            timestamp: 20,
+            frame_id: 2,
+            reverse: true,
+        };
+        let entries: Vec<CursorAnchor> = [(30, 3), (10, 1)]
+            .into_iter()
+            .map(|(timestamp, frame_id)| CursorAnchor::Timeline {
+                timestamp,
+                frame_id,
+                reverse: true,
+            })
+            .collect();
+        assert_eq!(pager(Some((1, Some(last)))).start(&entries), 1);
+    }
+}
@@ -1,8 +1,11 @@
 #![cfg(feature = "lex")]
 
+use super::cursor::{CursorAnchor, Pager, search_query_hash};
 #[cfg(feature = "temporal_track")]
 use super::helpers::attach_temporal_metadata;
-use super::helpers::{build_context, empty_search_response, parse_cursor, timestamp_to_rfc3339};
+use super::helpers::{
+    apply_highlights, build_context, empty_search_response, frame_facets, timestamp_to_rfc3339,
+};
 use crate::lex::{LexMatch, compute_snippet_slices};
 use crate::memvid::lifecycle::Memvid;
 use crate::search::{EvaluationContext, ParsedQuery};
@@ -23,6 +26,11 @@ pub(super) fn search_with_lex_fallback(
     start_time: Instant,
     candidate_filter: Option<&HashSet<FrameId>>,
 ) -> Result<SearchResponse> {
+    let pager = Pager::new(
+        memvid,
+        request.cursor.as_deref(),
+        search_query_hash(request),
+    )?;
     let index = memvid
         .lex_index
         .as_ref()
@@ -34,12 +42,23 @@ pub(super) fn search_with_lex_fallback(
         request.scope.as_deref()
     };
 
-    let matches: Vec<LexMatch> = index.compute_matches(query_tokens, uri_filter, scope_filter);
+    // Fuzzy words rarely occur verbatim, so they are matched by edit distance instead.
+    let fuzzy_terms = parsed.fuzzy_terms();
+    let literal_tokens: Vec<String> = query_tokens
+        .iter()
+        .filter(|token| !fuzzy_terms.iter().any(|(word, _)| word == *token))
+        .cloned()
+        .collect();
+    let matches: Vec<LexMatch> =
+        index.compute_matches(&literal_tokens, &fuzzy_terms, uri_filter, scope_filter);
     let snippet_window = request.snippet_chars.max(80);
     let max_snippets_per_doc = request.top_k.max(1);
 
     let mut evaluated = Vec::new();
     for matched in &matches {
+        if !pager.admits(matched.frame_id) {
+            continue;
+        }
         if let Some(filter) = candidate_filter {
             if !filter.contains(&matched.frame_id) {
                 continue;
@@ -58,6 +77,7 @@ pub(super) fn search_with_lex_fallback(
         if !parsed.evaluate(&ctx) {
             continue;
         }
+        let score = matched.score * parsed.boost_for(&ctx);
 
         let slices = compute_snippet_slices(
             &matched.content,
@@ -65,10 +85,21 @@ pub(super) fn search_with_lex_fallback(
             snippet_window,
             max_snippets_per_doc,
         );
-        evaluated.push((matched, slices));
+        evaluated.push((matched, slices, score));
     }
+    evaluated.sort_by(|left, right| {
+        right
+            .2
+            .total_cmp(&left.2)
+            .then_with(|| left.0.frame_id.cmp(&right.0.frame_id))
+    });
+    let facets = frame_facets(
+        memvid,
+        evaluated.iter().map(|(matched, _, _)| matched.frame_id),
+        request.facets.as_ref(),
+    );
 
-    let total_slices: usize = evaluated.iter().map(|(_, slices)| slices.len()).sum();
+    let total_slices: usize = evaluated.iter().map(|(_, slices, _)| slices.len()).sum();
     if total_slices == 0 {
         let elapsed_ms = start_time.elapsed().as_millis();
         return Ok(empty_search_response(
@@ -79,12 +110,22 @@ pub(super) fn search_with_lex_fallback(
         ));
     }
 
-    let offset = parse_cursor(request.cursor.as_deref(), total_slices)?;
+    let anchors: Vec<CursorAnchor> = evaluated
+        .iter()
+        .flat_map(|(matched, slices, score)| {
+            (0..slices.len()).map(|slice| CursorAnchor::Hit {
+                score: *score,
+                frame_id: matched.frame_id,
+                slice,
+            })
+        })
+        .collect();
+    let offset = pager.start(&anchors);
     let effective_top_k = request.top_k.max(1);
 
     let mut hits = Vec::new();
     let mut produced = 0usize;
-    for (matched, slices) in evaluated {
+    for (matched, slices, score) in evaluated {
         let frame_meta = memvid
             .toc
             .frames
@@ -156,7 +197,7 @@ pub(super) fn search_with_lex_fallback(
                 String::from_utf8_lossy(&canonical_bytes[global_start..global_end]).to_string();
             let chunk_text =
                 String::from_utf8_lossy(&canonical_bytes[chunk_start..chunk_end]).to_string();
-            hits.push(SearchHit {
+            let mut search_hit = SearchHit {
                 rank: hits.len() + 1,
                 frame_id: matched.frame_id,
                 uri: uri.clone(),
@@ -166,15 +207,32 @@ pub(super) fn search_with_lex_fallback(
                 matches: matches_in_slice,
                 chunk_range: Some(chunk_range),
                 chunk_text: Some(chunk_text),
-                score: Some(matched.score),
+                score: Some(score),
                 metadata: Some(metadata),
-            });
+                retriever_scores: Vec::new(),
+                highlights: Vec::new(),
+                chunk_highlights: Vec::new(),
+                fragments: Vec::new(),
+                rerank: None,
+                expanded: None,
+            };
+            if let Some(options) = request.highlight.as_ref() {
+                // Occurrences are relative to the matched chunk, as is the snippet.
+                apply_highlights(
+                    &mut search_hit,
+                    &matched.occurrences,
+                    global_start - chunk_start,
+                    options,
+                    request.snippet_chars,
+                );
+            }
+            hits.push(search_hit);
             produced += 1;
         }
     }
 
     let next_cursor = if produced < total_slices {
-        Some(produced.to_string())
+        Some(pager.next(produced, produced.checked_sub(1).map(|last| anchors[last]))?)
     } else {
         None
     };
@@ -193,6 +251,9 @@ pub(super) fn search_with_lex_fallback(
         context,
         next_cursor,
         engine: SearchEngineKind::LexFallback,
+        facets,
+        suggestions: Vec::new(),
+        corrected_query: None,
     })
 }
 
@@ -204,6 +265,11 @@ pub(super) fn search_with_filters_only(
     start_time: Instant,
     candidate_filter: Option<&HashSet<FrameId>>,
 ) -> Result<SearchResponse> {
+    let pager = Pager::new(
+        memvid,
+        request.cursor.as_deref(),
+        search_query_hash(request),
+    )?;
     let mut matches = Vec::new();
     let snippet_limit = request.snippet_chars.max(80);
     let frames: Vec<Frame> = if let Some(filter) = candidate_filter {
@@ -219,6 +285,9 @@ pub(super) fn search_with_filters_only(
     };
 
     for frame in frames {
+        if !pager.admits(frame.id) {
+            continue;
+        }
         let search_text = memvid.frame_search_text(&frame)?;
         let content_lower = search_text.to_ascii_lowercase();
         let ctx = EvaluationContext {
@@ -231,6 +300,11 @@ pub(super) fn search_with_filters_only(
         matches.push((frame.id, frame, search_text));
     }
 
+    let facets = frame_facets(
+        memvid,
+        matches.iter().map(|(frame_id, _, _)| *frame_id),
+        request.facets.as_ref(),
+    );
     let total_hits = matches.len();
     if total_hits == 0 {
         let elapsed_ms = start_time.elapsed().as_millis().max(1);
@@ -243,10 +317,22 @@ pub(super) fn search_with_filters_only(
             context: build_context(&[]),
             next_cursor: None,
             engine: SearchEngineKind::LexFallback,
+            facets,
+            suggestions: Vec::new(),
+            corrected_query: None,
         });
     }
 
-    let offset = parse_cursor(request.cursor.as_deref(), total_hits)?;
+    // Filter-only hits are unscored and listed in frame order.
+    let anchors: Vec<CursorAnchor> = matches
+        .iter()
+        .map(|(frame_id, _, _)| CursorAnchor::Hit {
+            score: 0.0,
+            frame_id: *frame_id,
+            slice: 0,
+        })
+        .collect();
+    let offset = pager.start(&anchors);
     let effective_top_k = request.top_k.max(1);
     let mut hits = Vec::new();
     let mut produced = 0usize;
@@ -290,12 +376,19 @@ pub(super) fn search_with_filters_only(
             chunk_text: Some(snippet),
             score: None,
             metadata: Some(metadata),
+            retriever_scores: Vec::new(),
+            highlights: Vec::new(),
+            chunk_highlights: Vec::new(),
+            fragments: Vec::new(),
+            rerank: None,
+            expanded: None,
         });
         produced += 1;
     }
 
-    let next_cursor = if offset + produced < total_hits {
-        Some((offset + produced).to_string())
+    let consumed = offset + produced;
+    let next_cursor = if consumed < total_hits {
+        Some(pager.next(consumed, consumed.checked_sub(1).map(|last| anchors[last]))?)
     } else {
         None
     };
@@ -314,5 +407,8 @@ pub(super) fn search_with_filters_only(
         context,
         next_cursor,
         engine: SearchEngineKind::LexFallback,
+        facets,
+        suggestions: Vec::new(),
+        corrected_query: None,
     })
 }
@@ -0,0 +1,539 @@
+//! Fusion of lexical and semantic candidate lists.
+//!
+//! Every strategy works on tagged lists of ranked hits and produces a single list whose
+//! `score` is the fused score and whose `retriever_scores` record how each retriever
+//! ranked the hit. Lists without scores fall back to rank-derived scores so timeline and
+//! filter-only results can still be fused with score-based strategies.
+
+use std::collections::{HashMap, HashSet};
+use std::time::Instant;
+
+use crate::memvid::lifecycle::Memvid;
+use crate::types::{
+    FusionStrategy, HybridRetriever, RetrieverScore, SearchEngineKind, SearchHit, SearchParams,
+    SearchRequest, SearchResponse, VecSearchFilter, normalize_scores,
+};
+use crate::{MemvidError, Result};
+
+use super::helpers::build_context;
+use super::mmr::{MMR_CANDIDATE_FACTOR, validate_mmr_lambda};
+
+/// Minimum candidates fetched per retriever before fusing.
+const HYBRID_CANDIDATE_FLOOR: usize = 24;
+
+/// A candidate list and the retriever that produced it.
+pub(crate) type CandidateList = (HybridRetriever, Vec<SearchHit>);
+
+/// Accumulated state for one frame while fusing.
+struct Fused {
+    hit: SearchHit,
+    /// Best rank and raw score per retriever.
+    seen: HashMap<HybridRetriever, (usize, Option<f32>)>,
+    /// Summed contributions per retriever (Convex keeps the best normalised score).
+    contributions: HashMap<HybridRetriever, f32>,
+}
+
+/// Reject strategies whose parameters cannot produce a meaningful ranking.
+pub(crate) fn validate_fusion(strategy: &FusionStrategy) -> Result<()> {
+    let valid = match *strategy {
+        FusionStrategy::Rrf { k } => k.is_finite() && k >= 0.0,
+        FusionStrategy::Linear { lexical, semantic }
+        | FusionStrategy::Convex { lexical, semantic } => {
+            lexical.is_finite()
+                && semantic.is_finite()
+                && lexical >= 0.0
+                && semantic >= 0.0
+                && lexical + semantic > 0.0
+        }
+        FusionStrategy::Dbsf => true,
+    };
+    if valid {
+        Ok(())
+    } else {
+        Err(MemvidError::InvalidQuery {
+            reason: "fusion parameters must be finite and non-negative with a positive weight"
+                .into(),
+        })
+    }
+}
+
+/// Interleave lists from one retriever into a single ranked list, keeping each frame at
+/// its first position, so the retriever contributes once per frame when fused.
+pub(crate) fn merge_ranked_lists(lists: Vec<Vec<SearchHit>>) -> Vec<SearchHit> {
+    let depth = lists.iter().map(Vec::len).max().unwrap_or(0);
+    let mut lists: Vec<_> = lists.into_iter().map(Vec::into_iter).collect();
+    let mut seen = HashSet::new();
+    let mut merged = Vec::new();
+    for _ in 0..depth {
+        for hit in lists.iter_mut().filter_map(Iterator::next) {
+            if seen.insert(hit.frame_id) {
+                merged.push(hit);
+            }
+        }
+    }
+    for (idx, hit) in merged.iter_mut().enumerate() {
+        hit.rank = idx + 1;
+    }
+    merged
+}
+
+/// Candidate lists for one retriever run with several variants of the same query, plus
+/// the variants merged with [`merge_ranked_lists`].
+///
+/// RRF fuses each variant as its own list, so a frame several variants agree on keeps
+/// its summed reciprocal ranks. Score-based strategies fuse the merged list instead, so
+/// the retriever's normalised scores are not added once per variant. Either way the
+/// merged list is what [`attribute_merged_ranks`] reports.
+pub(crate) fn variant_lists(
+    retriever: HybridRetriever,
+    lists: Vec<Vec<SearchHit>>,
+    strategy: &FusionStrategy,
+) -> (Vec<CandidateList>, Vec<SearchHit>) {
+    let merged = merge_ranked_lists(lists.clone());
+    let candidates = if matches!(strategy, FusionStrategy::Rrf { .. }) {
+        lists.into_iter().map(|list| (retriever, list)).collect()
+    } else {
+        vec![(retriever, merged.clone())]
+    };
+    (candidates, merged)
+}
+
+/// Report `retriever`'s rank and raw score from `merged` for every fused hit it holds,
+/// leaving the fused contributions as they are.
+pub(crate) fn attribute_merged_ranks(
+    hits: &mut [SearchHit],
+    retriever: HybridRetriever,
+    merged: &[SearchHit],
+) {
+    let positions: HashMap<u64, &SearchHit> =
+        merged.iter().map(|hit| (hit.frame_id, hit)).collect();
+    for hit in hits {
+        let Some(source) = positions.get(&hit.frame_id) else {
+            continue;
+        };
+        for score in &mut hit.retriever_scores {
+            if score.retriever == retriever {
+                score.rank = source.rank;
+                score.score = source.score;
+            }
+        }
+    }
+}
+
+/// Fuse candidate lists with `strategy`, keeping at most `target` hits.
+///
+/// Returns `None` when every list is empty.
+pub(crate) fn fuse_hits(
+    mut lists: Vec<CandidateList>,
+    strategy: &FusionStrategy,
+    target: usize,
+) -> Option<Vec<SearchHit>> {
+    lists.retain(|(_, hits)| !hits.is_empty());
+    if lists.is_empty() {
+        return None;
+    }
+
+    let mut fused: HashMap<u64, Fused> = HashMap::new();
+    for (retriever, list) in &lists {
+        let normalized = list_scores(list, strategy);
+        for (idx, (hit, norm)) in list.iter().zip(normalized).enumerate() {
+            let rank = idx + 1;
+            let entry = fused.entry(hit.frame_id).or_insert_with(|| Fused {
+                hit: hit.clone(),
+                seen: HashMap::new(),
+                contributions: HashMap::new(),
+            });
+
+            // Keep the hit with more matches or earlier rank as the representative.
+            if hit.matches > entry.hit.matches
+                || (hit.matches == entry.hit.matches && rank < entry.hit.rank)
+            {
+                entry.hit = hit.clone();
+            }
+
+            let seen = entry.seen.entry(*retriever).or_insert((rank, hit.score));
+            if rank < seen.0 {
+                *seen = (rank, hit.score);
+            }
+
+            let contribution = entry.contributions.entry(*retriever).or_insert(0.0);
+            match *strategy {
+                FusionStrategy::Rrf { k } => {
+                    *contribution += 1.0 / (k + crate::count_to_f32(rank));
+                }
+                FusionStrategy::Linear { lexical, semantic } => {
+                    *contribution += weight_for(*retriever, lexical, semantic) * norm;
+                }
+                FusionStrategy::Convex { .. } => *contribution = contribution.max(norm),
+                FusionStrategy::Dbsf => *contribution += norm,
+            }
+        }
+    }
+
+    if let FusionStrategy::Convex { lexical, semantic } = *strategy {
+        let total = lexical + semantic;
+        for entry in fused.values_mut() {
+            for (retriever, contribution) in &mut entry.contributions {
+                *contribution *= weight_for(*retriever, lexical, semantic) / total;
+            }
+        }
+    }
+
+    let mut combined: Vec<(f32, SearchHit)> = fused
+        .into_values()
+        .map(|entry| {
+            let mut retriever_scores: Vec<RetrieverScore> = entry
+                .seen
+                .iter()
+                .map(|(retriever, (rank, score))| RetrieverScore {
+                    retriever: *retriever,
+                    rank: *rank,
+                    score: *score,
+                    contribution: entry.contributions.get(retriever).copied().unwrap_or(0.0),
+                })
+                .collect();
+            retriever_scores.sort_by_key(|score| score.retriever);
+            let total = retriever_scores
+                .iter()
+                .map(|score| score.contribution)
+                .sum();
+            let mut hit = entry.hit;
+            hit.retriever_scores = retriever_scores;
+            (total, hit)
+        })
+        .collect();
+
+    combined.sort_by(|a, b| {
+        b.0.partial_cmp(&a.0)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then(a.1.rank.cmp(&b.1.rank))
+            .then(a.1.frame_id.cmp(&b.1.frame_id))
+    });
+
+    let result: Vec<SearchHit> = combined
+        .into_iter()
+        .take(target.max(1))
+        .enumerate()
+        .map(|(idx, (score, mut hit))| {
+            hit.rank = idx + 1;
+            hit.score = Some(score);
+            hit
+        })
+        .collect();
+    Some(result)
+}
+
+fn weight_for(retriever: HybridRetriever, lexical: f32, semantic: f32) -> f32 {
+    match retriever {
+        HybridRetriever::Lexical | HybridRetriever::Sketch => lexical,
+        HybridRetriever::Semantic => semantic,
+    }
+}
+
+/// Per-hit normalised scores for score-based strategies (unused by RRF).
+fn list_scores(list: &[SearchHit], strategy: &FusionStrategy) -> Vec<f32> {
+    let raw: Vec<f32> = if list.iter().all(|hit| hit.score.is_some()) {
+        list.iter().map(|hit| hit.score.unwrap_or(0.0)).collect()
+    } else {
+        let count = crate::count_to_f32(list.len());
+        (0..list.len())
+            .map(|idx| 1.0 - crate::count_to_f32(idx) / count)
+            .collect()
+    };
+    match strategy {
+        FusionStrategy::Rrf { .. } => vec![0.0; raw.len()],
+        FusionStrategy::Linear { .. } | FusionStrategy::Convex { .. } => normalize_scores(&raw),
+        FusionStrategy::Dbsf => distribution_normalize(&raw),
+    }
+}
+
+/// Scale scores into `[0, 1]` using `mean ± 3 * stddev` as the bounds.
+fn distribution_normalize(scores: &[f32]) -> Vec<f32> {
+    let count = crate::count_to_f32(scores.len());
+    let mean = scores.iter().sum::<f32>() / count;
+    let variance = scores.iter().map(|s| (s - mean).powi(2)).sum::<f32>() / count;
+    let std_dev = variance.sqrt();
+    if std_dev < f32::EPSILON {
+        return vec![1.0; scores.len()];
+    }
+    let low = mean - 3.0 * std_dev;
+    scores
+        .iter()
+        .map(|s| ((s - low) / (6.0 * std_dev)).clamp(0.0, 1.0))
+        .collect()
+}
+
+impl Memvid {
+    /// Run lexical and vector retrieval for one query and fuse the lists with `strategy`.
+    ///
+    /// `query_embedding` must come from the model backing the vector index. Each hit's
+    /// `retriever_scores` records the rank, raw score and fused contribution from every
+    /// retriever that returned it. Without a lexical index only the vector list is used.
+    /// Pagination cursors are not supported.
+    pub fn search_hybrid(
+        &mut self,
+        request: SearchRequest,
+        query_embedding: &[f32],
+        strategy: &FusionStrategy,
+    ) -> Result<SearchResponse> {
+        validate_fusion(strategy)?;
+        if let Some(lambda) = request.mmr_lambda {
+            validate_mmr_lambda(lambda)?;
+        }
+        if request.cursor.is_some() {
+            return Err(MemvidError::InvalidQuery {
+                reason: "hybrid search does not support cursors".into(),
+            });
+        }
+
+        let start_time = Instant::now();
+        let pool = if request.mmr_lambda.is_some() {
+            request.top_k.saturating_mul(MMR_CANDIDATE_FACTOR)
+        } else {
+            request.top_k
+        }
+        .max(HYBRID_CANDIDATE_FLOOR);
+
+        let mut lexical_request = request.clone();
+        lexical_request.top_k = pool;
+        lexical_request.mmr_lambda = None;
+        let lexical_hits = match self.search(lexical_request) {
+            Ok(lexical) => lexical.hits,
+            Err(MemvidError::LexNotEnabled) => Vec::new(),
+            Err(err) => return Err(err),
+        };
+
+        let filter = VecSearchFilter::from(&request);
+        let semantic = self.vec_search_with_embedding(
+            &request.query,
+            query_embedding,
+            pool,
+            request.snippet_chars,
+            &filter,
+        )?;
+
+        let lists = vec![
+            (HybridRetriever::Lexical, lexical_hits),
+            (HybridRetriever::Semantic, semantic.hits),
+        ];
+        let mut hits = fuse_hits(lists, strategy, pool).unwrap_or_default();
+        if let Some(lambda) = request.mmr_lambda {
+            self.apply_mmr(&mut hits, lambda, request.top_k, None)?;
+        } else {
+            hits.truncate(request.top_k);
+        }
+
+        Ok(SearchResponse {
+            query: request.query,
+            elapsed_ms: start_time.elapsed().as_millis(),
+            total_hits: hits.len(),
+            params: SearchParams {
+                top_k: request.top_k,
+                snippet_chars: request.snippet_chars,
+                cursor: None,
+            },
+            context: build_context(&hits),
+            hits,
+            next_cursor: None,
+            engine: SearchEngineKind::Hybrid,
+            facets: Vec::new(),
+            suggestions: Vec::new(),
+            corrected_query: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hit(frame_id: u64, rank: usize, score: f32) -> SearchHit {
+        SearchHit {
+            rank,
+            frame_id,
+            uri: format!("mv2://doc/{frame_id}"),
+            title: None,
+            range: (0, 0),
+            text: String::new(),
+            matches: 1,
+            chunk_range: None,
+            chunk_text: None,
+            score: Some(score),
+            metadata: None,
+            retriever_scores: Vec::new(),
+            highlights: Vec::new(),
+            chunk_highlights: Vec::new(),
+            fragments: Vec::new(),
+            rerank: None,
+            expanded: None,
+        }
+    }
+
+    fn lists() -> Vec<CandidateList> {
+        vec![
+            (
+                HybridRetriever::Lexical,
+                vec![hit(1, 1, 12.0), hit(2, 2, 6.0), hit(3, 3, 1.0)],
+            ),
+            (
+                HybridRetriever::Semantic,
+                vec![hit(3, 1, 0.9), hit(2, 2, 0.8), hit(4, 3, 0.1)],
+            ),
+        ]
+    }
+
+    #[test]
+    fn rrf_rewards_agreement_and_reports_breakdown() {
+        let fused = fuse_hits(lists(), &FusionStrategy::default(), 10).unwrap();
+        assert_eq!(fused[0].frame_id, 3);
+        let breakdown = &fused[0].retriever_scores;
+        assert_eq!(breakdown.len(), 2);
+        assert_eq!(breakdown[0].retriever, HybridRetriever::Lexical);
+        assert_eq!(breakdown[0].rank, 3);
+        assert_eq!(breakdown[1].score, Some(0.9));
+        let total: f32 = breakdown.iter().map(|s| s.contribution).sum();
+        assert!((fused[0].score.unwrap() - total).abs() < 1e-6);
+    }
+
+    #[test]
+    fn weights_steer_linear_and_convex_fusion() {
+        for strategy in [
+            FusionStrategy::Linear {
+                lexical: 1.0,
+                semantic: 0.0,
+            },
+            FusionStrategy::Convex {
+                lexical: 2.0,
+                semantic: 0.0,
+            },
+        ] {
+            let fused = fuse_hits(lists(), &strategy, 10).unwrap();
+            assert_eq!(fused[0].frame_id, 1, "{strategy:?}");
+        }
+        let fused = fuse_hits(
+            lists(),
+            &FusionStrategy::Convex {
+                lexical: 0.0,
+                semantic: 1.0,
+            },
+            10,
+        )
+        .unwrap();
+        assert_eq!(fused[0].frame_id, 3);
+        assert!(fused.iter().all(|hit| hit.score.unwrap() <= 1.0));
+    }
+
+    #[test]
+    fn dbsf_normalises_each_list() {
+        let fused = fuse_hits(lists(), &FusionStrategy::Dbsf, 2).unwrap();
+        assert_eq!(fused.len(), 2);
+        assert_eq!(fused[0].rank, 1);
+        assert!(fused[0].score >= fused[1].score);
+    }
+
+    #[test]
+    fn merged_lists_count_each_frame_once() {
+        let merged = merge_ranked_lists(vec![
+            vec![hit(1, 1, 3.0), hit(2, 2, 2.0)],
+            vec![hit(2, 1, 5.0), hit(3, 2, 1.0)],
+        ]);
+        let frames: Vec<u64> = merged.iter().map(|hit| hit.frame_id).collect();
+        assert_eq!(frames, vec![1, 2, 3]);
+        assert_eq!(merged[1].rank, 2);
+
+        let fused = fuse_hits(
+            vec![(HybridRetriever::Lexical, merged)],
+            &FusionStrategy::default(),
+            10,
+        )
+        .unwrap();
+        assert!(fused.iter().all(|hit| hit.retriever_scores.len() == 1));
+        assert_eq!(fused[0].frame_id, 1);
+    }
+
+    #[test]
+    fn rrf_keeps_agreement_between_lexical_variants() {
+        // Frame 1 tops both lexical variants; frame 3 only the second variant and the
+        // semantic list.
+        let variants = vec![vec![hit(1, 1, 4.0)], vec![hit(1, 1, 4.0), hit(3, 2, 2.0)]];
+        let semantic = vec![hit(3, 1, 0.9), hit(4, 2, 0.8), hit(1, 3, 0.7)];
+
+        let strategy = FusionStrategy::default();
+        let (mut lists, merged) =
+            variant_lists(HybridRetriever::Lexical, variants.clone(), &strategy);
+        assert_eq!(lists.len(), 2);
+        lists.push((HybridRetriever::Semantic, semantic.clone()));
+        let mut fused = fuse_hits(lists, &strategy, 10).unwrap();
+        attribute_merged_ranks(&mut fused, HybridRetriever::Lexical, &merged);
+        let frames: Vec<u64> = fused.iter().map(|hit| hit.frame_id).collect();
+        assert_eq!(frames, vec![1, 3, 4]);
+        let lexical = &fused[1].retriever_scores[0];
+        assert_eq!(lexical.retriever, HybridRetriever::Lexical);
+        assert_eq!(lexical.rank, 2);
+        assert!((fused[0].retriever_scores[0].contribution - 2.0 / 61.0).abs() < 1e-6);
+
+        // Score-based strategies see the variants once, merged.
+        let linear = FusionStrategy::Linear {
+            lexical: 1.0,
+            semantic: 1.0,
+        };
+        let (lists, _) = variant_lists(HybridRetriever::Lexical, variants, &linear);
+        assert_eq!(lists.len(), 1);
+        assert_eq!(lists[0].1.len(), 2);
+    }
+
+    #[test]
+    fn hybrid_search_without_lex_uses_vectors_only() {
+        crate::run_serial_test(|| {
+            let dir = tempfile::tempdir().expect("tmp");
+            let mut mem = Memvid::create(dir.path().join("hybrid.mv2")).expect("create");
+            mem.enable_vec().expect("vec");
+            for (content, embedding) in [("alpha", vec![1.0, 0.0]), ("beta", vec![0.0, 1.0])] {
+                mem.put_with_embedding_and_options(
+                    content.as_bytes(),
+                    embedding,
+                    crate::PutOptions::default(),
+                )
+                .expect("put");
+            }
+            mem.commit().expect("commit");
+            mem.lex_enabled = false;
+
+            let request = SearchRequest {
+                query: "alpha".to_string(),
+                top_k: 2,
+                snippet_chars: 100,
+                ..SearchRequest::default()
+            };
+            let response = mem
+                .search_hybrid(request, &[0.0, 1.0], &FusionStrategy::default())
+                .expect("hybrid");
+            assert_eq!(response.hits.len(), 2);
+            assert!(response.hits[0].text.contains("beta"));
+            assert!(response.hits.iter().all(|hit| {
+                hit.retriever_scores
+                    .iter()
+                    .all(|score| score.retriever == HybridRetriever::Semantic)
+            }));
+        });
+    }
+
+    #[test]
+    fn invalid_fusion_parameters_are_rejected() {
+        assert!(validate_fusion(&FusionStrategy::Rrf { k: f32::NAN }).is_err());
+        assert!(
+            validate_fusion(&FusionStrategy::Linear {
+                lexical: 0.0,
+                semantic: 0.0
+            })
+            .is_err()
+        );
+        assert!(
+            validate_fusion(&FusionStrategy::Convex {
+                lexical: -1.0,
+                semantic: 2.0
+            })
+            .is_err()
+        );
+    }
+}
@@ -1,14 +1,23 @@
+#[cfg(feature = "temporal_track")]
 use crate::MemvidError;
+#[cfg(feature = "temporal_track")]
 use crate::Result;
 use crate::memvid::lifecycle::Memvid;
+#[cfg(feature = "lex")]
+use crate::search::facets::FacetCounter;
 #[cfg(not(feature = "temporal_track"))]
 #[allow(unused_imports)]
 use crate::types::FrameId;
+#[cfg(feature = "lex")]
+use crate::types::{FacetRequest, FacetResult};
 #[cfg(feature = "temporal_track")]
 use crate::types::{
     FrameId, SearchHitTemporal, SearchHitTemporalAnchor, SearchHitTemporalMention, TemporalMention,
 };
-use crate::types::{SearchEngineKind, SearchHit, SearchHitMetadata, SearchParams, SearchResponse};
+use crate::types::{
+    HighlightFragment, HighlightOptions, SearchEngineKind, SearchHit, SearchHitMetadata,
+    SearchParams, SearchResponse, SpellingSuggestion,
+};
 #[cfg(feature = "temporal_track")]
 use std::collections::HashMap;
 #[cfg(feature = "temporal_track")]
@@ -31,7 +40,63 @@ pub(super) fn empty_search_response(
         context: String::new(),
         next_cursor: None,
         engine,
+        facets: Vec::new(),
+        suggestions: Vec::new(),
+        corrected_query: None,
+    }
+}
+
+/// Facet counts over the TOC entries of `frame_ids`; empty when none were requested.
+#[cfg(feature = "lex")]
+pub(super) fn frame_facets(
+    memvid: &Memvid,
+    frame_ids: impl IntoIterator<Item = FrameId>,
+    request: Option<&FacetRequest>,
+) -> Vec<FacetResult> {
+    let Some(request) = request else {
+        return Vec::new();
+    };
+    let mut counter = FacetCounter::new(request);
+    for frame_id in frame_ids.into_iter().collect::<StdHashSet<_>>() {
+        let frame = usize::try_from(frame_id)
+            .ok()
+            .and_then(|index| memvid.toc.frames.get(index));
+        if let Some(frame) = frame {
+            counter.add_frame(frame);
+        }
+    }
+    counter.finish()
+}
+
+/// `query` with every whole word that has a suggestion replaced by it; operators,
+/// field filters and spacing are kept as typed.
+pub(super) fn apply_spelling_suggestions(
+    query: &str,
+    suggestions: &[SpellingSuggestion],
+) -> String {
+    let mut corrected = String::with_capacity(query.len());
+    let mut word_start: Option<usize> = None;
+    let flush = |corrected: &mut String, word: &str| {
+        let lower = word.to_lowercase();
+        match suggestions.iter().find(|entry| entry.word == lower) {
+            Some(entry) => corrected.push_str(&entry.suggestion),
+            None => corrected.push_str(word),
+        }
+    };
+    for (index, ch) in query.char_indices() {
+        if ch.is_alphanumeric() {
+            word_start.get_or_insert(index);
+            continue;
+        }
+        if let Some(start) = word_start.take() {
+            flush(&mut corrected, &query[start..index]);
+        }
+        corrected.push(ch);
     }
+    if let Some(start) = word_start {
+        flush(&mut corrected, &query[start..]);
+    }
+    corrected
 }
 
 pub(super) fn timestamp_to_rfc3339(timestamp: i64) -> Option<String> {
@@ -43,27 +108,6 @@ pub(super) fn timestamp_to_rfc3339(timestamp: i64) -> Option<String> {
         })
 }
 
-pub(super) fn parse_cursor(cursor: Option<&str>, total_hits: usize) -> Result<usize> {
-    let Some(token) = cursor else {
-        return Ok(0);
-    };
-    let trimmed = token.trim();
-    if trimmed.is_empty() {
-        return Ok(0);
-    }
-    let value = trimmed
-        .parse::<usize>()
-        .map_err(|_| MemvidError::InvalidCursor {
-            reason: "cursor not an integer",
-        })?;
-    if value > total_hits {
-        return Err(MemvidError::InvalidCursor {
-            reason: "cursor beyond total hits",
-        });
-    }
-    Ok(value)
-}
-
 /// Build context for LLM from search hits using a multi-document strategy.
 ///
 /// Key design decisions for deterministic, comprehensive context:
@@ -201,6 +245,130 @@ pub(super) fn collect_token_occurrences(
     occurrences
 }
 
+/// Fills a hit's highlight fields from match ranges within its `chunk_text`.
+///
+/// `text_offset` is where the hit's `text` starts inside `chunk_text`.
+pub(super) fn apply_highlights(
+    hit: &mut SearchHit,
+    chunk_highlights: &[(usize, usize)],
+    text_offset: usize,
+    options: &HighlightOptions,
+    snippet_chars: usize,
+) {
+    let chunk_len = hit.chunk_text.as_ref().map_or(0, String::len);
+    let ranges = merge_ranges(chunk_highlights, chunk_len);
+    let text_end = text_offset + hit.text.len();
+    hit.highlights = ranges
+        .iter()
+        .filter(|(start, end)| *start >= text_offset && *end <= text_end)
+        .map(|(start, end)| (start - text_offset, end - text_offset))
+        .collect();
+    if let Some(chunk_text) = hit.chunk_text.as_deref() {
+        hit.fragments = highlight_fragments(
+            chunk_text,
+            &ranges,
+            options.fragment_chars.unwrap_or(snippet_chars),
+            options.fragments,
+        );
+    }
+    hit.chunk_highlights = ranges;
+}
+
+/// Sorted ranges clipped to `limit`, with overlapping ranges merged.
+fn merge_ranges(ranges: &[(usize, usize)], limit: usize) -> Vec<(usize, usize)> {
+    let mut sorted: Vec<(usize, usize)> = ranges
+        .iter()
+        .map(|(start, end)| (*start, (*end).min(limit)))
+        .filter(|(start, end)| start < end)
+        .collect();
+    sorted.sort_unstable();
+    let mut merged: Vec<(usize, usize)> = Vec::with_capacity(sorted.len());
+    for (start, end) in sorted {
+        match merged.last_mut() {
+            Some(last) if start < last.1 => last.1 = last.1.max(end),
+            _ => merged.push((start, end)),
+        }
+    }
+    merged
+}
+
+/// Picks up to `count` non-overlapping windows of at most `max_chars` characters
+/// holding the most highlights, then pads each with surrounding context.
+fn highlight_fragments(
+    text: &str,
+    highlights: &[(usize, usize)],
+    max_chars: usize,
+    count: usize,
+) -> Vec<HighlightFragment> {
+    if count == 0 || highlights.is_empty() {
+        return Vec::new();
+    }
+    let mut candidates: Vec<(usize, usize, usize)> = Vec::with_capacity(highlights.len());
+    for (index, &(start, _)) in highlights.iter().enumerate() {
+        let mut end = start;
+        let mut score = 0;
+        for &(_, highlight_end) in &highlights[index..] {
+            if score > 0 && text[start..highlight_end].chars().count() > max_chars {
+                break;
+            }
+            end = highlight_end;
+            score += 1;
+        }
+        candidates.push((score, start, end));
+    }
+    candidates.sort_by(|left, right| right.0.cmp(&left.0).then(left.1.cmp(&right.1)));
+
+    let mut chosen: Vec<(usize, usize)> = Vec::new();
+    for (_, start, end) in candidates {
+        if chosen.len() == count {
+            break;
+        }
+        if chosen
+            .iter()
+            .all(|(left, right)| end <= *left || start >= *right)
+        {
+            chosen.push((start, end));
+        }
+    }
+    chosen.sort_unstable();
+
+    for index in 0..chosen.len() {
+        let (start, end) = chosen[index];
+        let lower = if index == 0 { 0 } else { chosen[index - 1].1 };
+        let upper = chosen.get(index + 1).map_or(text.len(), |next| next.0);
+        let spare = max_chars.saturating_sub(text[start..end].chars().count());
+        let padded_start = text[lower..start]
+            .char_indices()
+            .rev()
+            .take(spare / 2)
+            .last()
+            .map_or(start, |(pos, _)| lower + pos);
+        let remaining = spare - text[padded_start..start].chars().count();
+        let padded_end = text[end..upper]
+            .char_indices()
+            .nth(remaining)
+            .map_or(upper, |(pos, _)| end + pos);
+        chosen[index] = (padded_start, padded_end);
+    }
+
+    chosen
+        .into_iter()
+        .map(|(start, end)| HighlightFragment {
+            range: (start, end),
+            text: text[start..end].to_string(),
+            highlights: highlights
+                .iter()
+                .filter(|(highlight_start, highlight_end)| {
+                    *highlight_start >= start && *highlight_end <= end
+                })
+                .map(|(highlight_start, highlight_end)| {
+                    (highlight_start - start, highlight_end - start)
+                })
+                .collect(),
+        })
+        .collect()
+}
+
 pub(crate) fn reorder_hits_by_token_matches(hits: &mut Vec<SearchHit>, tokens: &[String]) {
     if hits.is_empty() || tokens.is_empty() {
         return;
@@ -417,3 +585,53 @@ pub(super) fn enrich_hits_with_entities(hits: &mut [SearchHit], memvid: &Memvid)
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fragments_cover_densest_matches() {
+        let text = "alpha beta gamma. filler words here. beta beta close together.";
+        let highlights: Vec<(usize, usize)> = text
+            .match_indices("beta")
+            .map(|(start, word)| (start, start + word.len()))
+            .collect();
+        let fragments = highlight_fragments(text, &highlights, 20, 1);
+        assert_eq!(fragments.len(), 1);
+        assert_eq!(fragments[0].highlights, vec![(5, 9), (10, 14)]);
+        assert!(fragments[0].text.chars().count() <= 20);
+        let (start, end) = fragments[0].range;
+        assert_eq!(&text[start..end], fragments[0].text);
+
+        let both = highlight_fragments(text, &highlights, 20, 3);
+        assert_eq!(both.len(), 2);
+        assert!(both[0].range.1 <= both[1].range.0);
+        assert!(highlight_fragments(text, &[], 20, 1).is_empty());
+    }
+
+    #[test]
+    fn spelling_suggestions_replace_whole_words_only() {
+        let suggestions = [SpellingSuggestion {
+            word: "volcanoe".to_string(),
+            suggestion: "volcano".to_string(),
+            distance: 1,
+            doc_frequency: 3,
+        }];
+        assert_eq!(
+            apply_spelling_suggestions(
+                "Volcanoe AND tag:volcanoes \"active volcanoe\"",
+                &suggestions
+            ),
+            "volcano AND tag:volcanoes \"active volcano\""
+        );
+    }
+
+    #[test]
+    fn merge_ranges_joins_overlaps() {
+        assert_eq!(
+            merge_ranges(&[(4, 8), (0, 2), (6, 10), (12, 40)], 20),
+            vec![(0, 2), (4, 10), (12, 20)]
+        );
+    }
+}
@@ -0,0 +1,197 @@
+//! Maximal Marginal Relevance (MMR) re-selection of ranked hits.
+//!
+//! Near-duplicate chunks of the same document tend to occupy every `top_k` slot. MMR
+//! greedily picks the hit that maximises `lambda * relevance - (1 - lambda) * redundancy`,
+//! where redundancy is the highest similarity to any hit already selected.
+//!
+//! Relevance is derived from the incoming order (`1 - position / n`), so lexical, vector
+//! and fused lists with incomparable score scales are handled alike. Similarity uses the
+//! stored embeddings when every candidate has one, and the `SimHash` of the hit text
+//! otherwise.
+
+use crate::memvid::lifecycle::Memvid;
+use crate::types::{
+    DistanceMetric, SearchHit, compute_simhash, compute_token_weights, tokenize_for_sketch,
+};
+use crate::{MemvidError, Result};
+
+/// Candidates fetched per requested hit so MMR has alternatives to choose from.
+pub(crate) const MMR_CANDIDATE_FACTOR: usize = 4;
+
+/// Representation used to compare two hits.
+enum HitSignature {
+    Embedding(Vec<f32>),
+    SimHash(u64),
+}
+
+impl HitSignature {
+    fn similarity(&self, other: &Self) -> f32 {
+        match (self, other) {
+            (Self::Embedding(a), Self::Embedding(b)) => {
+                let metric = DistanceMetric::Cosine;
+                metric.similarity(metric.distance(a, b))
+            }
+            (Self::SimHash(a), Self::SimHash(b)) => {
+                let differing = crate::count_to_f32((a ^ b).count_ones() as usize);
+                1.0 - differing / 64.0
+            }
+            _ => 0.0,
+        }
+    }
+}
+
+/// Reject lambdas outside `[0, 1]`.
+pub(crate) fn validate_mmr_lambda(lambda: f32) -> Result<()> {
+    if (0.0..=1.0).contains(&lambda) {
+        Ok(())
+    } else {
+        Err(MemvidError::InvalidQuery {
+            reason: "mmr lambda must be between 0 and 1".into(),
+        })
+    }
+}
+
+impl Memvid {
+    /// Re-select `target` hits from `hits` with MMR and renumber their ranks.
+    ///
+    /// Embeddings are read from `space` when given, otherwise from the default index.
+    pub(crate) fn apply_mmr(
+        &mut self,
+        hits: &mut Vec<SearchHit>,
+        lambda: f32,
+        target: usize,
+        space: Option<&str>,
+    ) -> Result<()> {
+        validate_mmr_lambda(lambda)?;
+        if hits.is_empty() {
+            return Ok(());
+        }
+        let signatures = self.hit_signatures(hits, space)?;
+        let order = mmr_order(&signatures, lambda, target);
+
+        let mut slots: Vec<Option<SearchHit>> = hits.drain(..).map(Some).collect();
+        hits.extend(order.into_iter().filter_map(|idx| slots[idx].take()));
+        for (idx, hit) in hits.iter_mut().enumerate() {
+            hit.rank = idx + 1;
+        }
+        Ok(())
+    }
+
+    /// MMR-order all of `hits` in consecutive windows of `window` candidates and
+    /// renumber their ranks.
+    ///
+    /// Each window is ordered on its own, so the order of a prefix does not depend on how
+    /// many candidates follow it: a wider pool only appends to the order a narrower one
+    /// produced, which keeps paged results consistent.
+    pub(crate) fn apply_mmr_windows(
+        &mut self,
+        hits: &mut Vec<SearchHit>,
+        lambda: f32,
+        window: usize,
+    ) -> Result<()> {
+        let mut rest = std::mem::take(hits);
+        while !rest.is_empty() {
+            let tail = rest.split_off(window.max(1).min(rest.len()));
+            let len = rest.len();
+            self.apply_mmr(&mut rest, lambda, len, None)?;
+            hits.append(&mut rest);
+            rest = tail;
+        }
+        for (idx, hit) in hits.iter_mut().enumerate() {
+            hit.rank = idx + 1;
+        }
+        Ok(())
+    }
+
+    fn hit_signatures(
+        &mut self,
+        hits: &[SearchHit],
+        space: Option<&str>,
+    ) -> Result<Vec<HitSignature>> {
+        if self.vec_enabled {
+            let mut embeddings = Vec::with_capacity(hits.len());
+            for hit in hits {
+                let embedding = match space {
+                    Some(space) => self.frame_space_embedding(space, hit.frame_id)?,
+                    None => self.frame_embedding(hit.frame_id)?,
+                };
+                match embedding {
+                    Some(embedding) => embeddings.push(HitSignature::Embedding(embedding)),
+                    None => break,
+                }
+            }
+            if embeddings.len() == hits.len() {
+                return Ok(embeddings);
+            }
+        }
+        Ok(hits
+            .iter()
+            .map(|hit| {
+                let text = hit.chunk_text.as_deref().unwrap_or(&hit.text);
+                let tokens = tokenize_for_sketch(text);
+                HitSignature::SimHash(compute_simhash(&compute_token_weights(&tokens, None)))
+            })
+            .collect())
+    }
+}
+
+/// Indices of the MMR selection, in selection order.
+fn mmr_order(signatures: &[HitSignature], lambda: f32, target: usize) -> Vec<usize> {
+    let count = crate::count_to_f32(signatures.len());
+    let mut remaining: Vec<usize> = (0..signatures.len()).collect();
+    // Highest similarity of each remaining candidate to the selection so far.
+    let mut redundancy = vec![0.0f32; signatures.len()];
+    let mut selected = Vec::with_capacity(target.min(signatures.len()));
+
+    while selected.len() < target && !remaining.is_empty() {
+        let mut best = 0;
+        let mut best_score = f32::NEG_INFINITY;
+        for (slot, &idx) in remaining.iter().enumerate() {
+            let relevance = 1.0 - crate::count_to_f32(idx) / count;
+            let score = lambda * relevance - (1.0 - lambda) * redundancy[idx];
+            if score > best_score {
+                best = slot;
+                best_score = score;
+            }
+        }
+        let chosen = remaining.remove(best);
+        for &idx in &remaining {
+            let similarity = signatures[chosen].similarity(&signatures[idx]);
+            redundancy[idx] = redundancy[idx].max(similarity);
+        }
+        selected.push(chosen);
+    }
+    selected
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mmr_skips_near_duplicates() {
+        let signatures = vec![
+            HitSignature::Embedding(vec![1.0, 0.0]),
+            HitSignature::Embedding(vec![0.99, 0.01]),
+            HitSignature::Embedding(vec![0.98, 0.02]),
+            HitSignature::Embedding(vec![0.0, 1.0]),
+        ];
+        assert_eq!(mmr_order(&signatures, 0.5, 2), vec![0, 3]);
+        // lambda = 1 keeps the incoming ranking.
+        assert_eq!(mmr_order(&signatures, 1.0, 3), vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn simhash_similarity_spans_unit_range() {
+        let a = HitSignature::SimHash(0);
+        assert!((a.similarity(&HitSignature::SimHash(0)) - 1.0).abs() < f32::EPSILON);
+        assert!(a.similarity(&HitSignature::SimHash(u64::MAX)).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn lambda_outside_unit_range_is_rejected() {
+        assert!(validate_mmr_lambda(0.7).is_ok());
+        assert!(validate_mmr_lambda(1.5).is_err());
+        assert!(validate_mmr_lambda(f32::NAN).is_err());
+    }
+}
@@ -16,10 +16,19 @@ use crate::{MemvidError, Result};
 
 mod api;
 mod builders;
+pub(crate) mod cursor;
 #[cfg(feature = "lex")]
 mod fallback;
+#[cfg(feature = "lex")]
+pub(crate) mod fusion;
 pub(crate) mod helpers;
 #[cfg(feature = "lex")]
+pub(crate) mod mmr;
+#[cfg(feature = "lex")]
+mod rerank;
+#[cfg(feature = "lex")]
+mod similar;
+#[cfg(feature = "lex")]
 mod tantivy;
 #[cfg(any(feature = "lex", feature = "temporal_track"))]
 mod time_filter;
@@ -29,6 +38,8 @@ pub use api::{
     DEFAULT_MAX_INDEX_PAYLOAD, is_frame_text_indexable, is_text_indexable_mime, max_index_payload,
 };
 
+#[cfg(feature = "lex")]
+use cursor::{CursorAnchor, Pager, rebind_cursor, search_query_hash};
 #[cfg(feature = "lex")]
 use fallback::{search_with_filters_only, search_with_lex_fallback};
 use helpers::empty_search_response;
@@ -68,6 +79,42 @@ impl Memvid {
             });
         }
 
+        // MMR and the reranker reorder a candidate pool wider than `top_k`; pages then
+        // walk that reordered pool, so the engine itself always serves the pool from the
+        // start. The pool grows with the entries earlier pages handed out, plus one to
+        // tell whether another page follows, so paging only ends with the matches.
+        let requested_top_k = request.top_k;
+        let first_page = request.cursor.is_none();
+        let query_hash = search_query_hash(&request);
+        let original_cursor = request.cursor.clone();
+        let mut request = request;
+        let reordered_pager = if request.mmr_lambda.is_some() || request.rerank {
+            let pager = Pager::new(self, request.cursor.as_deref(), query_hash)?;
+            Some((pager, request.cursor.take()))
+        } else {
+            None
+        };
+        let pool_needed = reordered_pager.as_ref().map_or(0, |(pager, _)| {
+            pager
+                .offset_hint()
+                .saturating_add(requested_top_k.max(1))
+                .saturating_add(1)
+        });
+        let mmr_window = requested_top_k
+            .max(1)
+            .saturating_mul(mmr::MMR_CANDIDATE_FACTOR);
+        if let Some(lambda) = request.mmr_lambda {
+            mmr::validate_mmr_lambda(lambda)?;
+            // Whole windows, so each is diversified the same way on every page.
+            request.top_k = pool_needed.div_ceil(mmr_window).saturating_mul(mmr_window);
+        }
+        if request.rerank {
+            request.top_k = request
+                .top_k
+                .max(self.reranker_candidates()?)
+                .max(pool_needed);
+        }
+
         let params = SearchParams {
             top_k: request.top_k,
             snippet_chars: request.snippet_chars,
@@ -256,11 +303,67 @@ impl Memvid {
             }
         };
 
+        // Zero hits: suggest indexed spellings for unknown words, and retry with them
+        // when the caller opted in. Auto-corrected searches keep retrying on later pages.
+        if response.hits.is_empty()
+            && (first_page || (request.auto_correct && response.total_hits == 0))
+        {
+            let words = parsed.spelling_words();
+            let suggestions = match self.tantivy.as_ref() {
+                Some(engine) if !words.is_empty() => engine.spelling_suggestions(&words)?,
+                _ => Vec::new(),
+            };
+            if !suggestions.is_empty() {
+                let corrected = helpers::apply_spelling_suggestions(&request.query, &suggestions);
+                if request.auto_correct {
+                    let mut retry = request.clone();
+                    retry.query.clone_from(&corrected);
+                    retry.top_k = requested_top_k;
+                    retry.auto_correct = false;
+                    // Cursors stay bound to the query as the caller wrote it.
+                    let retry_hash = search_query_hash(&retry);
+                    retry.cursor = original_cursor
+                        .as_deref()
+                        .filter(|token| !token.trim().is_empty())
+                        .map(|token| rebind_cursor(token, query_hash, retry_hash))
+                        .transpose()?;
+                    let mut retried = self.search(retry)?;
+                    retried.next_cursor = retried
+                        .next_cursor
+                        .as_deref()
+                        .map(|token| rebind_cursor(token, retry_hash, query_hash))
+                        .transpose()?;
+                    retried.params.cursor = original_cursor;
+                    retried.suggestions = suggestions;
+                    retried.corrected_query = Some(corrected);
+                    return Ok(retried);
+                }
+                response.suggestions = suggestions;
+                response.corrected_query = Some(corrected);
+            }
+        }
+
+        if let Some((pager, cursor)) = reordered_pager {
+            response.hits.retain(|hit| pager.admits(hit.frame_id));
+            if request.rerank {
+                self.apply_reranker(&request.query, &mut response.hits)?;
+            }
+            if let Some(lambda) = request.mmr_lambda {
+                self.apply_mmr_windows(&mut response.hits, lambda, mmr_window)?;
+            }
+            page_reordered_hits(&mut response, &pager, requested_top_k)?;
+            response.params.cursor = cursor;
+        }
+
         // Enrich hits with Logic-Mesh entities if mesh is available
         if self.has_logic_mesh() {
             helpers::enrich_hits_with_entities(&mut response.hits, self);
         }
 
+        if let Some(expansion) = request.expand {
+            self.expand_hits(&mut response.hits, expansion)?;
+        }
+
         // Record the search action if a replay session is active
         #[cfg(feature = "replay")]
         {
@@ -277,6 +380,35 @@ impl Memvid {
     }
 }
 
+/// Cut the page the cursor points at out of hits reordered after retrieval, and
+/// point `next_cursor` past its last hit.
+#[cfg(feature = "lex")]
+fn page_reordered_hits(response: &mut SearchResponse, pager: &Pager, top_k: usize) -> Result<()> {
+    let anchors: Vec<CursorAnchor> = response
+        .hits
+        .iter()
+        .map(|hit| CursorAnchor::Reordered {
+            frame_id: hit.frame_id,
+            slice: hit.range.0,
+        })
+        .collect();
+    let start = pager.start(&anchors);
+    let end = start.saturating_add(top_k.max(1)).min(anchors.len());
+    response.hits.truncate(end);
+    response.hits.drain(..start);
+    for (idx, hit) in response.hits.iter_mut().enumerate() {
+        hit.rank = idx + 1;
+    }
+    response.next_cursor = if end < anchors.len() {
+        Some(pager.next(end, Some(anchors[end - 1]))?)
+    } else {
+        None
+    };
+    response.params.top_k = top_k;
+    response.context = helpers::build_context(&response.hits);
+    Ok(())
+}
+
 #[cfg(not(feature = "lex"))]
 impl Memvid {
     pub fn search(&mut self, _request: SearchRequest) -> Result<SearchResponse> {
@@ -0,0 +1,166 @@
+//! Second-stage reranking of ranked hits with a registered [`Reranker`].
+//!
+//! The first `max_candidates` hits are handed to the reranker as
+//! `RerankerDocument`s built from their chunk text (plus title and tags when
+//! `use_metadata` is set); document ids are candidate positions. Hits come back in
+//! the reranker's order, each recording its score and its rank before and after
+//! the stage. Candidates the reranker omits or scores below `min_score` are
+//! dropped; hits beyond the candidate pool keep their order after the reranked ones.
+
+use std::sync::Arc;
+
+use crate::memvid::lifecycle::Memvid;
+use crate::types::reranker::{Reranker, RerankerConfig, RerankerDocument};
+use crate::types::{SearchHit, SearchHitRerank};
+use crate::{MemvidError, Result};
+
+impl Memvid {
+    /// Register the reranker used by searches and asks that set `rerank`.
+    pub fn set_reranker(&mut self, reranker: Arc<dyn Reranker>, config: RerankerConfig) {
+        self.reranker = Some((reranker, config));
+    }
+
+    /// Remove the registered reranker.
+    pub fn clear_reranker(&mut self) {
+        self.reranker = None;
+    }
+
+    /// Candidates the registered reranker considers, or an error when none is ready.
+    pub(crate) fn reranker_candidates(&self) -> Result<usize> {
+        self.ready_reranker()
+            .map(|(_, config)| config.max_candidates.max(1))
+    }
+
+    /// Reorder `hits` for `query` with the registered reranker and renumber their ranks.
+    pub(crate) fn apply_reranker(&self, query: &str, hits: &mut Vec<SearchHit>) -> Result<()> {
+        let (reranker, config) = self.ready_reranker()?;
+        if hits.is_empty() {
+            return Ok(());
+        }
+        let pool = hits.len().min(config.max_candidates.max(1));
+        let documents: Vec<RerankerDocument> = hits[..pool]
+            .iter()
+            .enumerate()
+            .map(|(idx, hit)| reranker_document(idx as u64, hit, config.use_metadata))
+            .collect();
+        let results = reranker.rerank(query, &documents, pool)?;
+
+        let rest = hits.split_off(pool);
+        let mut slots: Vec<Option<SearchHit>> = hits.drain(..).map(Some).collect();
+        for result in results {
+            if result.score < config.min_score {
+                continue;
+            }
+            let Some(idx) = usize::try_from(result.id)
+                .ok()
+                .filter(|idx| *idx < slots.len())
+            else {
+                continue;
+            };
+            if let Some(mut hit) = slots[idx].take() {
+                hit.rerank = Some(SearchHitRerank {
+                    score: result.score,
+                    original_rank: idx + 1,
+                    new_rank: hits.len() + 1,
+                });
+                hits.push(hit);
+            }
+        }
+        hits.extend(rest);
+        for (idx, hit) in hits.iter_mut().enumerate() {
+            hit.rank = idx + 1;
+        }
+        Ok(())
+    }
+
+    fn ready_reranker(&self) -> Result<(&Arc<dyn Reranker>, &RerankerConfig)> {
+        let Some((reranker, config)) = self.reranker.as_ref() else {
+            return Err(MemvidError::InvalidQuery {
+                reason: "rerank requested but no reranker is set".into(),
+            });
+        };
+        if !reranker.is_ready() {
+            return Err(MemvidError::InvalidQuery {
+                reason: format!("reranker '{}' is not ready", reranker.kind()),
+            });
+        }
+        Ok((reranker, config))
+    }
+}
+
+fn reranker_document(id: u64, hit: &SearchHit, use_metadata: bool) -> RerankerDocument {
+    let text = hit.chunk_text.as_deref().unwrap_or(&hit.text);
+    if !use_metadata {
+        return RerankerDocument::new(id, text);
+    }
+    let mut metadata: Vec<String> = Vec::new();
+    if let Some(title) = hit
+        .title
+        .as_deref()
+        .filter(|title| !title.trim().is_empty())
+    {
+        metadata.push(format!("title: {}", title.trim()));
+    }
+    if let Some(tags) = hit
+        .metadata
+        .as_ref()
+        .map(|metadata| &metadata.tags)
+        .filter(|tags| !tags.is_empty())
+    {
+        metadata.push(format!("tags: {}", tags.join(", ")));
+    }
+    if metadata.is_empty() {
+        RerankerDocument::new(id, text)
+    } else {
+        RerankerDocument::with_metadata(id, text, metadata.join("\n"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::SearchHitMetadata;
+
+    fn hit(title: Option<&str>, tags: &[&str]) -> SearchHit {
+        SearchHit {
+            rank: 1,
+            frame_id: 7,
+            uri: "mv2://doc".to_string(),
+            title: title.map(str::to_string),
+            range: (0, 4),
+            text: "full text".to_string(),
+            matches: 1,
+            chunk_range: Some((0, 4)),
+            chunk_text: Some("chunk".to_string()),
+            score: None,
+            metadata: Some(SearchHitMetadata {
+                tags: tags.iter().map(|tag| (*tag).to_string()).collect(),
+                ..SearchHitMetadata::default()
+            }),
+            retriever_scores: Vec::new(),
+            highlights: Vec::new(),
+            chunk_highlights: Vec::new(),
+            fragments: Vec::new(),
+            rerank: None,
+            expanded: None,
+        }
+    }
+
+    #[test]
+    fn reranker_document_adds_metadata_on_request() {
+        let plain = reranker_document(3, &hit(Some("Runbook"), &["ops"]), false);
+        assert_eq!((plain.id, plain.text.as_str()), (3, "chunk"));
+        assert!(plain.metadata.is_none());
+
+        let rich = reranker_document(3, &hit(Some("Runbook"), &["ops", "urgent"]), true);
+        assert_eq!(
+            rich.metadata.as_deref(),
+            Some("title: Runbook\ntags: ops, urgent")
+        );
+        assert!(
+            reranker_document(3, &hit(None, &[]), true)
+                .metadata
+                .is_none()
+        );
+    }
+}
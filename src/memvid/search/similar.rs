@@ -0,0 +1,276 @@
+//! "More like this" retrieval seeded by an existing frame.
+//!
+//! Up to three candidate lists are built from what the memory already stores about the
+//! frame: its vector embedding, its sketch (`SimHash` and top terms) and a Tantivy
+//! more-like-this query over its indexed content. The lists are fused with RRF, so each
+//! hit's `retriever_scores` shows which signals found it.
+
+use std::collections::HashSet;
+
+use super::fusion::{CandidateList, fuse_hits};
+use super::helpers::timestamp_to_rfc3339;
+use crate::Result;
+use crate::memvid::lifecycle::Memvid;
+use crate::types::{
+    FrameId, FusionStrategy, HybridRetriever, QuerySketch, SearchHit, SearchHitMetadata,
+    VecSearchFilter,
+};
+
+/// Minimum candidates fetched per signal before fusing.
+const SIMILAR_CANDIDATE_FLOOR: usize = 24;
+
+/// Sketch Hamming threshold; as relaxed as the search pre-filter, since fusion reranks.
+const SIMILAR_HAMMING_THRESHOLD: u32 = 32;
+
+/// Characters of frame content returned as each hit's snippet.
+const SIMILAR_SNIPPET_CHARS: usize = 200;
+
+impl Memvid {
+    /// Frames most similar to `frame_id`, best first, for "more like this" navigation.
+    ///
+    /// Combines the frame's stored embedding, its sketch and a Tantivy more-like-this
+    /// query on its content; signals the memory lacks are skipped. Hits are restricted
+    /// by `filter` and never include the frame itself or its child chunks.
+    ///
+    /// # Errors
+    ///
+    /// Returns `FrameNotFound` for an unknown `frame_id`, or any error raised while
+    /// reading candidate frames or querying the vector and lexical indexes.
+    pub fn similar_frames(
+        &mut self,
+        frame_id: FrameId,
+        k: usize,
+        filter: &VecSearchFilter,
+    ) -> Result<Vec<SearchHit>> {
+        let source = self.frame_by_id(frame_id)?;
+        let excluded: HashSet<FrameId> = self
+            .toc
+            .frames
+            .iter()
+            .filter(|frame| frame.parent_id == Some(source.id))
+            .map(|frame| frame.id)
+            .chain(std::iter::once(source.id))
+            .collect();
+        let pool = k.max(1).max(SIMILAR_CANDIDATE_FLOOR);
+        let fetch = pool + excluded.len();
+        let allowed = self.vec_filter_allowed_ids(filter)?;
+        let accepts = |memvid: &Memvid, candidate: FrameId| {
+            !excluded.contains(&candidate)
+                && memvid.vec_filter_accepts(filter, allowed.as_ref(), candidate)
+        };
+
+        let mut lists: Vec<CandidateList> = Vec::new();
+
+        let embedding = match filter.space.as_deref() {
+            _ if !self.vec_enabled => None,
+            Some(space) => self.frame_space_embedding(space, frame_id)?,
+            None => self.frame_embedding(frame_id)?,
+        };
+        if let Some(embedding) = embedding {
+            let hits = self.search_vec_filtered(&embedding, fetch, filter)?;
+            let scored = hits
+                .into_iter()
+                .map(|hit| (hit.frame_id, hit.similarity))
+                .collect();
+            lists.push((
+                HybridRetriever::Semantic,
+                self.similar_hits(scored, pool, &accepts)?,
+            ));
+        }
+
+        if let Some(entry) = self.sketch_track.get(frame_id) {
+            let sketch = QuerySketch::from_entry(entry);
+            let scored = self.sketch_track.find_candidates_filtered(
+                &sketch,
+                SIMILAR_HAMMING_THRESHOLD,
+                pool,
+                |candidate| accepts(self, candidate),
+            );
+            lists.push((
+                HybridRetriever::Sketch,
+                self.similar_hits(scored, pool, &accepts)?,
+            ));
+        }
+
+        // Tantivy can't evaluate the filter itself, so it gets the accepted frame ids.
+        let frame_filter: Option<Vec<FrameId>> =
+            (!filter.is_empty() || allowed.is_some()).then(|| {
+                self.toc
+                    .frames
+                    .iter()
+                    .map(|frame| frame.id)
+                    .filter(|candidate| accepts(self, *candidate))
+                    .collect()
+            });
+        let lexical = match self.tantivy.as_ref() {
+            Some(engine) => engine.more_like_this(frame_id, fetch, frame_filter.as_deref())?,
+            None => Vec::new(),
+        };
+        if !lexical.is_empty() {
+            let scored = lexical
+                .into_iter()
+                .map(|hit| (hit.frame_id, hit.score))
+                .collect();
+            lists.push((
+                HybridRetriever::Lexical,
+                self.similar_hits(scored, pool, &accepts)?,
+            ));
+        }
+
+        let mut hits = fuse_hits(lists, &FusionStrategy::default(), pool).unwrap_or_default();
+        hits.truncate(k);
+        Ok(hits)
+    }
+
+    /// Turn `(frame_id, score)` candidates into ranked hits, keeping those `accepts` allows.
+    fn similar_hits(
+        &mut self,
+        scored: Vec<(FrameId, f32)>,
+        limit: usize,
+        accepts: &impl Fn(&Memvid, FrameId) -> bool,
+    ) -> Result<Vec<SearchHit>> {
+        let mut hits = Vec::new();
+        for (frame_id, score) in scored {
+            if hits.len() == limit {
+                break;
+            }
+            if !accepts(self, frame_id) {
+                continue;
+            }
+            let Ok(frame) = self.frame_by_id(frame_id) else {
+                continue;
+            };
+            let snippet = self.frame_text_prefix(&frame, SIMILAR_SNIPPET_CHARS)?;
+            let snippet_bytes = snippet.len();
+            let uri = frame
+                .uri
+                .clone()
+                .unwrap_or_else(|| crate::default_uri(frame.id));
+            let title = frame
+                .title
+                .clone()
+                .or_else(|| crate::infer_title_from_uri(&uri));
+            let metadata = SearchHitMetadata {
+                matches: 1,
+                tags: frame.tags.clone(),
+                labels: frame.labels.clone(),
+                track: frame.track.clone(),
+                created_at: timestamp_to_rfc3339(frame.timestamp),
+                content_dates: frame.content_dates.clone(),
+                entities: Vec::new(),
+                #[cfg(feature = "temporal_track")]
+                temporal: None,
+            };
+            hits.push(SearchHit {
+                rank: hits.len() + 1,
+                frame_id: frame.id,
+                uri,
+                title,
+                range: (0, snippet_bytes),
+                text: snippet.clone(),
+                matches: 1,
+                chunk_range: Some((0, snippet_bytes)),
+                chunk_text: Some(snippet),
+                score: Some(score),
+                metadata: Some(metadata),
+                retriever_scores: Vec::new(),
+                highlights: Vec::new(),
+                chunk_highlights: Vec::new(),
+                fragments: Vec::new(),
+                rerank: None,
+                expanded: None,
+            });
+        }
+        Ok(hits)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{PutOptions, SketchVariant};
+
+    fn put(mem: &mut Memvid, uri: &str, content: &str, tags: &[&str]) {
+        let options = PutOptions {
+            uri: Some(uri.to_string()),
+            tags: tags.iter().map(ToString::to_string).collect(),
+            ..PutOptions::default()
+        };
+        mem.put_bytes_with_options(content.as_bytes(), options)
+            .expect("put");
+    }
+
+    #[test]
+    #[cfg(feature = "lex")]
+    fn selective_filters_are_applied_inside_each_retriever() {
+        crate::run_serial_test(|| {
+            let dir = tempfile::tempdir().expect("tmp");
+            let mut mem = Memvid::create(dir.path().join("similar.mv2")).expect("create");
+            mem.enable_lex().expect("lex");
+            let text = "volcanic eruptions reshape island coastlines with lava flows";
+            put(&mut mem, "mv2://docs/source", text, &[]);
+            // More exact copies than either retriever fetches, all outside the filter.
+            for copy in 0..40 {
+                put(
+                    &mut mem,
+                    &format!("mv2://docs/copy-{copy}"),
+                    text,
+                    &["copy"],
+                );
+            }
+            put(
+                &mut mem,
+                "mv2://docs/kept",
+                "volcanic eruptions reshape island coastlines with basalt",
+                &["kept"],
+            );
+            mem.commit().expect("commit");
+            mem.build_all_sketches(SketchVariant::Small);
+
+            let filter = VecSearchFilter {
+                tags: vec!["kept".to_string()],
+                ..VecSearchFilter::default()
+            };
+            let hits = mem.similar_frames(0, 5, &filter).expect("similar");
+            let uris: Vec<_> = hits.iter().map(|hit| hit.uri.as_str()).collect();
+            assert_eq!(uris, vec!["mv2://docs/kept"]);
+            let retrievers: Vec<_> = hits[0]
+                .retriever_scores
+                .iter()
+                .map(|score| score.retriever)
+                .collect();
+            assert!(
+                retrievers.contains(&HybridRetriever::Sketch),
+                "{retrievers:?}"
+            );
+            assert!(
+                retrievers.contains(&HybridRetriever::Lexical),
+                "{retrievers:?}"
+            );
+        });
+    }
+
+    #[test]
+    fn snippets_hold_the_leading_characters() {
+        crate::run_serial_test(|| {
+            let dir = tempfile::tempdir().expect("tmp");
+            let mut mem = Memvid::create(dir.path().join("similar.mv2")).expect("create");
+            mem.enable_lex().expect("lex");
+            let long = "Gletscher formen Täler über Jahrtausende. ".repeat(200);
+            put(&mut mem, "mv2://docs/source", "Gletscher formen Täler", &[]);
+            put(&mut mem, "mv2://docs/long", &long, &[]);
+            mem.commit().expect("commit");
+
+            let hits = mem
+                .similar_frames(0, 5, &VecSearchFilter::default())
+                .expect("similar");
+            let hit = hits
+                .iter()
+                .find(|hit| hit.uri == "mv2://docs/long")
+                .expect("long document");
+            let expected: String = long.chars().take(SIMILAR_SNIPPET_CHARS).collect();
+            assert_eq!(hit.text, expected);
+            assert_eq!(hit.range, (0, expected.len()));
+        });
+    }
+}
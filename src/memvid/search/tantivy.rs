@@ -1,17 +1,18 @@
 #![cfg(feature = "lex")]
 
+use super::cursor::{CursorAnchor, Pager, search_query_hash};
 #[cfg(feature = "temporal_track")]
 use super::helpers::attach_temporal_metadata;
 use super::helpers::{
-    build_context, collect_token_occurrences, parse_cursor, timestamp_to_rfc3339,
+    apply_highlights, build_context, collect_token_occurrences, frame_facets, timestamp_to_rfc3339,
 };
 use crate::lex::compute_snippet_slices;
 use crate::memvid::frame::ChunkInfo;
 use crate::memvid::lifecycle::Memvid;
 use crate::search::{EvaluationContext, ParsedQuery};
 use crate::types::{
-    FrameId, SearchEngineKind, SearchHit, SearchHitMetadata, SearchParams, SearchRequest,
-    SearchResponse,
+    Frame, FrameId, FrameStatus, SearchEngineKind, SearchHit, SearchHitMetadata, SearchParams,
+    SearchRequest, SearchResponse,
 };
 use crate::{MemvidError, Result};
 use log::warn;
@@ -39,17 +40,18 @@ pub(super) fn try_tantivy_search(
     // to search for stemmed forms in the content as well.
     let mut stemmed_tokens = Vec::new();
     for token in query_tokens {
-        let analyzed = engine.analyse_text(token);
-        stemmed_tokens.extend(analyzed);
+        for variant in engine.analyse_variants(token) {
+            stemmed_tokens.extend(variant.into_iter().map(|(_, text)| text));
+        }
     }
     let stemmed_tokens = stemmed_tokens;
 
-    let offset_hint = request
-        .cursor
-        .as_deref()
-        .and_then(|cursor| cursor.parse::<usize>().ok())
-        .unwrap_or(0);
-    let base_docs = request.top_k.max(1) + offset_hint;
+    let pager = Pager::new(
+        memvid,
+        request.cursor.as_deref(),
+        search_query_hash(request),
+    )?;
+    let base_docs = request.top_k.max(1) + pager.offset_hint();
     let mut doc_limit = base_docs.saturating_mul(4).max(20);
     if let Some(filter) = candidate_filter {
         doc_limit = doc_limit.min(filter.len().max(1));
@@ -116,10 +118,26 @@ pub(super) fn try_tantivy_search(
         )));
     }
 
+    // Facets cover every matching document, not just the `doc_limit` fetched above,
+    // after the same post-filters as the hits.
+    let facets = match request.facets.as_ref() {
+        Some(facet_request) => {
+            let matched =
+                engine.matching_frame_ids(parsed, uri_filter, scope_filter, frame_filter_slice)?;
+            let counted =
+                facet_frame_ids(memvid, parsed, &pager, uri_filter, scope_filter, matched)?;
+            frame_facets(memvid, counted, Some(facet_request))
+        }
+        None => Vec::new(),
+    };
+
     let snippet_window = request.snippet_chars.max(80);
     let max_snippets_per_doc = request.top_k.max(1);
     let mut evaluated = Vec::new();
     for hit in search_hits {
+        if !pager.admits(hit.frame_id) {
+            continue;
+        }
         let frame_meta = memvid
             .toc
             .frames
@@ -129,15 +147,8 @@ pub(super) fn try_tantivy_search(
                 reason: "frame id out of range".into(),
             })?;
         let _ = &hit.content;
-        if let Some(uri_expected) = uri_filter {
-            if !uri_matches(frame_meta.uri.as_deref(), uri_expected) {
-                continue;
-            }
-        } else if let Some(scope) = scope_filter {
-            match frame_meta.uri.as_deref() {
-                Some(uri) if uri.starts_with(scope) => {}
-                _ => continue,
-            }
+        if !uri_in_scope(&frame_meta, uri_filter, scope_filter) {
+            continue;
         }
 
         let chunk_info = match memvid.resolve_chunk_context(&frame_meta) {
@@ -186,7 +197,7 @@ pub(super) fn try_tantivy_search(
 
     // Apply recency boosting: re-sort by combined score (BM25 + recency)
     // This helps knowledge-update questions find the most recent information
-    if evaluated.len() > 1 {
+    let mut ranked: Vec<(f32, _)> = if evaluated.len() > 1 {
         // Use RELATIVE recency within the result set, not absolute time from "now"
         // This ensures documents from different time periods are fairly compared
         let max_ts = evaluated
@@ -196,7 +207,7 @@ pub(super) fn try_tantivy_search(
             .unwrap_or(0);
 
         // Calculate recency-boosted scores and attach to items
-        let mut with_scores: Vec<(f32, _)> = evaluated
+        evaluated
             .into_iter()
             .map(|(hit, occurrences, slices, chunk_info, timestamp)| {
                 let bm25_score = hit.score;
@@ -213,14 +224,32 @@ pub(super) fn try_tantivy_search(
                     (hit, occurrences, slices, chunk_info, timestamp),
                 )
             })
-            .collect();
+            .collect()
+    } else {
+        evaluated
+            .into_iter()
+            .map(|item| (item.0.score, item))
+            .collect()
+    };
 
-        // Sort by combined score (descending)
-        with_scores.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(std::cmp::Ordering::Equal));
+    // Sort by combined score (descending); frame id breaks ties so cursors resume reliably
+    ranked.sort_by(|a, b| {
+        b.0.total_cmp(&a.0)
+            .then_with(|| (a.1).0.frame_id.cmp(&(b.1).0.frame_id))
+    });
+    let anchors: Vec<CursorAnchor> = ranked
+        .iter()
+        .flat_map(|(score, (hit, _, slices, _, _))| {
+            (0..slices.len()).map(|slice| CursorAnchor::Hit {
+                score: *score,
+                frame_id: hit.frame_id,
+                slice,
+            })
+        })
+        .collect();
 
-        // Extract back to evaluated
-        evaluated = with_scores.into_iter().map(|(_, item)| item).collect();
-    }
+    // Extract back to evaluated
+    let evaluated: Vec<_> = ranked.into_iter().map(|(_, item)| item).collect();
 
     if evaluated.is_empty() {
         tracing::debug!("tantivy evaluation produced zero hits; falling back to legacy lex",);
@@ -256,8 +285,9 @@ pub(super) fn try_tantivy_search(
         )?));
     }
 
-    let offset = parse_cursor(request.cursor.as_deref(), total_slices)?;
+    let offset = pager.start(&anchors);
     let effective_top_k = request.top_k.max(1);
+    let fuzzy_terms = parsed.fuzzy_terms();
 
     let mut hits = Vec::new();
     let mut produced = 0usize;
@@ -289,6 +319,19 @@ pub(super) fn try_tantivy_search(
         } = chunk_info;
         let chunk_bytes = chunk_text.as_bytes();
         let chunk_range = (chunk_start, chunk_end);
+        // Analyzer-aware ranges mark stemmed matches; substring occurrences cover
+        // tokens the analyzer drops.
+        let chunk_highlights = match (request.highlight.as_ref(), memvid.tantivy.as_ref()) {
+            (Some(_), Some(engine)) => {
+                let ranges = engine.highlight_ranges(&chunk_text, &stemmed_tokens, &fuzzy_terms);
+                if ranges.is_empty() {
+                    occurrences.clone()
+                } else {
+                    ranges
+                }
+            }
+            _ => Vec::new(),
+        };
 
         for (start, end) in slices {
             if produced < offset {
@@ -327,7 +370,7 @@ pub(super) fn try_tantivy_search(
                 continue;
             }
             let snippet_text = chunk_text[local_start..local_end].to_string();
-            hits.push(SearchHit {
+            let mut search_hit = SearchHit {
                 rank: hits.len() + 1,
                 frame_id: hit.frame_id,
                 uri: uri.clone(),
@@ -339,13 +382,29 @@ pub(super) fn try_tantivy_search(
                 chunk_text: Some(chunk_text.clone()),
                 score: Some(hit.score),
                 metadata: Some(metadata),
-            });
+                retriever_scores: Vec::new(),
+                highlights: Vec::new(),
+                chunk_highlights: Vec::new(),
+                fragments: Vec::new(),
+                rerank: None,
+                expanded: None,
+            };
+            if let Some(options) = request.highlight.as_ref() {
+                apply_highlights(
+                    &mut search_hit,
+                    &chunk_highlights,
+                    local_start,
+                    options,
+                    request.snippet_chars,
+                );
+            }
+            hits.push(search_hit);
             produced += 1;
         }
     }
 
     let next_cursor = if produced < total_slices {
-        Some(produced.to_string())
+        Some(pager.next(produced, produced.checked_sub(1).map(|last| anchors[last]))?)
     } else {
         None
     };
@@ -363,9 +422,62 @@ pub(super) fn try_tantivy_search(
         context,
         next_cursor,
         engine: SearchEngineKind::Tantivy,
+        facets,
+        suggestions: Vec::new(),
+        corrected_query: None,
     }))
 }
 
+/// Whether `frame` passes the exact URI or case-sensitive scope re-check applied to hits.
+fn uri_in_scope(frame: &Frame, uri_filter: Option<&str>, scope_filter: Option<&str>) -> bool {
+    match (uri_filter, scope_filter) {
+        (Some(expected), _) => uri_matches(frame.uri.as_deref(), expected),
+        (None, Some(scope)) => frame
+            .uri
+            .as_deref()
+            .is_some_and(|uri| uri.starts_with(scope)),
+        (None, None) => true,
+    }
+}
+
+/// Matching frames that survive the checks hits go through, for facet counting.
+fn facet_frame_ids(
+    memvid: &mut Memvid,
+    parsed: &ParsedQuery,
+    pager: &Pager,
+    uri_filter: Option<&str>,
+    scope_filter: Option<&str>,
+    frame_ids: Vec<FrameId>,
+) -> Result<Vec<FrameId>> {
+    let mut counted = Vec::with_capacity(frame_ids.len());
+    for frame_id in frame_ids {
+        if !pager.admits(frame_id) {
+            continue;
+        }
+        let Some(frame) = memvid.toc.frames.get(frame_id as usize).cloned() else {
+            continue;
+        };
+        if frame.status != FrameStatus::Active || !uri_in_scope(&frame, uri_filter, scope_filter) {
+            continue;
+        }
+        if parsed.contains_field_terms() {
+            let Ok(chunk_info) = memvid.resolve_chunk_context(&frame) else {
+                continue;
+            };
+            let content_lower = chunk_info.text.to_ascii_lowercase();
+            let ctx = EvaluationContext {
+                frame: &frame,
+                content_lower: &content_lower,
+            };
+            if !parsed.evaluate(&ctx) {
+                continue;
+            }
+        }
+        counted.push(frame_id);
+    }
+    Ok(counted)
+}
+
 fn uri_matches(candidate: Option<&str>, expected: &str) -> bool {
     let Some(uri) = candidate else {
         return false;
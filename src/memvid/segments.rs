@@ -12,6 +12,7 @@ use crate::types::{
 };
 use crate::vec::{VecIndexArtifact, VecIndexBuilder};
 use crate::vec_pq::{QuantizedVecIndexArtifact, QuantizedVecIndexBuilder};
+use crate::vec_quant::{ScalarKind, ScalarQuantizedIndex};
 use crate::{MemvidError, Result, TimeIndexEntry, time_index_append};
 #[cfg(feature = "temporal_track")]
 use crate::{
@@ -193,8 +194,8 @@ impl Memvid {
 
         match effective_compression {
             VectorCompression::None => {
-                // Uncompressed path - use regular VecIndexBuilder
-                let mut builder = VecIndexBuilder::new();
+                // Uncompressed path - segments are merged on open, so no graph
+                let mut builder = VecIndexBuilder::new().without_hnsw();
                 for (frame_id, vector) in embeddings {
                     if vector.is_empty() {
                         continue;
@@ -275,6 +276,38 @@ impl Memvid {
                     bytes_uncompressed: 0, // PQ doesn't track uncompressed size
                 }))
             }
+            VectorCompression::Int8 | VectorCompression::Binary => {
+                // Scalar quantization needs no training set, so it applies at any size
+                let kind = ScalarKind::from_compression(&effective_compression)
+                    .unwrap_or(ScalarKind::Int8);
+                let documents: Vec<(FrameId, Vec<f32>)> = embeddings
+                    .iter()
+                    .filter(|(_, vector)| !vector.is_empty())
+                    .cloned()
+                    .collect();
+                let VecIndexArtifact {
+                    bytes,
+                    vector_count,
+                    dimension: artifact_dimension,
+                    checksum,
+                    #[cfg(feature = "parallel_segments")]
+                    bytes_uncompressed,
+                } = ScalarQuantizedIndex::build(kind, &documents)?.encode()?;
+
+                if vector_count == 0 {
+                    return Ok(None);
+                }
+
+                Ok(Some(VecSegmentArtifact {
+                    bytes,
+                    vector_count,
+                    dimension: artifact_dimension.max(dimension),
+                    checksum,
+                    compression: effective_compression,
+                    #[cfg(feature = "parallel_segments")]
+                    bytes_uncompressed,
+                }))
+            }
         }
     }
 
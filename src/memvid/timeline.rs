@@ -2,9 +2,10 @@
 
 use crate::io::time_index::{TimeIndexEntry, read_track as time_index_read};
 use crate::memvid::lifecycle::Memvid;
+use crate::memvid::search::cursor::{CursorAnchor, Pager};
 #[cfg(feature = "temporal_track")]
 use crate::memvid::search::frame_ids_for_temporal_filter;
-use crate::types::{FrameId, FrameRole, FrameStatus, TimelineEntry};
+use crate::types::{FrameId, FrameRole, FrameStatus, TimelineEntry, TimelinePage};
 #[cfg(feature = "temporal_track")]
 use crate::types::{
     SearchHitTemporal, SearchHitTemporalAnchor, SearchHitTemporalMention, TemporalFilter,
@@ -19,12 +20,13 @@ use time::{OffsetDateTime, format_description::well_known::Rfc3339};
 
 pub(crate) fn build_timeline(
     memvid: &mut Memvid,
+    pager: &Pager,
     limit: Option<NonZeroU64>,
     since: Option<i64>,
     until: Option<i64>,
     reverse: bool,
     #[cfg(feature = "temporal_track")] temporal: Option<&TemporalFilter>,
-) -> Result<Vec<TimelineEntry>> {
+) -> Result<TimelinePage> {
     #[cfg(feature = "temporal_track")]
     let temporal_candidates: Option<HashSet<FrameId>> = if let Some(filter) = temporal {
         if filter.is_empty() {
@@ -34,7 +36,10 @@ pub(crate) fn build_timeline(
                 Some(ids) => {
                     let set: HashSet<FrameId> = ids.into_iter().collect();
                     if set.is_empty() {
-                        return Ok(Vec::new());
+                        return Ok(TimelinePage {
+                            entries: Vec::new(),
+                            next_cursor: None,
+                        });
                     }
                     Some(set)
                 }
@@ -81,18 +86,33 @@ pub(crate) fn build_timeline(
     entries.retain(|entry| {
         let after_since = since.map_or(true, |s| entry.timestamp >= s);
         let before_until = until.map_or(true, |u| entry.timestamp <= u);
-        after_since && before_until
+        after_since && before_until && pager.admits(entry.frame_id)
     });
 
     if reverse {
         entries.reverse();
     }
 
+    let anchors: Vec<CursorAnchor> = entries
+        .iter()
+        .map(|entry| CursorAnchor::Timeline {
+            timestamp: entry.timestamp,
+            frame_id: entry.frame_id,
+            reverse,
+        })
+        .collect();
+    let start = pager.start(&anchors);
     let limit = limit.map_or(entries.len(), |nz| nz.get() as usize);
-    let mut result = Vec::with_capacity(entries.len().min(limit));
+    let end = start.saturating_add(limit).min(entries.len());
+    let next_cursor = if end < entries.len() {
+        Some(pager.next(end, anchors.get(end - 1).copied())?)
+    } else {
+        None
+    };
+    let mut result = Vec::with_capacity(end - start);
     #[cfg(feature = "temporal_track")]
     let temporal_track_snapshot = memvid.temporal_track_ref()?.cloned();
-    for entry in entries.into_iter().take(limit) {
+    for entry in entries.into_iter().take(end).skip(start) {
         let frame = memvid
             .toc
             .frames
@@ -135,7 +155,10 @@ pub(crate) fn build_timeline(
             temporal: temporal_info,
         });
     }
-    Ok(result)
+    Ok(TimelinePage {
+        entries: result,
+        next_cursor,
+    })
 }
 
 #[cfg(feature = "temporal_track")]
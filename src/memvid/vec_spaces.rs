@@ -0,0 +1,260 @@
+//! Named vector spaces: extra embedding indexes stored alongside the default one.
+//!
+//! Each space is described by a `VecSpaceManifest` in the TOC and carries its own
+//! dimension, metric and embedding identity, so vectors from different models can
+//! coexist (e.g. while a memory is re-embedded). Embeddings routed to a space through
+//! `PutOptions::vec_space` are staged when the WAL is applied and folded into the
+//! space's index on the next commit.
+
+use std::io::{Seek, SeekFrom, Write};
+
+use crate::memvid::lifecycle::Memvid;
+use crate::types::{
+    DistanceMetric, EmbeddingIdentity, FrameId, VecIndexManifest, VecSearchFilter,
+    VecSpaceManifest, VectorCompression,
+};
+use crate::vec::{VecIndex, VecIndexArtifact, VecIndexBuilder, VecSearchHit};
+use crate::{MemvidError, Result};
+
+impl Memvid {
+    /// Register a named vector space.
+    ///
+    /// Registering an existing space again with the same dimension and metric is a no-op
+    /// (apart from updating the identity when one is given); a conflicting dimension or
+    /// metric is rejected.
+    pub fn create_vec_space(
+        &mut self,
+        name: &str,
+        dimension: u32,
+        metric: DistanceMetric,
+        identity: Option<EmbeddingIdentity>,
+    ) -> Result<()> {
+        self.ensure_writable()?;
+        if name.trim().is_empty() {
+            return Err(MemvidError::InvalidVecSpace {
+                name: name.to_string(),
+                reason: "name must not be empty",
+            });
+        }
+        if dimension == 0 {
+            return Err(MemvidError::InvalidVecSpace {
+                name: name.to_string(),
+                reason: "dimension must be greater than zero",
+            });
+        }
+
+        if let Some(space) = self
+            .toc
+            .indexes
+            .vec_spaces
+            .iter_mut()
+            .find(|space| space.name == name)
+        {
+            if space.index.dimension != dimension {
+                return Err(MemvidError::VecDimensionMismatch {
+                    expected: space.index.dimension,
+                    actual: dimension as usize,
+                });
+            }
+            if space.index.metric != metric {
+                return Err(MemvidError::VecMetricMismatch {
+                    expected: space.index.metric,
+                    actual: metric,
+                });
+            }
+            if identity.is_some() && space.identity != identity {
+                space.identity = identity;
+                self.dirty = true;
+            }
+            return Ok(());
+        }
+
+        if !self.vec_enabled {
+            self.enable_vec()?;
+        }
+        self.toc.indexes.vec_spaces.push(VecSpaceManifest {
+            name: name.to_string(),
+            identity,
+            index: VecIndexManifest {
+                vector_count: 0,
+                dimension,
+                bytes_offset: self.data_end,
+                bytes_length: 0,
+                checksum: *blake3::hash(&[]).as_bytes(),
+                compression_mode: VectorCompression::None,
+                metric,
+            },
+        });
+        self.vec_space_indexes.insert(
+            name.to_string(),
+            VecIndex::Uncompressed {
+                documents: Vec::new(),
+                metric,
+            },
+        );
+        self.dirty = true;
+        Ok(())
+    }
+
+    /// Named vector spaces registered in this memory.
+    #[must_use]
+    pub fn vec_spaces(&self) -> &[VecSpaceManifest] {
+        &self.toc.indexes.vec_spaces
+    }
+
+    /// Nearest-neighbour search over a named vector space.
+    pub fn search_vec_space(
+        &mut self,
+        space: &str,
+        query: &[f32],
+        limit: usize,
+    ) -> Result<Vec<VecSearchHit>> {
+        let filter = VecSearchFilter {
+            space: Some(space.to_string()),
+            ..VecSearchFilter::default()
+        };
+        self.search_vec_filtered(query, limit, &filter)
+    }
+
+    /// Embedding stored for `frame_id` in a named vector space.
+    pub fn frame_space_embedding(
+        &mut self,
+        space: &str,
+        frame_id: FrameId,
+    ) -> Result<Option<Vec<f32>>> {
+        self.ensure_vec_space_index(space)?;
+        Ok(self
+            .vec_space_indexes
+            .get(space)
+            .and_then(|index| index.embedding_for(frame_id))
+            .map(std::borrow::Cow::into_owned))
+    }
+
+    pub(crate) fn vec_space_manifest(&self, name: &str) -> Result<&VecSpaceManifest> {
+        self.toc
+            .indexes
+            .vec_space(name)
+            .ok_or_else(|| MemvidError::VecSpaceNotFound {
+                name: name.to_string(),
+            })
+    }
+
+    /// Make sure the index for `name` is in memory.
+    pub(crate) fn ensure_vec_space_index(&mut self, name: &str) -> Result<()> {
+        if self.vec_space_indexes.contains_key(name) {
+            return Ok(());
+        }
+        let manifest = self.vec_space_manifest(name)?.index.clone();
+        let index = if manifest.bytes_length == 0 {
+            VecIndex::Uncompressed {
+                documents: Vec::new(),
+                metric: manifest.metric,
+            }
+        } else {
+            self.load_vec_index_range(
+                manifest.bytes_offset,
+                manifest.bytes_length,
+                manifest.compression_mode,
+            )?
+            .with_metric(manifest.metric)
+        };
+        self.vec_space_indexes.insert(name.to_string(), index);
+        Ok(())
+    }
+
+    /// Load every named space at open time, before new payloads can overwrite the
+    /// index region. Unreadable spaces are skipped, like the CLIP index.
+    pub(crate) fn load_vec_space_indexes(&mut self) {
+        let names: Vec<String> = self
+            .toc
+            .indexes
+            .vec_spaces
+            .iter()
+            .map(|space| space.name.clone())
+            .collect();
+        for name in names {
+            if let Err(err) = self.ensure_vec_space_index(&name) {
+                tracing::warn!(space = %name, "failed to load vector space index: {err}");
+            }
+        }
+    }
+
+    /// True when embeddings are staged for a named space.
+    pub(crate) fn vec_spaces_pending(&self) -> bool {
+        self.pending_vec_space_docs
+            .values()
+            .any(|docs| !docs.is_empty())
+    }
+
+    /// Rebuild every named space from its current index plus staged embeddings, keeping
+    /// only active frames. Must run before the index region of the file is overwritten.
+    pub(crate) fn build_vec_space_artifacts(&mut self) -> Result<Vec<VecIndexArtifact>> {
+        let spaces: Vec<(String, DistanceMetric)> = self
+            .toc
+            .indexes
+            .vec_spaces
+            .iter()
+            .map(|space| (space.name.clone(), space.index.metric))
+            .collect();
+        let mut artifacts = Vec::with_capacity(spaces.len());
+        for (name, metric) in spaces {
+            self.ensure_vec_space_index(&name)?;
+            let mut builder = match self.vec_space_indexes.get(&name) {
+                Some(index) => {
+                    VecIndexBuilder::from_index(index, |frame_id| self.frame_is_active(frame_id))
+                }
+                None => VecIndexBuilder::new(),
+            }
+            .with_metric(metric);
+            for (frame_id, embedding) in self
+                .pending_vec_space_docs
+                .remove(&name)
+                .unwrap_or_default()
+            {
+                builder.add_document(frame_id, embedding);
+            }
+            let artifact = builder.finish()?;
+            let index = VecIndex::decode(&artifact.bytes)?.with_metric(metric);
+            self.vec_space_indexes.insert(name, index);
+            artifacts.push(artifact);
+        }
+        Ok(artifacts)
+    }
+
+    /// Write artifacts from [`Self::build_vec_space_artifacts`] starting at `offset` and
+    /// point the space manifests at them. Returns the offset past the last artifact.
+    pub(crate) fn write_vec_space_artifacts(
+        &mut self,
+        artifacts: Vec<VecIndexArtifact>,
+        mut offset: u64,
+    ) -> Result<u64> {
+        for (space, artifact) in self.toc.indexes.vec_spaces.iter_mut().zip(artifacts) {
+            let padding = crate::vec_mmap::alignment_padding(offset);
+            self.file.seek(SeekFrom::Start(offset))?;
+            self.file.write_all(padding)?;
+            offset += padding.len() as u64;
+            self.file.write_all(&artifact.bytes)?;
+            space.index.vector_count = artifact.vector_count;
+            space.index.bytes_offset = offset;
+            space.index.bytes_length = artifact.bytes.len() as u64;
+            space.index.checksum = artifact.checksum;
+            space.index.compression_mode = VectorCompression::None;
+            offset += artifact.bytes.len() as u64;
+        }
+        Ok(offset)
+    }
+
+    /// Persist named spaces after the current footer without a full rebuild.
+    pub(crate) fn persist_vec_spaces(&mut self) -> Result<()> {
+        if !self.vec_spaces_pending() {
+            return Ok(());
+        }
+        let artifacts = self.build_vec_space_artifacts()?;
+        let end = self.write_vec_space_artifacts(artifacts, self.header.footer_offset)?;
+        self.header.footer_offset = end;
+        if self.file.metadata()?.len() < end {
+            self.file.set_len(end)?;
+        }
+        Ok(())
+    }
+}
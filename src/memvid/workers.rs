@@ -289,8 +289,8 @@ fn build_vec_artifact(
 
     match effective_compression {
         VectorCompression::None => {
-            // Uncompressed path - use regular VecIndexBuilder
-            let mut builder = crate::vec::VecIndexBuilder::new();
+            // Uncompressed path - segments are merged on open, so no graph
+            let mut builder = crate::vec::VecIndexBuilder::new().without_hnsw();
             let mut vectors = 0usize;
             let mut dimension = 0u32;
 
@@ -406,6 +406,50 @@ fn build_vec_artifact(
             };
             Ok(Some(SegmentArtifact { artifact, stats }))
         }
+        VectorCompression::Int8 | VectorCompression::Binary => {
+            // Scalar quantization needs no training set, so it applies at any size
+            let kind = crate::vec_quant::ScalarKind::from_compression(&effective_compression)
+                .unwrap_or(crate::vec_quant::ScalarKind::Int8);
+            let documents: Vec<(crate::types::FrameId, Vec<f32>)> = plan
+                .chunks
+                .iter()
+                .filter_map(|chunk| {
+                    chunk
+                        .embedding
+                        .as_ref()
+                        .filter(|embedding| !embedding.is_empty())
+                        .map(|embedding| (chunk.frame_id, embedding.clone()))
+                })
+                .collect();
+            if documents.is_empty() {
+                return Ok(None);
+            }
+
+            let artifact =
+                crate::vec_quant::ScalarQuantizedIndex::build(kind, &documents)?.encode()?;
+            if artifact.vector_count == 0 {
+                return Ok(None);
+            }
+
+            let bytes_uncompressed = artifact.bytes_uncompressed;
+            let artifact = VecSegmentArtifact {
+                bytes: artifact.bytes,
+                vector_count: artifact.vector_count,
+                dimension: artifact.dimension,
+                checksum: artifact.checksum,
+                compression: effective_compression,
+                #[cfg(feature = "parallel_segments")]
+                bytes_uncompressed,
+            };
+            let stats = SegmentStats {
+                doc_count: 0,
+                vector_count: artifact.vector_count,
+                time_entries: 0,
+                bytes_uncompressed,
+                build_micros: start.elapsed().as_micros() as u64,
+            };
+            Ok(Some(SegmentArtifact { artifact, stats }))
+        }
     }
 }
 
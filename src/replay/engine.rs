@@ -235,6 +235,12 @@ impl<'a> ReplayEngine<'a> {
                             as_of_frame: None,
                             as_of_ts: None,
                             no_sketch: false,
+                            mmr_lambda: None,
+                            facets: None,
+                            highlight: None,
+                            auto_correct: false,
+                            rerank: false,
+                            expand: None,
                         };
                         match self.mem.search(search_request) {
                             Ok(response) => {
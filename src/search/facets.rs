@@ -0,0 +1,198 @@
+//! Facet counting shared by the Tantivy and fallback search engines.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use time::OffsetDateTime;
+
+use crate::types::{DateInterval, FacetBucket, FacetField, FacetRequest, FacetResult, Frame};
+
+/// Accumulates per-field term counts and a timestamp histogram, one frame at a time.
+///
+/// Keys are lowercased so both engines agree with the values Tantivy indexes.
+pub(crate) struct FacetCounter<'a> {
+    request: &'a FacetRequest,
+    terms: Vec<HashMap<String, usize>>,
+    histogram: BTreeMap<String, usize>,
+}
+
+impl<'a> FacetCounter<'a> {
+    pub(crate) fn new(request: &'a FacetRequest) -> Self {
+        Self {
+            request,
+            terms: vec![HashMap::new(); request.fields.len()],
+            histogram: BTreeMap::new(),
+        }
+    }
+
+    pub(crate) fn fields(&self) -> &'a [FacetField] {
+        &self.request.fields
+    }
+
+    /// Counts one frame's values for the field at `slot`; duplicates count once.
+    pub(crate) fn add_terms(&mut self, slot: usize, values: impl IntoIterator<Item = String>) {
+        let field = self.request.fields[slot];
+        let keys: BTreeSet<String> = values
+            .into_iter()
+            .filter_map(|value| match field {
+                FacetField::UriPrefix { depth } => uri_prefix(&value, depth),
+                _ => Some(value.to_ascii_lowercase()),
+            })
+            .filter(|key| !key.is_empty())
+            .collect();
+        for key in keys {
+            *self.terms[slot].entry(key).or_insert(0) += 1;
+        }
+    }
+
+    pub(crate) fn add_timestamp(&mut self, timestamp: i64) {
+        let Some(interval) = self.request.date_histogram else {
+            return;
+        };
+        if let Some(key) = date_bucket(timestamp, interval) {
+            *self.histogram.entry(key).or_insert(0) += 1;
+        }
+    }
+
+    /// Counts a frame straight from its TOC entry.
+    pub(crate) fn add_frame(&mut self, frame: &Frame) {
+        for (slot, field) in self.fields().iter().enumerate() {
+            let values: Vec<String> = match field {
+                FacetField::Tags => frame.tags.clone(),
+                FacetField::Labels => frame.labels.clone(),
+                FacetField::Track => frame.track.iter().cloned().collect(),
+                FacetField::Kind => frame.kind.iter().cloned().collect(),
+                FacetField::UriPrefix { .. } => frame.uri.iter().cloned().collect(),
+            };
+            self.add_terms(slot, values);
+        }
+        self.add_timestamp(frame.timestamp);
+    }
+
+    pub(crate) fn finish(self) -> Vec<FacetResult> {
+        let size = self.request.size;
+        let mut results: Vec<FacetResult> = self
+            .request
+            .fields
+            .iter()
+            .zip(self.terms)
+            .map(|(field, counts)| {
+                let mut buckets: Vec<FacetBucket> = counts
+                    .into_iter()
+                    .map(|(key, count)| FacetBucket { key, count })
+                    .collect();
+                buckets.sort_by(|left, right| {
+                    right
+                        .count
+                        .cmp(&left.count)
+                        .then_with(|| left.key.cmp(&right.key))
+                });
+                if let Some(size) = size {
+                    buckets.truncate(size);
+                }
+                FacetResult {
+                    field: Some(*field),
+                    interval: None,
+                    buckets,
+                }
+            })
+            .collect();
+        if let Some(interval) = self.request.date_histogram {
+            results.push(FacetResult {
+                field: None,
+                interval: Some(interval),
+                buckets: self
+                    .histogram
+                    .into_iter()
+                    .map(|(key, count)| FacetBucket { key, count })
+                    .collect(),
+            });
+        }
+        results
+    }
+}
+
+/// Scheme plus the first `depth` path segments of `uri`, lowercased.
+fn uri_prefix(uri: &str, depth: usize) -> Option<String> {
+    let uri = uri.to_ascii_lowercase();
+    let (scheme, path) = match uri.split_once("://") {
+        Some((scheme, path)) => (Some(scheme), path),
+        None => (None, uri.as_str()),
+    };
+    let segments: Vec<&str> = path
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .take(depth.max(1))
+        .collect();
+    if segments.is_empty() {
+        return None;
+    }
+    let joined = segments.join("/");
+    Some(match scheme {
+        Some(scheme) => format!("{scheme}://{joined}"),
+        None => joined,
+    })
+}
+
+/// UTC bucket label: `YYYY-MM-DD`, `YYYY-MM` or `YYYY`, which sort chronologically.
+fn date_bucket(timestamp: i64, interval: DateInterval) -> Option<String> {
+    let date = OffsetDateTime::from_unix_timestamp(timestamp).ok()?.date();
+    let (year, month, day) = (date.year(), u8::from(date.month()), date.day());
+    Some(match interval {
+        DateInterval::Day => format!("{year:04}-{month:02}-{day:02}"),
+        DateInterval::Month => format!("{year:04}-{month:02}"),
+        DateInterval::Year => format!("{year:04}"),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn buckets_uri_prefixes_and_dates() {
+        assert_eq!(
+            uri_prefix("mv2://Docs/api/intro.md", 2).as_deref(),
+            Some("mv2://docs/api")
+        );
+        assert_eq!(uri_prefix("mv2://docs", 3).as_deref(), Some("mv2://docs"));
+        assert_eq!(uri_prefix("notes/today", 0).as_deref(), Some("notes"));
+        // 2024-03-05T12:00:00Z
+        let ts = 1_709_640_000;
+        assert_eq!(
+            date_bucket(ts, DateInterval::Day).as_deref(),
+            Some("2024-03-05")
+        );
+        assert_eq!(
+            date_bucket(ts, DateInterval::Month).as_deref(),
+            Some("2024-03")
+        );
+        assert_eq!(date_bucket(ts, DateInterval::Year).as_deref(), Some("2024"));
+    }
+
+    #[test]
+    fn orders_term_buckets_by_count_then_key() {
+        let request = FacetRequest {
+            fields: vec![FacetField::Tags],
+            date_histogram: None,
+            size: Some(2),
+        };
+        let mut counter = FacetCounter::new(&request);
+        counter.add_terms(0, ["Ops".to_string(), "ops".to_string()]);
+        counter.add_terms(0, ["dev".to_string(), "ops".to_string()]);
+        counter.add_terms(0, ["qa".to_string()]);
+        let results = counter.finish();
+        assert_eq!(
+            results[0].buckets,
+            vec![
+                FacetBucket {
+                    key: "ops".into(),
+                    count: 2
+                },
+                FacetBucket {
+                    key: "dev".into(),
+                    count: 1
+                },
+            ]
+        );
+    }
+}
@@ -1,10 +1,12 @@
+#[cfg(feature = "lex")]
+pub(crate) mod facets;
 mod parser;
 
 #[cfg(feature = "lex")]
 mod tantivy;
 
 use crate::types::Frame;
-use parser::{Expr, FieldTerm, Term, TextTerm};
+use parser::{Expr, FieldTerm, MetaValue, Term, TextTerm};
 
 pub(crate) use parser::parse_query;
 pub(crate) use parser::{DateRange, ParsedQuery};
@@ -12,7 +14,8 @@ pub(crate) use parser::{DateRange, ParsedQuery};
 #[cfg(feature = "lex")]
 #[allow(unused_imports)]
 pub(crate) use tantivy::{
-    EmbeddedLexSegment, EmbeddedLexStorage, LexWalBatch, TantivyEngine, TantivySnapshot,
+    DEFAULT_TITLE_BOOST, DEFAULT_TOKENIZER, EmbeddedLexSegment, EmbeddedLexStorage, LexWalBatch,
+    TantivyEngine, TantivySnapshot,
 };
 
 pub struct EvaluationContext<'a> {
@@ -36,6 +39,26 @@ impl ParsedQuery {
     pub fn contains_field_terms(&self) -> bool {
         self.expr.contains_field_terms()
     }
+
+    /// Fuzzy words with their edit distances; these match without appearing verbatim.
+    pub fn fuzzy_terms(&self) -> Vec<(String, u8)> {
+        let mut terms = Vec::new();
+        self.expr.collect_fuzzy(&mut terms);
+        terms
+    }
+
+    /// Words typed verbatim (plain words, phrases and proximity phrases) that a
+    /// spelling correction may replace; fuzzy and wildcard terms are left out.
+    pub fn spelling_words(&self) -> Vec<String> {
+        let mut words = Vec::new();
+        self.expr.collect_spelling_words(&mut words);
+        words
+    }
+
+    /// Product of the `^boost` factors whose sub-queries match this frame.
+    pub fn boost_for(&self, ctx: &EvaluationContext<'_>) -> f32 {
+        self.expr.boost_for(ctx)
+    }
 }
 
 impl TextTerm {
@@ -50,10 +73,51 @@ impl TextTerm {
                 haystack.contains(&needle)
             }
             TextTerm::Wildcard(pattern) => pattern.regex.is_match(haystack),
+            TextTerm::Fuzzy { word, distance } => haystack_words(haystack)
+                .any(|candidate| crate::text::edit_distance(candidate, word) <= *distance as usize),
+            TextTerm::Proximity { phrase, slop } => proximity_matches(haystack, phrase, *slop),
         }
     }
 }
 
+fn haystack_words(haystack: &str) -> impl Iterator<Item = &str> {
+    haystack
+        .split(|ch: char| !ch.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+}
+
+/// Each phrase word must sit within `slop` positions of where the exact phrase
+/// would place it after the previous word, matching Tantivy's sloppy phrase
+/// scoring. Words match by prefix so inflected forms count, like the stemmed
+/// Tantivy phrase query does.
+fn proximity_matches(haystack: &str, phrase: &str, slop: u32) -> bool {
+    let needles: Vec<&str> = haystack_words(phrase).collect();
+    if needles.is_empty() {
+        return true;
+    }
+    let words: Vec<&str> = haystack_words(haystack).collect();
+    let slop = slop as usize;
+    words
+        .iter()
+        .enumerate()
+        .filter(|(_, word)| word.starts_with(needles[0]))
+        .any(|(start, _)| {
+            let mut previous = start;
+            needles[1..].iter().all(|needle| {
+                let expected = previous + 1;
+                let found = words
+                    .iter()
+                    .enumerate()
+                    .filter(|(position, word)| {
+                        position.abs_diff(expected) <= slop && word.starts_with(needle)
+                    })
+                    .map(|(position, _)| position)
+                    .min_by_key(|position| position.abs_diff(expected));
+                found.inspect(|position| previous = *position).is_some()
+            })
+        })
+}
+
 impl FieldTerm {
     pub(crate) fn matches(&self, ctx: &EvaluationContext<'_>) -> bool {
         match self {
@@ -85,11 +149,74 @@ impl FieldTerm {
                 .labels
                 .iter()
                 .any(|value| value.eq_ignore_ascii_case(label)),
+            FieldTerm::Title(value) => ctx
+                .frame
+                .title
+                .as_deref()
+                .is_some_and(|title| title_matches(title, value)),
             FieldTerm::DateRange(range) => range.matches(ctx.frame),
+            FieldTerm::Meta { key, value } => {
+                meta_values(ctx.frame, key).any(|candidate| candidate == *value)
+            }
+            FieldTerm::MetaRange { key, start, end } => {
+                meta_values(ctx.frame, key).any(|candidate| match candidate {
+                    MetaValue::Number(number) => number.within(*start, *end),
+                    MetaValue::Text(_) => false,
+                })
+            }
         }
     }
 }
 
+/// Typed values of the frame's `extra_metadata` entries named `key` (case-insensitive).
+fn meta_values<'a>(frame: &'a Frame, key: &'a str) -> impl Iterator<Item = MetaValue> + 'a {
+    frame
+        .extra_metadata
+        .iter()
+        .filter(move |(candidate, _)| candidate.eq_ignore_ascii_case(key))
+        .filter_map(|(_, value)| MetaValue::parse(value))
+}
+
+/// Inflectional endings tolerated between a `title:` word and a title word.
+const TITLE_SUFFIXES: [&str; 6] = ["s", "es", "ed", "ing", "er", "'s"];
+
+/// Every word of `value` must be a whole word of `title`.
+///
+/// Words may differ by one inflectional ending so titles matched by the stemmed
+/// Tantivy field ("houses" against "House") survive post-filtering, while partial
+/// words ("re" against "Report") do not.
+fn title_matches(title: &str, value: &str) -> bool {
+    let title = title.to_lowercase();
+    let title_words: Vec<&str> = title
+        .split(|ch: char| !ch.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect();
+    let value = value.to_lowercase();
+    value
+        .split(|ch: char| !ch.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .all(|word| {
+            title_words
+                .iter()
+                .any(|candidate| same_inflected_word(candidate, word))
+        })
+}
+
+fn same_inflected_word(left: &str, right: &str) -> bool {
+    if left == right {
+        return true;
+    }
+    let (short, long) = if left.len() < right.len() {
+        (left, right)
+    } else {
+        (right, left)
+    };
+    short.chars().count() >= 3
+        && long
+            .strip_prefix(short)
+            .is_some_and(|ending| TITLE_SUFFIXES.contains(&ending))
+}
+
 impl DateRange {
     fn matches(&self, frame: &Frame) -> bool {
         if self.start.is_none() && self.end.is_none() {
@@ -153,10 +280,55 @@ impl Expr {
             Expr::Or(children) => children.iter().any(|child| child.evaluate(ctx)),
             Expr::And(children) => children.iter().all(|child| child.evaluate(ctx)),
             Expr::Not(child) => !child.evaluate(ctx),
+            Expr::Boost(child, _) => child.evaluate(ctx),
             Expr::Term(term) => term.evaluate(ctx),
         }
     }
 
+    fn boost_for(&self, ctx: &EvaluationContext<'_>) -> f32 {
+        match self {
+            Expr::Or(children) | Expr::And(children) => {
+                children.iter().map(|child| child.boost_for(ctx)).product()
+            }
+            Expr::Boost(child, boost) if child.evaluate(ctx) => boost * child.boost_for(ctx),
+            Expr::Boost(..) | Expr::Not(_) | Expr::Term(_) => 1.0,
+        }
+    }
+
+    fn collect_spelling_words(&self, words: &mut Vec<String>) {
+        match self {
+            Expr::Or(children) | Expr::And(children) => {
+                for child in children {
+                    child.collect_spelling_words(words);
+                }
+            }
+            Expr::Boost(child, _) => child.collect_spelling_words(words),
+            Expr::Term(Term::Text(
+                TextTerm::Word(text)
+                | TextTerm::Phrase(text)
+                | TextTerm::Proximity { phrase: text, .. },
+            )) => {
+                words.extend(haystack_words(text).map(str::to_string));
+            }
+            Expr::Not(_) | Expr::Term(_) => {}
+        }
+    }
+
+    fn collect_fuzzy(&self, terms: &mut Vec<(String, u8)>) {
+        match self {
+            Expr::Or(children) | Expr::And(children) => {
+                for child in children {
+                    child.collect_fuzzy(terms);
+                }
+            }
+            Expr::Boost(child, _) => child.collect_fuzzy(terms),
+            Expr::Term(Term::Text(TextTerm::Fuzzy { word, distance })) => {
+                terms.push((word.clone(), *distance));
+            }
+            Expr::Not(_) | Expr::Term(_) => {}
+        }
+    }
+
     fn collect_tokens(&self) -> Vec<String> {
         let mut tokens = Vec::new();
         self.collect_into(&mut tokens);
@@ -170,9 +342,16 @@ impl Expr {
                     child.collect_into(tokens);
                 }
             }
-            Expr::Not(child) => child.collect_into(tokens),
+            // Excluded terms are never searched for, only filtered out.
+            Expr::Not(_) => {}
+            Expr::Boost(child, _) => child.collect_into(tokens),
             Expr::Term(Term::Text(text)) => match text {
-                TextTerm::Word(word) | TextTerm::Phrase(word) => tokens.push(word.clone()),
+                TextTerm::Word(word) | TextTerm::Phrase(word) | TextTerm::Fuzzy { word, .. } => {
+                    tokens.push(word.clone());
+                }
+                TextTerm::Proximity { phrase, .. } => {
+                    tokens.extend(haystack_words(phrase).map(str::to_string));
+                }
                 TextTerm::Wildcard(pattern) => {
                     if let Some(seed) = pattern.seed() {
                         tokens.push(seed);
@@ -198,6 +377,7 @@ impl Expr {
                 }
                 combined
             }
+            Expr::Boost(child, _) => child.required_date_range(),
             Expr::Or(_) | Expr::Not(_) => None,
             Expr::Term(_) => None,
         }
@@ -208,7 +388,7 @@ impl Expr {
             Expr::Or(children) | Expr::And(children) => {
                 children.iter().any(|child| child.contains_field_terms())
             }
-            Expr::Not(child) => child.contains_field_terms(),
+            Expr::Not(child) | Expr::Boost(child, _) => child.contains_field_terms(),
             Expr::Term(term) => term.contains_field_terms(),
         }
     }
@@ -226,3 +406,37 @@ impl Term {
         matches!(self, Term::Field(_))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text_matches(query: &str, content: &str) -> bool {
+        let parsed = parse_query(query).expect("parse");
+        let Expr::Term(Term::Text(term)) = parsed.expr else {
+            panic!("expected a text term");
+        };
+        term.matches(&content.to_lowercase())
+    }
+
+    #[test]
+    fn matches_fuzzy_and_proximity_terms() {
+        let content = "The quick brown fox jumps over the lazy dog";
+        assert!(text_matches("qiuck~1", content));
+        assert!(!text_matches("quark~1", content));
+        assert!(text_matches("\"quick fox\"~1", content));
+        assert!(!text_matches("\"quick fox\"~0", content));
+        assert!(text_matches("\"fox quick\"~3", content));
+        assert!(!text_matches("\"fox quick\"~2", content));
+    }
+
+    #[test]
+    fn title_terms_match_whole_words() {
+        assert!(title_matches("Quarterly Report", "report"));
+        assert!(title_matches("Tomatoes", "tomato"));
+        assert!(title_matches("House plans", "houses plan"));
+        assert!(!title_matches("Quarterly Report", "re"));
+        assert!(!title_matches("Quarterly Report", "rep"));
+        assert!(!title_matches("Reporting", "reporter"));
+    }
+}
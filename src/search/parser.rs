@@ -21,6 +21,7 @@ pub(crate) enum Expr {
     Or(Vec<Expr>),
     And(Vec<Expr>),
     Not(Box<Expr>),
+    Boost(Box<Expr>, f32),
     Term(Term),
 }
 
@@ -35,8 +36,21 @@ pub(crate) enum TextTerm {
     Word(String),
     Phrase(String),
     Wildcard(WildcardPattern),
+    /// `word~N`: matches words within `distance` edits.
+    Fuzzy {
+        word: String,
+        distance: u8,
+    },
+    /// `"phrase"~N`: phrase words each at most `slop` positions from their exact spot.
+    Proximity {
+        phrase: String,
+        slop: u32,
+    },
 }
 
+/// Largest edit distance accepted by `word~N`; Tantivy's automata stop at two.
+pub(crate) const MAX_FUZZY_DISTANCE: u8 = 2;
+
 #[derive(Debug, Clone)]
 pub(crate) struct WildcardPattern {
     pub raw: String,
@@ -50,7 +64,126 @@ pub(crate) enum FieldTerm {
     Track(String),
     Tag(String),
     Label(String),
+    Title(String),
     DateRange(DateRange),
+    /// `meta.key:value` against `Frame.extra_metadata`.
+    Meta {
+        key: String,
+        value: MetaValue,
+    },
+    /// `meta.key:[start TO end]`; bounds are inclusive numbers or dates.
+    MetaRange {
+        key: String,
+        start: Option<MetaNumber>,
+        end: Option<MetaNumber>,
+    },
+}
+
+/// Prefix selecting an `extra_metadata` key in field queries.
+pub(crate) const META_FIELD_PREFIX: &str = "meta.";
+
+/// Typed `extra_metadata` value shared by indexing, Tantivy queries and post-filtering.
+///
+/// Numbers and dates (as unix seconds) compare numerically so ranges work; anything
+/// else is lowercased text. Zero-padded digits such as `00123` are identifiers and stay
+/// text.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum MetaValue {
+    Number(MetaNumber),
+    Text(String),
+}
+
+/// Numeric metadata value. Integers keep their exact value instead of rounding to `f64`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum MetaNumber {
+    I64(i64),
+    /// Only used above `i64::MAX`.
+    U64(u64),
+    F64(f64),
+}
+
+impl MetaNumber {
+    /// Numeric ordering across variants; integers compare exactly.
+    pub(crate) fn compare(self, other: Self) -> Option<std::cmp::Ordering> {
+        match (self.as_i128(), other.as_i128()) {
+            (Some(left), Some(right)) => Some(left.cmp(&right)),
+            _ => self.as_f64().partial_cmp(&other.as_f64()),
+        }
+    }
+
+    /// True when inside the inclusive range; `None` bounds are open.
+    pub(crate) fn within(self, start: Option<Self>, end: Option<Self>) -> bool {
+        start.is_none_or(|start| self.compare(start).is_some_and(std::cmp::Ordering::is_ge))
+            && end.is_none_or(|end| self.compare(end).is_some_and(std::cmp::Ordering::is_le))
+    }
+
+    fn as_i128(self) -> Option<i128> {
+        match self {
+            MetaNumber::I64(value) => Some(i128::from(value)),
+            MetaNumber::U64(value) => Some(i128::from(value)),
+            MetaNumber::F64(_) => None,
+        }
+    }
+
+    /// Nearest `f64`, for comparisons and range bounds that mix integers and floats.
+    #[allow(clippy::cast_precision_loss)]
+    pub(crate) fn as_f64(self) -> f64 {
+        match self {
+            MetaNumber::I64(value) => value as f64,
+            MetaNumber::U64(value) => value as f64,
+            MetaNumber::F64(value) => value,
+        }
+    }
+}
+
+impl MetaValue {
+    /// Parses a stored or queried value. Values written through
+    /// `PutOptionsBuilder::metadata_entry` are JSON-encoded and are unwrapped first;
+    /// objects and arrays are not scalar attributes and yield `None`.
+    pub(crate) fn parse(raw: &str) -> Option<Self> {
+        let trimmed = raw.trim();
+        match serde_json::from_str::<serde_json::Value>(trimmed) {
+            Ok(serde_json::Value::String(inner)) if inner != trimmed => Self::parse(&inner),
+            Ok(serde_json::Value::Number(number)) => number
+                .as_i64()
+                .map(MetaNumber::I64)
+                .or_else(|| number.as_u64().map(MetaNumber::U64))
+                .or_else(|| number.as_f64().map(MetaNumber::F64))
+                .map(MetaValue::Number),
+            Ok(serde_json::Value::Object(_) | serde_json::Value::Array(_)) => None,
+            _ => Some(Self::parse_scalar(trimmed)),
+        }
+    }
+
+    fn parse_scalar(value: &str) -> Self {
+        if let Some(number) = parse_number(value) {
+            return MetaValue::Number(number);
+        }
+        match parse_date_value(value) {
+            Some(timestamp) => MetaValue::Number(MetaNumber::I64(timestamp)),
+            None => MetaValue::Text(value.to_ascii_lowercase()),
+        }
+    }
+}
+
+fn parse_number(value: &str) -> Option<MetaNumber> {
+    let digits = value.strip_prefix(['+', '-']).unwrap_or(value);
+    let zero_padded =
+        digits.len() > 1 && digits.starts_with('0') && digits.as_bytes()[1].is_ascii_digit();
+    if zero_padded {
+        return None;
+    }
+    if let Ok(number) = value.parse::<i64>() {
+        return Some(MetaNumber::I64(number));
+    }
+    if let Ok(number) = value.parse::<u64>() {
+        return Some(MetaNumber::U64(number));
+    }
+    value
+        .parse::<f64>()
+        .ok()
+        .filter(|number| number.is_finite())
+        .map(MetaNumber::F64)
 }
 
 #[derive(Debug, Clone, Default)]
@@ -64,12 +197,16 @@ enum Token {
     Word(String),
     Phrase(String),
     Field(String, String),
-    DateRange(String, String, String),
+    Range(String, String, String),
     LParen,
     RParen,
     And,
     Or,
     Not,
+    Required,
+    Excluded,
+    Tilde(Option<u32>),
+    Caret(f32),
 }
 
 struct Lexer<'a> {
@@ -103,14 +240,28 @@ impl<'a> Lexer<'a> {
                 ')' => {
                     self.index += 1;
                     tokens.push(Token::RParen);
+                    self.read_modifiers(&mut tokens)?;
                 }
                 '"' => {
                     let phrase = self.read_quoted()?;
                     tokens.push(Token::Phrase(phrase));
+                    self.read_modifiers(&mut tokens)?;
+                }
+                '+' | '-' if self.prefixes_operand() => {
+                    self.index += 1;
+                    tokens.push(if ch == '+' {
+                        Token::Required
+                    } else {
+                        Token::Excluded
+                    });
                 }
                 _ => {
                     if let Some(token) = self.read_field_or_word()? {
+                        let is_word = matches!(token, Token::Word(_));
                         tokens.push(token);
+                        if is_word {
+                            self.read_modifiers(&mut tokens)?;
+                        }
                     }
                 }
             }
@@ -120,7 +271,7 @@ impl<'a> Lexer<'a> {
 
     /// Known field names that should be treated as field queries when followed by `:`
     const KNOWN_FIELDS: &'static [&'static str] =
-        &["uri", "scope", "track", "tag", "label", "date"];
+        &["uri", "scope", "track", "tag", "label", "title", "date"];
 
     fn read_field_or_word(&mut self) -> Result<Option<Token>, MemvidError> {
         let start = self.index;
@@ -131,6 +282,9 @@ impl<'a> Lexer<'a> {
             if ch.is_whitespace() || ch == '(' || ch == ')' {
                 break;
             }
+            if self.index > start && colon_pos.is_none() && self.modifier_starts() {
+                break;
+            }
             if ch == ':' && colon_pos.is_none() {
                 colon_pos = Some(self.index);
             }
@@ -142,7 +296,9 @@ impl<'a> Lexer<'a> {
             let potential_field: String = self.chars[start..colon_idx].iter().collect();
             let potential_field_lower = potential_field.to_ascii_lowercase();
 
-            if Self::KNOWN_FIELDS.contains(&potential_field_lower.as_str()) {
+            let is_meta_field = potential_field_lower.len() > META_FIELD_PREFIX.len()
+                && potential_field_lower.starts_with(META_FIELD_PREFIX);
+            if is_meta_field || Self::KNOWN_FIELDS.contains(&potential_field_lower.as_str()) {
                 // Reset index to just after the colon and parse as field
                 self.index = colon_idx + 1;
                 return self.read_field(start);
@@ -171,10 +327,10 @@ impl<'a> Lexer<'a> {
                 let value = self.read_until_quote()?;
                 Token::Field(field, value)
             }
-            Some('[') if field == "date" => {
+            Some('[') if field == "date" || field.starts_with(META_FIELD_PREFIX) => {
                 self.index += 1; // skip '['
                 let (start, end) = self.read_date_range()?;
-                Token::DateRange(field, start, end)
+                Token::Range(field, start, end)
             }
             _ => {
                 let value_start = self.index;
@@ -191,6 +347,62 @@ impl<'a> Lexer<'a> {
         Ok(Some(value_token))
     }
 
+    /// `+` and `-` only act as operators when glued to the following operand.
+    fn prefixes_operand(&self) -> bool {
+        self.chars.get(self.index + 1).is_some_and(|next| {
+            !next.is_whitespace() && *next != ')' && *next != '+' && *next != '-'
+        })
+    }
+
+    /// Whether the cursor sits on a `~` or `^` suffix rather than inside a word.
+    fn modifier_starts(&self) -> bool {
+        let next = self.chars.get(self.index + 1).copied();
+        match self.peek() {
+            Some('~') => next.is_none_or(|ch| {
+                ch.is_ascii_digit() || ch.is_whitespace() || matches!(ch, '(' | ')' | '^')
+            }),
+            Some('^') => next.is_some_and(|ch| ch.is_ascii_digit() || ch == '.'),
+            _ => false,
+        }
+    }
+
+    /// Reads `~N` and `^boost` suffixes trailing a word, phrase or group.
+    fn read_modifiers(&mut self, tokens: &mut Vec<Token>) -> Result<(), MemvidError> {
+        while self.modifier_starts() {
+            let marker = self.peek();
+            self.index += 1;
+            let start = self.index;
+            while self
+                .peek()
+                .is_some_and(|ch| ch.is_ascii_digit() || (marker == Some('^') && ch == '.'))
+            {
+                self.index += 1;
+            }
+            let value: String = self.chars[start..self.index].iter().collect();
+            if marker == Some('~') {
+                let amount = if value.is_empty() {
+                    None
+                } else {
+                    Some(value.parse().map_err(|_| MemvidError::InvalidQuery {
+                        reason: format!("invalid proximity value: ~{value}"),
+                    })?)
+                };
+                tokens.push(Token::Tilde(amount));
+            } else {
+                let boost: f32 = value.parse().map_err(|_| MemvidError::InvalidQuery {
+                    reason: format!("invalid boost value: ^{value}"),
+                })?;
+                if !boost.is_finite() || boost <= 0.0 {
+                    return Err(MemvidError::InvalidQuery {
+                        reason: format!("boost must be positive: ^{value}"),
+                    });
+                }
+                tokens.push(Token::Caret(boost));
+            }
+        }
+        Ok(())
+    }
+
     fn read_quoted(&mut self) -> Result<String, MemvidError> {
         self.index += 1; // skip opening quote
         let value = self.read_until_quote()?;
@@ -297,11 +509,26 @@ impl Parser {
     }
 
     fn parse_factor(&mut self) -> Result<Expr, MemvidError> {
-        if self.match_token(TokenKind::Not) {
+        if self.match_token(TokenKind::Not) || self.match_token(TokenKind::Excluded) {
             let inner = self.parse_factor()?;
             return Ok(Expr::Not(Box::new(inner)));
         }
-        self.parse_primary()
+        // Terms are ANDed by default, so `+term` is already required.
+        if self.match_token(TokenKind::Required) {
+            return self.parse_factor();
+        }
+        let mut expr = self.parse_primary()?;
+        if let Some(Token::Tilde(amount)) = self.peek_token() {
+            let amount = *amount;
+            self.position += 1;
+            expr = apply_tilde(expr, amount)?;
+        }
+        if let Some(Token::Caret(boost)) = self.peek_token() {
+            let boost = *boost;
+            self.position += 1;
+            expr = Expr::Boost(Box::new(expr), boost);
+        }
+        Ok(expr)
     }
 
     fn parse_primary(&mut self) -> Result<Expr, MemvidError> {
@@ -319,8 +546,8 @@ impl Parser {
                 let term = FieldTerm::from_pair(&field, &value)?;
                 Ok(Expr::Term(Term::Field(term)))
             }
-            Some(Token::DateRange(field, start, end)) => {
-                let term = FieldTerm::from_date_range(&field, &start, &end)?;
+            Some(Token::Range(field, start, end)) => {
+                let term = FieldTerm::from_range(&field, &start, &end)?;
                 Ok(Expr::Term(Term::Field(term)))
             }
             Some(token) => Err(MemvidError::InvalidQuery {
@@ -332,6 +559,10 @@ impl Parser {
         }
     }
 
+    fn peek_token(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
     fn advance(&mut self) -> Option<Token> {
         if self.is_end() {
             None
@@ -380,6 +611,8 @@ enum TokenKind {
     And,
     Or,
     Not,
+    Required,
+    Excluded,
 }
 
 impl TokenKind {
@@ -391,10 +624,41 @@ impl TokenKind {
                 | (TokenKind::And, Token::And)
                 | (TokenKind::Or, Token::Or)
                 | (TokenKind::Not, Token::Not)
+                | (TokenKind::Required, Token::Required)
+                | (TokenKind::Excluded, Token::Excluded)
         )
     }
 }
 
+/// Turns `word~N` into a fuzzy term and `"phrase"~N` into a proximity term.
+fn apply_tilde(expr: Expr, amount: Option<u32>) -> Result<Expr, MemvidError> {
+    match expr {
+        Expr::Term(Term::Text(TextTerm::Word(word))) if !word.is_empty() => {
+            let distance = match amount {
+                Some(amount) => u8::try_from(amount)
+                    .ok()
+                    .filter(|distance| *distance <= MAX_FUZZY_DISTANCE)
+                    .ok_or_else(|| MemvidError::InvalidQuery {
+                        reason: format!(
+                            "fuzzy edit distance must be at most {MAX_FUZZY_DISTANCE}, got {amount}"
+                        ),
+                    })?,
+                None => MAX_FUZZY_DISTANCE,
+            };
+            Ok(Expr::Term(Term::Text(TextTerm::Fuzzy { word, distance })))
+        }
+        Expr::Term(Term::Text(TextTerm::Phrase(phrase))) => {
+            let slop = amount.ok_or_else(|| MemvidError::InvalidQuery {
+                reason: "proximity search needs a distance, e.g. \"quick fox\"~3".into(),
+            })?;
+            Ok(Expr::Term(Term::Text(TextTerm::Proximity { phrase, slop })))
+        }
+        _ => Err(MemvidError::InvalidQuery {
+            reason: "`~` only applies to a word or a quoted phrase".into(),
+        }),
+    }
+}
+
 impl TextTerm {
     fn from_word(word: String) -> Self {
         // Strip trailing question marks - they're punctuation, not wildcards
@@ -428,13 +692,42 @@ impl FieldTerm {
             "track" => Ok(FieldTerm::Track(normalized)),
             "tag" => Ok(FieldTerm::Tag(normalized)),
             "label" => Ok(FieldTerm::Label(normalized)),
+            "title" => Ok(FieldTerm::Title(normalized)),
+            _ if field.starts_with(META_FIELD_PREFIX) => {
+                let value =
+                    MetaValue::parse(&normalized).ok_or_else(|| MemvidError::InvalidQuery {
+                        reason: format!("unsupported value for {field}: {value}"),
+                    })?;
+                Ok(FieldTerm::Meta {
+                    key: field[META_FIELD_PREFIX.len()..].to_string(),
+                    value,
+                })
+            }
             _ => Err(MemvidError::InvalidQuery {
                 reason: format!("unsupported field: {field}"),
             }),
         }
     }
 
-    fn from_date_range(field: &str, start: &str, end: &str) -> Result<Self, MemvidError> {
+    fn from_range(field: &str, start: &str, end: &str) -> Result<Self, MemvidError> {
+        if let Some(key) = field.strip_prefix(META_FIELD_PREFIX) {
+            let bound = |value: &str| -> Result<Option<MetaNumber>, MemvidError> {
+                if value == "*" {
+                    return Ok(None);
+                }
+                match MetaValue::parse(value) {
+                    Some(MetaValue::Number(number)) => Ok(Some(number)),
+                    _ => Err(MemvidError::InvalidQuery {
+                        reason: format!("range bounds for {field} must be numbers or dates"),
+                    }),
+                }
+            };
+            return Ok(FieldTerm::MetaRange {
+                key: key.to_string(),
+                start: bound(start)?,
+                end: bound(end)?,
+            });
+        }
         if field != "date" {
             return Err(MemvidError::InvalidQuery {
                 reason: format!("unexpected field for date range: {field}"),
@@ -561,6 +854,7 @@ mod tests {
         assert!(parse_query("scope:project").is_ok());
         assert!(parse_query("track:main").is_ok());
         assert!(parse_query("label:todo").is_ok());
+        assert!(parse_query("title:\"quarterly report\"").is_ok());
     }
 
     #[test]
@@ -597,4 +891,133 @@ mod tests {
             _ => panic!("expected Word variant"),
         }
     }
+
+    #[test]
+    fn parses_fuzzy_proximity_and_boost_modifiers() {
+        let parsed = parse_query("memvdi~1").expect("parse");
+        assert!(matches!(
+            parsed.expr,
+            Expr::Term(Term::Text(TextTerm::Fuzzy { ref word, distance: 1 })) if word == "memvdi"
+        ));
+        let parsed = parse_query("memvdi~").expect("parse");
+        assert!(matches!(
+            parsed.expr,
+            Expr::Term(Term::Text(TextTerm::Fuzzy { distance: 2, .. }))
+        ));
+        let parsed = parse_query("\"quick fox\"~3").expect("parse");
+        assert!(matches!(
+            parsed.expr,
+            Expr::Term(Term::Text(TextTerm::Proximity { ref phrase, slop: 3 })) if phrase == "quick fox"
+        ));
+        let parsed = parse_query("rust^2.5").expect("parse");
+        assert!(matches!(parsed.expr, Expr::Boost(_, boost) if boost == 2.5));
+        let parsed = parse_query("(alpha OR beta)^3 gamma~1^2").expect("parse");
+        let Expr::And(children) = parsed.expr else {
+            panic!("expected conjunction");
+        };
+        assert!(matches!(children[0], Expr::Boost(ref inner, _) if matches!(**inner, Expr::Or(_))));
+        assert!(matches!(children[1], Expr::Boost(ref inner, _)
+            if matches!(**inner, Expr::Term(Term::Text(TextTerm::Fuzzy { .. })))));
+    }
+
+    #[test]
+    fn parses_required_and_excluded_prefixes() {
+        let parsed = parse_query("+rust -java").expect("parse");
+        let Expr::And(children) = parsed.expr else {
+            panic!("expected conjunction");
+        };
+        assert!(
+            matches!(children[0], Expr::Term(Term::Text(TextTerm::Word(ref w))) if w == "rust")
+        );
+        assert!(matches!(children[1], Expr::Not(_)));
+        // Dashes inside or around words stay literal.
+        let parsed = parse_query("state-of-the-art").expect("parse");
+        assert!(matches!(
+            parsed.expr,
+            Expr::Term(Term::Text(TextTerm::Word(_)))
+        ));
+    }
+
+    #[test]
+    fn rejects_invalid_modifiers() {
+        assert!(parse_query("memvid~3").is_err());
+        assert!(parse_query("\"quick fox\"~").is_err());
+        assert!(parse_query("rust^0").is_err());
+        assert!(parse_query("tag:rust~1").is_ok());
+    }
+
+    #[test]
+    fn parses_meta_fields_and_ranges() {
+        let parsed = parse_query("meta.Customer:ACME").expect("parse");
+        assert!(matches!(
+            parsed.expr,
+            Expr::Term(Term::Field(FieldTerm::Meta { ref key, value: MetaValue::Text(ref text) }))
+                if key == "customer" && text == "acme"
+        ));
+        let parsed = parse_query("meta.priority:[1 TO *]").expect("parse");
+        assert!(matches!(
+            parsed.expr,
+            Expr::Term(Term::Field(FieldTerm::MetaRange {
+                start: Some(MetaNumber::I64(1)),
+                end: None,
+                ..
+            }))
+        ));
+        assert!(parse_query("meta.customer:[a TO b]").is_err());
+        assert!(parse_query("meta.:value").is_ok());
+    }
+
+    #[test]
+    fn meta_values_unwrap_json_and_dates() {
+        assert_eq!(
+            MetaValue::parse("\"Acme\""),
+            Some(MetaValue::Text("acme".into()))
+        );
+        assert_eq!(
+            MetaValue::parse("3"),
+            Some(MetaValue::Number(MetaNumber::I64(3)))
+        );
+        assert_eq!(
+            MetaValue::parse("2.5"),
+            Some(MetaValue::Number(MetaNumber::F64(2.5)))
+        );
+        assert_eq!(
+            MetaValue::parse("2024-01-01"),
+            Some(MetaValue::Number(MetaNumber::I64(1_704_067_200)))
+        );
+        assert_eq!(MetaValue::parse("{\"nested\":1}"), None);
+    }
+
+    #[test]
+    fn meta_integers_stay_exact_and_padded_ids_stay_text() {
+        // Both round to the same f64.
+        let first = MetaValue::parse("9007199254740993");
+        let second = MetaValue::parse("9007199254740992");
+        assert_eq!(
+            first,
+            Some(MetaValue::Number(MetaNumber::I64(9_007_199_254_740_993)))
+        );
+        assert_ne!(first, second);
+        assert_eq!(
+            MetaValue::parse("18446744073709551615"),
+            Some(MetaValue::Number(MetaNumber::U64(u64::MAX)))
+        );
+        assert_eq!(
+            MetaValue::parse("00123"),
+            Some(MetaValue::Text("00123".into()))
+        );
+        assert_ne!(MetaValue::parse("00123"), MetaValue::parse("123"));
+        assert_eq!(
+            MetaValue::parse("0.5"),
+            Some(MetaValue::Number(MetaNumber::F64(0.5)))
+        );
+        assert_eq!(
+            MetaNumber::I64(-1).compare(MetaNumber::U64(u64::MAX)),
+            Some(std::cmp::Ordering::Less)
+        );
+        assert_eq!(
+            MetaNumber::I64(3).compare(MetaNumber::F64(2.5)),
+            Some(std::cmp::Ordering::Greater)
+        );
+    }
 }
@@ -0,0 +1,190 @@
+use super::tokenizer::MemvidTokenizer;
+use crate::types::{LexAnalyzerConfig, LexLanguage};
+use tantivy::Index;
+use tantivy::tokenizer::{
+    AsciiFoldingFilter, Language, LowerCaser, Stemmer, StopWordFilter, TextAnalyzer, Token,
+};
+
+/// Tokenizer name baked into the schema for analyzed text fields.
+///
+/// Renamed from [`LEGACY_TOKENIZER`] when CJK runs started splitting into bigrams,
+/// so doctor can tell snapshots indexed with the old segmentation apart.
+pub(crate) const DEFAULT_TOKENIZER: &str = "memvid_text";
+
+/// Earlier name of [`DEFAULT_TOKENIZER`], kept registered so older snapshots stay
+/// searchable until doctor rebuilds them.
+const LEGACY_TOKENIZER: &str = "memvid_default";
+
+/// Tokenizer for the unstemmed `words` field that spelling suggestions draw from.
+pub(super) const WORDS_TOKENIZER: &str = "memvid_words";
+
+/// Minimum function-word hits before a frame's language is trusted.
+const MIN_DETECTION_HITS: usize = 2;
+
+/// Words scanned per frame when detecting its language.
+const DETECTION_WORD_LIMIT: usize = 512;
+
+/// Registers the configured chain under [`DEFAULT_TOKENIZER`] and its legacy name,
+/// its unstemmed form under [`WORDS_TOKENIZER`] and, when detection is enabled, one
+/// chain per supported language.
+pub(super) fn register_analyzers(index: &Index, config: &LexAnalyzerConfig) {
+    index
+        .tokenizers()
+        .register(DEFAULT_TOKENIZER, build_analyzer(config.language, config));
+    index
+        .tokenizers()
+        .register(LEGACY_TOKENIZER, build_analyzer(config.language, config));
+    index
+        .tokenizers()
+        .register(WORDS_TOKENIZER, build_analyzer(None, config));
+    if config.detect_language {
+        for language in LexLanguage::ALL {
+            index.tokenizers().register(
+                tokenizer_name(language),
+                build_analyzer(Some(language), config),
+            );
+        }
+    }
+}
+
+pub(super) fn tokenizer_name(language: LexLanguage) -> &'static str {
+    match language {
+        LexLanguage::English => "memvid_en",
+        LexLanguage::German => "memvid_de",
+        LexLanguage::French => "memvid_fr",
+        LexLanguage::Spanish => "memvid_es",
+        LexLanguage::Italian => "memvid_it",
+        LexLanguage::Portuguese => "memvid_pt",
+        LexLanguage::Dutch => "memvid_nl",
+    }
+}
+
+fn build_analyzer(language: Option<LexLanguage>, config: &LexAnalyzerConfig) -> TextAnalyzer {
+    let mut builder = TextAnalyzer::builder(MemvidTokenizer).filter_dynamic(LowerCaser);
+    if let Some(language) = language {
+        let language = tantivy_language(language);
+        if config.stopwords {
+            if let Some(filter) = StopWordFilter::new(language) {
+                builder = builder.filter_dynamic(filter);
+            }
+        }
+        builder = builder.filter_dynamic(Stemmer::new(language));
+    }
+    if config.ascii_folding {
+        builder = builder.filter_dynamic(AsciiFoldingFilter);
+    }
+    builder.build()
+}
+
+fn tantivy_language(language: LexLanguage) -> Language {
+    match language {
+        LexLanguage::English => Language::English,
+        LexLanguage::German => Language::German,
+        LexLanguage::French => Language::French,
+        LexLanguage::Spanish => Language::Spanish,
+        LexLanguage::Italian => Language::Italian,
+        LexLanguage::Portuguese => Language::Portuguese,
+        LexLanguage::Dutch => Language::Dutch,
+    }
+}
+
+/// Runs `analyzer` over `text`, keeping token positions so phrase queries
+/// stay aligned across removed stopwords.
+pub(super) fn collect_tokens(analyzer: &mut TextAnalyzer, text: &str) -> Vec<Token> {
+    let mut stream = analyzer.token_stream(text);
+    let mut tokens = Vec::new();
+    while stream.advance() {
+        tokens.push(stream.token().clone());
+    }
+    tokens
+}
+
+fn function_words(language: LexLanguage) -> &'static [&'static str] {
+    // Only words that are frequent in one language and rare in the others.
+    match language {
+        LexLanguage::English => &[
+            "the", "and", "is", "are", "of", "with", "this", "that", "was", "for", "which",
+        ],
+        LexLanguage::German => &[
+            "der", "die", "das", "und", "ist", "nicht", "ein", "eine", "mit", "auf", "ich", "sind",
+            "den", "dem", "auch",
+        ],
+        LexLanguage::French => &[
+            "le", "les", "et", "est", "une", "des", "du", "pas", "avec", "dans", "sur", "qui",
+            "pour", "au",
+        ],
+        LexLanguage::Spanish => &[
+            "el", "los", "las", "y", "es", "del", "por", "está", "son", "pero", "muy", "también",
+        ],
+        LexLanguage::Italian => &[
+            "il", "gli", "è", "di", "della", "che", "non", "per", "sono", "nel", "anche", "questo",
+        ],
+        LexLanguage::Portuguese => &[
+            "o", "os", "é", "um", "uma", "do", "da", "não", "com", "são", "em", "também", "mais",
+        ],
+        LexLanguage::Dutch => &[
+            "de", "het", "een", "en", "van", "niet", "met", "op", "zijn", "voor", "ook", "wij",
+        ],
+    }
+}
+
+/// Guesses the language of `text` from function-word frequencies.
+///
+/// Returns `None` when too few function words are found or two languages tie,
+/// leaving the caller to fall back to the configured language.
+pub(crate) fn detect_language(text: &str) -> Option<LexLanguage> {
+    let mut counts: Vec<(LexLanguage, usize)> = LexLanguage::ALL
+        .iter()
+        .map(|language| (*language, 0))
+        .collect();
+    let words = text
+        .split(|ch: char| !ch.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .take(DETECTION_WORD_LIMIT);
+    for word in words {
+        let word = word.to_lowercase();
+        for (language, count) in &mut counts {
+            if function_words(*language).contains(&word.as_str()) {
+                *count += 1;
+            }
+        }
+    }
+
+    counts.sort_by(|left, right| right.1.cmp(&left.1));
+    let (language, best) = counts[0];
+    let runner_up = counts[1].1;
+    (best >= MIN_DETECTION_HITS && best > runner_up).then_some(language)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_language_from_function_words() {
+        assert_eq!(
+            detect_language("Die Häuser sind alt und das Dach ist nicht dicht"),
+            Some(LexLanguage::German)
+        );
+        assert_eq!(
+            detect_language("Les maisons sont vieilles et le toit est abîmé"),
+            Some(LexLanguage::French)
+        );
+        assert_eq!(
+            detect_language("The houses are old and the roof is leaking"),
+            Some(LexLanguage::English)
+        );
+        assert_eq!(detect_language("Kubernetes 1.29 rollout"), None);
+    }
+
+    #[test]
+    fn german_chain_stems_and_folds() {
+        let config = LexAnalyzerConfig::for_language(LexLanguage::German);
+        let mut analyzer = build_analyzer(config.language, &config);
+        let tokens: Vec<String> = collect_tokens(&mut analyzer, "Die Häuser")
+            .into_iter()
+            .map(|token| token.text)
+            .collect();
+        assert_eq!(tokens, vec!["haus".to_string()]);
+    }
+}
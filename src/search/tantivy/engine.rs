@@ -1,31 +1,53 @@
+use super::analyzer::{DEFAULT_TOKENIZER, collect_tokens, detect_language, tokenizer_name};
 use super::query;
 use super::schema::{build_schema, initialise_tokenizer};
+use super::spelling;
 use super::util::to_search_value;
-use crate::search::parser::ParsedQuery;
-use crate::types::{Frame, FrameId};
+use crate::search::parser::{MetaNumber, MetaValue, ParsedQuery};
+use crate::text::edit_distance;
+use crate::types::{Frame, FrameId, LexAnalyzerConfig, LexLanguage, SpellingSuggestion};
 use crate::{MemvidError, Result};
 use blake3::{Hasher, hash};
-use tantivy::collector::TopDocs;
+use std::collections::BTreeMap;
+use tantivy::collector::{DocSetCollector, TopDocs};
+use tantivy::columnar::Column;
 use tantivy::indexer::IndexWriter;
-use tantivy::schema::{Field, OwnedValue, Schema, TantivyDocument};
-use tantivy::{Index, IndexReader, Term, doc};
+use tantivy::query::{BooleanQuery, MoreLikeThisQuery, Occur, Query, TermQuery, TermSetQuery};
+use tantivy::schema::{Field, IndexRecordOption, OwnedValue, Schema, TantivyDocument};
+use tantivy::tokenizer::PreTokenizedString;
+use tantivy::{DocAddress, Index, IndexReader, Searcher, Term, doc};
 use tempfile::TempDir;
 
+/// Default score multiplier for free-text matches in frame titles.
+pub const DEFAULT_TITLE_BOOST: f32 = 2.0;
+
+/// Most distinctive terms a more-like-this query keeps from the source document.
+const MORE_LIKE_THIS_TERMS: usize = 25;
+
 /// Tantivy-backed search index used when the `lex` feature is enabled.
 pub struct TantivyEngine {
     pub(super) work_dir: TempDir,
     pub(super) index: Index,
     pub(super) _schema: Schema,
     pub(super) content: Field,
+    /// Absent in snapshots built before titles were indexed; doctor's lex rebuild adds it.
+    pub(super) title: Option<Field>,
+    pub(super) title_boost: f32,
+    /// Unstemmed content words; absent in snapshots built before spelling suggestions.
+    pub(super) words: Option<Field>,
     pub(super) tags: Field,
     pub(super) labels: Field,
     pub(super) track: Field,
     pub(super) timestamp: Field,
     pub(super) uri: Field,
+    /// `extra_metadata` as a JSON object; absent in snapshots built before it was indexed.
+    pub(super) meta: Option<Field>,
     pub(super) frame_id: Field,
     pub(super) index_writer: Option<IndexWriter>,
     pub(super) reader: IndexReader,
     pub(super) tokenizer: Option<String>,
+    pub(super) analyzer: LexAnalyzerConfig,
+    pub(super) spelling_dictionary: spelling::DictionaryCache,
 }
 
 /// Search hit returned from Tantivy queries.
@@ -50,7 +72,7 @@ pub struct TantivySegmentBlob {
 }
 
 impl TantivyEngine {
-    pub fn create() -> Result<Self> {
+    pub fn create(analyzer: &LexAnalyzerConfig) -> Result<Self> {
         let dir = TempDir::new().map_err(|err| MemvidError::Tantivy {
             reason: format!("failed to allocate Tantivy work directory: {}", err),
         })?;
@@ -60,25 +82,33 @@ impl TantivyEngine {
                 reason: err.to_string(),
             }
         })?;
-        initialise_tokenizer(&index);
-        Self::from_parts(dir, index, schema)
+        initialise_tokenizer(&index, analyzer);
+        Self::from_parts(dir, index, schema, analyzer)
     }
 
-    pub fn open_from_dir(dir: TempDir) -> Result<Self> {
+    pub fn open_from_dir(dir: TempDir, analyzer: &LexAnalyzerConfig) -> Result<Self> {
         let index = Index::open_in_dir(dir.path()).map_err(|err| MemvidError::Tantivy {
             reason: err.to_string(),
         })?;
-        initialise_tokenizer(&index);
+        initialise_tokenizer(&index, analyzer);
         let schema = index.schema();
-        Self::from_parts(dir, index, schema)
+        Self::from_parts(dir, index, schema, analyzer)
     }
 
-    fn from_parts(dir: TempDir, index: Index, schema: Schema) -> Result<Self> {
+    fn from_parts(
+        dir: TempDir,
+        index: Index,
+        schema: Schema,
+        analyzer: &LexAnalyzerConfig,
+    ) -> Result<Self> {
         let content = schema
             .get_field("content")
             .map_err(|err| MemvidError::Tantivy {
                 reason: err.to_string(),
             })?;
+        let title = schema.get_field("title").ok();
+        let words = schema.get_field("words").ok();
+        let meta = schema.get_field("meta").ok();
         let tags = schema
             .get_field("tags")
             .map_err(|err| MemvidError::Tantivy {
@@ -124,15 +154,21 @@ impl TantivyEngine {
             index,
             _schema: schema,
             content,
+            title,
+            title_boost: DEFAULT_TITLE_BOOST,
+            words,
             tags,
             labels,
             track,
             timestamp,
             uri,
+            meta,
             frame_id,
             index_writer: Some(writer),
             reader,
-            tokenizer: Some("memvid_default".to_string()),
+            tokenizer: Some(DEFAULT_TOKENIZER.to_string()),
+            analyzer: analyzer.clone(),
+            spelling_dictionary: spelling::DictionaryCache::default(),
         })
     }
 
@@ -163,10 +199,31 @@ impl TantivyEngine {
         }
         if frame.id <= 20 || (frame.id % 100 == 0) {}
         let mut document = doc!(
-            self.content => content,
             self.timestamp => frame.timestamp,
             self.frame_id => frame.id,
         );
+        match self.detected_language(content) {
+            Some(language) => {
+                let tokens = match self.index.tokenizers().get(tokenizer_name(language)) {
+                    Some(mut analyzer) => collect_tokens(&mut analyzer, content),
+                    None => Vec::new(),
+                };
+                document.add_pre_tokenized_text(
+                    self.content,
+                    PreTokenizedString {
+                        text: content.to_string(),
+                        tokens,
+                    },
+                );
+            }
+            None => document.add_text(self.content, content),
+        }
+        if let Some(field) = self.words {
+            document.add_text(field, content);
+        }
+        if let (Some(field), Some(title)) = (self.title, frame.title.as_deref()) {
+            document.add_text(field, title);
+        }
         for tag in &frame.tags {
             document.add_text(self.tags, to_search_value(tag));
         }
@@ -179,6 +236,24 @@ impl TantivyEngine {
         if let Some(uri) = &frame.uri {
             document.add_text(self.uri, to_search_value(uri));
         }
+        if let Some(field) = self.meta {
+            let object: BTreeMap<String, OwnedValue> = frame
+                .extra_metadata
+                .iter()
+                .filter_map(|(key, value)| {
+                    let value = match MetaValue::parse(value)? {
+                        MetaValue::Number(MetaNumber::I64(number)) => OwnedValue::I64(number),
+                        MetaValue::Number(MetaNumber::U64(number)) => OwnedValue::U64(number),
+                        MetaValue::Number(MetaNumber::F64(number)) => OwnedValue::F64(number),
+                        MetaValue::Text(text) => OwnedValue::Str(text),
+                    };
+                    Some((to_search_value(key), value))
+                })
+                .collect();
+            if !object.is_empty() {
+                document.add_object(field, object);
+            }
+        }
         self.writer_mut()?
             .add_document(document)
             .map_err(|err| MemvidError::Tantivy {
@@ -284,41 +359,165 @@ impl TantivyEngine {
             .map_err(|err| MemvidError::Tantivy {
                 reason: err.to_string(),
             })?;
-        let mut results = Vec::new();
-        for (score, address) in top_docs {
-            let document: TantivyDocument =
-                searcher.doc(address).map_err(|err| MemvidError::Tantivy {
-                    reason: err.to_string(),
-                })?;
-            let frame_id = match document.get_first(self.frame_id) {
-                Some(value) => match OwnedValue::from(value) {
-                    OwnedValue::U64(id) => id,
-                    _ => {
-                        return Err(MemvidError::Tantivy {
-                            reason: "tantivy doc missing frame_id".into(),
-                        });
-                    }
-                },
-                None => {
+        top_docs
+            .into_iter()
+            .map(|(score, address)| self.doc_hit(&searcher, score, address))
+            .collect()
+    }
+
+    /// Documents sharing the most distinctive content terms with `frame_id`'s document,
+    /// best first. The frame's own document is included; an unindexed frame yields none.
+    /// A `frame_filter` restricts the hits to those frame ids, as in
+    /// [`Self::search_documents`].
+    pub fn more_like_this(
+        &self,
+        frame_id: FrameId,
+        limit: usize,
+        frame_filter: Option<&[u64]>,
+    ) -> Result<Vec<TantivyDocHit>> {
+        if frame_filter.is_some_and(<[u64]>::is_empty) {
+            return Ok(Vec::new());
+        }
+        let searcher = self.reader.searcher();
+        let own = TermQuery::new(
+            Term::from_field_u64(self.frame_id, frame_id),
+            IndexRecordOption::Basic,
+        );
+        let found = searcher
+            .search(&own, &TopDocs::with_limit(1))
+            .map_err(|err| MemvidError::Tantivy {
+                reason: err.to_string(),
+            })?;
+        let Some((_, address)) = found.first() else {
+            return Ok(Vec::new());
+        };
+        let document: TantivyDocument =
+            searcher.doc(*address).map_err(|err| MemvidError::Tantivy {
+                reason: err.to_string(),
+            })?;
+        // Stored content keeps its analyzed tokens, so terms match what was indexed.
+        let content: Vec<OwnedValue> = document
+            .get_all(self.content)
+            .map(OwnedValue::from)
+            .collect();
+        if content.is_empty() {
+            return Ok(Vec::new());
+        }
+        let query = MoreLikeThisQuery::builder()
+            .with_min_doc_frequency(1)
+            .with_min_term_frequency(1)
+            .with_max_query_terms(MORE_LIKE_THIS_TERMS)
+            .with_document_fields(vec![(self.content, content)]);
+        let query: Box<dyn Query> = match frame_filter {
+            Some(ids) => {
+                let terms = ids
+                    .iter()
+                    .map(|id| Term::from_field_u64(self.frame_id, *id))
+                    .collect::<Vec<_>>();
+                Box::new(BooleanQuery::new(vec![
+                    (Occur::Must, Box::new(query) as Box<dyn Query>),
+                    (Occur::Must, Box::new(TermSetQuery::new(terms))),
+                ]))
+            }
+            None => Box::new(query),
+        };
+        let top_docs = searcher
+            .search(&query, &TopDocs::with_limit(limit.max(1)))
+            .map_err(|err| MemvidError::Tantivy {
+                reason: err.to_string(),
+            })?;
+        top_docs
+            .into_iter()
+            .map(|(score, address)| self.doc_hit(&searcher, score, address))
+            .collect()
+    }
+
+    fn doc_hit(
+        &self,
+        searcher: &Searcher,
+        score: f32,
+        address: DocAddress,
+    ) -> Result<TantivyDocHit> {
+        let document: TantivyDocument =
+            searcher.doc(address).map_err(|err| MemvidError::Tantivy {
+                reason: err.to_string(),
+            })?;
+        let frame_id = match document.get_first(self.frame_id) {
+            Some(value) => match OwnedValue::from(value) {
+                OwnedValue::U64(id) => id,
+                _ => {
                     return Err(MemvidError::Tantivy {
                         reason: "tantivy doc missing frame_id".into(),
                     });
                 }
-            };
-            let content = match document.get_first(self.content) {
-                Some(value) => match OwnedValue::from(value) {
-                    OwnedValue::Str(text) => text,
-                    _ => String::new(),
-                },
-                None => String::new(),
-            };
-            results.push(TantivyDocHit {
-                frame_id,
-                score,
-                content,
+            },
+            None => {
+                return Err(MemvidError::Tantivy {
+                    reason: "tantivy doc missing frame_id".into(),
+                });
+            }
+        };
+        let content = match document.get_first(self.content) {
+            Some(value) => match OwnedValue::from(value) {
+                OwnedValue::Str(text) => text,
+                OwnedValue::PreTokStr(pre) => pre.text,
+                _ => String::new(),
+            },
+            None => String::new(),
+        };
+        Ok(TantivyDocHit {
+            frame_id,
+            score,
+            content,
+        })
+    }
+
+    /// Frame ids of every document matching the root query, read from the fast
+    /// `frame_id` column (stored values in snapshots built before it was fast).
+    pub fn matching_frame_ids(
+        &self,
+        parsed: &ParsedQuery,
+        uri_filter: Option<&str>,
+        scope_filter: Option<&str>,
+        frame_filter: Option<&[u64]>,
+    ) -> Result<Vec<FrameId>> {
+        if frame_filter.is_some_and(<[u64]>::is_empty) {
+            return Ok(Vec::new());
+        }
+        let query = query::build_root_query(self, parsed, uri_filter, scope_filter, frame_filter)?;
+        let searcher = self.reader.searcher();
+        let addresses =
+            searcher
+                .search(&query, &DocSetCollector)
+                .map_err(|err| MemvidError::Tantivy {
+                    reason: err.to_string(),
+                })?;
+        let mut columns: BTreeMap<u32, Option<Column<u64>>> = BTreeMap::new();
+        let mut frame_ids = Vec::with_capacity(addresses.len());
+        for address in addresses {
+            let column = columns.entry(address.segment_ord).or_insert_with(|| {
+                searcher
+                    .segment_reader(address.segment_ord)
+                    .fast_fields()
+                    .u64("frame_id")
+                    .ok()
             });
+            if let Some(column) = column {
+                frame_ids.extend(column.first(address.doc_id));
+                continue;
+            }
+            let document: TantivyDocument =
+                searcher.doc(address).map_err(|err| MemvidError::Tantivy {
+                    reason: err.to_string(),
+                })?;
+            if let Some(OwnedValue::U64(id)) =
+                document.get_first(self.frame_id).map(OwnedValue::from)
+            {
+                frame_ids.push(id);
+            }
         }
-        Ok(results)
+        frame_ids.sort_unstable();
+        Ok(frame_ids)
     }
 
     pub fn snapshot_segments(&self) -> Result<TantivySnapshot> {
@@ -383,22 +582,96 @@ impl TantivyEngine {
         })
     }
 
-    pub(crate) fn analyse_text(&self, text: &str) -> Vec<String> {
-        if let Some(name) = &self.tokenizer {
-            if let Some(mut analyzer) = self.index.tokenizers().get(name) {
-                let mut stream = analyzer.token_stream(text);
-                let mut tokens = Vec::new();
-                while stream.advance() {
-                    tokens.push(stream.token().text.to_string());
-                }
-                return tokens;
+    /// Language to analyze `content` with when it differs from the configured one.
+    fn detected_language(&self, content: &str) -> Option<LexLanguage> {
+        if !self.analyzer.detect_language {
+            return None;
+        }
+        detect_language(content).filter(|language| Some(*language) != self.analyzer.language)
+    }
+
+    /// Chains a document may have been indexed with, the configured one first.
+    pub(super) fn analyzer_names(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = self.tokenizer.as_deref().into_iter().collect();
+        if self.analyzer.detect_language {
+            names.extend(
+                LexLanguage::ALL
+                    .into_iter()
+                    .map(|language| tokenizer_name(language)),
+            );
+        }
+        names
+    }
+
+    /// Positioned tokens for `text` under every chain a document may have been indexed with.
+    ///
+    /// The configured chain comes first; with language detection enabled each other
+    /// language's chain is added when it yields different tokens.
+    pub(crate) fn analyse_variants(&self, text: &str) -> Vec<Vec<(usize, String)>> {
+        let mut variants: Vec<Vec<(usize, String)>> = Vec::new();
+        for name in self.analyzer_names() {
+            let Some(mut analyzer) = self.index.tokenizers().get(name) else {
+                continue;
+            };
+            let tokens: Vec<(usize, String)> = collect_tokens(&mut analyzer, text)
+                .into_iter()
+                .map(|token| (token.position, token.text))
+                .collect();
+            if !tokens.is_empty() && !variants.contains(&tokens) {
+                variants.push(tokens);
             }
         }
-        if text.trim().is_empty() {
-            Vec::new()
-        } else {
-            vec![text.to_ascii_lowercase()]
+        variants
+    }
+
+    /// Byte ranges of `text` whose analyzed tokens match the analyzed query `terms`,
+    /// or come within edit distance of an analyzed `fuzzy` word.
+    ///
+    /// Runs every chain a document may have been indexed with, so stemmed and
+    /// language-specific matches are marked the way the index matched them.
+    pub(crate) fn highlight_ranges(
+        &self,
+        text: &str,
+        terms: &[String],
+        fuzzy: &[(String, u8)],
+    ) -> Vec<(usize, usize)> {
+        let fuzzy_tokens: Vec<(String, u8)> = fuzzy
+            .iter()
+            .flat_map(|(word, distance)| {
+                self.analyse_variants(word)
+                    .into_iter()
+                    .flatten()
+                    .map(move |(_, token)| (token, *distance))
+            })
+            .collect();
+        let mut ranges = Vec::new();
+        for name in self.analyzer_names() {
+            let Some(mut analyzer) = self.index.tokenizers().get(name) else {
+                continue;
+            };
+            for token in collect_tokens(&mut analyzer, text) {
+                let matched = terms.contains(&token.text)
+                    || fuzzy_tokens.iter().any(|(word, distance)| {
+                        edit_distance(&token.text, word) <= usize::from(*distance)
+                    });
+                if matched {
+                    ranges.push((token.offset_from, token.offset_to));
+                }
+            }
         }
+        ranges.sort_unstable();
+        ranges.dedup();
+        ranges
+    }
+
+    /// Closest indexed words for query `words` the content vocabulary lacks.
+    pub fn spelling_suggestions(&self, words: &[String]) -> Result<Vec<SpellingSuggestion>> {
+        spelling::suggest(self, words)
+    }
+
+    /// Score multiplier applied to title matches of free-text terms.
+    pub fn set_title_boost(&mut self, boost: f32) {
+        self.title_boost = boost;
     }
 
     pub fn num_docs(&self) -> u64 {
@@ -1,14 +1,18 @@
 //! Tantivy-backed lexical search integration.
 
+mod analyzer;
 mod engine;
 mod query;
 mod schema;
+mod spelling;
 mod storage;
+mod tokenizer;
 mod util;
 mod wal;
 
+pub(crate) use analyzer::DEFAULT_TOKENIZER;
 #[allow(unused_imports)]
-pub use engine::{TantivyDocHit, TantivyEngine, TantivySnapshot};
+pub use engine::{DEFAULT_TITLE_BOOST, TantivyDocHit, TantivyEngine, TantivySnapshot};
 #[allow(unused_imports)]
 pub(crate) use storage::{EmbeddedLexSegment, EmbeddedLexStorage};
 #[allow(unused_imports)]
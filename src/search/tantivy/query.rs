@@ -2,14 +2,16 @@ use std::ops::Bound;
 
 use super::engine::TantivyEngine;
 use super::util::{combine_should_queries, to_search_value};
-use crate::search::parser::{Expr, FieldTerm, ParsedQuery, Term as ParsedTerm, TextTerm};
+use crate::search::parser::{
+    Expr, FieldTerm, MetaNumber, MetaValue, ParsedQuery, Term as ParsedTerm, TextTerm,
+};
 use crate::{MemvidError, Result};
 use tantivy::Term;
 use tantivy::query::{
-    AllQuery, BooleanQuery, Occur, PhraseQuery, Query, RangeQuery, RegexQuery, TermQuery,
-    TermSetQuery,
+    AllQuery, BooleanQuery, BoostQuery, FuzzyTermQuery, Occur, PhraseQuery, Query, RangeQuery,
+    RegexQuery, TermQuery, TermSetQuery,
 };
-use tantivy::schema::IndexRecordOption;
+use tantivy::schema::{Field, IndexRecordOption};
 
 pub(super) fn build_root_query(
     engine: &TantivyEngine,
@@ -43,8 +45,9 @@ impl<'a> QueryPlanner<'a> {
                 Occur::Must,
                 Box::new(TermQuery::new(term, IndexRecordOption::Basic)),
             ));
-        } else if scope_filter.is_some() {
-            // Scope filters are evaluated after fetching the documents.
+        } else if let Some(scope) = scope_filter {
+            // Hits re-check the case-sensitive prefix; this keeps facet counts in scope.
+            clauses.push((Occur::Must, self.build_scope_query(scope)?));
         }
 
         if let Some(ids) = frame_filter {
@@ -96,6 +99,10 @@ impl<'a> QueryPlanner<'a> {
                 (Occur::Must, Box::new(AllQuery)),
                 (Occur::MustNot, self.build_expr_query(child)?),
             ]))),
+            Expr::Boost(child, boost) => Ok(Box::new(BoostQuery::new(
+                self.build_expr_query(child)?,
+                *boost,
+            ))),
             Expr::Term(term) => self.build_term_query(term),
         }
     }
@@ -110,7 +117,9 @@ impl<'a> QueryPlanner<'a> {
     fn build_text_query(&self, text: &TextTerm) -> Result<Box<dyn Query>> {
         match text {
             TextTerm::Word(word) => self.build_word_query(word),
-            TextTerm::Phrase(phrase) => self.build_phrase_query(phrase),
+            TextTerm::Phrase(phrase) => self.build_phrase_query(phrase, 0),
+            TextTerm::Proximity { phrase, slop } => self.build_phrase_query(phrase, *slop),
+            TextTerm::Fuzzy { word, distance } => self.build_fuzzy_query(word, *distance),
             TextTerm::Wildcard(pattern) => {
                 let regex = pattern.regex.as_str().to_ascii_lowercase();
                 let query =
@@ -124,6 +133,17 @@ impl<'a> QueryPlanner<'a> {
         }
     }
 
+    /// URIs are indexed lowercased and whole, so a prefix is a regex over the raw term.
+    fn build_scope_query(&self, prefix: &str) -> Result<Box<dyn Query>> {
+        let pattern = format!("{}.*", regex::escape(&to_search_value(prefix)));
+        let query = RegexQuery::from_pattern(&pattern, self.engine.uri).map_err(|err| {
+            MemvidError::Tantivy {
+                reason: err.to_string(),
+            }
+        })?;
+        Ok(Box::new(query))
+    }
+
     fn build_field_query(&self, field: &FieldTerm) -> Result<Box<dyn Query>> {
         match field {
             FieldTerm::Uri(value) => {
@@ -133,7 +153,7 @@ impl<'a> QueryPlanner<'a> {
                     IndexRecordOption::Basic,
                 )))
             }
-            FieldTerm::Scope(_) => Ok(Box::new(AllQuery)),
+            FieldTerm::Scope(prefix) => self.build_scope_query(prefix),
             FieldTerm::Track(value) => {
                 let normalized = to_search_value(value);
                 Ok(Box::new(TermQuery::new(
@@ -155,6 +175,74 @@ impl<'a> QueryPlanner<'a> {
                     IndexRecordOption::Basic,
                 )))
             }
+            FieldTerm::Title(value) => {
+                // Older snapshots lack the field; post-filtering evaluates the term instead.
+                let Some(title) = self.engine.title else {
+                    return Ok(Box::new(AllQuery));
+                };
+                let variants = self.engine.analyse_variants(value);
+                if variants.is_empty() {
+                    return Ok(Box::new(AllQuery));
+                }
+                Ok(combine_should_queries(
+                    variants
+                        .iter()
+                        .map(|tokens| self.build_analyzed_query(title, tokens, 0))
+                        .collect(),
+                ))
+            }
+            FieldTerm::Meta { key, value } => {
+                // Older snapshots lack the field; post-filtering evaluates the term instead.
+                let Some(meta) = self.engine.meta else {
+                    return Ok(Box::new(AllQuery));
+                };
+                let term_query = |append: &dyn Fn(&mut Term)| -> Box<dyn Query> {
+                    let mut term = Term::from_field_json_path(meta, key, true);
+                    append(&mut term);
+                    Box::new(TermQuery::new(term, IndexRecordOption::Basic))
+                };
+                Ok(match value {
+                    MetaValue::Number(number @ MetaNumber::F64(_)) => {
+                        term_query(&|term| append_meta_number(term, *number))
+                    }
+                    // Older snapshots indexed integers as `f64`; post-filtering keeps the
+                    // exact comparison.
+                    MetaValue::Number(number) => combine_should_queries(vec![
+                        term_query(&|term| append_meta_number(term, *number)),
+                        term_query(&|term| term.append_type_and_fast_value(number.as_f64())),
+                    ]),
+                    MetaValue::Text(text) => term_query(&|term| term.append_type_and_str(text)),
+                })
+            }
+            FieldTerm::MetaRange { key, start, end } => {
+                let Some(meta) = self.engine.meta else {
+                    return Ok(Box::new(AllQuery));
+                };
+                if start.is_none() && end.is_none() {
+                    return Ok(Box::new(AllQuery));
+                }
+                // Both bounds must share a type; mixed bounds compare as floats.
+                let (start, end) = match (start, end) {
+                    (Some(lower), Some(upper))
+                        if std::mem::discriminant(lower) != std::mem::discriminant(upper) =>
+                    {
+                        (
+                            Some(MetaNumber::F64(lower.as_f64())),
+                            Some(MetaNumber::F64(upper.as_f64())),
+                        )
+                    }
+                    _ => (*start, *end),
+                };
+                let bound = |value: Option<MetaNumber>| match value {
+                    Some(value) => {
+                        let mut term = Term::from_field_json_path(meta, key, true);
+                        append_meta_number(&mut term, value);
+                        Bound::Included(term)
+                    }
+                    None => Bound::Unbounded,
+                };
+                Ok(Box::new(RangeQuery::new(bound(start), bound(end))))
+            }
             FieldTerm::DateRange(range) => {
                 let lower = range
                     .start
@@ -179,24 +267,23 @@ impl<'a> QueryPlanner<'a> {
             return Ok(Box::new(AllQuery));
         }
 
-        let tokens = self.engine.analyse_text(word);
-        if tokens.is_empty() {
+        let variants = self.engine.analyse_variants(word);
+        if variants.is_empty() {
             // Word produced no tokens after analysis - match all instead of erroring
             // This can happen with punctuation-only or stop-word-only terms
             return Ok(Box::new(AllQuery));
         }
-        let mut queries: Vec<Box<dyn Query>> = Vec::new();
-        if tokens.len() == 1 {
-            queries.push(Box::new(TermQuery::new(
-                Term::from_field_text(self.engine.content, &tokens[0]),
-                IndexRecordOption::WithFreqsAndPositions,
-            )));
-        } else {
-            let terms: Vec<Term> = tokens
-                .iter()
-                .map(|token| Term::from_field_text(self.engine.content, token))
-                .collect();
-            queries.push(Box::new(PhraseQuery::new(terms)));
+        let mut queries: Vec<Box<dyn Query>> = variants
+            .iter()
+            .map(|tokens| self.build_analyzed_query(self.engine.content, tokens, 0))
+            .collect();
+        if let Some(title) = self.engine.title {
+            for tokens in &variants {
+                queries.push(Box::new(BoostQuery::new(
+                    self.build_analyzed_query(title, tokens, 0),
+                    self.engine.title_boost,
+                )));
+            }
         }
 
         let normalized = to_search_value(word);
@@ -220,29 +307,83 @@ impl<'a> QueryPlanner<'a> {
         Ok(combine_should_queries(queries))
     }
 
-    fn build_phrase_query(&self, phrase: &str) -> Result<Box<dyn Query>> {
+    /// Term query for a single token, phrase query (keeping stopword gaps) otherwise.
+    fn build_analyzed_query(
+        &self,
+        field: Field,
+        tokens: &[(usize, String)],
+        slop: u32,
+    ) -> Box<dyn Query> {
+        if let [(_, token)] = tokens {
+            return Box::new(TermQuery::new(
+                Term::from_field_text(field, token),
+                IndexRecordOption::WithFreqsAndPositions,
+            ));
+        }
+        let base = tokens.first().map_or(0, |(position, _)| *position);
+        let terms: Vec<(usize, Term)> = tokens
+            .iter()
+            .map(|(position, token)| (position - base, Term::from_field_text(field, token)))
+            .collect();
+        let mut query = PhraseQuery::new_with_offset(terms);
+        query.set_slop(slop);
+        Box::new(query)
+    }
+
+    /// Fuzzy query over the analyzed form of `word`, so misspellings are compared
+    /// against stemmed index terms. Multi-token words fall back to exact matching.
+    fn build_fuzzy_query(&self, word: &str, distance: u8) -> Result<Box<dyn Query>> {
+        let variants = self.engine.analyse_variants(word);
+        if variants.is_empty() {
+            return Ok(Box::new(AllQuery));
+        }
+        let fuzzy = |field: Field, tokens: &[(usize, String)]| -> Box<dyn Query> {
+            match tokens {
+                [(_, token)] => Box::new(FuzzyTermQuery::new(
+                    Term::from_field_text(field, token),
+                    distance,
+                    true,
+                )),
+                _ => self.build_analyzed_query(field, tokens, 0),
+            }
+        };
+        let mut queries: Vec<Box<dyn Query>> = variants
+            .iter()
+            .map(|tokens| fuzzy(self.engine.content, tokens))
+            .collect();
+        if let Some(title) = self.engine.title {
+            for tokens in &variants {
+                queries.push(Box::new(BoostQuery::new(
+                    fuzzy(title, tokens),
+                    self.engine.title_boost,
+                )));
+            }
+        }
+        Ok(combine_should_queries(queries))
+    }
+
+    fn build_phrase_query(&self, phrase: &str, slop: u32) -> Result<Box<dyn Query>> {
         // Handle empty phrases gracefully
         if phrase.is_empty() {
             return Ok(Box::new(AllQuery));
         }
 
-        let tokens = self.engine.analyse_text(phrase);
-        if tokens.is_empty() {
+        let variants = self.engine.analyse_variants(phrase);
+        if variants.is_empty() {
             // Phrase produced no tokens after analysis - match all instead of erroring
             return Ok(Box::new(AllQuery));
         }
-        let mut queries: Vec<Box<dyn Query>> = Vec::new();
-        if tokens.len() == 1 {
-            queries.push(Box::new(TermQuery::new(
-                Term::from_field_text(self.engine.content, &tokens[0]),
-                IndexRecordOption::WithFreqsAndPositions,
-            )));
-        } else {
-            let terms: Vec<Term> = tokens
-                .iter()
-                .map(|token| Term::from_field_text(self.engine.content, token))
-                .collect();
-            queries.push(Box::new(PhraseQuery::new(terms)));
+        let mut queries: Vec<Box<dyn Query>> = variants
+            .iter()
+            .map(|tokens| self.build_analyzed_query(self.engine.content, tokens, slop))
+            .collect();
+        if let Some(title) = self.engine.title {
+            for tokens in &variants {
+                queries.push(Box::new(BoostQuery::new(
+                    self.build_analyzed_query(title, tokens, slop),
+                    self.engine.title_boost,
+                )));
+            }
         }
 
         let normalized = to_search_value(phrase);
@@ -266,3 +407,12 @@ impl<'a> QueryPlanner<'a> {
         Ok(combine_should_queries(queries))
     }
 }
+
+/// Append a metadata number with the same type it was indexed under.
+fn append_meta_number(term: &mut Term, number: MetaNumber) {
+    match number {
+        MetaNumber::I64(value) => term.append_type_and_fast_value(value),
+        MetaNumber::U64(value) => term.append_type_and_fast_value(value),
+        MetaNumber::F64(value) => term.append_type_and_fast_value(value),
+    }
+}
@@ -1,15 +1,13 @@
+use super::analyzer::{DEFAULT_TOKENIZER, WORDS_TOKENIZER, register_analyzers};
+use crate::types::LexAnalyzerConfig;
 use tantivy::Index;
-use tantivy::schema::{IndexRecordOption, NumericOptions, STRING, Schema, TEXT, TextFieldIndexing};
-use tantivy::tokenizer::{
-    Language, LowerCaser, RawTokenizer, SimpleTokenizer, Stemmer, TextAnalyzer,
+use tantivy::schema::{
+    IndexRecordOption, JsonObjectOptions, NumericOptions, STRING, Schema, TEXT, TextFieldIndexing,
 };
+use tantivy::tokenizer::RawTokenizer;
 
-pub(super) fn initialise_tokenizer(index: &Index) {
-    let analyzer = TextAnalyzer::builder(SimpleTokenizer::default())
-        .filter(LowerCaser)
-        .filter(Stemmer::new(Language::English))
-        .build();
-    index.tokenizers().register("memvid_default", analyzer);
+pub(super) fn initialise_tokenizer(index: &Index, analyzer: &LexAnalyzerConfig) {
+    register_analyzers(index, analyzer);
     index.tokenizers().register("raw", RawTokenizer::default());
 }
 
@@ -17,13 +15,24 @@ pub(super) fn build_schema() -> Schema {
     let mut schema_builder = tantivy::schema::SchemaBuilder::default();
 
     let content_options = TextFieldIndexing::default()
-        .set_tokenizer("memvid_default")
+        .set_tokenizer(DEFAULT_TOKENIZER)
         .set_index_option(IndexRecordOption::WithFreqsAndPositions);
     let content_field = TEXT.set_stored().set_indexing_options(content_options);
     schema_builder.add_text_field("content", content_field);
 
+    let title_options = TextFieldIndexing::default()
+        .set_tokenizer(DEFAULT_TOKENIZER)
+        .set_index_option(IndexRecordOption::WithFreqsAndPositions);
+    schema_builder.add_text_field("title", TEXT.set_indexing_options(title_options));
+
+    // Unstemmed content vocabulary for spelling suggestions; never queried directly.
+    let words_options = TextFieldIndexing::default()
+        .set_tokenizer(WORDS_TOKENIZER)
+        .set_index_option(IndexRecordOption::Basic);
+    schema_builder.add_text_field("words", TEXT.set_indexing_options(words_options));
+
     let keyword_indexing = TextFieldIndexing::default()
-        .set_tokenizer("memvid_default")
+        .set_tokenizer(DEFAULT_TOKENIZER)
         .set_index_option(IndexRecordOption::Basic);
     let keyword_field = STRING
         .set_stored()
@@ -38,13 +47,27 @@ pub(super) fn build_schema() -> Schema {
     let uri_field = STRING.set_stored().set_indexing_options(uri_indexing);
     schema_builder.add_text_field("uri", uri_field);
 
+    // Fast so numeric and date ranges over metadata keys can be answered.
+    let meta_indexing = TextFieldIndexing::default()
+        .set_tokenizer("raw")
+        .set_index_option(IndexRecordOption::Basic);
+    let meta_options = JsonObjectOptions::default()
+        .set_indexing_options(meta_indexing)
+        .set_expand_dots_enabled()
+        .set_fast(None);
+    schema_builder.add_json_field("meta", meta_options);
+
     let timestamp_options = NumericOptions::default()
         .set_indexed()
         .set_fast()
         .set_stored();
     schema_builder.add_i64_field("timestamp", timestamp_options);
 
-    let frame_id_options = NumericOptions::default().set_indexed().set_stored();
+    // Fast so facets can map every matching document to its frame without stored reads.
+    let frame_id_options = NumericOptions::default()
+        .set_indexed()
+        .set_fast()
+        .set_stored();
     schema_builder.add_u64_field("frame_id", frame_id_options);
 
     schema_builder.build()
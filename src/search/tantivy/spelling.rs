@@ -0,0 +1,245 @@
+//! Spelling suggestions drawn from the indexed vocabulary.
+//!
+//! A query word is unknown when its analyzed form occurs in no indexed document.
+//! Unknown words are matched against the unstemmed `words` vocabulary within a
+//! small edit distance; the closest term wins, the most frequent one among equally
+//! close terms. Snapshots without that field fall back to the stemmed `content`
+//! terms, mapping the chosen stem back to a word as one document spells it.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use super::analyzer::collect_tokens;
+use super::engine::TantivyEngine;
+use crate::text::edit_distance;
+use crate::types::SpellingSuggestion;
+use crate::{MemvidError, Result};
+use tantivy::collector::TopDocs;
+use tantivy::query::TermQuery;
+use tantivy::schema::{Field, IndexRecordOption, OwnedValue, TantivyDocument};
+use tantivy::{Searcher, Term};
+
+/// Words shorter than this are never corrected.
+const MIN_CORRECTABLE_CHARS: usize = 3;
+
+/// Words up to this many characters allow one edit; longer words allow two.
+const SINGLE_EDIT_MAX_CHARS: usize = 4;
+
+/// Terms of one indexed field and the number of documents containing each.
+pub(super) struct TermDictionary {
+    terms: HashMap<String, u64>,
+}
+
+/// Dictionary loaded for the engine's current searcher generation, reused by every
+/// suggestion until a commit reloads the reader.
+#[derive(Default)]
+pub(super) struct DictionaryCache(Mutex<Option<(u64, Arc<TermDictionary>)>>);
+
+impl DictionaryCache {
+    fn get(&self, engine: &TantivyEngine, searcher: &Searcher) -> Result<Arc<TermDictionary>> {
+        let generation = searcher.generation().generation_id();
+        if let Some((_, dictionary)) = self
+            .0
+            .lock()
+            .ok()
+            .and_then(|cached| cached.clone())
+            .filter(|(cached, _)| *cached == generation)
+        {
+            return Ok(dictionary);
+        }
+        let dictionary = Arc::new(TermDictionary::load(
+            searcher,
+            engine.words.unwrap_or(engine.content),
+        )?);
+        if let Ok(mut cached) = self.0.lock() {
+            *cached = Some((generation, Arc::clone(&dictionary)));
+        }
+        Ok(dictionary)
+    }
+}
+
+impl TermDictionary {
+    fn load(searcher: &Searcher, field: Field) -> Result<Self> {
+        let mut terms: HashMap<String, u64> = HashMap::new();
+        for segment in searcher.segment_readers() {
+            let inverted = segment
+                .inverted_index(field)
+                .map_err(|err| MemvidError::Tantivy {
+                    reason: err.to_string(),
+                })?;
+            let mut stream = inverted
+                .terms()
+                .stream()
+                .map_err(|err| MemvidError::Tantivy {
+                    reason: err.to_string(),
+                })?;
+            while stream.advance() {
+                if let Ok(term) = std::str::from_utf8(stream.key()) {
+                    *terms.entry(term.to_string()).or_default() +=
+                        u64::from(stream.value().doc_freq);
+                }
+            }
+        }
+        Ok(Self { terms })
+    }
+
+    /// Most frequent indexed term within `max_distance` edits of `term`,
+    /// preferring fewer edits, then more documents, then lexical order.
+    fn closest(&self, term: &str, max_distance: usize) -> Option<(&str, usize, u64)> {
+        let length = term.chars().count();
+        self.terms
+            .iter()
+            .filter(|(candidate, _)| candidate.chars().count().abs_diff(length) <= max_distance)
+            .map(|(candidate, freq)| (candidate.as_str(), edit_distance(term, candidate), *freq))
+            .filter(|(_, distance, _)| (1..=max_distance).contains(distance))
+            .min_by(|left, right| {
+                left.1
+                    .cmp(&right.1)
+                    .then_with(|| right.2.cmp(&left.2))
+                    .then_with(|| left.0.cmp(right.0))
+            })
+    }
+}
+
+/// Suggestions for the `words` of a query that found nothing, in query order.
+pub(super) fn suggest(engine: &TantivyEngine, words: &[String]) -> Result<Vec<SpellingSuggestion>> {
+    let searcher = engine.reader.searcher();
+    let mut suggestions: Vec<SpellingSuggestion> = Vec::new();
+    for word in words {
+        let word = word.to_lowercase();
+        let length = word.chars().count();
+        if length < MIN_CORRECTABLE_CHARS
+            || !word.chars().all(char::is_alphabetic)
+            || suggestions.iter().any(|known| known.word == word)
+            || content_frequency(engine, &searcher, &word) != Some(0)
+        {
+            // Stopwords and words the analyzer splits yield no frequency.
+            continue;
+        }
+        let max_distance = if length <= SINGLE_EDIT_MAX_CHARS {
+            1
+        } else {
+            2
+        };
+        let dictionary = engine.spelling_dictionary.get(engine, &searcher)?;
+        let best = if engine.words.is_some() {
+            dictionary
+                .closest(&word, max_distance)
+                .map(|(candidate, _, freq)| (candidate.to_string(), freq))
+        } else {
+            let stem = engine
+                .analyse_variants(&word)
+                .first()
+                .map(|tokens| tokens[0].1.clone())
+                .unwrap_or_default();
+            dictionary
+                .closest(&stem, max_distance)
+                .map(|(candidate, _, freq)| {
+                    let surface = surface_form(engine, &searcher, candidate)
+                        .unwrap_or_else(|| candidate.to_string());
+                    (surface, freq)
+                })
+        };
+        #[cfg(feature = "symspell_cleanup")]
+        let best = best.or_else(|| dictionary_correction(engine, &searcher, &word, max_distance));
+        let Some((suggestion, doc_frequency)) = best else {
+            continue;
+        };
+        let distance = edit_distance(&word, &suggestion);
+        suggestions.push(SpellingSuggestion {
+            word,
+            suggestion,
+            distance: u8::try_from(distance).unwrap_or(u8::MAX),
+            doc_frequency,
+        });
+    }
+    Ok(suggestions)
+}
+
+/// Documents whose content contains `word` as analyzed by any chain, or `None`
+/// when the word does not analyze to exactly one token.
+fn content_frequency(engine: &TantivyEngine, searcher: &Searcher, word: &str) -> Option<u64> {
+    let variants = engine.analyse_variants(word);
+    if variants.is_empty() || variants.iter().any(|tokens| tokens.len() != 1) {
+        return None;
+    }
+    Some(
+        variants
+            .iter()
+            .flatten()
+            .map(|(_, token)| {
+                searcher
+                    .doc_freq(&Term::from_field_text(engine.content, token))
+                    .unwrap_or(0)
+            })
+            .max()
+            .unwrap_or(0),
+    )
+}
+
+/// English dictionary correction that occurs in the indexed content.
+#[cfg(feature = "symspell_cleanup")]
+fn dictionary_correction(
+    engine: &TantivyEngine,
+    searcher: &Searcher,
+    word: &str,
+    max_distance: usize,
+) -> Option<(String, u64)> {
+    crate::symspell_cleanup::closest_words(word, max_distance)
+        .into_iter()
+        .find_map(|candidate| {
+            let freq = content_frequency(engine, searcher, &candidate).filter(|freq| *freq > 0)?;
+            Some((candidate, freq))
+        })
+}
+
+/// `term` as written in the best document containing it, lower-cased.
+fn surface_form(engine: &TantivyEngine, searcher: &Searcher, term: &str) -> Option<String> {
+    let query = TermQuery::new(
+        Term::from_field_text(engine.content, term),
+        IndexRecordOption::Basic,
+    );
+    let (_, address) = searcher
+        .search(&query, &TopDocs::with_limit(1))
+        .ok()?
+        .into_iter()
+        .next()?;
+    let document: TantivyDocument = searcher.doc(address).ok()?;
+    let text = match OwnedValue::from(document.get_first(engine.content)?) {
+        OwnedValue::Str(text) => text,
+        OwnedValue::PreTokStr(pre) => pre.text,
+        _ => return None,
+    };
+    engine.analyzer_names().into_iter().find_map(|name| {
+        let mut analyzer = engine.index.tokenizers().get(name)?;
+        collect_tokens(&mut analyzer, &text)
+            .into_iter()
+            .find(|token| token.text == term)
+            .and_then(|token| text.get(token.offset_from..token.offset_to))
+            .map(str::to_lowercase)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dictionary(terms: &[(&str, u64)]) -> TermDictionary {
+        TermDictionary {
+            terms: terms
+                .iter()
+                .map(|(term, freq)| ((*term).to_string(), *freq))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn closest_prefers_fewer_edits_then_frequency() {
+        let dictionary = dictionary(&[("volcano", 2), ("volcan", 9), ("volume", 40)]);
+        assert_eq!(dictionary.closest("volcanoe", 2), Some(("volcano", 1, 2)));
+        assert_eq!(dictionary.closest("volcam", 1), Some(("volcan", 1, 9)));
+        assert_eq!(dictionary.closest("zzzzzz", 2), None);
+        // Exact matches are not corrections.
+        assert_eq!(dictionary.closest("volume", 0), None);
+    }
+}
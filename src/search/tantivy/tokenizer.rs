@@ -0,0 +1,88 @@
+use crate::text::cjk_segments;
+use tantivy::tokenizer::{Token, TokenStream, Tokenizer};
+
+/// Splits on non-alphanumerics like Tantivy's `SimpleTokenizer`, then breaks
+/// CJK runs into overlapping bigrams via [`cjk_segments`].
+#[derive(Clone, Default)]
+pub(super) struct MemvidTokenizer;
+
+pub(super) struct MemvidTokenStream {
+    tokens: Vec<Token>,
+    cursor: usize,
+}
+
+impl Tokenizer for MemvidTokenizer {
+    type TokenStream<'a> = MemvidTokenStream;
+
+    fn token_stream<'a>(&'a mut self, text: &'a str) -> MemvidTokenStream {
+        let mut tokens = Vec::new();
+        let mut word_start = None;
+        for (offset, ch) in text
+            .char_indices()
+            .chain(std::iter::once((text.len(), ' ')))
+        {
+            match (ch.is_alphanumeric(), word_start) {
+                (true, None) => word_start = Some(offset),
+                (false, Some(start)) => {
+                    for (from, to) in cjk_segments(&text[start..offset]) {
+                        tokens.push(Token {
+                            offset_from: start + from,
+                            offset_to: start + to,
+                            position: tokens.len(),
+                            text: text[start + from..start + to].to_string(),
+                            position_length: 1,
+                        });
+                    }
+                    word_start = None;
+                }
+                _ => {}
+            }
+        }
+        MemvidTokenStream { tokens, cursor: 0 }
+    }
+}
+
+impl TokenStream for MemvidTokenStream {
+    fn advance(&mut self) -> bool {
+        if self.cursor < self.tokens.len() {
+            self.cursor += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn token(&self) -> &Token {
+        &self.tokens[self.cursor - 1]
+    }
+
+    fn token_mut(&mut self) -> &mut Token {
+        &mut self.tokens[self.cursor - 1]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_simple_tokenizer_and_splits_cjk() {
+        let mut tokenizer = MemvidTokenizer;
+        let mut stream = tokenizer.token_stream("Hello, 東京タワー!");
+        let mut tokens = Vec::new();
+        while stream.advance() {
+            let token = stream.token();
+            tokens.push((token.position, token.text.clone()));
+        }
+        assert_eq!(
+            tokens,
+            vec![
+                (0, "Hello".to_string()),
+                (1, "東京".to_string()),
+                (2, "京タ".to_string()),
+                (3, "タワ".to_string()),
+                (4, "ワー".to_string()),
+            ]
+        );
+    }
+}
@@ -64,6 +64,16 @@ fn get_symspell() -> &'static SymSpell<AsciiStringStrategy> {
     SYMSPELL.get_or_init(init_symspell)
 }
 
+/// Dictionary words within `max_distance` edits of `word`, most frequent first.
+pub(crate) fn closest_words(word: &str, max_distance: usize) -> Vec<String> {
+    let max_distance = i64::try_from(max_distance).unwrap_or(i64::MAX);
+    get_symspell()
+        .lookup(&word.to_lowercase(), Verbosity::Closest, max_distance)
+        .into_iter()
+        .map(|suggestion| suggestion.term)
+        .collect()
+}
+
 /// Check if a word is a common short English word
 fn is_common_word(s: &str) -> bool {
     let lower = s.to_ascii_lowercase();
@@ -67,6 +67,7 @@ mod tests {
                         as_of_frame: None,
                         as_of_ts: None,
                         no_sketch: false,
+                        ..Default::default()
                     })
                     .expect("search must succeed");
 
@@ -118,6 +118,92 @@ pub fn truncate_at_grapheme_boundary(s: &str, limit: usize) -> usize {
     }
 }
 
+/// Returns true for Han, Kana and Hangul characters, which are written without
+/// spaces between words.
+#[must_use]
+pub fn is_cjk(ch: char) -> bool {
+    matches!(ch as u32,
+        0x1100..=0x11FF     // Hangul Jamo
+        | 0x3040..=0x30FF   // Hiragana, Katakana
+        | 0x3130..=0x318F   // Hangul Compatibility Jamo
+        | 0x31F0..=0x31FF   // Katakana Phonetic Extensions
+        | 0x3400..=0x4DBF   // CJK Extension A
+        | 0x4E00..=0x9FFF   // CJK Unified Ideographs
+        | 0xAC00..=0xD7AF   // Hangul Syllables
+        | 0xF900..=0xFAFF   // CJK Compatibility Ideographs
+        | 0xFF66..=0xFF9F   // Halfwidth Katakana
+        | 0x20000..=0x2FA1F // CJK Extensions B-F and supplement
+    )
+}
+
+/// Splits a word into byte ranges for indexing.
+///
+/// Runs of CJK characters become overlapping bigrams (a lone character stays a
+/// unigram) so queries match inside unsegmented sentences; other runs are
+/// returned whole. Every lexical path (Tantivy, the fallback lex index and
+/// sketches) segments words through this function so they agree.
+#[must_use]
+pub fn cjk_segments(word: &str) -> Vec<(usize, usize)> {
+    let mut segments = Vec::new();
+    let mut chars = word.char_indices().peekable();
+    while let Some((start, ch)) = chars.next() {
+        if is_cjk(ch) {
+            match chars.peek() {
+                Some(&(next_start, next)) if is_cjk(next) => {
+                    segments.push((start, next_start + next.len_utf8()));
+                    // A bigram ending the run covers its last character.
+                    let run_continues = word[next_start + next.len_utf8()..]
+                        .chars()
+                        .next()
+                        .is_some_and(is_cjk);
+                    if !run_continues {
+                        chars.next();
+                    }
+                }
+                _ => segments.push((start, start + ch.len_utf8())),
+            }
+        } else {
+            let mut end = start + ch.len_utf8();
+            while let Some(&(next_start, next)) = chars.peek() {
+                if is_cjk(next) {
+                    break;
+                }
+                end = next_start + next.len_utf8();
+                chars.next();
+            }
+            segments.push((start, end));
+        }
+    }
+    segments
+}
+
+/// Levenshtein distance between two words, counting an adjacent transposition
+/// as a single edit to mirror Tantivy's fuzzy term queries.
+#[must_use]
+pub fn edit_distance(left: &str, right: &str) -> usize {
+    let left: Vec<char> = left.chars().collect();
+    let right: Vec<char> = right.chars().collect();
+    let width = right.len() + 1;
+    let mut rows = vec![0usize; (left.len() + 1) * width];
+    for (j, cell) in rows.iter_mut().take(width).enumerate() {
+        *cell = j;
+    }
+    for i in 1..=left.len() {
+        rows[i * width] = i;
+        for j in 1..=right.len() {
+            let cost = usize::from(left[i - 1] != right[j - 1]);
+            let mut best = (rows[(i - 1) * width + j] + 1)
+                .min(rows[i * width + j - 1] + 1)
+                .min(rows[(i - 1) * width + j - 1] + cost);
+            if i > 1 && j > 1 && left[i - 1] == right[j - 2] && left[i - 2] == right[j - 1] {
+                best = best.min(rows[(i - 2) * width + j - 2] + 1);
+            }
+            rows[i * width + j] = best;
+        }
+    }
+    rows[left.len() * width + right.len()]
+}
+
 /// Fix spurious character-level spacing from PDF extraction.
 ///
 /// Some PDF extractors produce text like "man ager" instead of "manager"
@@ -393,4 +479,33 @@ mod tests {
         assert!(idx >= 4);
         assert_eq!(&s[..idx], "🇮🇳");
     }
+
+    #[test]
+    fn cjk_segments_split_runs_into_bigrams() {
+        let word = "iphone手机壳";
+        let parts: Vec<&str> = cjk_segments(word)
+            .into_iter()
+            .map(|(start, end)| &word[start..end])
+            .collect();
+        assert_eq!(parts, vec!["iphone", "手机", "机壳"]);
+
+        let word = "東京タワー";
+        let parts: Vec<&str> = cjk_segments(word)
+            .into_iter()
+            .map(|(start, end)| &word[start..end])
+            .collect();
+        assert_eq!(parts, vec!["東京", "京タ", "タワ", "ワー"]);
+
+        assert_eq!(cjk_segments("한"), vec![(0, 3)]);
+        assert_eq!(cjk_segments("rust"), vec![(0, 4)]);
+    }
+
+    #[test]
+    fn edit_distance_counts_transpositions_once() {
+        assert_eq!(edit_distance("memvid", "memvid"), 0);
+        assert_eq!(edit_distance("memvdi", "memvid"), 1);
+        assert_eq!(edit_distance("memvd", "memvid"), 1);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("", "abc"), 3);
+    }
 }
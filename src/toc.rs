@@ -5,8 +5,9 @@ use serde::{Deserialize, Serialize};
 use crate::{
     error::{MemvidError, Result},
     types::{
-        Frame, IndexManifests, MemoryBinding, SegmentCatalog, SegmentMeta, TemporalTrackManifest,
-        TicketRef, TimeIndexManifest, Toc,
+        DistanceMetric, Frame, IndexManifests, LexIndexManifest, LexSegmentManifest, MemoryBinding,
+        SegmentCatalog, SegmentMeta, TemporalTrackManifest, TicketRef, TimeIndexManifest, Toc,
+        VecIndexManifest, VectorCompression,
     },
 };
 
@@ -17,6 +18,91 @@ fn canonical_config() -> impl bincode::config::Config {
         .with_limit::<{ crate::MAX_INDEX_BYTES as usize }>()
 }
 
+/// Legacy index manifests without distance metrics or named vector spaces.
+/// Every legacy TOC layout up to V3 was written with this shape.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct LegacyIndexManifestsV1 {
+    pub lex: Option<LexIndexManifest>,
+    pub lex_segments: Vec<LexSegmentManifest>,
+    pub vec: Option<LegacyVecIndexManifestV1>,
+    pub clip: Option<LegacyClipIndexManifestV1>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct LegacyVecIndexManifestV1 {
+    pub vector_count: u64,
+    pub dimension: u32,
+    pub bytes_offset: u64,
+    pub bytes_length: u64,
+    pub checksum: [u8; 32],
+    pub compression_mode: VectorCompression,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct LegacyClipIndexManifestV1 {
+    pub bytes_offset: u64,
+    pub bytes_length: u64,
+    pub vector_count: u64,
+    pub dimension: u32,
+    pub checksum: [u8; 32],
+    pub model_name: String,
+}
+
+impl From<LegacyIndexManifestsV1> for IndexManifests {
+    fn from(legacy: LegacyIndexManifestsV1) -> Self {
+        IndexManifests {
+            lex: legacy.lex,
+            lex_segments: legacy.lex_segments,
+            vec: legacy.vec.map(|vec| VecIndexManifest {
+                vector_count: vec.vector_count,
+                dimension: vec.dimension,
+                bytes_offset: vec.bytes_offset,
+                bytes_length: vec.bytes_length,
+                checksum: vec.checksum,
+                compression_mode: vec.compression_mode,
+                metric: DistanceMetric::L2,
+            }),
+            clip: legacy.clip.map(|clip| crate::clip::ClipIndexManifest {
+                bytes_offset: clip.bytes_offset,
+                bytes_length: clip.bytes_length,
+                vector_count: clip.vector_count,
+                dimension: clip.dimension,
+                checksum: clip.checksum,
+                model_name: clip.model_name,
+                metric: DistanceMetric::L2,
+            }),
+            vec_spaces: Vec::new(),
+            reembed: None,
+            lex_analyzer: None,
+        }
+    }
+}
+
+impl From<&IndexManifests> for LegacyIndexManifestsV1 {
+    fn from(indexes: &IndexManifests) -> Self {
+        LegacyIndexManifestsV1 {
+            lex: indexes.lex.clone(),
+            lex_segments: indexes.lex_segments.clone(),
+            vec: indexes.vec.as_ref().map(|vec| LegacyVecIndexManifestV1 {
+                vector_count: vec.vector_count,
+                dimension: vec.dimension,
+                bytes_offset: vec.bytes_offset,
+                bytes_length: vec.bytes_length,
+                checksum: vec.checksum,
+                compression_mode: vec.compression_mode.clone(),
+            }),
+            clip: indexes.clip.as_ref().map(|clip| LegacyClipIndexManifestV1 {
+                bytes_offset: clip.bytes_offset,
+                bytes_length: clip.bytes_length,
+                vector_count: clip.vector_count,
+                dimension: clip.dimension,
+                checksum: clip.checksum,
+                model_name: clip.model_name.clone(),
+            }),
+        }
+    }
+}
+
 /// Legacy TOC format without memories_track field (pre-v2.0.105).
 /// Used for backwards compatibility with older .mv2 files.
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -24,7 +110,7 @@ struct LegacyTocV1 {
     pub toc_version: u64,
     pub segments: Vec<SegmentMeta>,
     pub frames: Vec<Frame>,
-    pub indexes: IndexManifests,
+    pub indexes: LegacyIndexManifestsV1,
     pub time_index: Option<TimeIndexManifest>,
     pub temporal_track: Option<TemporalTrackManifest>,
     // Note: memories_track, logic_mesh, replay_manifest NOT present
@@ -42,7 +128,7 @@ struct LegacyTocV2 {
     pub toc_version: u64,
     pub segments: Vec<SegmentMeta>,
     pub frames: Vec<Frame>,
-    pub indexes: IndexManifests,
+    pub indexes: LegacyIndexManifestsV1,
     pub time_index: Option<TimeIndexManifest>,
     pub temporal_track: Option<TemporalTrackManifest>,
     pub memories_track: Option<crate::types::MemoriesTrackManifest>,
@@ -55,13 +141,36 @@ struct LegacyTocV2 {
     pub toc_checksum: [u8; 32],
 }
 
+/// Legacy TOC format with the current layout but pre-metric index manifests.
+/// Used for backwards compatibility with files created before named vector spaces,
+/// lex analyzer settings and vacuum generations.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct LegacyTocV3 {
+    pub toc_version: u64,
+    pub segments: Vec<SegmentMeta>,
+    pub frames: Vec<Frame>,
+    pub indexes: LegacyIndexManifestsV1,
+    pub time_index: Option<TimeIndexManifest>,
+    pub temporal_track: Option<TemporalTrackManifest>,
+    pub memories_track: Option<crate::types::MemoriesTrackManifest>,
+    pub logic_mesh: Option<crate::types::LogicMeshManifest>,
+    pub sketch_track: Option<crate::types::SketchTrackManifest>,
+    pub segment_catalog: SegmentCatalog,
+    pub ticket_ref: TicketRef,
+    pub memory_binding: Option<MemoryBinding>,
+    pub replay_manifest: Option<crate::replay::ReplayManifest>,
+    pub enrichment_queue: crate::types::EnrichmentQueueManifest,
+    pub merkle_root: [u8; 32],
+    pub toc_checksum: [u8; 32],
+}
+
 impl From<LegacyTocV1> for Toc {
     fn from(legacy: LegacyTocV1) -> Self {
         Toc {
             toc_version: legacy.toc_version,
             segments: legacy.segments,
             frames: legacy.frames,
-            indexes: legacy.indexes,
+            indexes: legacy.indexes.into(),
             time_index: legacy.time_index,
             temporal_track: legacy.temporal_track,
             memories_track: None, // Default for legacy files
@@ -72,6 +181,7 @@ impl From<LegacyTocV1> for Toc {
             memory_binding: legacy.memory_binding,
             replay_manifest: None,                // Default for legacy files
             enrichment_queue: Default::default(), // Default for legacy files
+            vacuum_generation: 0,
             merkle_root: legacy.merkle_root,
             toc_checksum: legacy.toc_checksum,
         }
@@ -84,7 +194,7 @@ impl From<LegacyTocV2> for Toc {
             toc_version: legacy.toc_version,
             segments: legacy.segments,
             frames: legacy.frames,
-            indexes: legacy.indexes,
+            indexes: legacy.indexes.into(),
             time_index: legacy.time_index,
             temporal_track: legacy.temporal_track,
             memories_track: legacy.memories_track,
@@ -95,6 +205,31 @@ impl From<LegacyTocV2> for Toc {
             memory_binding: legacy.memory_binding,
             replay_manifest: None, // Default for pre-replay files
             enrichment_queue: Default::default(), // Default for legacy files
+            vacuum_generation: 0,
+            merkle_root: legacy.merkle_root,
+            toc_checksum: legacy.toc_checksum,
+        }
+    }
+}
+
+impl From<LegacyTocV3> for Toc {
+    fn from(legacy: LegacyTocV3) -> Self {
+        Toc {
+            toc_version: legacy.toc_version,
+            segments: legacy.segments,
+            frames: legacy.frames,
+            indexes: legacy.indexes.into(),
+            time_index: legacy.time_index,
+            temporal_track: legacy.temporal_track,
+            memories_track: legacy.memories_track,
+            logic_mesh: legacy.logic_mesh,
+            sketch_track: legacy.sketch_track,
+            segment_catalog: legacy.segment_catalog,
+            ticket_ref: legacy.ticket_ref,
+            memory_binding: legacy.memory_binding,
+            replay_manifest: legacy.replay_manifest,
+            enrichment_queue: legacy.enrichment_queue,
+            vacuum_generation: 0,
             merkle_root: legacy.merkle_root,
             toc_checksum: legacy.toc_checksum,
         }
@@ -123,6 +258,20 @@ impl Toc {
             Err(_) => {}
         }
 
+        // Try V3 format (pre-metric index manifests, no vacuum generation)
+        match decode_from_slice::<LegacyTocV3, _>(bytes, canonical_config()) {
+            Ok((legacy, bytes_read)) => {
+                if bytes_read != bytes.len() {
+                    return Err(MemvidError::InvalidToc {
+                        reason: "unexpected trailing bytes in V3 format".into(),
+                    });
+                }
+                tracing::debug!("Decoded TOC V3 format (pre-metric)");
+                return Ok(legacy.into());
+            }
+            Err(_) => {}
+        }
+
         // Try V2 format (with memories_track/logic_mesh, without replay_manifest)
         match decode_from_slice::<LegacyTocV2, _>(bytes, canonical_config()) {
             Ok((legacy, bytes_read)) => {
@@ -159,6 +308,11 @@ impl Toc {
         if let Ok((toc, _)) = decode_from_slice::<Toc, _>(bytes, canonical_config()) {
             return Ok(toc);
         }
+        // Try V3 format (pre-metric index manifests, no vacuum generation)
+        if let Ok((legacy, _)) = decode_from_slice::<LegacyTocV3, _>(bytes, canonical_config()) {
+            tracing::debug!("Decoded TOC V3 format (pre-metric) in lenient mode");
+            return Ok(legacy.into());
+        }
         // Try V2 format (with memories_track/logic_mesh, without replay_manifest)
         if let Ok((legacy, _)) = decode_from_slice::<LegacyTocV2, _>(bytes, canonical_config()) {
             tracing::debug!("Decoded TOC V2 format (pre-replay_manifest) in lenient mode");
@@ -189,6 +343,13 @@ impl LegacyTocV2 {
     }
 }
 
+impl LegacyTocV3 {
+    /// Encode V3 TOC format for checksum verification.
+    fn encode(&self) -> Result<Vec<u8>> {
+        Ok(encode_to_vec(self, canonical_config())?)
+    }
+}
+
 impl Toc {
     /// Computes the BLAKE3 checksum used for the TOC integrity field.
     pub fn calculate_checksum(bytes: &[u8]) -> [u8; 32] {
@@ -209,6 +370,31 @@ impl Toc {
             return Ok(());
         }
 
+        // Try V3 format (pre-metric index manifests, no vacuum generation)
+        let legacy_v3 = LegacyTocV3 {
+            toc_version: self.toc_version,
+            segments: self.segments.clone(),
+            frames: self.frames.clone(),
+            indexes: (&self.indexes).into(),
+            time_index: self.time_index.clone(),
+            temporal_track: self.temporal_track.clone(),
+            memories_track: self.memories_track.clone(),
+            logic_mesh: self.logic_mesh.clone(),
+            sketch_track: self.sketch_track.clone(),
+            segment_catalog: self.segment_catalog.clone(),
+            ticket_ref: self.ticket_ref.clone(),
+            memory_binding: self.memory_binding.clone(),
+            replay_manifest: self.replay_manifest.clone(),
+            enrichment_queue: self.enrichment_queue.clone(),
+            merkle_root: self.merkle_root,
+            toc_checksum: [0u8; 32],
+        };
+        let v3_digest = Self::calculate_checksum(&legacy_v3.encode()?);
+        if v3_digest == self.toc_checksum {
+            tracing::debug!("TOC checksum verified using V3 format (pre-metric)");
+            return Ok(());
+        }
+
         // Try V2 format (with memories_track/logic_mesh, without replay_manifest)
         // Only try if replay_manifest is None (indicates pre-replay origin)
         if self.replay_manifest.is_none() {
@@ -216,7 +402,7 @@ impl Toc {
                 toc_version: self.toc_version,
                 segments: self.segments.clone(),
                 frames: self.frames.clone(),
-                indexes: self.indexes.clone(),
+                indexes: (&self.indexes).into(),
                 time_index: self.time_index.clone(),
                 temporal_track: self.temporal_track.clone(),
                 memories_track: self.memories_track.clone(),
@@ -242,7 +428,7 @@ impl Toc {
                 toc_version: self.toc_version,
                 segments: self.segments.clone(),
                 frames: self.frames.clone(),
-                indexes: self.indexes.clone(),
+                indexes: (&self.indexes).into(),
                 time_index: self.time_index.clone(),
                 temporal_track: self.temporal_track.clone(),
                 segment_catalog: self.segment_catalog.clone(),
@@ -370,6 +556,7 @@ mod tests {
             memory_binding: None,
             replay_manifest: None,
             enrichment_queue: Default::default(),
+            vacuum_generation: 0,
             merkle_root: [0x55; 32],
             toc_checksum: [0u8; 32],
         }
@@ -408,4 +595,47 @@ mod tests {
         let err = Toc::decode(&bytes).expect_err("should reject");
         matches!(err, MemvidError::InvalidToc { .. });
     }
+
+    #[test]
+    fn decode_baseline_layout() {
+        let mut toc = sample_toc();
+        toc.indexes.vec = Some(VecIndexManifest {
+            vector_count: 2,
+            dimension: 4,
+            bytes_offset: 9000,
+            bytes_length: 64,
+            checksum: [0x66; 32],
+            compression_mode: VectorCompression::None,
+            metric: DistanceMetric::L2,
+        });
+        let mut legacy = LegacyTocV3 {
+            toc_version: toc.toc_version,
+            segments: toc.segments.clone(),
+            frames: toc.frames.clone(),
+            indexes: (&toc.indexes).into(),
+            time_index: toc.time_index.clone(),
+            temporal_track: None,
+            memories_track: None,
+            logic_mesh: None,
+            sketch_track: None,
+            segment_catalog: toc.segment_catalog.clone(),
+            ticket_ref: toc.ticket_ref.clone(),
+            memory_binding: None,
+            replay_manifest: None,
+            enrichment_queue: Default::default(),
+            merkle_root: toc.merkle_root,
+            toc_checksum: [0u8; 32],
+        };
+        legacy.toc_checksum = Toc::calculate_checksum(&legacy.encode().expect("encode legacy"));
+        let bytes = legacy.encode().expect("encode legacy");
+
+        let decoded = Toc::decode(&bytes).expect("decode legacy toc");
+        decoded.verify_checksum().expect("legacy checksum matches");
+        let vec = decoded.indexes.vec.expect("vec manifest");
+        assert_eq!(vec.dimension, 4);
+        assert_eq!(vec.metric, DistanceMetric::L2);
+        assert!(decoded.indexes.vec_spaces.is_empty());
+        assert!(decoded.indexes.lex_analyzer.is_none());
+        assert_eq!(decoded.vacuum_generation, 0);
+    }
 }
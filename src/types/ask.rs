@@ -4,7 +4,7 @@ use super::adaptive::AdaptiveConfig;
 use super::common::FrameId;
 #[cfg(feature = "temporal_track")]
 use super::search::SearchHitTemporal;
-use super::search::SearchResponse;
+use super::search::{FusionStrategy, SearchResponse};
 #[cfg(feature = "temporal_track")]
 use super::temporal::TemporalFilter;
 use crate::Result;
@@ -18,6 +18,15 @@ pub enum AskMode {
     Sem,
     /// Hybrid (lexical + semantic) retrieval.
     Hybrid,
+    /// Hybrid retrieval over several reformulations of the question.
+    ///
+    /// The question plus every reformulation from [`AskRequest::queries`] and the
+    /// handle's [`QueryRewriter`] are each run through the lexical and vector
+    /// retrievers, and all candidate lists are fused with [`AskRequest::fusion`].
+    /// Without caller or rewriter reformulations, singular/plural expansions of the
+    /// question's key terms are used. [`AskResponse::queries`] records what each
+    /// query retrieved.
+    MultiQuery,
 }
 
 impl Default for AskMode {
@@ -74,12 +83,40 @@ pub struct AskRequest {
     /// Adaptive retrieval configuration. When set, dynamically determines how many
     /// results to retrieve based on relevancy score distribution.
     pub adaptive: Option<AdaptiveConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Named vector space used for vector recall and semantic re-ranking.
+    /// The embedder passed to `ask` must produce vectors for this space.
+    pub vec_space: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Diversify retrieved context with Maximal Marginal Relevance (see
+    /// [`SearchRequest::mmr_lambda`](crate::types::SearchRequest::mmr_lambda)).
+    pub mmr_lambda: Option<f32>,
+    #[serde(default)]
+    /// Strategy used to merge lexical and vector candidate lists.
+    pub fusion: FusionStrategy,
+    #[serde(default)]
+    /// Reorder the fused context with the reranker registered through
+    /// `Memvid::set_reranker` before it is diversified and synthesized.
+    pub rerank: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    /// Caller-supplied reformulations of the question for [`AskMode::MultiQuery`].
+    pub queries: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Token budget for the context handed to the synthesizer. When set, fragments
+    /// are packed by score with the handle's [`TokenCounter`], expanded with
+    /// neighbouring chunks of their document while budget remains, and the
+    /// fragment that overflows the budget is trimmed and marked
+    /// [`AskContextFragmentKind::Summary`].
+    pub context_budget_tokens: Option<usize>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct AskStats {
     /// Time spent retrieving context in milliseconds.
     pub retrieval_ms: u128,
+    #[serde(default)]
+    /// Time spent in the reranker stage in milliseconds.
+    pub rerank_ms: u128,
     /// Time spent synthesizing the answer in milliseconds.
     pub synthesis_ms: u128,
     /// End-to-end latency in milliseconds.
@@ -132,6 +169,35 @@ pub enum AskContextFragmentKind {
     Summary,
 }
 
+/// Origin of a query run by a multi-query ask.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum AskQuerySource {
+    /// The question itself.
+    Question,
+    /// [`AskRequest::queries`].
+    Caller,
+    /// The handle's [`QueryRewriter`].
+    Rewriter,
+    /// Built-in expansion of the question's key terms.
+    Expansion,
+}
+
+/// What one query contributed to a multi-query ask.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AskQueryAttribution {
+    pub query: String,
+    pub source: AskQuerySource,
+    /// Hits the lexical retriever returned for this query.
+    pub lexical_hits: usize,
+    /// Hits the vector retriever returned for this query.
+    pub semantic_hits: usize,
+    /// 1-based `context_fragments` positions (matching `AskCitation::index`) whose
+    /// frame this query retrieved.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fragments: Vec<usize>,
+}
+
 /// Response for `ask` containing retrieval context, optional answer, citations, and timings.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AskResponse {
@@ -143,12 +209,42 @@ pub struct AskResponse {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub answer: Option<String>,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    /// `AskCitation::index` values the answer cites, in order of first use.
+    pub answer_citations: Vec<usize>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub citations: Vec<AskCitation>,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub context_fragments: Vec<AskContextFragment>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    /// Per-query attribution for [`AskMode::MultiQuery`], the question first.
+    pub queries: Vec<AskQueryAttribution>,
     pub stats: AskStats,
 }
 
+/// Counts tokens the way the downstream model does, for context budgeting.
+pub trait TokenCounter: Send + Sync {
+    fn count_tokens(&self, text: &str) -> usize;
+}
+
+/// Model-agnostic estimate of roughly four characters per token.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ApproxTokenCounter;
+
+impl TokenCounter for ApproxTokenCounter {
+    fn count_tokens(&self, text: &str) -> usize {
+        text.chars().count().div_ceil(4)
+    }
+}
+
+/// Produces reformulations of a question for [`AskMode::MultiQuery`].
+pub trait QueryRewriter: Send + Sync {
+    /// Return the rewriter kind identifier.
+    fn kind(&self) -> &str;
+
+    /// Reformulations of `question`, best first; the question itself need not be included.
+    fn rewrite(&self, question: &str) -> Result<Vec<String>>;
+}
+
 pub trait VecEmbedder {
     fn embed_query(&self, text: &str) -> Result<Vec<f32>>;
 
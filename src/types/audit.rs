@@ -394,6 +394,7 @@ fn format_mode(mode: AskMode) -> &'static str {
         AskMode::Lex => "Lexical (keyword search)",
         AskMode::Sem => "Semantic (vector similarity)",
         AskMode::Hybrid => "Hybrid (lexical + semantic)",
+        AskMode::MultiQuery => "Multi-query (fused reformulations)",
     }
 }
 
@@ -564,6 +565,7 @@ mod tests {
             total_hits: 5,
             stats: AskStats {
                 retrieval_ms: 10,
+                rerank_ms: 0,
                 synthesis_ms: 5,
                 latency_ms: 15,
             },
@@ -1,5 +1,9 @@
 use std::collections::BTreeMap;
 
+use serde::{Deserialize, Serialize};
+
+use super::embedding::EmbeddingProvider;
+
 /// Frame-level embedding metadata keys (stored in `Frame.extra_metadata`).
 ///
 /// These are intentionally persisted per-frame (instead of in the TOC schema) to avoid
@@ -14,7 +18,7 @@ pub const MEMVID_EMBEDDING_NORMALIZED_KEY: &str = "memvid.embedding.normalized";
 ///
 /// Dimensions alone are not sufficient to guarantee compatibility (multiple models can share a
 /// dimension), so production-safe auto-detection should prefer `provider` + `model` when present.
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct EmbeddingIdentity {
     pub provider: Option<Box<str>>,
     pub model: Option<Box<str>>,
@@ -66,6 +70,57 @@ impl EmbeddingIdentity {
             normalized,
         })
     }
+
+    /// Identity of the vectors produced by `provider`.
+    #[must_use]
+    pub fn from_provider<P: EmbeddingProvider + ?Sized>(provider: &P) -> Self {
+        Self {
+            provider: Some(provider.kind().trim().to_ascii_lowercase().into_boxed_str()),
+            model: Some(provider.model().trim().to_string().into_boxed_str()),
+            dimension: u32::try_from(provider.dimension())
+                .ok()
+                .filter(|dim| *dim > 0),
+            normalized: None,
+        }
+    }
+
+    /// Record this identity in a frame's `extra_metadata`, replacing any previous one.
+    pub fn write_extra_metadata(&self, extra: &mut BTreeMap<String, String>) {
+        Self::clear_extra_metadata(extra);
+        if let Some(provider) = &self.provider {
+            extra.insert(
+                MEMVID_EMBEDDING_PROVIDER_KEY.to_string(),
+                provider.to_string(),
+            );
+        }
+        if let Some(model) = &self.model {
+            extra.insert(MEMVID_EMBEDDING_MODEL_KEY.to_string(), model.to_string());
+        }
+        if let Some(dimension) = self.dimension {
+            extra.insert(
+                MEMVID_EMBEDDING_DIMENSION_KEY.to_string(),
+                dimension.to_string(),
+            );
+        }
+        if let Some(normalized) = self.normalized {
+            extra.insert(
+                MEMVID_EMBEDDING_NORMALIZED_KEY.to_string(),
+                normalized.to_string(),
+            );
+        }
+    }
+
+    /// Remove any embedding identity from a frame's `extra_metadata`.
+    pub fn clear_extra_metadata(extra: &mut BTreeMap<String, String>) {
+        for key in [
+            MEMVID_EMBEDDING_PROVIDER_KEY,
+            MEMVID_EMBEDDING_MODEL_KEY,
+            MEMVID_EMBEDDING_DIMENSION_KEY,
+            MEMVID_EMBEDDING_NORMALIZED_KEY,
+        ] {
+            extra.remove(key);
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -28,6 +28,9 @@ pub struct TimelineQuery {
     #[cfg(feature = "temporal_track")]
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub temporal: Option<TemporalFilter>,
+    /// Opaque cursor from a previous [`TimelinePage::next_cursor`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cursor: Option<String>,
 }
 
 impl TimelineQuery {
@@ -46,6 +49,7 @@ impl Default for TimelineQuery {
             reverse: false,
             #[cfg(feature = "temporal_track")]
             temporal: None,
+            cursor: None,
         }
     }
 }
@@ -82,6 +86,12 @@ impl TimelineQueryBuilder {
         self
     }
 
+    /// Continue from the cursor returned with a previous page.
+    pub fn cursor(mut self, cursor: impl Into<String>) -> Self {
+        self.inner.cursor = Some(cursor.into());
+        self
+    }
+
     pub fn no_limit(mut self) -> Self {
         self.inner.limit = None;
         self
@@ -163,6 +173,14 @@ pub struct TimelineEntry {
     pub temporal: Option<SearchHitTemporal>,
 }
 
+/// One page of timeline entries plus the cursor for the next page, if any.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimelinePage {
+    pub entries: Vec<TimelineEntry>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
+}
+
 /// Frame - core content unit serialized to TOC.
 /// binary format compatibility. Feature flags control functionality, NOT structure.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -6,7 +6,9 @@ use serde::{
     ser::SerializeStruct,
 };
 
-use super::{common::FrameId, frame::Frame, ticket::TicketRef};
+use super::{
+    common::FrameId, embedding_identity::EmbeddingIdentity, frame::Frame, ticket::TicketRef,
+};
 
 use std::{fmt, marker::PhantomData};
 
@@ -687,6 +689,15 @@ pub struct IndexManifests {
     /// CLIP visual embeddings index (separate from text vec index due to different dimensions)
     #[serde(default)]
     pub clip: Option<crate::clip::ClipIndexManifest>,
+    /// Named embedding indexes kept alongside the default `vec` index
+    #[serde(default)]
+    pub vec_spaces: Vec<VecSpaceManifest>,
+    /// Re-embedding job in progress, if any
+    #[serde(default)]
+    pub reembed: Option<ReembedJobManifest>,
+    /// Analyzer chain the lexical index was built with; `None` means the default
+    #[serde(default)]
+    pub lex_analyzer: Option<LexAnalyzerConfig>,
 }
 
 impl Default for IndexManifests {
@@ -696,10 +707,21 @@ impl Default for IndexManifests {
             lex_segments: Vec::new(),
             vec: None,
             clip: None,
+            vec_spaces: Vec::new(),
+            reembed: None,
+            lex_analyzer: None,
         }
     }
 }
 
+impl IndexManifests {
+    /// Look up a named vector space.
+    #[must_use]
+    pub fn vec_space(&self, name: &str) -> Option<&VecSpaceManifest> {
+        self.vec_spaces.iter().find(|space| space.name == name)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LexIndexManifest {
     pub doc_count: u64,
@@ -710,6 +732,73 @@ pub struct LexIndexManifest {
     pub checksum: [u8; 32],
 }
 
+/// Languages with a stemmer and stopword list for the lexical analyzer.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub enum LexLanguage {
+    English,
+    German,
+    French,
+    Spanish,
+    Italian,
+    Portuguese,
+    Dutch,
+}
+
+impl LexLanguage {
+    /// Every supported language, in detection tie-break order.
+    pub const ALL: [Self; 7] = [
+        Self::English,
+        Self::German,
+        Self::French,
+        Self::Spanish,
+        Self::Italian,
+        Self::Portuguese,
+        Self::Dutch,
+    ];
+}
+
+/// Analyzer chain for text indexed by the lexical engine, fixed when the memory is created.
+///
+/// Text is split on non-alphanumerics and lowercased, then optionally stripped of the
+/// language's stopwords, stemmed and folded to ASCII. The default matches memories
+/// created before analyzers were configurable: English stemming only.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct LexAnalyzerConfig {
+    /// Stemmer and stopword language; `None` disables both.
+    pub language: Option<LexLanguage>,
+    /// Remove the language's stopwords.
+    pub stopwords: bool,
+    /// Fold accented characters to their ASCII equivalents.
+    pub ascii_folding: bool,
+    /// Detect each frame's language and analyze it with that language's chain,
+    /// falling back to `language` when detection is inconclusive.
+    pub detect_language: bool,
+}
+
+impl Default for LexAnalyzerConfig {
+    fn default() -> Self {
+        Self {
+            language: Some(LexLanguage::English),
+            stopwords: false,
+            ascii_folding: false,
+            detect_language: false,
+        }
+    }
+}
+
+impl LexAnalyzerConfig {
+    /// Chain for `language` with stopwords and ASCII folding enabled.
+    #[must_use]
+    pub fn for_language(language: LexLanguage) -> Self {
+        Self {
+            language: Some(language),
+            stopwords: true,
+            ascii_folding: true,
+            detect_language: false,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LexSegmentManifest {
     pub path: String,
@@ -720,8 +809,10 @@ pub struct LexSegmentManifest {
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum VectorCompression {
-    None, // Full f32 vectors (1,536 bytes for 384 dims)
-    Pq96, // Product quantization with 96 subspaces (96 bytes)
+    None,   // Full f32 vectors (1,536 bytes for 384 dims)
+    Pq96,   // Product quantization with 96 subspaces (96 bytes)
+    Int8,   // Scalar quantization, one signed byte per dimension (4x)
+    Binary, // Sign bits, one bit per dimension (32x), rescored after a hamming shortlist
 }
 
 impl Default for VectorCompression {
@@ -730,6 +821,48 @@ impl Default for VectorCompression {
     }
 }
 
+/// Distance function used to rank vectors, fixed when the index is created.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub enum DistanceMetric {
+    /// Euclidean distance (legacy default; sensitive to embedding magnitude)
+    #[default]
+    L2,
+    /// Cosine similarity (angle only; suits OpenAI/BGE style embeddings)
+    Cosine,
+    /// Raw inner product (for models trained with dot-product objectives)
+    Dot,
+}
+
+impl DistanceMetric {
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            DistanceMetric::L2 => "l2",
+            DistanceMetric::Cosine => "cosine",
+            DistanceMetric::Dot => "dot",
+        }
+    }
+}
+
+impl std::fmt::Display for DistanceMetric {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for DistanceMetric {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "l2" | "euclidean" => Ok(DistanceMetric::L2),
+            "cosine" | "cos" => Ok(DistanceMetric::Cosine),
+            "dot" | "ip" | "inner_product" => Ok(DistanceMetric::Dot),
+            _ => Err(format!("Unknown distance metric: {s}")),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VecIndexManifest {
     pub vector_count: u64,
@@ -740,6 +873,38 @@ pub struct VecIndexManifest {
     /// Compression mode for vector storage (default: None for backward compatibility)
     #[serde(default)]
     pub compression_mode: VectorCompression,
+    /// Distance metric the index was built with (default: L2 for backward compatibility)
+    #[serde(default)]
+    pub metric: DistanceMetric,
+}
+
+/// A named embedding index, e.g. one per embedding model while re-embedding.
+///
+/// Each space carries its own dimension and metric (in `index`) and the identity of the
+/// model that produced its vectors. An empty placeholder has `bytes_length == 0`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VecSpaceManifest {
+    pub name: String,
+    #[serde(default)]
+    pub identity: Option<EmbeddingIdentity>,
+    pub index: VecIndexManifest,
+}
+
+/// Progress of a re-embedding job.
+///
+/// New vectors are staged in the named space `space` and replace the default `vec` index
+/// once every frame below `frames.len()` has been visited. Counters only cover batches
+/// that have been committed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReembedJobManifest {
+    pub space: String,
+    pub identity: EmbeddingIdentity,
+    /// Next frame to visit; every frame below it has been embedded or skipped.
+    pub next_frame_id: FrameId,
+    pub embedded_frames: u64,
+    pub skipped_frames: u64,
+    pub started_at: i64,
+    pub updated_at: i64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -812,6 +977,10 @@ pub struct Toc {
     /// Tracks frames needing background Phase 2 work (full extraction + embeddings).
     #[serde(default)]
     pub enrichment_queue: EnrichmentQueueManifest,
+    /// Bumped each time `vacuum` rewrites payloads and rebuilds the indexes.
+    /// Pagination cursors issued under an older generation are rejected as stale.
+    #[serde(default)]
+    pub vacuum_generation: u64,
     pub merkle_root: [u8; 32],
     pub toc_checksum: [u8; 32],
 }
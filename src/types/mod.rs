@@ -20,14 +20,16 @@ pub mod schema;
 pub mod search;
 pub mod sketch_track;
 pub mod structure;
+pub mod synthesizer;
 #[cfg(feature = "temporal_track")]
 pub mod temporal;
 pub mod ticket;
 pub mod verification;
 
 pub use ask::{
-    AskCitation, AskContextFragment, AskContextFragmentKind, AskMode, AskRequest, AskResponse,
-    AskRetriever, AskStats, VecEmbedder,
+    ApproxTokenCounter, AskCitation, AskContextFragment, AskContextFragmentKind, AskMode,
+    AskQueryAttribution, AskQuerySource, AskRequest, AskResponse, AskRetriever, AskStats,
+    QueryRewriter, TokenCounter, VecEmbedder,
 };
 pub use audit::{AuditOptions, AuditReport, SourceSpan};
 pub use binding::{FileInfo, MemoryBinding};
@@ -35,18 +37,20 @@ pub use common::{
     CanonicalEncoding, EnrichmentState, EnrichmentTask, FrameId, FrameRole, FrameStatus,
     MemvidHandle, Open, Sealed, Tier,
 };
+pub use synthesizer::{AnswerSynthesizer, ExtractiveSynthesizer, SynthesizedAnswer};
 // AnchorSource always exported - not feature-gated to maintain binary compatibility
 pub use frame::AnchorSource;
-pub use frame::{Frame, Stats, TimelineEntry, TimelineQuery, TimelineQueryBuilder};
+pub use frame::{Frame, Stats, TimelineEntry, TimelinePage, TimelineQuery, TimelineQueryBuilder};
 // Serialized manifest types - always exported for binary compatibility
 pub use manifest::TemporalSegmentDescriptor;
 pub use manifest::TemporalTrackManifest;
 pub use manifest::{
-    EnrichmentQueueManifest, Header, IndexManifests, IndexSegmentRef, LexIndexManifest,
-    LexSegmentDescriptor, LexSegmentManifest, LogicMeshManifest, MemoriesTrackManifest,
-    SegmentCatalog, SegmentCommon, SegmentCompression, SegmentKind, SegmentMeta, SegmentSpan,
-    SegmentStats, SketchTrackManifest, TantivySegmentDescriptor, TimeIndexManifest,
-    TimeSegmentDescriptor, Toc, VecIndexManifest, VecSegmentDescriptor, VectorCompression,
+    DistanceMetric, EnrichmentQueueManifest, Header, IndexManifests, IndexSegmentRef,
+    LexAnalyzerConfig, LexIndexManifest, LexLanguage, LexSegmentDescriptor, LexSegmentManifest,
+    LogicMeshManifest, MemoriesTrackManifest, ReembedJobManifest, SegmentCatalog, SegmentCommon,
+    SegmentCompression, SegmentKind, SegmentMeta, SegmentSpan, SegmentStats, SketchTrackManifest,
+    TantivySegmentDescriptor, TimeIndexManifest, TimeSegmentDescriptor, Toc, VecIndexManifest,
+    VecSegmentDescriptor, VecSpaceManifest, VectorCompression,
 };
 // Logic-Mesh types for entity-relationship graph traversal
 pub use logic_mesh::{
@@ -59,8 +63,10 @@ pub use metadata::{
 };
 pub use options::{PutManyOpts, PutOptions, PutOptionsBuilder, PutRequest};
 pub use search::{
-    SearchEngineKind, SearchHit, SearchHitEntity, SearchHitMetadata, SearchParams, SearchRequest,
-    SearchResponse,
+    ChunkExpansion, ChunkWindow, DateInterval, FacetBucket, FacetField, FacetRequest, FacetResult,
+    FusionStrategy, HighlightFragment, HighlightOptions, HybridRetriever, RetrieverScore,
+    SearchEngineKind, SearchHit, SearchHitEntity, SearchHitMetadata, SearchHitRerank, SearchParams,
+    SearchRequest, SearchResponse, SpellingSuggestion, VecSearchFilter,
 };
 #[cfg(feature = "temporal_track")]
 pub use search::{SearchHitTemporal, SearchHitTemporalAnchor, SearchHitTemporalMention};
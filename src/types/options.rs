@@ -71,6 +71,10 @@ pub struct PutOptions {
     /// Default: 350ms (optimized for sub-second total ingestion).
     #[serde(default = "default_extraction_budget_ms")]
     pub extraction_budget_ms: u64,
+    /// Named vector space that receives the supplied embeddings.
+    /// `None` stores them in the default vector index.
+    #[serde(default)]
+    pub vec_space: Option<String>,
 }
 
 fn default_extraction_budget_ms() -> u64 {
@@ -101,6 +105,7 @@ impl Default for PutOptions {
             dedup: false,
             instant_index: true, // Instant searchability by default
             extraction_budget_ms: default_extraction_budget_ms(),
+            vec_space: None,
         }
     }
 }
@@ -248,6 +253,12 @@ impl PutOptionsBuilder {
         self
     }
 
+    /// Store the supplied embeddings in a named vector space.
+    pub fn vec_space<S: Into<String>>(mut self, space: S) -> Self {
+        self.inner.vec_space = Some(space.into());
+        self
+    }
+
     pub fn build(self) -> PutOptions {
         self.inner
     }
@@ -2,9 +2,11 @@
 
 use serde::{Deserialize, Serialize};
 
+use super::ask::AskRequest;
 use super::common::FrameId;
 #[cfg(feature = "temporal_track")]
 use super::frame::AnchorSource;
+use super::frame::Frame;
 #[cfg(feature = "temporal_track")]
 use super::temporal::{TemporalFilter, TemporalMentionFlags, TemporalMentionKind};
 
@@ -36,7 +38,7 @@ impl Default for SearchEngineKind {
 }
 
 /// Search request accepted by the core; supports lexical, hybrid, and temporal filters.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct SearchRequest {
     /// Query string (lexical or semantic depending on engine).
     pub query: String,
@@ -65,6 +67,302 @@ pub struct SearchRequest {
     #[serde(default)]
     /// Disable sketch pre-filtering for this query.
     pub no_sketch: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Diversify hits with Maximal Marginal Relevance. `1.0` keeps pure relevance,
+    /// lower values trade relevance for novelty. Must be within `[0, 1]`.
+    pub mmr_lambda: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Facet counts to compute over every matching frame, not just the returned page.
+    pub facets: Option<FacetRequest>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Mark matched terms in each hit and pick its best-matching fragments.
+    pub highlight: Option<HighlightOptions>,
+    #[serde(default)]
+    /// When the query finds nothing and spelling suggestions exist, rerun it with
+    /// [`SearchResponse::corrected_query`] and return those hits instead.
+    pub auto_correct: bool,
+    #[serde(default)]
+    /// Reorder hits with the reranker registered through `Memvid::set_reranker`,
+    /// drawing on a pool of its `max_candidates` hits.
+    pub rerank: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Attach the text around each hit's chunk as [`SearchHit::expanded`].
+    pub expand: Option<ChunkExpansion>,
+}
+
+/// Surrounding text to fetch for each hit ("small-to-big" retrieval).
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case", tag = "kind")]
+pub enum ChunkExpansion {
+    /// The hit's chunk plus up to `before` and `after` neighbouring chunks of its document.
+    Adjacent { before: usize, after: usize },
+    /// The markdown section enclosing the hit: from the nearest heading before it up
+    /// to the next heading of the same or a higher level. Documents without
+    /// headings are returned whole.
+    Section,
+}
+
+/// Span of a document's canonical text around a chunk.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ChunkWindow {
+    /// Document frame the window belongs to; the hit frame itself when unchunked.
+    pub parent_id: FrameId,
+    /// Byte range of `text` within the document's canonical text.
+    pub range: (usize, usize),
+    /// Index of the first chunk the window overlaps.
+    pub first_chunk: usize,
+    /// Index of the last chunk the window overlaps.
+    pub last_chunk: usize,
+    /// Number of chunks in the document.
+    pub chunk_count: usize,
+    pub text: String,
+}
+
+/// Per-hit highlighting requested alongside a search.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct HighlightOptions {
+    /// Fragments of `chunk_text` to return per hit, best-matching first.
+    #[serde(default = "default_highlight_fragments")]
+    pub fragments: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Maximum fragment length in characters; defaults to the request's `snippet_chars`.
+    pub fragment_chars: Option<usize>,
+}
+
+fn default_highlight_fragments() -> usize {
+    1
+}
+
+impl Default for HighlightOptions {
+    fn default() -> Self {
+        Self {
+            fragments: default_highlight_fragments(),
+            fragment_chars: None,
+        }
+    }
+}
+
+/// Facet counts requested alongside a search.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct FacetRequest {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    /// Term facets to count, one result per field.
+    pub fields: Vec<FacetField>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Bucket matching frames by `timestamp` at this interval (UTC).
+    pub date_histogram: Option<DateInterval>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Maximum buckets per term facet; `None` returns every bucket.
+    pub size: Option<usize>,
+}
+
+/// Frame attribute counted by a term facet.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(tag = "field", rename_all = "snake_case")]
+pub enum FacetField {
+    Tags,
+    Labels,
+    Track,
+    Kind,
+    /// The first `depth` path segments of the URI, e.g. `mv2://docs/api` for depth 2.
+    UriPrefix {
+        depth: usize,
+    },
+}
+
+/// Bucket width for timestamp histograms.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum DateInterval {
+    Day,
+    Month,
+    Year,
+}
+
+/// Counts for one requested facet.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FacetResult {
+    /// Counted field, or `None` for the date histogram.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub field: Option<FacetField>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub interval: Option<DateInterval>,
+    /// Term buckets ordered by count (then key); histogram buckets in date order.
+    pub buckets: Vec<FacetBucket>,
+}
+
+/// One facet value and the number of matching frames carrying it.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct FacetBucket {
+    pub key: String,
+    pub count: usize,
+}
+
+/// Metadata filters applied while scanning the vector index.
+///
+/// Mirrors the filter surface of [`SearchRequest`] (plus the field filters accepted in
+/// lexical queries) so vector retrieval fills `top_k` with in-scope hits instead of
+/// discarding out-of-scope neighbours afterwards.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct VecSearchFilter {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Restrict to a single URI (case-insensitive, like `uri:`).
+    pub uri: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Restrict to URIs starting with this prefix (like `scope:`).
+    pub scope: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    /// Frames must carry every listed tag (case-insensitive).
+    pub tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    /// Frames must carry every listed label (case-insensitive).
+    pub labels: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Restrict to a track (case-insensitive).
+    pub track: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Replay: only frames with id <= as_of_frame.
+    pub as_of_frame: Option<FrameId>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Replay: only frames with timestamp <= as_of_ts.
+    pub as_of_ts: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Only frames with timestamp >= start.
+    pub start: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Only frames with timestamp <= end.
+    pub end: Option<i64>,
+    #[cfg(feature = "temporal_track")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub temporal: Option<TemporalFilter>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Named vector space to search; `None` searches the default index.
+    /// Selects the index rather than constraining frames, so it is ignored by `is_empty`.
+    pub space: Option<String>,
+}
+
+impl VecSearchFilter {
+    /// Filter restricted to a URI prefix, the most common case.
+    #[must_use]
+    pub fn scoped(scope: Option<&str>) -> Self {
+        Self {
+            scope: scope.map(str::to_string),
+            ..Self::default()
+        }
+    }
+
+    /// True when no constraint is set.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        #[cfg(feature = "temporal_track")]
+        if self
+            .temporal
+            .as_ref()
+            .is_some_and(|temporal| !temporal.is_empty())
+        {
+            return false;
+        }
+        self.uri.is_none()
+            && self.scope.is_none()
+            && self.tags.is_empty()
+            && self.labels.is_empty()
+            && self.track.is_none()
+            && self.as_of_frame.is_none()
+            && self.as_of_ts.is_none()
+            && self.start.is_none()
+            && self.end.is_none()
+    }
+
+    /// Check the frame-level constraints. The temporal filter needs the temporal track
+    /// and is resolved separately by the caller.
+    #[must_use]
+    pub fn matches_frame(&self, frame: &Frame) -> bool {
+        let default_uri;
+        let uri = match frame.uri.as_deref() {
+            Some(uri) => uri,
+            None => {
+                default_uri = crate::default_uri(frame.id);
+                default_uri.as_str()
+            }
+        };
+        if let Some(expected) = self.uri.as_deref() {
+            if !uri.eq_ignore_ascii_case(expected) {
+                return false;
+            }
+        } else if let Some(prefix) = self.scope.as_deref() {
+            if !uri.starts_with(prefix) {
+                return false;
+            }
+        }
+        if let Some(track) = self.track.as_deref() {
+            if !frame
+                .track
+                .as_deref()
+                .is_some_and(|value| value.eq_ignore_ascii_case(track))
+            {
+                return false;
+            }
+        }
+        let has_all = |values: &[String], required: &[String]| {
+            required.iter().all(|needle| {
+                values
+                    .iter()
+                    .any(|value| value.eq_ignore_ascii_case(needle))
+            })
+        };
+        if !has_all(&frame.tags, &self.tags) || !has_all(&frame.labels, &self.labels) {
+            return false;
+        }
+        if self.as_of_frame.is_some_and(|cutoff| frame.id > cutoff) {
+            return false;
+        }
+        let latest = match (self.as_of_ts, self.end) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+        if latest.is_some_and(|cutoff| frame.timestamp > cutoff) {
+            return false;
+        }
+        !self.start.is_some_and(|start| frame.timestamp < start)
+    }
+}
+
+impl From<&SearchRequest> for VecSearchFilter {
+    fn from(request: &SearchRequest) -> Self {
+        // A `date:[.. TO ..]` range every lexical match must satisfy bounds vector hits too.
+        let dates = crate::search::parse_query(&request.query)
+            .ok()
+            .and_then(|parsed| parsed.required_date_range())
+            .unwrap_or_default();
+        Self {
+            uri: request.uri.clone(),
+            scope: request.scope.clone(),
+            as_of_frame: request.as_of_frame,
+            as_of_ts: request.as_of_ts,
+            start: dates.start,
+            end: dates.end,
+            #[cfg(feature = "temporal_track")]
+            temporal: request.temporal.clone(),
+            ..Self::default()
+        }
+    }
+}
+
+impl From<&AskRequest> for VecSearchFilter {
+    fn from(request: &AskRequest) -> Self {
+        Self {
+            uri: request.uri.clone(),
+            scope: request.scope.clone(),
+            as_of_frame: request.as_of_frame,
+            as_of_ts: request.as_of_ts,
+            start: request.start,
+            end: request.end,
+            #[cfg(feature = "temporal_track")]
+            temporal: request.temporal.clone(),
+            space: request.vec_space.clone(),
+            ..Self::default()
+        }
+    }
 }
 
 /// A single ranked hit with snippet metadata.
@@ -86,6 +384,92 @@ pub struct SearchHit {
     pub score: Option<f32>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub metadata: Option<SearchHitMetadata>,
+    /// Per-retriever breakdown when the hit comes from a fused hybrid search.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub retriever_scores: Vec<RetrieverScore>,
+    /// Byte ranges of matched terms within `text`, when highlighting was requested.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub highlights: Vec<(usize, usize)>,
+    /// Byte ranges of matched terms within `chunk_text`, when highlighting was requested.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub chunk_highlights: Vec<(usize, usize)>,
+    /// Best-matching fragments of `chunk_text`, in text order.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fragments: Vec<HighlightFragment>,
+    /// Reranker score and movement, when a reranker stage reordered the hits.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rerank: Option<SearchHitRerank>,
+    /// Text around the hit's chunk, when the request asked for an expansion.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expanded: Option<ChunkWindow>,
+}
+
+/// How the second-stage reranker scored and moved a hit.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct SearchHitRerank {
+    /// Relevance score returned by the reranker.
+    pub score: f32,
+    /// 1-based rank before reranking.
+    pub original_rank: usize,
+    /// 1-based rank the reranker assigned.
+    pub new_rank: usize,
+}
+
+/// Excerpt of a hit's `chunk_text` around its densest matches.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct HighlightFragment {
+    /// Byte range within `chunk_text`.
+    pub range: (usize, usize),
+    pub text: String,
+    /// Byte ranges of matched terms within `text`.
+    pub highlights: Vec<(usize, usize)>,
+}
+
+/// Retriever contributing a candidate list to hybrid fusion.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum HybridRetriever {
+    /// Lexical search (including query expansions and timeline fallbacks).
+    Lexical,
+    /// Vector search.
+    Semantic,
+    /// Sketch-track resemblance (`SimHash` and top terms), used by `similar_frames`.
+    Sketch,
+}
+
+/// How one retriever ranked and scored a fused hit.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RetrieverScore {
+    pub retriever: HybridRetriever,
+    /// Best 1-based rank the hit reached in this retriever's lists.
+    pub rank: usize,
+    /// Raw score reported by the retriever, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub score: Option<f32>,
+    /// Amount this retriever added to the fused score.
+    pub contribution: f32,
+}
+
+/// Strategy used to merge lexical and semantic candidate lists.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum FusionStrategy {
+    /// Reciprocal Rank Fusion: each list adds `1 / (k + rank)`.
+    Rrf { k: f32 },
+    /// Each list adds its min-max normalised score times the retriever weight.
+    Linear { lexical: f32, semantic: f32 },
+    /// Weighted mean of each retriever's best normalised score; weights are scaled to
+    /// sum to 1, so fused scores stay within `[0, 1]`.
+    Convex { lexical: f32, semantic: f32 },
+    /// Distribution-based score fusion: each list is normalised against
+    /// `mean ± 3 * stddev` of its own scores before summing.
+    Dbsf,
+}
+
+impl Default for FusionStrategy {
+    fn default() -> Self {
+        Self::Rrf { k: 60.0 }
+    }
 }
 
 /// Entity reference in search hit metadata.
@@ -180,4 +564,27 @@ pub struct SearchResponse {
     #[serde(default)]
     /// Engine responsible for the results.
     pub engine: SearchEngineKind,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    /// Facet counts, in request order, when [`SearchRequest::facets`] was set.
+    pub facets: Vec<FacetResult>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    /// Closest indexed words for query words the index lacks; set when nothing matched.
+    pub suggestions: Vec<SpellingSuggestion>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// "Did you mean" query with every suggestion applied. With
+    /// [`SearchRequest::auto_correct`] the hits and `next_cursor` belong to this query.
+    pub corrected_query: Option<String>,
+}
+
+/// Closest indexed word for a query word that no indexed document contains.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SpellingSuggestion {
+    /// Query word as typed, lower-cased.
+    pub word: String,
+    /// Indexed word to search for instead.
+    pub suggestion: String,
+    /// Edit distance between the two.
+    pub distance: u8,
+    /// Indexed documents containing the suggestion.
+    pub doc_frequency: u64,
 }
@@ -30,7 +30,10 @@ use crate::types::FrameId;
 pub const SKETCH_TRACK_MAGIC: [u8; 4] = *b"MVSK";
 
 /// Current version of the sketch track format.
-pub const SKETCH_TRACK_VERSION: u16 = 1;
+///
+/// Version 2 splits CJK runs into bigrams before hashing terms; tracks written
+/// with version 1 are still readable but should be rebuilt.
+pub const SKETCH_TRACK_VERSION: u16 = 2;
 
 /// Default Hamming distance threshold for SimHash similarity (out of 64 bits).
 /// Lower = stricter matching. 8-12 is typical for near-duplicate detection.
@@ -615,6 +618,7 @@ pub fn term_filter_maybe_contains(filter: &[u8], token_hash: u64) -> bool {
 /// - Unicode NFKC normalization
 /// - Lowercase
 /// - Split on whitespace and punctuation
+/// - Split CJK runs into overlapping bigrams
 /// - Keep only alphanumeric tokens >= 2 chars
 #[must_use]
 pub fn tokenize_for_sketch(text: &str) -> Vec<String> {
@@ -623,9 +627,14 @@ pub fn tokenize_for_sketch(text: &str) -> Vec<String> {
     // NFKC normalize and lowercase
     let normalized: String = text.nfkc().collect::<String>().to_lowercase();
 
-    // Split on non-alphanumeric, filter short tokens
+    // Split on non-alphanumeric, break CJK runs into bigrams, filter short tokens
     normalized
         .split(|c: char| !c.is_alphanumeric())
+        .flat_map(|word| {
+            crate::text::cjk_segments(word)
+                .into_iter()
+                .map(move |(start, end)| &word[start..end])
+        })
         .filter(|s| s.len() >= 2)
         .map(String::from)
         .collect()
@@ -782,6 +791,22 @@ impl QuerySketch {
         }
     }
 
+    /// Use a stored frame sketch as the query, to find frames resembling that frame.
+    #[must_use]
+    pub fn from_entry(entry: &SketchEntry) -> Self {
+        Self {
+            simhash: entry.simhash,
+            term_filter: entry.term_filter.clone(),
+            top_terms: entry
+                .top_terms
+                .iter()
+                .copied()
+                .filter(|term| *term != 0)
+                .collect(),
+            token_count: usize::from(entry.length_hint) * 10,
+        }
+    }
+
     /// Score a sketch entry against this query.
     /// Returns a score in [0.0, 1.0] where higher is better.
     #[must_use]
@@ -830,6 +855,8 @@ impl QuerySketch {
 pub struct SketchTrack {
     /// Sketch variant used.
     pub variant: SketchVariant,
+    /// Format version the entries were generated with.
+    pub version: u16,
     /// Sketch entries indexed by frame ID.
     entries: HashMap<FrameId, SketchEntry>,
     /// Ordered list of frame IDs for sequential scanning.
@@ -848,6 +875,7 @@ impl SketchTrack {
     pub fn new(variant: SketchVariant) -> Self {
         Self {
             variant,
+            version: SKETCH_TRACK_VERSION,
             entries: HashMap::new(),
             frame_order: Vec::new(),
         }
@@ -896,9 +924,23 @@ impl SketchTrack {
         query: &QuerySketch,
         hamming_threshold: u32,
         max_candidates: usize,
+    ) -> Vec<(FrameId, f32)> {
+        self.find_candidates_filtered(query, hamming_threshold, max_candidates, |_| true)
+    }
+
+    /// Like [`Self::find_candidates`], but only frames accepted by `accept` count
+    /// towards `max_candidates`.
+    #[must_use]
+    pub fn find_candidates_filtered(
+        &self,
+        query: &QuerySketch,
+        hamming_threshold: u32,
+        max_candidates: usize,
+        accept: impl Fn(FrameId) -> bool,
     ) -> Vec<(FrameId, f32)> {
         let mut candidates: Vec<(FrameId, f32)> = self
             .iter()
+            .filter(|entry| accept(entry.frame_id))
             .filter_map(|entry| {
                 query
                     .score_entry(entry, hamming_threshold)
@@ -1041,7 +1083,8 @@ pub fn write_sketch_track<W: Write + Seek>(
     let mut hasher = Hasher::new();
 
     // Write header
-    let header = SketchTrackHeader::new(track.variant, track.len() as u64);
+    let mut header = SketchTrackHeader::new(track.variant, track.len() as u64);
+    header.version = track.version;
     let header_bytes = header.to_bytes();
     writer.write_all(&header_bytes)?;
     hasher.update(&header_bytes);
@@ -1103,6 +1146,7 @@ pub fn read_sketch_track<R: Read + Seek>(
 
     // Read entries
     let mut track = SketchTrack::new(variant);
+    track.version = header.version;
     let _entry_size = header.entry_size as usize;
 
     for frame_id in 0..header.entry_count {
@@ -1141,6 +1185,12 @@ mod tests {
     use super::*;
     use std::io::Cursor;
 
+    #[test]
+    fn test_tokenize_cjk_bigrams() {
+        let tokens = tokenize_for_sketch("北京大学 Peking");
+        assert_eq!(tokens, vec!["北京", "京大", "大学", "peking"]);
+    }
+
     #[test]
     fn test_simhash_similar_texts() {
         let text1 = "the quick brown fox jumps over the lazy dog";
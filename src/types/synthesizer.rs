@@ -0,0 +1,346 @@
+//! Answer synthesizer trait and the default extractive implementation.
+//!
+//! `Memvid::ask` retrieves context and hands the question plus its
+//! `AskContextFragment`s to an `AnswerSynthesizer`, which returns the answer
+//! text and the citations it relies on. Plug in a local LLM by implementing the
+//! trait; without one, `ask` uses [`ExtractiveSynthesizer`].
+//!
+//! ## Citations
+//!
+//! Citation indices are 1-based positions in the fragment slice, matching
+//! `AskCitation::index` and the `[n]` markers placed in the answer text.
+//!
+//! ## Usage
+//!
+//! ```ignore
+//! struct LocalLlm;
+//!
+//! impl AnswerSynthesizer for LocalLlm {
+//!     fn kind(&self) -> &str { "local-llm" }
+//!
+//!     fn synthesize(
+//!         &self,
+//!         question: &str,
+//!         fragments: &[AskContextFragment],
+//!     ) -> Result<Option<SynthesizedAnswer>> {
+//!         // Prompt the model with the question and numbered fragments
+//!         todo!()
+//!     }
+//! }
+//!
+//! let response = mem.ask(request, None::<&dyn VecEmbedder>, Some(&LocalLlm))?;
+//! ```
+
+use std::collections::HashSet;
+
+use super::ask::AskContextFragment;
+use crate::error::Result;
+
+/// Answer produced by a synthesizer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SynthesizedAnswer {
+    /// Answer text, typically with `[n]` citation markers.
+    pub text: String,
+    /// 1-based fragment indices the answer cites, in order of first use.
+    pub citations: Vec<usize>,
+}
+
+/// Trait for turning retrieved context into an answer.
+pub trait AnswerSynthesizer {
+    /// Return the synthesizer kind identifier.
+    fn kind(&self) -> &str;
+
+    /// Answer `question` from `fragments`, ordered best first.
+    ///
+    /// Returns `None` when no answer should be attached to the response.
+    fn synthesize(
+        &self,
+        question: &str,
+        fragments: &[AskContextFragment],
+    ) -> Result<Option<SynthesizedAnswer>>;
+}
+
+/// Question words ignored when scoring sentences.
+const STOPWORDS: &[&str] = &[
+    "a", "about", "an", "and", "are", "as", "at", "be", "been", "but", "by", "can", "did", "do",
+    "does", "for", "from", "had", "has", "have", "how", "i", "if", "in", "is", "it", "its", "me",
+    "my", "of", "on", "or", "our", "should", "so", "that", "the", "their", "them", "there",
+    "these", "they", "this", "those", "to", "was", "we", "were", "what", "when", "where", "which",
+    "who", "whom", "why", "will", "with", "would", "you", "your",
+];
+
+/// Prefixes of the metadata lines appended to a frame's search text at ingest.
+const METADATA_LINE_PREFIXES: &[&str] = &[
+    "title: ",
+    "uri: ",
+    "track: ",
+    "tags: ",
+    "labels: ",
+    "dates: ",
+    "metadata: ",
+];
+
+/// Shortest shared prefix that counts as a match between inflected words.
+const MIN_PREFIX_MATCH_CHARS: usize = 4;
+
+/// Deterministic extractive synthesizer.
+///
+/// Splits every fragment into sentences, scores each by the share of question
+/// words it contains and returns the best `max_sentences`, each followed by its
+/// fragment's `[n]` marker. Ties go to higher-ranked fragments and earlier
+/// sentences. When no sentence shares a word with the question, the opening
+/// sentence of the first non-empty fragment is returned.
+#[derive(Debug, Clone)]
+pub struct ExtractiveSynthesizer {
+    /// Maximum number of sentences in the answer.
+    pub max_sentences: usize,
+}
+
+impl Default for ExtractiveSynthesizer {
+    fn default() -> Self {
+        Self { max_sentences: 3 }
+    }
+}
+
+impl AnswerSynthesizer for ExtractiveSynthesizer {
+    fn kind(&self) -> &str {
+        "extractive"
+    }
+
+    fn synthesize(
+        &self,
+        question: &str,
+        fragments: &[AskContextFragment],
+    ) -> Result<Option<SynthesizedAnswer>> {
+        if fragments.is_empty() {
+            return Ok(None);
+        }
+
+        let terms = question_terms(question);
+        // (score, fragment index, sentence index, sentence)
+        let mut scored: Vec<(f32, usize, usize, String)> = Vec::new();
+        for (fragment_idx, fragment) in fragments.iter().enumerate() {
+            for (sentence_idx, sentence) in split_sentences(&fragment.text).into_iter().enumerate()
+            {
+                let score = sentence_score(&sentence, &terms);
+                scored.push((score, fragment_idx, sentence_idx, sentence));
+            }
+        }
+
+        if scored.is_empty() {
+            return Ok(Some(SynthesizedAnswer {
+                text: format!(
+                    "No direct synthesis available for '{question}'. Review the top contexts manually.",
+                ),
+                citations: Vec::new(),
+            }));
+        }
+
+        let mut picked: Vec<(usize, String)> = Vec::new();
+        if scored.iter().any(|(score, ..)| *score > 0.0) {
+            scored.sort_by(|a, b| {
+                b.0.partial_cmp(&a.0)
+                    .unwrap_or(std::cmp::Ordering::Equal)
+                    .then(a.1.cmp(&b.1))
+                    .then(a.2.cmp(&b.2))
+            });
+            let mut seen: HashSet<String> = HashSet::new();
+            for (score, fragment_idx, _, sentence) in scored {
+                if picked.len() >= self.max_sentences.max(1) || score <= 0.0 {
+                    break;
+                }
+                if seen.insert(sentence.to_lowercase()) {
+                    picked.push((fragment_idx + 1, sentence));
+                }
+            }
+        } else if let Some((_, fragment_idx, _, sentence)) = scored.into_iter().next() {
+            picked.push((fragment_idx + 1, sentence));
+        }
+
+        let mut citations: Vec<usize> = Vec::new();
+        let segments: Vec<String> = picked
+            .into_iter()
+            .map(|(index, sentence)| {
+                if !citations.contains(&index) {
+                    citations.push(index);
+                }
+                format!("{sentence} [{index}]")
+            })
+            .collect();
+        Ok(Some(SynthesizedAnswer {
+            text: segments.join(" "),
+            citations,
+        }))
+    }
+}
+
+/// Distinct lower-cased content words of the question.
+fn question_terms(question: &str) -> Vec<String> {
+    let mut terms: Vec<String> = Vec::new();
+    for word in words(question) {
+        if word.chars().count() < 2 || STOPWORDS.contains(&word.as_str()) {
+            continue;
+        }
+        if !terms.contains(&word) {
+            terms.push(word);
+        }
+    }
+    terms
+}
+
+/// Lower-cased alphanumeric words of `text`.
+fn words(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|ch: char| !ch.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+}
+
+/// Sentences of `text` with whitespace collapsed, split after `.`, `!` or `?`
+/// followed by whitespace and at line breaks. Ingest metadata lines are skipped.
+fn split_sentences(text: &str) -> Vec<String> {
+    let mut sentences = Vec::new();
+    for line in text.lines() {
+        let line = line.trim();
+        if METADATA_LINE_PREFIXES
+            .iter()
+            .any(|prefix| line.starts_with(prefix))
+        {
+            continue;
+        }
+        let mut current = String::new();
+        let mut chars = line.chars().peekable();
+        while let Some(ch) = chars.next() {
+            current.push(ch);
+            if matches!(ch, '.' | '!' | '?') && chars.peek().is_none_or(|next| next.is_whitespace())
+            {
+                push_sentence(&mut sentences, &current);
+                current.clear();
+            }
+        }
+        push_sentence(&mut sentences, &current);
+    }
+    sentences
+}
+
+fn push_sentence(sentences: &mut Vec<String>, raw: &str) {
+    let sentence = raw.split_whitespace().collect::<Vec<_>>().join(" ");
+    if sentence.chars().any(char::is_alphanumeric) {
+        sentences.push(sentence);
+    }
+}
+
+/// Share of question terms found in the sentence, counting inflected forms that
+/// share a prefix of at least [`MIN_PREFIX_MATCH_CHARS`].
+fn sentence_score(sentence: &str, terms: &[String]) -> f32 {
+    if terms.is_empty() {
+        return 0.0;
+    }
+    let tokens: Vec<String> = words(sentence).collect();
+    let matched = terms
+        .iter()
+        .filter(|term| tokens.iter().any(|token| words_match(term, token)))
+        .count();
+    matched as f32 / terms.len() as f32
+}
+
+fn words_match(term: &str, token: &str) -> bool {
+    if term == token {
+        return true;
+    }
+    let (shorter, longer) = if term.len() <= token.len() {
+        (term, token)
+    } else {
+        (token, term)
+    };
+    shorter.chars().count() >= MIN_PREFIX_MATCH_CHARS && longer.starts_with(shorter)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fragment(rank: usize, text: &str) -> AskContextFragment {
+        AskContextFragment {
+            rank,
+            frame_id: rank as u64,
+            uri: format!("mv2://doc/{rank}"),
+            title: None,
+            score: None,
+            matches: 0,
+            range: None,
+            chunk_range: None,
+            text: text.to_string(),
+            kind: None,
+            #[cfg(feature = "temporal_track")]
+            temporal: None,
+        }
+    }
+
+    #[test]
+    fn extractive_picks_sentences_matching_the_question() {
+        let fragments = vec![
+            fragment(
+                1,
+                "The office moved last spring. Parking is free on weekends.",
+            ),
+            fragment(
+                2,
+                "Budget reviews happen quarterly.\nThe office relocation finished in Berlin.",
+            ),
+        ];
+        let answer = ExtractiveSynthesizer::default()
+            .synthesize("Where did the office relocation go?", &fragments)
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            answer.text,
+            "The office relocation finished in Berlin. [2] The office moved last spring. [1]"
+        );
+        assert_eq!(answer.citations, vec![2, 1]);
+    }
+
+    #[test]
+    fn extractive_falls_back_to_leading_sentence() {
+        let fragments = vec![fragment(1, ""), fragment(2, "Unrelated text. More of it.")];
+        let answer = ExtractiveSynthesizer::default()
+            .synthesize("volcano eruptions", &fragments)
+            .unwrap()
+            .unwrap();
+        assert_eq!(answer.text, "Unrelated text. [2]");
+        assert_eq!(answer.citations, vec![2]);
+
+        assert!(
+            ExtractiveSynthesizer::default()
+                .synthesize("anything", &[])
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn extractive_skips_ingest_metadata_lines() {
+        let fragments = vec![fragment(
+            1,
+            "Lava flows north.\ntitle: Lava report\ntags: lava volcano",
+        )];
+        let answer = ExtractiveSynthesizer::default()
+            .synthesize("lava report", &fragments)
+            .unwrap()
+            .unwrap();
+        assert_eq!(answer.text, "Lava flows north. [1]");
+    }
+
+    #[test]
+    fn extractive_respects_sentence_limit_and_dedups() {
+        let fragments = vec![
+            fragment(1, "Lava flows north. Lava flows north."),
+            fragment(2, "Lava cools quickly. Lava glows red."),
+        ];
+        let synthesizer = ExtractiveSynthesizer { max_sentences: 2 };
+        let answer = synthesizer
+            .synthesize("lava flows", &fragments)
+            .unwrap()
+            .unwrap();
+        assert_eq!(answer.text, "Lava flows north. [1] Lava cools quickly. [2]");
+    }
+}
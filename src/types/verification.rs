@@ -84,6 +84,10 @@ pub enum DoctorFindingCode {
     VecIndexCorrupt,
     TantivySnapshotMissing,
     TantivySnapshotCorrupt,
+    /// Snapshot schema predates a field the engine now indexes (e.g. `title`).
+    TantivySnapshotOutdated,
+    /// Sketch track was generated with an older term tokenizer.
+    SketchTrackOutdated,
     MerkleMismatch,
     SegmentCatalogInconsistent,
     VacuumIncomplete,
@@ -1,7 +1,15 @@
+use std::borrow::Cow;
+
 use blake3::hash;
 use serde::{Deserialize, Serialize};
 
-use crate::{MemvidError, Result, types::FrameId};
+use crate::vec_hnsw::{
+    DistanceFn, HNSW_DEFAULT_EF_SEARCH, HNSW_MIN_VECTORS, HnswGraph, HnswParams,
+};
+use crate::{
+    MemvidError, Result,
+    types::{DistanceMetric, FrameId},
+};
 
 fn vec_config() -> impl bincode::config::Config {
     bincode::config::standard()
@@ -11,15 +19,33 @@ fn vec_config() -> impl bincode::config::Config {
 
 const VEC_DECODE_LIMIT: usize = crate::MAX_INDEX_BYTES as usize;
 
+/// Rebuild the graph from scratch once more than 1/N of its nodes are tombstones.
+const HNSW_REBUILD_DELETED_FRACTION: usize = 4;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VecDocument {
     pub frame_id: FrameId,
     pub embedding: Vec<f32>,
 }
 
-#[derive(Default)]
 pub struct VecIndexBuilder {
     documents: Vec<VecDocument>,
+    /// Graph parameters; `None` always emits a flat (brute-force) segment.
+    hnsw: Option<HnswParams>,
+    /// Graph carried over from a previous index so commits only insert new vectors.
+    graph: Option<HnswGraph>,
+    metric: DistanceMetric,
+}
+
+impl Default for VecIndexBuilder {
+    fn default() -> Self {
+        Self {
+            documents: Vec::new(),
+            hnsw: Some(HnswParams::default()),
+            graph: None,
+            metric: DistanceMetric::default(),
+        }
+    }
 }
 
 impl VecIndexBuilder {
@@ -27,6 +53,78 @@ impl VecIndexBuilder {
         Self::default()
     }
 
+    /// Seed a builder with the live documents of an existing index.
+    ///
+    /// When the index already carries an HNSW graph it is reused: documents rejected by
+    /// `keep` are tombstoned instead of dropped, so [`Self::finish`] only has to link the
+    /// newly added vectors. The graph is rebuilt once tombstones dominate.
+    pub fn from_index(index: &VecIndex, keep: impl Fn(FrameId) -> bool) -> Self {
+        let mut builder = Self::new();
+        builder.metric = index.metric();
+        let carried = match index {
+            VecIndex::Graph {
+                documents, graph, ..
+            } => Some((documents.clone(), graph.clone())),
+            VecIndex::Mapped(mapped) => mapped.graph_documents(),
+            _ => None,
+        };
+        if let Some((documents, mut graph)) = carried {
+            for (node, doc) in documents.iter().enumerate() {
+                if !graph.is_deleted(node) && !keep(doc.frame_id) {
+                    graph.mark_deleted(node);
+                }
+            }
+            if graph.deleted_count() * HNSW_REBUILD_DELETED_FRACTION <= graph.len() {
+                builder.documents = documents;
+                builder.graph = Some(graph);
+                return builder;
+            }
+        }
+        for (frame_id, embedding) in index.entries() {
+            if keep(frame_id) {
+                builder.add_document(frame_id, embedding.into_owned());
+            }
+        }
+        builder
+    }
+
+    /// Override the HNSW parameters used when the segment is large enough for a graph.
+    #[must_use]
+    pub fn with_hnsw(mut self, params: HnswParams) -> Self {
+        self.hnsw = Some(params);
+        self
+    }
+
+    /// Always emit a flat segment, e.g. for short-lived segments that get merged later.
+    #[must_use]
+    pub fn without_hnsw(mut self) -> Self {
+        self.hnsw = None;
+        self.discard_graph();
+        self
+    }
+
+    /// Distance metric recorded with the index. Switching metrics invalidates a carried graph.
+    #[must_use]
+    pub fn with_metric(mut self, metric: DistanceMetric) -> Self {
+        if metric != self.metric {
+            self.discard_graph();
+        }
+        self.metric = metric;
+        self
+    }
+
+    /// Drop the carried graph together with the tombstoned documents it was hiding.
+    fn discard_graph(&mut self) {
+        if let Some(graph) = self.graph.take() {
+            let mut node = 0;
+            self.documents.retain(|_| {
+                let live = !graph.is_deleted(node);
+                node += 1;
+                live
+            });
+        }
+    }
+
     pub fn add_document<I>(&mut self, frame_id: FrameId, embedding: I)
     where
         I: Into<Vec<f32>>,
@@ -37,15 +135,54 @@ impl VecIndexBuilder {
         });
     }
 
-    pub fn finish(self) -> Result<VecIndexArtifact> {
-        let bytes = bincode::serde::encode_to_vec(&self.documents, vec_config())?;
-
-        let checksum = *hash(&bytes).as_bytes();
+    pub fn finish(mut self) -> Result<VecIndexArtifact> {
         let dimension = self
             .documents
             .first()
             .map(|doc| doc.embedding.len() as u32)
             .unwrap_or(0);
+        let uniform_dimension = self
+            .documents
+            .windows(2)
+            .all(|pair| pair[0].embedding.len() == pair[1].embedding.len());
+
+        let reuse_graph = match (self.hnsw, &self.graph) {
+            (Some(params), Some(graph)) => uniform_dimension && graph.params() == params,
+            _ => false,
+        };
+        if !reuse_graph {
+            self.discard_graph();
+        }
+        let graph = match self.hnsw {
+            Some(params) if uniform_dimension => self.graph.take().or_else(|| {
+                (self.documents.len() >= HNSW_MIN_VECTORS).then(|| HnswGraph::new(params))
+            }),
+            _ => None,
+        };
+
+        let (bytes, vector_count) = match graph {
+            Some(mut graph) => {
+                graph.extend(&self.documents, self.metric.distance_fn());
+                let live = graph.len() - graph.deleted_count();
+                let bytes = crate::vec_mmap::encode(
+                    &self.documents,
+                    dimension,
+                    Some((self.metric, &graph)),
+                )?;
+                (bytes, live as u64)
+            }
+            // Mixed dimensions can't use the fixed-stride layout.
+            None if !uniform_dimension => (
+                bincode::serde::encode_to_vec(&self.documents, vec_config())?,
+                self.documents.len() as u64,
+            ),
+            None => (
+                crate::vec_mmap::encode(&self.documents, dimension, None)?,
+                self.documents.len() as u64,
+            ),
+        };
+
+        let checksum = *hash(&bytes).as_bytes();
         #[cfg(feature = "parallel_segments")]
         let bytes_uncompressed = self
             .documents
@@ -54,7 +191,7 @@ impl VecIndexBuilder {
             .sum::<usize>() as u64;
         Ok(VecIndexArtifact {
             bytes,
-            vector_count: self.documents.len() as u64,
+            vector_count,
             dimension,
             checksum,
             #[cfg(feature = "parallel_segments")]
@@ -75,8 +212,21 @@ pub struct VecIndexArtifact {
 
 #[derive(Debug, Clone)]
 pub enum VecIndex {
-    Uncompressed { documents: Vec<VecDocument> },
+    Uncompressed {
+        documents: Vec<VecDocument>,
+        metric: DistanceMetric,
+    },
     Compressed(crate::vec_pq::QuantizedVecIndex),
+    /// Int8 or binary codes, rescored against dequantized vectors.
+    Quantized(crate::vec_quant::ScalarQuantizedIndex),
+    /// Full-precision vectors navigated through an HNSW graph.
+    Graph {
+        documents: Vec<VecDocument>,
+        graph: HnswGraph,
+        metric: DistanceMetric,
+    },
+    /// Fixed-stride segment searched in place from a memory map.
+    Mapped(crate::vec_mmap::MappedVecIndex),
 }
 
 impl VecIndex {
@@ -96,6 +246,35 @@ impl VecIndex {
         bytes: &[u8],
         _compression: crate::VectorCompression,
     ) -> Result<Self> {
+        if bytes.starts_with(&crate::vec_mmap::FLAT_SEGMENT_MAGIC) {
+            let (documents, graph) = crate::vec_mmap::decode(bytes)?;
+            tracing::debug!(
+                bytes_len = bytes.len(),
+                docs_count = documents.len(),
+                has_graph = graph.is_some(),
+                "decoded as fixed-stride"
+            );
+            return Ok(match graph {
+                Some((metric, graph)) => Self::Graph {
+                    documents,
+                    graph,
+                    metric,
+                },
+                None => Self::Uncompressed {
+                    documents,
+                    metric: DistanceMetric::default(),
+                },
+            });
+        }
+        if bytes.starts_with(&crate::vec_quant::SCALAR_SEGMENT_MAGIC) {
+            let quantized = crate::vec_quant::ScalarQuantizedIndex::decode(bytes)?;
+            tracing::debug!(
+                bytes_len = bytes.len(),
+                docs_count = quantized.len(),
+                "decoded as scalar quantized"
+            );
+            return Ok(Self::Quantized(quantized));
+        }
         // Try uncompressed format first, regardless of compression flag.
         // This is necessary because MIN_VECTORS_FOR_PQ threshold (100 vectors)
         // causes most segments to be stored as uncompressed even when Pq96 is requested.
@@ -112,7 +291,10 @@ impl VecIndex {
                     docs_count = documents.len(),
                     "decoded as uncompressed"
                 );
-                return Ok(Self::Uncompressed { documents });
+                return Ok(Self::Uncompressed {
+                    documents,
+                    metric: DistanceMetric::default(),
+                });
             }
             Ok((_, read)) => {
                 tracing::debug!(
@@ -149,68 +331,321 @@ impl VecIndex {
         }
     }
 
+    /// Open the segment at `offset..offset + length` of a memory-mapped file.
+    ///
+    /// Fixed-stride segments are searched in place without decoding their vectors;
+    /// older encodings are decoded from the mapped bytes as usual.
+    pub fn map(
+        map: std::sync::Arc<memmap2::Mmap>,
+        offset: u64,
+        length: u64,
+        compression: crate::VectorCompression,
+    ) -> Result<Self> {
+        let range = usize::try_from(offset)
+            .ok()
+            .zip(usize::try_from(length).ok())
+            .and_then(|(offset, length)| Some(offset..offset.checked_add(length)?))
+            .filter(|range| range.end <= map.len())
+            .ok_or(MemvidError::InvalidToc {
+                reason: "mapped vector index out of bounds".into(),
+            })?;
+        if map[range.clone()].starts_with(&crate::vec_mmap::FLAT_SEGMENT_MAGIC) {
+            let mapped = crate::vec_mmap::MappedVecIndex::open(map, range.start, range.len())?;
+            return Ok(Self::Mapped(mapped));
+        }
+        Self::decode_with_compression(&map[range], compression)
+    }
+
+    /// Copy a memory-mapped index into memory so it stops reading from the file; other
+    /// encodings are already owned and returned unchanged.
+    #[must_use]
+    pub fn into_owned(self) -> Self {
+        match self {
+            VecIndex::Mapped(mapped) => mapped.to_owned_index(),
+            other => other,
+        }
+    }
+
+    /// Distance metric used to rank this index.
+    #[must_use]
+    pub fn metric(&self) -> DistanceMetric {
+        match self {
+            VecIndex::Uncompressed { metric, .. } | VecIndex::Graph { metric, .. } => *metric,
+            VecIndex::Compressed(quantized) => quantized.metric(),
+            VecIndex::Quantized(quantized) => quantized.metric(),
+            VecIndex::Mapped(mapped) => mapped.metric(),
+        }
+    }
+
+    /// Encoding of this index, as recorded in the manifest.
+    #[must_use]
+    pub fn compression(&self) -> crate::VectorCompression {
+        match self {
+            VecIndex::Uncompressed { .. } | VecIndex::Graph { .. } | VecIndex::Mapped(_) => {
+                crate::VectorCompression::None
+            }
+            VecIndex::Compressed(_) => crate::VectorCompression::Pq96,
+            VecIndex::Quantized(quantized) => quantized.kind().compression(),
+        }
+    }
+
+    /// Memory savings of a compressed index; `None` for full-precision indexes.
+    #[must_use]
+    pub fn compression_stats(&self) -> Option<crate::vec_pq::CompressionStats> {
+        match self {
+            VecIndex::Uncompressed { .. } | VecIndex::Graph { .. } | VecIndex::Mapped(_) => None,
+            VecIndex::Compressed(quantized) => Some(quantized.compression_stats()),
+            VecIndex::Quantized(quantized) => Some(quantized.compression_stats()),
+        }
+    }
+
+    /// Apply the metric recorded in the manifest.
+    ///
+    /// Flat and PQ encodings don't store a metric, so the loader supplies it. A graph built
+    /// under a different metric can't be navigated reliably and degrades to a flat scan.
+    #[must_use]
+    pub fn with_metric(self, metric: DistanceMetric) -> Self {
+        match self {
+            VecIndex::Uncompressed { documents, .. } => {
+                VecIndex::Uncompressed { documents, metric }
+            }
+            VecIndex::Compressed(quantized) => VecIndex::Compressed(quantized.with_metric(metric)),
+            VecIndex::Quantized(quantized) => VecIndex::Quantized(quantized.with_metric(metric)),
+            VecIndex::Mapped(mapped) => VecIndex::Mapped(mapped.with_metric(metric)),
+            VecIndex::Graph {
+                documents,
+                graph,
+                metric: built_with,
+            } => {
+                if built_with == metric {
+                    return VecIndex::Graph {
+                        documents,
+                        graph,
+                        metric,
+                    };
+                }
+                tracing::warn!(
+                    %built_with,
+                    %metric,
+                    "hnsw graph metric differs from manifest, falling back to flat scan"
+                );
+                let documents = documents
+                    .into_iter()
+                    .enumerate()
+                    .filter(|(node, _)| !graph.is_deleted(*node))
+                    .map(|(_, doc)| doc)
+                    .collect();
+                VecIndex::Uncompressed { documents, metric }
+            }
+        }
+    }
+
     pub fn search(&self, query: &[f32], limit: usize) -> Vec<VecSearchHit> {
-        if query.is_empty() {
+        self.search_with_ef(query, limit, HNSW_DEFAULT_EF_SEARCH)
+    }
+
+    /// Search with an explicit HNSW beam width.
+    ///
+    /// Larger `ef_search` values trade latency for recall; flat and PQ indexes ignore it.
+    pub fn search_with_ef(
+        &self,
+        query: &[f32],
+        limit: usize,
+        ef_search: usize,
+    ) -> Vec<VecSearchHit> {
+        self.search_filtered(query, limit, ef_search, &|_| true)
+    }
+
+    /// Search only among frames accepted by `accept`.
+    ///
+    /// The predicate is evaluated during the scan or graph walk, so `limit` is filled with
+    /// accepted hits; a selective filter widens the graph walk rather than the results.
+    pub fn search_filtered(
+        &self,
+        query: &[f32],
+        limit: usize,
+        ef_search: usize,
+        accept: &dyn Fn(FrameId) -> bool,
+    ) -> Vec<VecSearchHit> {
+        if query.is_empty() || limit == 0 {
             return Vec::new();
         }
         match self {
-            VecIndex::Uncompressed { documents } => {
-                let mut hits: Vec<VecSearchHit> = documents
-                    .iter()
-                    .map(|doc| {
-                        let distance = l2_distance(query, &doc.embedding);
-                        VecSearchHit {
-                            frame_id: doc.frame_id,
-                            distance,
-                        }
-                    })
-                    .collect();
-                hits.sort_by(|a, b| {
-                    a.distance
-                        .partial_cmp(&b.distance)
-                        .unwrap_or(std::cmp::Ordering::Equal)
-                });
-                hits.truncate(limit);
-                hits
+            VecIndex::Uncompressed { documents, metric } => scan_vectors(
+                document_entries(documents.iter()),
+                query,
+                limit,
+                *metric,
+                accept,
+            ),
+            VecIndex::Mapped(mapped) => mapped.search_filtered(query, limit, ef_search, accept),
+            VecIndex::Compressed(quantized) => quantized.search_filtered(query, limit, accept),
+            VecIndex::Quantized(quantized) => quantized.search_filtered(query, limit, accept),
+            VecIndex::Graph {
+                documents,
+                graph,
+                metric,
+            } => graph
+                .search(
+                    documents.as_slice(),
+                    query,
+                    limit,
+                    ef_search,
+                    metric.distance_fn(),
+                    &|node| accept(documents[node].frame_id),
+                )
+                .into_iter()
+                .map(|(node, distance)| {
+                    VecSearchHit::new(documents[node].frame_id, distance, *metric)
+                })
+                .collect(),
+        }
+    }
+
+    /// Fold `new_docs` into a PQ or scalar quantized index by encoding them with its
+    /// existing parameters, tombstoning frames rejected by `keep`. Returns `None` for
+    /// full-precision encodings, which are rebuilt through [`VecIndexBuilder::from_index`].
+    pub(crate) fn extend_compressed(
+        &self,
+        keep: impl Fn(FrameId) -> bool,
+        new_docs: &[(FrameId, Vec<f32>)],
+    ) -> Result<Option<(VecIndexArtifact, VecIndex)>> {
+        if let VecIndex::Quantized(quantized) = self {
+            let mut quantized = quantized.clone();
+            quantized.retain(keep);
+            for (frame_id, embedding) in new_docs {
+                quantized.insert(*frame_id, embedding)?;
             }
-            VecIndex::Compressed(quantized) => quantized.search(query, limit),
+            let artifact = quantized.encode()?;
+            let index = VecIndex::Quantized(
+                crate::vec_quant::ScalarQuantizedIndex::decode(&artifact.bytes)?
+                    .with_metric(quantized.metric()),
+            );
+            return Ok(Some((artifact, index)));
         }
+        let VecIndex::Compressed(quantized) = self else {
+            return Ok(None);
+        };
+        let mut quantized = quantized.clone();
+        quantized.retain(keep);
+        for (frame_id, embedding) in new_docs {
+            quantized.insert(*frame_id, embedding)?;
+        }
+        let encoded = quantized.encode()?;
+        let index = VecIndex::Compressed(
+            crate::vec_pq::QuantizedVecIndex::decode(&encoded.bytes)?
+                .with_metric(quantized.metric()),
+        );
+        let artifact = VecIndexArtifact {
+            #[cfg(feature = "parallel_segments")]
+            bytes_uncompressed: encoded.vector_count
+                * u64::from(encoded.dimension)
+                * std::mem::size_of::<f32>() as u64,
+            bytes: encoded.bytes,
+            vector_count: encoded.vector_count,
+            dimension: encoded.dimension,
+            checksum: encoded.checksum,
+        };
+        Ok(Some((artifact, index)))
     }
 
-    pub fn entries(&self) -> Box<dyn Iterator<Item = (FrameId, &[f32])> + '_> {
+    /// Encode the live vectors of `existing` accepted by `keep`, plus `new_docs`, as int8
+    /// or binary codes with freshly fitted parameters.
+    pub(crate) fn build_scalar_quantized(
+        existing: Option<&VecIndex>,
+        kind: crate::vec_quant::ScalarKind,
+        keep: impl Fn(FrameId) -> bool,
+        new_docs: &[(FrameId, Vec<f32>)],
+        metric: DistanceMetric,
+    ) -> Result<(VecIndexArtifact, VecIndex)> {
+        let mut documents: Vec<(FrameId, Vec<f32>)> = existing
+            .into_iter()
+            .flat_map(VecIndex::entries)
+            .filter(|(frame_id, _)| keep(*frame_id))
+            .map(|(frame_id, embedding)| (frame_id, embedding.into_owned()))
+            .collect();
+        documents.extend(new_docs.iter().cloned());
+        let artifact = crate::vec_quant::ScalarQuantizedIndex::build(kind, &documents)?.encode()?;
+        let index = VecIndex::Quantized(
+            crate::vec_quant::ScalarQuantizedIndex::decode(&artifact.bytes)?.with_metric(metric),
+        );
+        Ok((artifact, index))
+    }
+
+    /// Live vectors in the index. PQ and scalar quantized vectors are reconstructed
+    /// approximately from their codes.
+    #[must_use]
+    pub fn entries(&self) -> Box<dyn Iterator<Item = (FrameId, Cow<'_, [f32]>)> + '_> {
         match self {
-            VecIndex::Uncompressed { documents } => Box::new(
+            VecIndex::Uncompressed { documents, .. } => Box::new(
                 documents
                     .iter()
-                    .map(|doc| (doc.frame_id, doc.embedding.as_slice())),
+                    .map(|doc| (doc.frame_id, Cow::Borrowed(doc.embedding.as_slice()))),
+            ),
+            VecIndex::Compressed(quantized) => Box::new(
+                quantized
+                    .entries()
+                    .map(|(frame_id, embedding)| (frame_id, Cow::Owned(embedding))),
+            ),
+            VecIndex::Quantized(quantized) => Box::new(
+                quantized
+                    .entries()
+                    .map(|(frame_id, embedding)| (frame_id, Cow::Owned(embedding))),
+            ),
+            VecIndex::Mapped(mapped) => Box::new(
+                mapped
+                    .entries()
+                    .map(|(frame_id, embedding)| (frame_id, Cow::Borrowed(embedding))),
+            ),
+            VecIndex::Graph {
+                documents, graph, ..
+            } => Box::new(
+                documents
+                    .iter()
+                    .enumerate()
+                    .filter(|(node, _)| !graph.is_deleted(*node))
+                    .map(|(_, doc)| (doc.frame_id, Cow::Borrowed(doc.embedding.as_slice()))),
             ),
-            VecIndex::Compressed(_) => {
-                // Compressed vectors don't have direct f32 access
-                Box::new(std::iter::empty())
-            }
         }
     }
 
-    pub fn embedding_for(&self, frame_id: FrameId) -> Option<&[f32]> {
+    /// Vector stored for `frame_id`; approximate for PQ and scalar quantized indexes.
+    pub fn embedding_for(&self, frame_id: FrameId) -> Option<Cow<'_, [f32]>> {
         match self {
-            VecIndex::Uncompressed { documents } => documents
+            VecIndex::Uncompressed { documents, .. } => documents
                 .iter()
                 .find(|doc| doc.frame_id == frame_id)
-                .map(|doc| doc.embedding.as_slice()),
-            VecIndex::Compressed(_) => {
-                // Compressed vectors don't have direct f32 access
-                None
-            }
+                .map(|doc| Cow::Borrowed(doc.embedding.as_slice())),
+            VecIndex::Compressed(quantized) => quantized.embedding_for(frame_id).map(Cow::Owned),
+            VecIndex::Quantized(quantized) => quantized.embedding_for(frame_id).map(Cow::Owned),
+            VecIndex::Mapped(mapped) => mapped.embedding_for(frame_id).map(Cow::Borrowed),
+            VecIndex::Graph {
+                documents, graph, ..
+            } => documents
+                .iter()
+                .enumerate()
+                .find(|(node, doc)| doc.frame_id == frame_id && !graph.is_deleted(*node))
+                .map(|(_, doc)| Cow::Borrowed(doc.embedding.as_slice())),
         }
     }
 
     pub fn remove(&mut self, frame_id: FrameId) {
         match self {
-            VecIndex::Uncompressed { documents } => {
+            VecIndex::Uncompressed { documents, .. } => {
                 documents.retain(|doc| doc.frame_id != frame_id);
             }
-            VecIndex::Compressed(_quantized) => {
-                // Compressed indices are immutable
+            VecIndex::Compressed(quantized) => quantized.remove(frame_id),
+            VecIndex::Quantized(quantized) => quantized.remove(frame_id),
+            VecIndex::Mapped(mapped) => mapped.remove(frame_id),
+            VecIndex::Graph {
+                documents, graph, ..
+            } => {
+                // Keep the node for routing; the tombstone hides it from results.
+                for (node, doc) in documents.iter().enumerate() {
+                    if doc.frame_id == frame_id {
+                        graph.mark_deleted(node);
+                    }
+                }
             }
         }
     }
@@ -219,7 +654,77 @@ impl VecIndex {
 #[derive(Debug, Clone, PartialEq)]
 pub struct VecSearchHit {
     pub frame_id: FrameId,
+    /// Raw distance under the index metric (lower is closer).
     pub distance: f32,
+    /// Similarity derived from `distance` (higher is closer): cosine similarity,
+    /// inner product, or `1 / (1 + distance)` for L2.
+    pub similarity: f32,
+}
+
+fn document_entries<'a>(
+    documents: impl Iterator<Item = &'a VecDocument>,
+) -> impl Iterator<Item = (FrameId, &'a [f32])> {
+    documents.map(|doc| (doc.frame_id, doc.embedding.as_slice()))
+}
+
+/// Exhaustive scan over `(frame_id, vector)` pairs, keeping the `limit` closest accepted hits.
+pub(crate) fn scan_vectors<'a>(
+    entries: impl Iterator<Item = (FrameId, &'a [f32])>,
+    query: &[f32],
+    limit: usize,
+    metric: DistanceMetric,
+    accept: &dyn Fn(FrameId) -> bool,
+) -> Vec<VecSearchHit> {
+    let mut hits: Vec<VecSearchHit> = entries
+        .filter(|(frame_id, _)| accept(*frame_id))
+        .map(|(frame_id, embedding)| {
+            let distance = metric.distance(query, embedding);
+            VecSearchHit::new(frame_id, distance, metric)
+        })
+        .collect();
+    hits.sort_by(|a, b| {
+        a.distance
+            .partial_cmp(&b.distance)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    hits.truncate(limit);
+    hits
+}
+
+impl VecSearchHit {
+    pub(crate) fn new(frame_id: FrameId, distance: f32, metric: DistanceMetric) -> Self {
+        Self {
+            frame_id,
+            distance,
+            similarity: metric.similarity(distance),
+        }
+    }
+}
+
+impl DistanceMetric {
+    /// Distance between two vectors under this metric (lower is closer).
+    #[must_use]
+    pub fn distance(self, a: &[f32], b: &[f32]) -> f32 {
+        (self.distance_fn())(a, b)
+    }
+
+    /// Convert a distance produced by [`Self::distance`] into a similarity (higher is closer).
+    #[must_use]
+    pub fn similarity(self, distance: f32) -> f32 {
+        match self {
+            DistanceMetric::L2 => 1.0 / (1.0 + distance.max(0.0)),
+            DistanceMetric::Cosine => 1.0 - distance,
+            DistanceMetric::Dot => -distance,
+        }
+    }
+
+    pub(crate) fn distance_fn(self) -> DistanceFn {
+        match self {
+            DistanceMetric::L2 => l2_distance,
+            DistanceMetric::Cosine => cosine_distance,
+            DistanceMetric::Dot => dot_distance,
+        }
+    }
 }
 
 fn l2_distance(a: &[f32], b: &[f32]) -> f32 {
@@ -230,6 +735,25 @@ fn l2_distance(a: &[f32], b: &[f32]) -> f32 {
         .sqrt()
 }
 
+/// `1 - cos(a, b)`; zero vectors are treated as orthogonal to everything.
+fn cosine_distance(a: &[f32], b: &[f32]) -> f32 {
+    let (mut dot, mut norm_a, mut norm_b) = (0.0f32, 0.0f32, 0.0f32);
+    for (x, y) in a.iter().zip(b.iter()) {
+        dot += x * y;
+        norm_a += x * x;
+        norm_b += y * y;
+    }
+    if norm_a <= f32::EPSILON || norm_b <= f32::EPSILON {
+        return 1.0;
+    }
+    1.0 - dot / (norm_a.sqrt() * norm_b.sqrt())
+}
+
+/// Negated inner product so that lower still means closer.
+fn dot_distance(a: &[f32], b: &[f32]) -> f32 {
+    -a.iter().zip(b.iter()).map(|(x, y)| x * y).sum::<f32>()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -248,6 +772,154 @@ mod tests {
         assert_eq!(hits[0].frame_id, 1);
     }
 
+    #[test]
+    fn large_builder_emits_graph() {
+        let mut builder = VecIndexBuilder::new();
+        for id in 0..HNSW_MIN_VECTORS as u64 {
+            let x = id as f32;
+            builder.add_document(id, vec![x.sin(), x.cos(), (x * 0.5).sin()]);
+        }
+        let artifact = builder.finish().expect("finish");
+        assert_eq!(artifact.vector_count, HNSW_MIN_VECTORS as u64);
+        assert!(
+            artifact
+                .bytes
+                .starts_with(&crate::vec_mmap::FLAT_SEGMENT_MAGIC)
+        );
+
+        let mut index = VecIndex::decode(&artifact.bytes).expect("decode");
+        assert!(matches!(index, VecIndex::Graph { .. }));
+        let probe = [7f32.sin(), 7f32.cos(), 3.5f32.sin()];
+        assert_eq!(index.search(&probe, 1)[0].frame_id, 7);
+
+        index.remove(7);
+        assert!(index.embedding_for(7).is_none());
+        assert_ne!(index.search(&probe, 1)[0].frame_id, 7);
+
+        // Reseeding keeps the graph and only links the new vector.
+        let mut builder = VecIndexBuilder::from_index(&index, |_| true);
+        builder.add_document(5_000, vec![1.0, 1.0, 1.0]);
+        let artifact = builder.finish().expect("finish");
+        assert_eq!(artifact.vector_count, HNSW_MIN_VECTORS as u64);
+        let index = VecIndex::decode(&artifact.bytes).expect("decode");
+        assert_eq!(index.search(&[1.0, 1.0, 1.0], 1)[0].frame_id, 5_000);
+    }
+
+    #[test]
+    fn reseeding_a_flat_segment_builds_the_graph() {
+        let mut builder = VecIndexBuilder::new().without_hnsw();
+        for id in 0..HNSW_MIN_VECTORS as u64 {
+            let x = id as f32;
+            builder.add_document(id, vec![x.sin(), x.cos(), (x * 0.5).sin()]);
+        }
+        let flat = VecIndex::decode(&builder.finish().expect("finish").bytes).expect("decode");
+        assert!(matches!(flat, VecIndex::Uncompressed { .. }));
+
+        let artifact = VecIndexBuilder::from_index(&flat, |_| true)
+            .finish()
+            .expect("finish");
+        let index = VecIndex::decode(&artifact.bytes).expect("decode");
+        assert!(matches!(index, VecIndex::Graph { .. }));
+        let probe = [7f32.sin(), 7f32.cos(), 3.5f32.sin()];
+        assert_eq!(index.search(&probe, 1)[0].frame_id, 7);
+    }
+
+    #[test]
+    fn compressed_index_reconstructs_and_extends() {
+        let dim = 384;
+        let training: Vec<Vec<f32>> = (0..40)
+            .map(|i| (0..dim).map(|j| ((i * 7 + j) % 13) as f32).collect())
+            .collect();
+        let mut builder = crate::vec_pq::QuantizedVecIndexBuilder::new();
+        builder.train_quantizer(&training, dim as u32).unwrap();
+        for (id, vector) in training.iter().take(4).enumerate() {
+            builder.add_document(id as FrameId, vector.clone()).unwrap();
+        }
+        let artifact = builder.finish().unwrap();
+        let mut index = VecIndex::decode(&artifact.bytes).unwrap();
+        assert!(matches!(index, VecIndex::Compressed(_)));
+        assert_eq!(index.entries().count(), 4);
+        assert_eq!(index.embedding_for(2).map(|e| e.len()), Some(dim));
+
+        index.remove(2);
+        assert!(index.embedding_for(2).is_none());
+        assert_eq!(index.entries().count(), 3);
+
+        // New vectors are encoded against the trained codebook; rejected frames are dropped.
+        let (artifact, index) = index
+            .extend_compressed(|frame_id| frame_id != 0, &[(9, training[20].clone())])
+            .unwrap()
+            .expect("compressed index");
+        assert_eq!(artifact.vector_count, 3);
+        assert!(matches!(index, VecIndex::Compressed(_)));
+        assert_eq!(index.search(&training[20], 1)[0].frame_id, 9);
+        assert!(index.embedding_for(0).is_none());
+    }
+
+    #[test]
+    fn filtered_graph_search_fills_limit() {
+        let mut builder = VecIndexBuilder::new();
+        for id in 0..HNSW_MIN_VECTORS as u64 {
+            let x = id as f32;
+            builder.add_document(id, vec![x.sin(), x.cos(), (x * 0.5).sin()]);
+        }
+        let artifact = builder.finish().expect("finish");
+        let index = VecIndex::decode(&artifact.bytes).expect("decode");
+
+        // Only every 97th frame is in scope; all of them must come back.
+        let accept = |frame_id: FrameId| frame_id % 97 == 0;
+        let hits = index.search_filtered(&[1.0, 0.0, 0.0], 20, 16, &accept);
+        let expected = (0..HNSW_MIN_VECTORS as u64)
+            .filter(|id| accept(*id))
+            .count();
+        assert_eq!(hits.len(), expected);
+        assert!(hits.iter().all(|hit| accept(hit.frame_id)));
+        assert!(
+            hits.windows(2)
+                .all(|pair| pair[0].distance <= pair[1].distance)
+        );
+    }
+
+    #[test]
+    fn small_builder_stays_flat() {
+        let mut builder = VecIndexBuilder::new();
+        builder.add_document(1, vec![0.0, 1.0]);
+        let artifact = builder.finish().expect("finish");
+        let index = VecIndex::decode(&artifact.bytes).expect("decode");
+        assert!(matches!(index, VecIndex::Uncompressed { .. }));
+    }
+
+    #[test]
+    fn metric_changes_ranking_and_similarity() {
+        let mut builder = VecIndexBuilder::new().with_metric(DistanceMetric::Cosine);
+        builder.add_document(1, vec![10.0, 0.0]);
+        builder.add_document(2, vec![0.9, 0.1]);
+        builder.add_document(3, vec![0.0, 1.0]);
+        let artifact = builder.finish().expect("finish");
+        let index = VecIndex::decode(&artifact.bytes)
+            .expect("decode")
+            .with_metric(DistanceMetric::Cosine);
+
+        let hits = index.search(&[1.0, 0.0], 3);
+        assert_eq!(hits[0].frame_id, 1);
+        assert!((hits[0].similarity - 1.0).abs() < 1e-6);
+        assert!(hits[2].similarity.abs() < 1e-6);
+
+        // Under L2 the short vector wins and similarity stays within (0, 1].
+        let index = index.with_metric(DistanceMetric::L2);
+        let hits = index.search(&[1.0, 0.0], 3);
+        assert_eq!(hits[0].frame_id, 2);
+        assert!(
+            hits.iter()
+                .all(|hit| hit.similarity > 0.0 && hit.similarity <= 1.0)
+        );
+
+        let dot = index.with_metric(DistanceMetric::Dot);
+        let hits = dot.search(&[1.0, 0.0], 1);
+        assert_eq!(hits[0].frame_id, 1);
+        assert!((hits[0].similarity - 10.0).abs() < 1e-6);
+    }
+
     #[test]
     fn l2_distance_behaves() {
         let d = l2_distance(&[0.0, 0.0], &[3.0, 4.0]);
@@ -0,0 +1,584 @@
+//! Hierarchical Navigable Small World (HNSW) graph for approximate nearest neighbours
+//!
+//! Replaces the linear scan in [`crate::vec::VecIndex`] once a segment holds enough
+//! vectors for graph traversal to pay off. The graph only stores adjacency lists; the
+//! full-precision embeddings stay in the owning index and are addressed by node id
+//! (the position of the document in the index).
+//!
+//! **Algorithm** (Malkov & Yashunin, 2016):
+//! 1. Each node is assigned a top layer drawn from an exponential distribution
+//! 2. Inserts greedily descend from the entry point to the node's top layer
+//! 3. On every layer below, the `ef_construction` closest candidates are linked (M per layer, 2M on layer 0)
+//! 4. Queries descend greedily, then run a best-first search with `ef_search` candidates on layer 0
+//!
+//! Layer assignment hashes the frame id instead of drawing from an RNG so that the same
+//! inserts always produce byte-identical graphs.
+
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BinaryHeap, HashSet};
+
+use serde::{Deserialize, Serialize};
+
+use crate::types::FrameId;
+use crate::vec::VecDocument;
+
+/// Default number of bi-directional links per node on upper layers (layer 0 keeps 2M).
+pub const HNSW_DEFAULT_M: u32 = 16;
+/// Default candidate list size used while inserting.
+pub const HNSW_DEFAULT_EF_CONSTRUCTION: u32 = 200;
+/// Default candidate list size used while searching.
+pub const HNSW_DEFAULT_EF_SEARCH: usize = 64;
+/// Segments smaller than this are searched with a brute-force scan instead of a graph.
+pub const HNSW_MIN_VECTORS: usize = 1_024;
+
+/// Hard cap on layer count; with M=16 reaching layer 8 needs ~4 billion nodes.
+const MAX_LEVEL: u32 = 16;
+
+/// Distance function used to order candidates (lower is closer).
+pub type DistanceFn = fn(&[f32], &[f32]) -> f32;
+
+/// Vectors addressed by node id during a graph search.
+///
+/// Implemented for in-memory documents and for memory-mapped vector segments, so a
+/// persisted graph can be searched without materializing its embeddings.
+pub trait VectorStore {
+    /// Number of nodes with a stored vector.
+    fn node_count(&self) -> usize;
+
+    /// Full-precision vector of `node`.
+    fn vector(&self, node: usize) -> &[f32];
+}
+
+impl VectorStore for [VecDocument] {
+    fn node_count(&self) -> usize {
+        self.len()
+    }
+
+    fn vector(&self, node: usize) -> &[f32] {
+        &self[node].embedding
+    }
+}
+
+/// Tunable HNSW construction parameters, persisted alongside the graph.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct HnswParams {
+    /// Maximum links per node on layers above 0.
+    pub m: u32,
+    /// Candidate list size used while inserting.
+    pub ef_construction: u32,
+}
+
+impl Default for HnswParams {
+    fn default() -> Self {
+        Self {
+            m: HNSW_DEFAULT_M,
+            ef_construction: HNSW_DEFAULT_EF_CONSTRUCTION,
+        }
+    }
+}
+
+impl HnswParams {
+    fn max_links(self, level: usize) -> usize {
+        let m = self.m.max(2) as usize;
+        if level == 0 { m * 2 } else { m }
+    }
+
+    fn level_multiplier(self) -> f64 {
+        1.0 / f64::from(self.m.max(2)).ln()
+    }
+}
+
+/// Candidate ordered by distance, ties broken by node id for determinism.
+#[derive(Debug, Clone, Copy)]
+struct Candidate {
+    distance: f32,
+    node: u32,
+}
+
+impl PartialEq for Candidate {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Candidate {}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Candidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.distance
+            .total_cmp(&other.distance)
+            .then(self.node.cmp(&other.node))
+    }
+}
+
+/// Layered proximity graph over the documents of a vector index.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HnswGraph {
+    params: HnswParams,
+    entry_point: Option<u32>,
+    /// `links[node][level]` lists the neighbours of `node` on `level`.
+    links: Vec<Vec<Vec<u32>>>,
+    /// Tombstones: deleted nodes keep routing queries but are never returned.
+    deleted: Vec<bool>,
+}
+
+impl HnswGraph {
+    #[must_use]
+    pub fn new(params: HnswParams) -> Self {
+        Self {
+            params,
+            entry_point: None,
+            links: Vec::new(),
+            deleted: Vec::new(),
+        }
+    }
+
+    /// Build a graph over all documents in insertion order.
+    #[must_use]
+    pub fn build(documents: &[VecDocument], params: HnswParams, distance: DistanceFn) -> Self {
+        let mut graph = Self::new(params);
+        graph.extend(documents, distance);
+        graph
+    }
+
+    #[must_use]
+    pub fn params(&self) -> HnswParams {
+        self.params
+    }
+
+    /// Number of nodes in the graph, including tombstoned ones.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.links.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.links.is_empty()
+    }
+
+    /// Number of tombstoned nodes.
+    #[must_use]
+    pub fn deleted_count(&self) -> usize {
+        self.deleted.iter().filter(|deleted| **deleted).count()
+    }
+
+    #[must_use]
+    pub fn is_deleted(&self, node: usize) -> bool {
+        self.deleted.get(node).copied().unwrap_or(true)
+    }
+
+    /// Tombstone a node so it no longer appears in results.
+    pub fn mark_deleted(&mut self, node: usize) {
+        if let Some(flag) = self.deleted.get_mut(node) {
+            *flag = true;
+        }
+    }
+
+    /// Insert every document that is not yet part of the graph.
+    pub fn extend(&mut self, documents: &[VecDocument], distance: DistanceFn) {
+        for node in self.links.len()..documents.len() {
+            self.insert(documents, node, distance);
+        }
+    }
+
+    fn insert(&mut self, documents: &[VecDocument], node: usize, distance: DistanceFn) {
+        debug_assert_eq!(node, self.links.len());
+        let Ok(node_id) = u32::try_from(node) else {
+            return;
+        };
+        let level = assign_level(documents[node].frame_id, self.params.level_multiplier());
+        self.links.push(vec![Vec::new(); level + 1]);
+        self.deleted.push(false);
+
+        let Some(entry) = self.entry_point else {
+            self.entry_point = Some(node_id);
+            return;
+        };
+
+        let query = documents[node].embedding.as_slice();
+        let entry_level = self.node_level(entry);
+        let mut entry_points = vec![Candidate {
+            distance: distance(query, &documents[entry as usize].embedding),
+            node: entry,
+        }];
+
+        for layer in (level + 1..=entry_level).rev() {
+            entry_points = self.search_layer(
+                documents,
+                query,
+                &entry_points,
+                1,
+                layer,
+                distance,
+                &accept_all,
+            );
+        }
+
+        let ef_construction = self.params.ef_construction.max(1) as usize;
+        for layer in (0..=level.min(entry_level)).rev() {
+            let candidates = self.search_layer(
+                documents,
+                query,
+                &entry_points,
+                ef_construction,
+                layer,
+                distance,
+                &accept_all,
+            );
+            let max_links = self.params.max_links(layer);
+            let neighbours = select_neighbours(
+                documents,
+                candidates
+                    .iter()
+                    .copied()
+                    .filter(|candidate| candidate.node != node_id),
+                max_links,
+                distance,
+            );
+            self.links[node][layer].clone_from(&neighbours);
+            for neighbour in neighbours {
+                self.connect(documents, neighbour, node_id, layer, distance);
+            }
+            entry_points = candidates;
+        }
+
+        if level > entry_level {
+            self.entry_point = Some(node_id);
+        }
+    }
+
+    /// Add a back-link from `from` to `to`, re-selecting neighbours when the list is full.
+    fn connect(
+        &mut self,
+        documents: &[VecDocument],
+        from: u32,
+        to: u32,
+        layer: usize,
+        distance: DistanceFn,
+    ) {
+        let max_links = self.params.max_links(layer);
+        let Some(list) = self.links[from as usize].get_mut(layer) else {
+            return;
+        };
+        list.push(to);
+        if list.len() <= max_links {
+            return;
+        }
+        let origin = documents[from as usize].embedding.as_slice();
+        let mut scored: Vec<Candidate> = list
+            .iter()
+            .map(|&node| Candidate {
+                distance: distance(origin, &documents[node as usize].embedding),
+                node,
+            })
+            .collect();
+        scored.sort();
+        *list = select_neighbours(documents, scored.into_iter(), max_links, distance);
+    }
+
+    fn node_level(&self, node: u32) -> usize {
+        self.links[node as usize].len().saturating_sub(1)
+    }
+
+    /// Best-first search restricted to one layer. Returns up to `ef` candidates accepted
+    /// by `accept`, sorted by distance.
+    ///
+    /// Rejected nodes are still walked through, so a selective filter widens the walk
+    /// until `ef` accepted nodes are found or the layer is exhausted.
+    #[allow(clippy::too_many_arguments)]
+    fn search_layer<S: VectorStore + ?Sized>(
+        &self,
+        documents: &S,
+        query: &[f32],
+        entry_points: &[Candidate],
+        ef: usize,
+        layer: usize,
+        distance: DistanceFn,
+        accept: &dyn Fn(usize) -> bool,
+    ) -> Vec<Candidate> {
+        let mut visited: HashSet<u32> = entry_points.iter().map(|c| c.node).collect();
+        let mut frontier: BinaryHeap<Reverse<Candidate>> =
+            entry_points.iter().copied().map(Reverse).collect();
+        let mut nearest: BinaryHeap<Candidate> = entry_points
+            .iter()
+            .copied()
+            .filter(|candidate| accept(candidate.node as usize))
+            .collect();
+        while nearest.len() > ef {
+            nearest.pop();
+        }
+
+        while let Some(Reverse(current)) = frontier.pop() {
+            if let Some(worst) = nearest.peek() {
+                if nearest.len() >= ef && current.distance > worst.distance {
+                    break;
+                }
+            }
+            let Some(neighbours) = self.links[current.node as usize].get(layer) else {
+                continue;
+            };
+            for &neighbour in neighbours {
+                if !visited.insert(neighbour) {
+                    continue;
+                }
+                let candidate = Candidate {
+                    distance: distance(query, documents.vector(neighbour as usize)),
+                    node: neighbour,
+                };
+                let admit = nearest.len() < ef
+                    || nearest
+                        .peek()
+                        .is_some_and(|worst| candidate.distance < worst.distance);
+                if admit {
+                    frontier.push(Reverse(candidate));
+                    if accept(neighbour as usize) {
+                        nearest.push(candidate);
+                        if nearest.len() > ef {
+                            nearest.pop();
+                        }
+                    }
+                }
+            }
+        }
+
+        nearest.into_sorted_vec()
+    }
+
+    /// Approximate k-nearest-neighbour search.
+    ///
+    /// Returns `(node, distance)` pairs sorted by distance, skipping tombstoned nodes
+    /// and nodes rejected by `accept`.
+    pub fn search<S: VectorStore + ?Sized>(
+        &self,
+        documents: &S,
+        query: &[f32],
+        limit: usize,
+        ef_search: usize,
+        distance: DistanceFn,
+        accept: &dyn Fn(usize) -> bool,
+    ) -> Vec<(usize, f32)> {
+        let Some(entry) = self.entry_point else {
+            return Vec::new();
+        };
+        if limit == 0 || documents.node_count() < self.links.len() {
+            return Vec::new();
+        }
+
+        let mut entry_points = vec![Candidate {
+            distance: distance(query, documents.vector(entry as usize)),
+            node: entry,
+        }];
+        for layer in (1..=self.node_level(entry)).rev() {
+            entry_points = self.search_layer(
+                documents,
+                query,
+                &entry_points,
+                1,
+                layer,
+                distance,
+                &accept_all,
+            );
+        }
+
+        // Tombstones and filtered nodes are walked through but never returned.
+        self.search_layer(
+            documents,
+            query,
+            &entry_points,
+            ef_search.max(limit),
+            0,
+            distance,
+            &|node| !self.is_deleted(node) && accept(node),
+        )
+        .into_iter()
+        .take(limit)
+        .map(|candidate| (candidate.node as usize, candidate.distance))
+        .collect()
+    }
+}
+
+/// Layer filter for graph construction and the upper-layer descent.
+fn accept_all(_node: usize) -> bool {
+    true
+}
+
+/// Neighbour selection heuristic (Algorithm 4 of the HNSW paper).
+///
+/// Walking candidates from closest to farthest, a candidate is kept only if it is closer
+/// to the base node than to every neighbour already kept. This spreads links across
+/// directions so clustered or low-dimensional data stays connected. Remaining slots are
+/// filled with the closest pruned candidates.
+fn select_neighbours(
+    documents: &[VecDocument],
+    sorted_candidates: impl Iterator<Item = Candidate>,
+    max_links: usize,
+    distance: DistanceFn,
+) -> Vec<u32> {
+    let mut selected: Vec<u32> = Vec::with_capacity(max_links);
+    let mut pruned: Vec<u32> = Vec::new();
+    for candidate in sorted_candidates {
+        if selected.len() >= max_links {
+            break;
+        }
+        let embedding = &documents[candidate.node as usize].embedding;
+        let diverse = selected.iter().all(|&kept| {
+            distance(embedding, &documents[kept as usize].embedding) > candidate.distance
+        });
+        if diverse {
+            selected.push(candidate.node);
+        } else {
+            pruned.push(candidate.node);
+        }
+    }
+    let missing = max_links.saturating_sub(selected.len());
+    selected.extend(pruned.into_iter().take(missing));
+    selected
+}
+
+/// Deterministic exponential layer assignment derived from the frame id.
+fn assign_level(frame_id: FrameId, multiplier: f64) -> usize {
+    let digest = blake3::hash(&frame_id.to_le_bytes());
+    let mut raw = [0u8; 4];
+    raw.copy_from_slice(&digest.as_bytes()[..4]);
+    // 32 random bits mapped into (0, 1].
+    let uniform = (f64::from(u32::from_le_bytes(raw)) + 1.0) / 4_294_967_296.0;
+    let height = -uniform.ln() * multiplier;
+    (1..=MAX_LEVEL)
+        .take_while(|&level| f64::from(level) <= height)
+        .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn l2(a: &[f32], b: &[f32]) -> f32 {
+        a.iter()
+            .zip(b.iter())
+            .map(|(x, y)| (x - y).powi(2))
+            .sum::<f32>()
+            .sqrt()
+    }
+
+    fn sample_documents(count: usize, dim: usize) -> Vec<VecDocument> {
+        let mut seed = 0x9E37_79B9_7F4A_7C15_u64;
+        (0..count)
+            .map(|idx| {
+                let embedding = (0..dim)
+                    .map(|_| {
+                        seed ^= seed << 13;
+                        seed ^= seed >> 7;
+                        seed ^= seed << 17;
+                        (seed % 10_000) as f32 / 10_000.0
+                    })
+                    .collect();
+                VecDocument {
+                    frame_id: idx as FrameId,
+                    embedding,
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn graph_finds_exact_match() {
+        let documents = sample_documents(500, 16);
+        let graph = HnswGraph::build(&documents, HnswParams::default(), l2);
+        assert_eq!(graph.len(), 500);
+
+        for probe in [0usize, 137, 499] {
+            let hits = graph.search(
+                documents.as_slice(),
+                &documents[probe].embedding,
+                5,
+                64,
+                l2,
+                &|_| true,
+            );
+            assert_eq!(hits[0].0, probe);
+            assert!(hits[0].1 < 1e-6);
+        }
+    }
+
+    #[test]
+    fn graph_recall_matches_brute_force() {
+        let documents = sample_documents(800, 24);
+        let graph = HnswGraph::build(&documents, HnswParams::default(), l2);
+        let queries = sample_documents(20, 24);
+
+        let mut found = 0usize;
+        for query in &queries {
+            let mut exact: Vec<(usize, f32)> = documents
+                .iter()
+                .enumerate()
+                .map(|(idx, doc)| (idx, l2(&query.embedding, &doc.embedding)))
+                .collect();
+            exact.sort_by(|a, b| a.1.total_cmp(&b.1));
+            let expected: HashSet<usize> = exact.iter().take(10).map(|(idx, _)| *idx).collect();
+            let approx = graph.search(documents.as_slice(), &query.embedding, 10, 64, l2, &|_| {
+                true
+            });
+            found += approx
+                .iter()
+                .filter(|(idx, _)| expected.contains(idx))
+                .count();
+        }
+        let recall = found as f32 / (queries.len() * 10) as f32;
+        assert!(recall > 0.9, "recall too low: {recall}");
+    }
+
+    #[test]
+    fn selective_filters_are_applied_during_the_walk() {
+        let documents = sample_documents(400, 8);
+        let graph = HnswGraph::build(&documents, HnswParams::default(), l2);
+        let accept = |node: usize| node % 97 == 5;
+        let hits = graph.search(
+            documents.as_slice(),
+            &documents[0].embedding,
+            10,
+            16,
+            l2,
+            &accept,
+        );
+        let nodes: Vec<usize> = hits.iter().map(|(node, _)| *node).collect();
+        let mut expected: Vec<usize> = (0..documents.len()).filter(|node| accept(*node)).collect();
+        expected.sort_by(|a, b| {
+            l2(&documents[0].embedding, &documents[*a].embedding)
+                .total_cmp(&l2(&documents[0].embedding, &documents[*b].embedding))
+        });
+        assert_eq!(nodes, expected);
+    }
+
+    #[test]
+    fn tombstoned_nodes_are_skipped() {
+        let documents = sample_documents(200, 8);
+        let mut graph = HnswGraph::build(&documents, HnswParams::default(), l2);
+        graph.mark_deleted(42);
+        let hits = graph.search(
+            documents.as_slice(),
+            &documents[42].embedding,
+            3,
+            32,
+            l2,
+            &|_| true,
+        );
+        assert!(hits.iter().all(|(idx, _)| *idx != 42));
+        assert_eq!(graph.deleted_count(), 1);
+    }
+
+    #[test]
+    fn build_is_deterministic() {
+        let documents = sample_documents(300, 8);
+        let a = HnswGraph::build(&documents, HnswParams::default(), l2);
+        let b = HnswGraph::build(&documents, HnswParams::default(), l2);
+        assert_eq!(a.links, b.links);
+        assert_eq!(a.entry_point, b.entry_point);
+    }
+}
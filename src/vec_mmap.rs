@@ -0,0 +1,577 @@
+//! Fixed-stride vector segment layout that can be searched straight from a memory map.
+//!
+//! Bincode-encoded segments have to be decoded into `Vec<VecDocument>` before the first
+//! query, which dominates cold-start time for large memories. This layout stores frame
+//! ids and vectors as flat little-endian arrays at fixed offsets, so a read-only open can
+//! map the `.mv2` file and address vector `n` directly:
+//!
+//! ```text
+//! 0   magic "MVVF"
+//! 4   version      u16
+//! 6   flags        u16   (bit 0: HNSW graph present)
+//! 8   dimension    u32
+//! 12  reserved     u32
+//! 16  count        u64
+//! 24  graph_len    u64
+//! 32  reserved     [u8; 32]
+//! 64  frame ids    count * u64
+//! ..  vectors      count * dimension * f32
+//! ..  graph        bincode (DistanceMetric, HnswGraph), graph_len bytes
+//! ```
+//!
+//! The vector section starts at an 8-byte boundary relative to the segment, so vectors
+//! are borrowed from the map whenever the segment itself is written 8-byte aligned
+//! (see [`VECTOR_SEGMENT_ALIGN`]). Misaligned segments are copied once instead.
+//! Only the graph adjacency lists are decoded on open.
+
+use std::collections::{HashMap, HashSet};
+use std::ops::Range;
+use std::sync::{Arc, OnceLock};
+
+use memmap2::Mmap;
+
+use crate::vec::{VecDocument, VecIndex, VecSearchHit, scan_vectors};
+use crate::vec_hnsw::{HnswGraph, VectorStore};
+use crate::{
+    MemvidError, Result,
+    types::{DistanceMetric, FrameId},
+};
+
+fn vec_config() -> impl bincode::config::Config {
+    bincode::config::standard()
+        .with_fixed_int_encoding()
+        .with_little_endian()
+}
+
+const VEC_DECODE_LIMIT: usize = crate::MAX_INDEX_BYTES as usize;
+
+/// Prefix identifying a fixed-stride vector segment.
+pub(crate) const FLAT_SEGMENT_MAGIC: [u8; 4] = *b"MVVF";
+
+/// File offset alignment writers use so mapped vectors can be borrowed as `&[f32]`.
+pub const VECTOR_SEGMENT_ALIGN: u64 = 8;
+
+const LAYOUT_VERSION: u16 = 1;
+const HEADER_LEN: usize = 64;
+const FLAG_GRAPH: u16 = 1;
+
+const ID_BYTES: usize = std::mem::size_of::<u64>();
+const FLOAT_BYTES: usize = std::mem::size_of::<f32>();
+
+/// Zero bytes that move a write at `offset` up to the next [`VECTOR_SEGMENT_ALIGN`] boundary.
+pub(crate) fn alignment_padding(offset: u64) -> &'static [u8] {
+    const ZEROS: [u8; VECTOR_SEGMENT_ALIGN as usize] = [0; VECTOR_SEGMENT_ALIGN as usize];
+    let padding = offset.next_multiple_of(VECTOR_SEGMENT_ALIGN) - offset;
+    &ZEROS[..usize::try_from(padding).unwrap_or(0)]
+}
+
+/// Encode `documents`, which must share `dimension`, with an optional graph over them.
+pub(crate) fn encode(
+    documents: &[VecDocument],
+    dimension: u32,
+    graph: Option<(DistanceMetric, &HnswGraph)>,
+) -> Result<Vec<u8>> {
+    let graph_bytes = match graph {
+        Some(graph) => bincode::serde::encode_to_vec(graph, vec_config())?,
+        None => Vec::new(),
+    };
+    let flags = if graph.is_some() { FLAG_GRAPH } else { 0 };
+    let vector_bytes = documents.len() * dimension as usize * FLOAT_BYTES;
+
+    let mut bytes = Vec::with_capacity(
+        HEADER_LEN + documents.len() * ID_BYTES + vector_bytes + graph_bytes.len(),
+    );
+    bytes.extend_from_slice(&FLAT_SEGMENT_MAGIC);
+    bytes.extend_from_slice(&LAYOUT_VERSION.to_le_bytes());
+    bytes.extend_from_slice(&flags.to_le_bytes());
+    bytes.extend_from_slice(&dimension.to_le_bytes());
+    bytes.extend_from_slice(&0u32.to_le_bytes());
+    bytes.extend_from_slice(&(documents.len() as u64).to_le_bytes());
+    bytes.extend_from_slice(&(graph_bytes.len() as u64).to_le_bytes());
+    bytes.resize(HEADER_LEN, 0);
+
+    for doc in documents {
+        bytes.extend_from_slice(&doc.frame_id.to_le_bytes());
+    }
+    for doc in documents {
+        if doc.embedding.len() != dimension as usize {
+            return Err(MemvidError::VecDimensionMismatch {
+                expected: dimension,
+                actual: doc.embedding.len(),
+            });
+        }
+        for value in &doc.embedding {
+            bytes.extend_from_slice(&value.to_le_bytes());
+        }
+    }
+    bytes.extend_from_slice(&graph_bytes);
+    Ok(bytes)
+}
+
+/// Section boundaries of an encoded segment, relative to its first byte.
+struct Layout {
+    dimension: usize,
+    count: usize,
+    ids: Range<usize>,
+    vectors: Range<usize>,
+    graph: Option<Range<usize>>,
+}
+
+fn invalid(reason: &'static str) -> MemvidError {
+    MemvidError::InvalidToc {
+        reason: reason.into(),
+    }
+}
+
+fn read_u16(bytes: &[u8], at: usize) -> u16 {
+    u16::from_le_bytes([bytes[at], bytes[at + 1]])
+}
+
+fn read_u32(bytes: &[u8], at: usize) -> u32 {
+    let mut raw = [0u8; 4];
+    raw.copy_from_slice(&bytes[at..at + 4]);
+    u32::from_le_bytes(raw)
+}
+
+fn read_u64(bytes: &[u8], at: usize) -> u64 {
+    let mut raw = [0u8; 8];
+    raw.copy_from_slice(&bytes[at..at + 8]);
+    u64::from_le_bytes(raw)
+}
+
+fn parse_layout(bytes: &[u8]) -> Result<Layout> {
+    if bytes.len() < HEADER_LEN || !bytes.starts_with(&FLAT_SEGMENT_MAGIC) {
+        return Err(invalid("fixed-stride vector segment header truncated"));
+    }
+    if read_u16(bytes, 4) != LAYOUT_VERSION {
+        return Err(invalid("unsupported fixed-stride vector segment version"));
+    }
+    let flags = read_u16(bytes, 6);
+    let dimension = read_u32(bytes, 8) as usize;
+    let count = usize::try_from(read_u64(bytes, 16))
+        .map_err(|_| invalid("fixed-stride vector count overflow"))?;
+    let graph_len = usize::try_from(read_u64(bytes, 24))
+        .map_err(|_| invalid("fixed-stride graph length overflow"))?;
+
+    let ids_end = count
+        .checked_mul(ID_BYTES)
+        .and_then(|len| len.checked_add(HEADER_LEN))
+        .ok_or_else(|| invalid("fixed-stride vector count overflow"))?;
+    let vectors_end = count
+        .checked_mul(dimension)
+        .and_then(|floats| floats.checked_mul(FLOAT_BYTES))
+        .and_then(|len| len.checked_add(ids_end))
+        .ok_or_else(|| invalid("fixed-stride vector count overflow"))?;
+    let graph_end = vectors_end
+        .checked_add(graph_len)
+        .ok_or_else(|| invalid("fixed-stride graph length overflow"))?;
+    if graph_end != bytes.len() {
+        return Err(invalid("fixed-stride vector segment length mismatch"));
+    }
+    let graph = (flags & FLAG_GRAPH != 0).then_some(vectors_end..graph_end);
+    Ok(Layout {
+        dimension,
+        count,
+        ids: HEADER_LEN..ids_end,
+        vectors: ids_end..vectors_end,
+        graph,
+    })
+}
+
+fn decode_graph(bytes: &[u8], count: usize) -> Result<(DistanceMetric, HnswGraph)> {
+    let ((metric, graph), read) =
+        bincode::serde::decode_from_slice::<(DistanceMetric, HnswGraph), _>(
+            bytes,
+            bincode::config::standard()
+                .with_fixed_int_encoding()
+                .with_little_endian()
+                .with_limit::<VEC_DECODE_LIMIT>(),
+        )?;
+    if read != bytes.len() || graph.len() != count {
+        return Err(invalid("hnsw vector index length mismatch"));
+    }
+    Ok((metric, graph))
+}
+
+/// Decode a segment into owned documents plus its graph, if any.
+pub(crate) fn decode(
+    bytes: &[u8],
+) -> Result<(Vec<VecDocument>, Option<(DistanceMetric, HnswGraph)>)> {
+    let layout = parse_layout(bytes)?;
+    let vectors = &bytes[layout.vectors.clone()];
+    let documents = bytes[layout.ids.clone()]
+        .chunks_exact(ID_BYTES)
+        .enumerate()
+        .map(|(node, raw)| {
+            let start = node * layout.dimension * FLOAT_BYTES;
+            VecDocument {
+                frame_id: read_u64(raw, 0),
+                embedding: floats_from_le(&vectors[start..start + layout.dimension * FLOAT_BYTES]),
+            }
+        })
+        .collect();
+    let graph = match layout.graph {
+        Some(range) => Some(decode_graph(&bytes[range], layout.count)?),
+        None => None,
+    };
+    Ok((documents, graph))
+}
+
+fn floats_from_le(bytes: &[u8]) -> Vec<f32> {
+    bytes
+        .chunks_exact(FLOAT_BYTES)
+        .map(|raw| f32::from_le_bytes([raw[0], raw[1], raw[2], raw[3]]))
+        .collect()
+}
+
+/// Where the vectors of a mapped segment live.
+#[derive(Debug, Clone)]
+enum VectorData {
+    /// Absolute byte range inside the map, suitably aligned for `f32`.
+    Mapped(Range<usize>),
+    /// Copy made when the segment was not written at an aligned offset.
+    Owned(Vec<f32>),
+}
+
+/// Vector index backed by a memory-mapped fixed-stride segment.
+///
+/// Frame ids and vectors are read from the map on demand; removals are tracked as
+/// tombstones on top of the immutable segment.
+#[derive(Debug, Clone)]
+pub struct MappedVecIndex {
+    map: Arc<Mmap>,
+    /// Absolute byte range of the frame id array inside the map.
+    ids: Range<usize>,
+    vectors: VectorData,
+    dimension: usize,
+    count: usize,
+    graph: Option<HnswGraph>,
+    metric: DistanceMetric,
+    removed: HashSet<usize>,
+    /// Nodes holding each frame id, built on the first lookup so opening stays lazy.
+    nodes_by_frame: OnceLock<HashMap<FrameId, Vec<usize>>>,
+}
+
+impl MappedVecIndex {
+    /// Map the segment stored at `offset..offset + length` of `map`.
+    pub fn open(map: Arc<Mmap>, offset: usize, length: usize) -> Result<Self> {
+        let end = offset
+            .checked_add(length)
+            .filter(|end| *end <= map.len())
+            .ok_or_else(|| invalid("mapped vector segment out of bounds"))?;
+        let bytes = &map[offset..end];
+        let layout = parse_layout(bytes)?;
+        let (metric, graph) = match layout.graph.clone() {
+            Some(range) => {
+                let (metric, graph) = decode_graph(&bytes[range], layout.count)?;
+                (metric, Some(graph))
+            }
+            None => (DistanceMetric::default(), None),
+        };
+
+        let vector_range = offset + layout.vectors.start..offset + layout.vectors.end;
+        let vectors = if borrow_floats(&map[vector_range.clone()]).is_some() {
+            VectorData::Mapped(vector_range)
+        } else {
+            tracing::debug!(offset, "vector segment is misaligned, copying vectors");
+            VectorData::Owned(floats_from_le(&map[vector_range]))
+        };
+
+        Ok(Self {
+            ids: offset + layout.ids.start..offset + layout.ids.end,
+            vectors,
+            dimension: layout.dimension,
+            count: layout.count,
+            graph,
+            metric,
+            removed: HashSet::new(),
+            nodes_by_frame: OnceLock::new(),
+            map,
+        })
+    }
+
+    /// Number of live vectors.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        (0..self.count).filter(|node| self.is_live(*node)).count()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    #[must_use]
+    pub fn dimension(&self) -> usize {
+        self.dimension
+    }
+
+    #[must_use]
+    pub fn metric(&self) -> DistanceMetric {
+        self.metric
+    }
+
+    /// True when queries are routed through a persisted HNSW graph.
+    #[must_use]
+    pub fn has_graph(&self) -> bool {
+        self.graph.is_some()
+    }
+
+    /// Apply the manifest metric; a graph built under another metric is dropped.
+    #[must_use]
+    pub fn with_metric(mut self, metric: DistanceMetric) -> Self {
+        if self.graph.is_some() && self.metric != metric {
+            tracing::warn!(
+                built_with = %self.metric,
+                %metric,
+                "hnsw graph metric differs from manifest, falling back to flat scan"
+            );
+            if let Some(graph) = self.graph.take() {
+                self.removed
+                    .extend((0..self.count).filter(|node| graph.is_deleted(*node)));
+            }
+        }
+        self.metric = metric;
+        self
+    }
+
+    fn frame_id(&self, node: usize) -> FrameId {
+        read_u64(&self.map, self.ids.start + node * ID_BYTES)
+    }
+
+    fn is_live(&self, node: usize) -> bool {
+        !self.removed.contains(&node)
+            && self
+                .graph
+                .as_ref()
+                .is_none_or(|graph| !graph.is_deleted(node))
+    }
+
+    fn live_entries(&self) -> impl Iterator<Item = (FrameId, &[f32])> + '_ {
+        (0..self.count)
+            .filter(|node| self.is_live(*node))
+            .map(|node| (self.frame_id(node), self.vector(node)))
+    }
+
+    /// Search only among frames accepted by `accept`, like [`crate::VecIndex::search_filtered`].
+    pub fn search_filtered(
+        &self,
+        query: &[f32],
+        limit: usize,
+        ef_search: usize,
+        accept: &dyn Fn(FrameId) -> bool,
+    ) -> Vec<VecSearchHit> {
+        if query.len() != self.dimension {
+            return Vec::new();
+        }
+        if let Some(graph) = &self.graph {
+            return graph
+                .search(
+                    self,
+                    query,
+                    limit,
+                    ef_search,
+                    self.metric.distance_fn(),
+                    &|node| !self.removed.contains(&node) && accept(self.frame_id(node)),
+                )
+                .into_iter()
+                .map(|(node, distance)| {
+                    VecSearchHit::new(self.frame_id(node), distance, self.metric)
+                })
+                .collect();
+        }
+        scan_vectors(self.live_entries(), query, limit, self.metric, accept)
+    }
+
+    /// Live `(frame_id, vector)` pairs in segment order.
+    pub fn entries(&self) -> impl Iterator<Item = (FrameId, &[f32])> + '_ {
+        self.live_entries()
+    }
+
+    #[must_use]
+    pub fn embedding_for(&self, frame_id: FrameId) -> Option<&[f32]> {
+        self.nodes_for(frame_id)
+            .iter()
+            .copied()
+            .find(|node| self.is_live(*node))
+            .map(|node| self.vector(node))
+    }
+
+    pub fn remove(&mut self, frame_id: FrameId) {
+        let nodes = self.nodes_for(frame_id).to_vec();
+        self.removed.extend(nodes);
+    }
+
+    fn nodes_for(&self, frame_id: FrameId) -> &[usize] {
+        self.nodes_by_frame
+            .get_or_init(|| {
+                let mut nodes: HashMap<FrameId, Vec<usize>> = HashMap::new();
+                for node in 0..self.count {
+                    nodes.entry(self.frame_id(node)).or_default().push(node);
+                }
+                nodes
+            })
+            .get(&frame_id)
+            .map_or(&[], Vec::as_slice)
+    }
+
+    /// In-memory copy of the live index, for handles that may overwrite the mapped region.
+    pub(crate) fn to_owned_index(&self) -> VecIndex {
+        if let Some((documents, graph)) = self.graph_documents() {
+            return VecIndex::Graph {
+                documents,
+                graph,
+                metric: self.metric,
+            };
+        }
+        let documents = self
+            .live_entries()
+            .map(|(frame_id, embedding)| VecDocument {
+                frame_id,
+                embedding: embedding.to_vec(),
+            })
+            .collect();
+        VecIndex::Uncompressed {
+            documents,
+            metric: self.metric,
+        }
+    }
+
+    /// Materialized documents and graph with tombstones applied, so a builder can keep
+    /// linking into the persisted graph. `None` when the segment has no graph.
+    pub(crate) fn graph_documents(&self) -> Option<(Vec<VecDocument>, HnswGraph)> {
+        let mut graph = self.graph.clone()?;
+        for node in &self.removed {
+            graph.mark_deleted(*node);
+        }
+        let documents = (0..self.count)
+            .map(|node| VecDocument {
+                frame_id: self.frame_id(node),
+                embedding: self.vector(node).to_vec(),
+            })
+            .collect();
+        Some((documents, graph))
+    }
+}
+
+impl VectorStore for MappedVecIndex {
+    fn node_count(&self) -> usize {
+        self.count
+    }
+
+    fn vector(&self, node: usize) -> &[f32] {
+        let range = node * self.dimension..(node + 1) * self.dimension;
+        match &self.vectors {
+            VectorData::Mapped(bytes) => {
+                borrow_floats(&self.map[bytes.clone()]).map_or(&[], |floats| &floats[range])
+            }
+            VectorData::Owned(floats) => &floats[range],
+        }
+    }
+}
+
+/// Reinterpret little-endian `f32` bytes in place, if alignment and endianness allow.
+fn borrow_floats(bytes: &[u8]) -> Option<&[f32]> {
+    if cfg!(target_endian = "big") {
+        return None;
+    }
+    // SAFETY: every bit pattern is a valid `f32`, and `align_to` only yields the
+    // correctly aligned middle part; any unaligned prefix or suffix is rejected below.
+    let (prefix, floats, suffix) = unsafe { bytes.align_to::<f32>() };
+    (prefix.is_empty() && suffix.is_empty()).then_some(floats)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vec_hnsw::HnswParams;
+    use std::io::Write;
+
+    fn sample_documents(count: u64) -> Vec<VecDocument> {
+        (0..count)
+            .map(|id| {
+                let x = id as f32;
+                VecDocument {
+                    frame_id: id * 3,
+                    embedding: vec![x.sin(), x.cos(), (x * 0.5).sin()],
+                }
+            })
+            .collect()
+    }
+
+    fn map_bytes(bytes: &[u8], padding: usize) -> (tempfile::NamedTempFile, Arc<Mmap>) {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        file.write_all(&vec![0u8; padding]).unwrap();
+        file.write_all(bytes).unwrap();
+        file.flush().unwrap();
+        let map = unsafe { Mmap::map(file.as_file()).unwrap() };
+        (file, Arc::new(map))
+    }
+
+    #[test]
+    fn mapped_flat_segment_matches_decoded() {
+        let documents = sample_documents(50);
+        let bytes = encode(&documents, 3, None).unwrap();
+        let (decoded, graph) = decode(&bytes).unwrap();
+        assert!(graph.is_none());
+        assert_eq!(decoded.len(), documents.len());
+        assert_eq!(decoded[7].embedding, documents[7].embedding);
+
+        // Aligned and misaligned placements must behave identically.
+        for padding in [8, 3] {
+            let (_file, map) = map_bytes(&bytes, padding);
+            let mut index = MappedVecIndex::open(map, padding, bytes.len()).unwrap();
+            assert_eq!(
+                matches!(index.vectors, VectorData::Mapped(_)),
+                padding % 4 == 0
+            );
+            assert_eq!(index.len(), 50);
+            assert_eq!(
+                index.embedding_for(21),
+                Some(documents[7].embedding.as_slice())
+            );
+            let hits = index.search_filtered(&documents[7].embedding, 3, 16, &|_| true);
+            assert_eq!(hits[0].frame_id, 21);
+
+            index.remove(21);
+            assert!(index.embedding_for(21).is_none());
+            let hits = index.search_filtered(&documents[7].embedding, 3, 16, &|_| true);
+            assert!(hits.iter().all(|hit| hit.frame_id != 21));
+        }
+    }
+
+    #[test]
+    fn mapped_graph_segment_searches_without_decoding_vectors() {
+        let documents = sample_documents(300);
+        let graph = HnswGraph::build(
+            &documents,
+            HnswParams::default(),
+            DistanceMetric::L2.distance_fn(),
+        );
+        let bytes = encode(&documents, 3, Some((DistanceMetric::L2, &graph))).unwrap();
+        let (_file, map) = map_bytes(&bytes, 0);
+        let index = MappedVecIndex::open(map, 0, bytes.len()).unwrap();
+        assert!(index.has_graph());
+
+        let probe = &documents[42].embedding;
+        let hits = index.search_filtered(probe, 5, 64, &|_| true);
+        assert_eq!(hits[0].frame_id, 126);
+
+        // Only frames divisible by 9 are accepted; the scan fallback fills the limit.
+        let hits = index.search_filtered(probe, 5, 8, &|frame_id| frame_id % 9 == 0);
+        assert_eq!(hits.len(), 5);
+        assert!(hits.iter().all(|hit| hit.frame_id % 9 == 0));
+
+        // A different manifest metric drops the graph but keeps the vectors.
+        let index = index.with_metric(DistanceMetric::Cosine);
+        assert!(!index.has_graph());
+        assert_eq!(index.len(), 300);
+    }
+
+    #[test]
+    fn truncated_segment_is_rejected() {
+        let bytes = encode(&sample_documents(4), 3, None).unwrap();
+        assert!(decode(&bytes[..bytes.len() - 1]).is_err());
+        assert!(decode(&bytes[..10]).is_err());
+    }
+}
@@ -13,7 +13,10 @@ use blake3::hash;
 use serde::{Deserialize, Serialize};
 
 use crate::vec::VecSearchHit;
-use crate::{MemvidError, Result, types::FrameId};
+use crate::{
+    MemvidError, Result,
+    types::{DistanceMetric, FrameId, VectorCompression},
+};
 
 fn vec_config() -> impl bincode::config::Config {
     bincode::config::standard()
@@ -214,6 +217,46 @@ impl ProductQuantizer {
 
         total_dist_sq.sqrt()
     }
+
+    /// Asymmetric distance under `metric` (lower is closer).
+    ///
+    /// Dot products decompose per subspace just like squared L2, and the reconstructed
+    /// vector's norm is the sum of its centroids' squared norms, so cosine needs no decode.
+    #[must_use]
+    pub fn asymmetric_distance_with_metric(
+        &self,
+        query: &[f32],
+        codes: &[u8],
+        metric: DistanceMetric,
+    ) -> f32 {
+        if metric == DistanceMetric::L2 {
+            return self.asymmetric_distance(query, codes);
+        }
+        if query.len() != TOTAL_DIM || codes.len() != NUM_SUBSPACES {
+            return f32::INFINITY;
+        }
+
+        let mut dot = 0.0f32;
+        let mut norm_sq = 0.0f32;
+        for (subspace_idx, &code) in codes.iter().enumerate() {
+            let start_dim = subspace_idx * SUBSPACE_DIM;
+            let query_subspace = &query[start_dim..start_dim + SUBSPACE_DIM];
+            let centroid = self.codebooks[subspace_idx].get_centroid(code);
+            for (q, c) in query_subspace.iter().zip(centroid) {
+                dot += q * c;
+                norm_sq += c * c;
+            }
+        }
+
+        if metric == DistanceMetric::Dot {
+            return -dot;
+        }
+        let query_norm_sq: f32 = query.iter().map(|q| q * q).sum();
+        if query_norm_sq <= f32::EPSILON || norm_sq <= f32::EPSILON {
+            return 1.0;
+        }
+        1.0 - dot / (query_norm_sq.sqrt() * norm_sq.sqrt())
+    }
 }
 
 /// Compressed vector document
@@ -265,22 +308,27 @@ impl QuantizedVecIndexBuilder {
         let quantizer = self.quantizer.ok_or_else(|| MemvidError::InvalidQuery {
             reason: "Quantizer not trained".to_string(),
         })?;
-
-        let vector_count = self.documents.len() as u64;
-        let bytes =
-            bincode::serde::encode_to_vec(&(quantizer.clone(), self.documents), vec_config())?;
-        let checksum = *hash(&bytes).as_bytes();
-
-        Ok(QuantizedVecIndexArtifact {
-            bytes,
-            vector_count,
-            dimension: quantizer.dimension,
-            checksum,
-            compression_ratio: 16.0, // 1536 bytes -> 96 bytes
-        })
+        encode_artifact(&quantizer, &self.documents)
     }
 }
 
+fn encode_artifact(
+    quantizer: &ProductQuantizer,
+    documents: &[QuantizedVecDocument],
+) -> Result<QuantizedVecIndexArtifact> {
+    let vector_count = documents.len() as u64;
+    let bytes = bincode::serde::encode_to_vec((quantizer, documents), vec_config())?;
+    let checksum = *hash(&bytes).as_bytes();
+
+    Ok(QuantizedVecIndexArtifact {
+        bytes,
+        vector_count,
+        dimension: quantizer.dimension,
+        checksum,
+        compression_ratio: 16.0, // 1536 bytes -> 96 bytes
+    })
+}
+
 #[derive(Debug, Clone)]
 pub struct QuantizedVecIndexArtifact {
     pub bytes: Vec<u8>,
@@ -294,6 +342,10 @@ pub struct QuantizedVecIndexArtifact {
 pub struct QuantizedVecIndex {
     quantizer: ProductQuantizer,
     documents: Vec<QuantizedVecDocument>,
+    /// Tombstones parallel to `documents`; removed codes are dropped on the next encode.
+    deleted: Vec<bool>,
+    /// Not part of the encoding; supplied from the manifest on load.
+    metric: DistanceMetric,
 }
 
 impl QuantizedVecIndex {
@@ -310,10 +362,7 @@ impl QuantizedVecIndex {
         >(bytes, config.clone())
         {
             if read == bytes.len() {
-                return Ok(Self {
-                    quantizer,
-                    documents,
-                });
+                return Ok(Self::from_parts(quantizer, documents));
             }
         }
 
@@ -341,27 +390,114 @@ impl QuantizedVecIndex {
             dimension: (NUM_SUBSPACES * SUBSPACE_DIM) as u32,
         };
 
-        Ok(Self {
+        Ok(Self::from_parts(quantizer, documents))
+    }
+
+    fn from_parts(quantizer: ProductQuantizer, documents: Vec<QuantizedVecDocument>) -> Self {
+        Self {
+            deleted: vec![false; documents.len()],
             quantizer,
             documents,
+            metric: DistanceMetric::default(),
+        }
+    }
+
+    /// Encode the live codes with the current codebook, compacting tombstones away.
+    pub fn encode(&self) -> Result<QuantizedVecIndexArtifact> {
+        let live: Vec<QuantizedVecDocument> = self.live_documents().cloned().collect();
+        encode_artifact(&self.quantizer, &live)
+    }
+
+    /// Number of live (non-removed) vectors.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.deleted.iter().filter(|deleted| !**deleted).count()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Dimension of the vectors the codebook was trained on.
+    #[must_use]
+    pub fn dimension(&self) -> u32 {
+        self.quantizer.dimension
+    }
+
+    fn live_documents(&self) -> impl Iterator<Item = &QuantizedVecDocument> {
+        self.documents
+            .iter()
+            .zip(&self.deleted)
+            .filter(|(_, deleted)| !**deleted)
+            .map(|(doc, _)| doc)
+    }
+
+    /// Approximate reconstruction of every live vector from its codes.
+    pub fn entries(&self) -> impl Iterator<Item = (FrameId, Vec<f32>)> + '_ {
+        self.live_documents().filter_map(|doc| {
+            self.quantizer
+                .decode(&doc.codes)
+                .ok()
+                .map(|embedding| (doc.frame_id, embedding))
         })
     }
 
+    /// Approximate reconstruction of the vector stored for `frame_id`.
+    #[must_use]
+    pub fn embedding_for(&self, frame_id: FrameId) -> Option<Vec<f32>> {
+        self.live_documents()
+            .find(|doc| doc.frame_id == frame_id)
+            .and_then(|doc| self.quantizer.decode(&doc.codes).ok())
+    }
+
+    /// Encode `embedding` against the trained codebook and add it, replacing any live
+    /// vector already stored for `frame_id`.
+    pub fn insert(&mut self, frame_id: FrameId, embedding: &[f32]) -> Result<()> {
+        let codes = self.quantizer.encode(embedding)?;
+        self.remove(frame_id);
+        self.documents
+            .push(QuantizedVecDocument { frame_id, codes });
+        self.deleted.push(false);
+        Ok(())
+    }
+
+    #[must_use]
+    pub fn metric(&self) -> DistanceMetric {
+        self.metric
+    }
+
+    /// Rank with `metric` instead of the default L2.
+    #[must_use]
+    pub fn with_metric(mut self, metric: DistanceMetric) -> Self {
+        self.metric = metric;
+        self
+    }
+
     /// Search using asymmetric distance computation
     pub fn search(&self, query: &[f32], limit: usize) -> Vec<VecSearchHit> {
+        self.search_filtered(query, limit, &|_| true)
+    }
+
+    /// Search only among frames accepted by `accept`, skipping rejected codes entirely
+    pub fn search_filtered(
+        &self,
+        query: &[f32],
+        limit: usize,
+        accept: &dyn Fn(FrameId) -> bool,
+    ) -> Vec<VecSearchHit> {
         if query.is_empty() {
             return Vec::new();
         }
 
         let mut hits: Vec<VecSearchHit> = self
-            .documents
-            .iter()
+            .live_documents()
+            .filter(|doc| accept(doc.frame_id))
             .map(|doc| {
-                let distance = self.quantizer.asymmetric_distance(query, &doc.codes);
-                VecSearchHit {
-                    frame_id: doc.frame_id,
-                    distance,
-                }
+                let distance =
+                    self.quantizer
+                        .asymmetric_distance_with_metric(query, &doc.codes, self.metric);
+                VecSearchHit::new(doc.frame_id, distance, self.metric)
             })
             .collect();
 
@@ -375,19 +511,35 @@ impl QuantizedVecIndex {
         hits
     }
 
+    /// Tombstone every vector whose frame is rejected by `keep`.
+    pub fn retain(&mut self, keep: impl Fn(FrameId) -> bool) {
+        for (doc, deleted) in self.documents.iter().zip(self.deleted.iter_mut()) {
+            if !keep(doc.frame_id) {
+                *deleted = true;
+            }
+        }
+    }
+
+    /// Tombstone every live vector stored for `frame_id`.
     pub fn remove(&mut self, frame_id: FrameId) {
-        self.documents.retain(|doc| doc.frame_id != frame_id);
+        for (doc, deleted) in self.documents.iter().zip(self.deleted.iter_mut()) {
+            if doc.frame_id == frame_id {
+                *deleted = true;
+            }
+        }
     }
 
     /// Get compression statistics
     pub fn compression_stats(&self) -> CompressionStats {
-        let original_bytes = self.documents.len() * TOTAL_DIM * std::mem::size_of::<f32>();
-        let compressed_bytes = self.documents.len() * NUM_SUBSPACES; // 96 bytes per vector
+        let live = self.len();
+        let original_bytes = live * TOTAL_DIM * std::mem::size_of::<f32>();
+        let compressed_bytes = live * NUM_SUBSPACES; // 96 bytes per vector
         let codebook_bytes =
             NUM_SUBSPACES * NUM_CENTROIDS * SUBSPACE_DIM * std::mem::size_of::<f32>();
 
         CompressionStats {
-            vector_count: self.documents.len() as u64,
+            mode: VectorCompression::Pq96,
+            vector_count: live as u64,
             original_bytes: original_bytes as u64,
             compressed_bytes: compressed_bytes as u64,
             codebook_bytes: codebook_bytes as u64,
@@ -399,6 +551,8 @@ impl QuantizedVecIndex {
 
 #[derive(Debug, Clone)]
 pub struct CompressionStats {
+    /// Encoding the statistics describe.
+    pub mode: VectorCompression,
     pub vector_count: u64,
     pub original_bytes: u64,
     pub compressed_bytes: u64,
@@ -569,6 +723,13 @@ mod tests {
         // Distance between original and decoded should be small
         let dist = l2_distance_squared(test_vec, &decoded).sqrt();
         assert!(dist < 10.0, "Reconstruction error too large: {}", dist);
+
+        // Table-based cosine/dot must agree with the metric applied to the decoded vector
+        for metric in [DistanceMetric::Cosine, DistanceMetric::Dot] {
+            let adc = pq.asymmetric_distance_with_metric(test_vec, &codes, metric);
+            let exact = metric.distance(test_vec, &decoded);
+            assert!((adc - exact).abs() < 1e-3, "{metric}: {adc} vs {exact}");
+        }
     }
 
     #[test]
@@ -609,6 +770,46 @@ mod tests {
         assert_eq!(hits[0].frame_id, 1); // Should find exact match first
     }
 
+    #[test]
+    fn test_quantized_index_remove_insert_reconstruct() {
+        let training_vecs: Vec<Vec<f32>> = (0..50)
+            .map(|i| (0..TOTAL_DIM).map(|j| ((i + j) % 10) as f32).collect())
+            .collect();
+        let mut builder = QuantizedVecIndexBuilder::new();
+        builder
+            .train_quantizer(&training_vecs, TOTAL_DIM as u32)
+            .unwrap();
+        for (i, vec) in training_vecs.iter().take(3).enumerate() {
+            builder.add_document(i as FrameId, vec.clone()).unwrap();
+        }
+        let mut index = QuantizedVecIndex::decode(&builder.finish().unwrap().bytes).unwrap();
+
+        // Reconstruction is approximate but close to the original vector.
+        let approx = index.embedding_for(1).unwrap();
+        assert!(l2_distance_squared(&approx, &training_vecs[1]).sqrt() < 10.0);
+        assert_eq!(index.entries().count(), 3);
+
+        index.remove(1);
+        assert!(index.embedding_for(1).is_none());
+        assert!(
+            index
+                .search(&training_vecs[1], 3)
+                .iter()
+                .all(|hit| hit.frame_id != 1)
+        );
+
+        index.insert(7, &training_vecs[13]).unwrap();
+        assert_eq!(index.search(&training_vecs[13], 1)[0].frame_id, 7);
+        assert_eq!(index.len(), 3);
+
+        // Encoding compacts tombstones away.
+        let artifact = index.encode().unwrap();
+        assert_eq!(artifact.vector_count, 3);
+        let reloaded = QuantizedVecIndex::decode(&artifact.bytes).unwrap();
+        let ids: Vec<FrameId> = reloaded.entries().map(|(id, _)| id).collect();
+        assert_eq!(ids, vec![0, 2, 7]);
+    }
+
     #[test]
     fn test_kmeans_simple() {
         let vectors = vec![
@@ -0,0 +1,624 @@
+//! Scalar (int8) and binary quantization for mid-sized vector indexes.
+//!
+//! Unlike PQ these need no codebook training, so they apply at any vector count:
+//! - **Int8** stores one signed byte per dimension against a single abs-max scale (4x).
+//! - **Binary** stores one sign bit per dimension (32x) plus the mean magnitude of each
+//!   dimension, which is used to reconstruct approximate vectors.
+//!
+//! Search ranks every stored code cheaply (integer arithmetic for int8, hamming distance
+//! for binary) and then rescores a shortlist of `limit * rescore factor` candidates with
+//! the full-precision query against the dequantized vectors.
+
+use blake3::hash;
+use serde::{Deserialize, Serialize};
+
+use crate::vec::{VecIndexArtifact, VecSearchHit};
+use crate::vec_pq::CompressionStats;
+use crate::{
+    MemvidError, Result,
+    types::{DistanceMetric, FrameId, VectorCompression},
+};
+
+fn vec_config() -> impl bincode::config::Config {
+    bincode::config::standard()
+        .with_fixed_int_encoding()
+        .with_little_endian()
+}
+
+const VEC_DECODE_LIMIT: usize = crate::MAX_INDEX_BYTES as usize;
+
+/// Prefix identifying a scalar or binary quantized vector index.
+pub(crate) const SCALAR_SEGMENT_MAGIC: [u8; 4] = *b"MVSQ";
+
+/// Shortlist sizes as a multiple of the requested limit.
+const INT8_RESCORE_FACTOR: usize = 4;
+const BINARY_RESCORE_FACTOR: usize = 10;
+
+/// Largest magnitude of an int8 code; -128 is unused so the range stays symmetric.
+const INT8_MAX_CODE: f32 = 127.0;
+
+/// Quantization schemes handled by [`ScalarQuantizedIndex`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScalarKind {
+    Int8,
+    Binary,
+}
+
+impl ScalarKind {
+    /// Scheme selected by `compression`, if it is a scalar one.
+    #[must_use]
+    pub fn from_compression(compression: &VectorCompression) -> Option<Self> {
+        match compression {
+            VectorCompression::Int8 => Some(Self::Int8),
+            VectorCompression::Binary => Some(Self::Binary),
+            VectorCompression::None | VectorCompression::Pq96 => None,
+        }
+    }
+
+    /// Manifest value recorded for this scheme.
+    #[must_use]
+    pub fn compression(self) -> VectorCompression {
+        match self {
+            Self::Int8 => VectorCompression::Int8,
+            Self::Binary => VectorCompression::Binary,
+        }
+    }
+
+    fn rescore_factor(self) -> usize {
+        match self {
+            Self::Int8 => INT8_RESCORE_FACTOR,
+            Self::Binary => BINARY_RESCORE_FACTOR,
+        }
+    }
+}
+
+/// Parameters needed to encode and reconstruct vectors.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ScalarQuantizer {
+    /// `x ≈ code * scale`, with `scale = max|x| / 127` over the training vectors.
+    Int8 { dimension: u32, scale: f32 },
+    /// `x ≈ ±magnitudes[d]`, the sign taken from the stored bit.
+    Binary { magnitudes: Vec<f32> },
+}
+
+/// Query encoded for the cheap shortlist pass.
+enum QueryCodes {
+    /// The query in code units, neither rounded nor clamped, so out-of-range queries
+    /// keep their ranking.
+    Int8(Vec<f32>),
+    Binary(Vec<u8>),
+}
+
+impl ScalarQuantizer {
+    /// Fit the quantizer parameters to `vectors`, which must share `dimension`.
+    #[must_use]
+    pub fn train(kind: ScalarKind, vectors: &[&[f32]], dimension: u32) -> Self {
+        match kind {
+            ScalarKind::Int8 => {
+                let max_abs = vectors
+                    .iter()
+                    .flat_map(|vector| vector.iter())
+                    .fold(0.0f32, |max, value| max.max(value.abs()));
+                let scale = if max_abs > f32::EPSILON {
+                    max_abs / INT8_MAX_CODE
+                } else {
+                    1.0
+                };
+                Self::Int8 { dimension, scale }
+            }
+            ScalarKind::Binary => {
+                let mut magnitudes = vec![0.0f32; dimension as usize];
+                for vector in vectors {
+                    for (sum, value) in magnitudes.iter_mut().zip(vector.iter()) {
+                        *sum += value.abs();
+                    }
+                }
+                let count = crate::count_to_f32(vectors.len().max(1));
+                for magnitude in &mut magnitudes {
+                    *magnitude = if *magnitude > 0.0 {
+                        *magnitude / count
+                    } else {
+                        1.0
+                    };
+                }
+                Self::Binary { magnitudes }
+            }
+        }
+    }
+
+    #[must_use]
+    pub fn kind(&self) -> ScalarKind {
+        match self {
+            Self::Int8 { .. } => ScalarKind::Int8,
+            Self::Binary { .. } => ScalarKind::Binary,
+        }
+    }
+
+    #[must_use]
+    pub fn dimension(&self) -> u32 {
+        match self {
+            Self::Int8 { dimension, .. } => *dimension,
+            Self::Binary { magnitudes } => u32::try_from(magnitudes.len()).unwrap_or(u32::MAX),
+        }
+    }
+
+    /// Bytes used to store one vector.
+    #[must_use]
+    pub fn code_bytes(&self) -> usize {
+        match self {
+            Self::Int8 { dimension, .. } => *dimension as usize,
+            Self::Binary { magnitudes } => magnitudes.len().div_ceil(8),
+        }
+    }
+
+    /// Encoded size of the quantizer: a `u32` variant tag followed by its fields, with
+    /// the binary magnitudes behind a `u64` length prefix.
+    fn parameter_bytes(&self) -> usize {
+        let fields = match self {
+            Self::Int8 { .. } => std::mem::size_of::<u32>() + std::mem::size_of::<f32>(),
+            Self::Binary { magnitudes } => {
+                std::mem::size_of::<u64>() + magnitudes.len() * std::mem::size_of::<f32>()
+            }
+        };
+        std::mem::size_of::<u32>() + fields
+    }
+
+    fn check_dimension(&self, vector: &[f32]) -> Result<()> {
+        let expected = self.dimension();
+        if vector.len() != expected as usize {
+            return Err(MemvidError::VecDimensionMismatch {
+                expected,
+                actual: vector.len(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Encode a vector; int8 values outside the trained range are clamped.
+    pub fn encode(&self, vector: &[f32]) -> Result<Vec<u8>> {
+        self.check_dimension(vector)?;
+        Ok(match self {
+            Self::Int8 { scale, .. } => vector
+                .iter()
+                .map(|value| {
+                    // Rounded and clamped to ±127 first, so the cast is exact.
+                    #[allow(clippy::cast_possible_truncation)]
+                    let code = (value / scale).round().clamp(-INT8_MAX_CODE, INT8_MAX_CODE) as i8;
+                    code.to_le_bytes()[0]
+                })
+                .collect(),
+            Self::Binary { .. } => pack_signs(vector),
+        })
+    }
+
+    /// Approximate reconstruction of an encoded vector.
+    #[must_use]
+    pub fn decode(&self, codes: &[u8]) -> Vec<f32> {
+        match self {
+            Self::Int8 { scale, .. } => codes
+                .iter()
+                .map(|code| f32::from(i8::from_le_bytes([*code])) * scale)
+                .collect(),
+            Self::Binary { magnitudes } => magnitudes
+                .iter()
+                .enumerate()
+                .map(|(dim, magnitude)| {
+                    if bit_is_set(codes, dim) {
+                        *magnitude
+                    } else {
+                        -magnitude
+                    }
+                })
+                .collect(),
+        }
+    }
+
+    fn encode_query(&self, query: &[f32]) -> Result<QueryCodes> {
+        self.check_dimension(query)?;
+        Ok(match self {
+            Self::Int8 { scale, .. } => {
+                QueryCodes::Int8(query.iter().map(|value| value / scale).collect())
+            }
+            Self::Binary { .. } => QueryCodes::Binary(pack_signs(query)),
+        })
+    }
+}
+
+/// Shortlist distance between an encoded query and stored codes (lower is closer).
+///
+/// Only the ordering matters: the shortlist is rescored in full precision.
+fn code_distance(query: &QueryCodes, codes: &[u8], metric: DistanceMetric) -> f32 {
+    match query {
+        QueryCodes::Int8(query) => {
+            let (mut dot, mut norm_q, mut norm_c, mut l2) = (0.0f32, 0.0f32, 0.0f32, 0.0f32);
+            for (q, code) in query.iter().zip(codes) {
+                let c = f32::from(i8::from_le_bytes([*code]));
+                dot += q * c;
+                norm_q += q * q;
+                norm_c += c * c;
+                l2 += (q - c) * (q - c);
+            }
+            match metric {
+                DistanceMetric::L2 => l2,
+                DistanceMetric::Dot => -dot,
+                DistanceMetric::Cosine => {
+                    if norm_q <= f32::EPSILON || norm_c <= f32::EPSILON {
+                        return 1.0;
+                    }
+                    1.0 - dot / (norm_q.sqrt() * norm_c.sqrt())
+                }
+            }
+        }
+        QueryCodes::Binary(query) => {
+            let hamming: usize = query
+                .iter()
+                .zip(codes)
+                .map(|(q, code)| (q ^ code).count_ones() as usize)
+                .sum();
+            crate::count_to_f32(hamming)
+        }
+    }
+}
+
+fn pack_signs(vector: &[f32]) -> Vec<u8> {
+    let mut bits = vec![0u8; vector.len().div_ceil(8)];
+    for (dim, value) in vector.iter().enumerate() {
+        if *value > 0.0 {
+            bits[dim / 8] |= 1 << (dim % 8);
+        }
+    }
+    bits
+}
+
+fn bit_is_set(bits: &[u8], dim: usize) -> bool {
+    bits.get(dim / 8)
+        .is_some_and(|byte| byte & (1 << (dim % 8)) != 0)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScalarQuantizedDocument {
+    pub frame_id: FrameId,
+    pub codes: Vec<u8>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ScalarQuantizedIndex {
+    quantizer: ScalarQuantizer,
+    documents: Vec<ScalarQuantizedDocument>,
+    /// Tombstones parallel to `documents`; removed codes are dropped on the next encode.
+    deleted: Vec<bool>,
+    /// Not part of the encoding; supplied from the manifest on load.
+    metric: DistanceMetric,
+}
+
+impl ScalarQuantizedIndex {
+    /// Fit a quantizer to `documents` and encode them. Later entries for the same frame
+    /// replace earlier ones.
+    pub fn build(kind: ScalarKind, documents: &[(FrameId, Vec<f32>)]) -> Result<Self> {
+        let dimension = documents
+            .iter()
+            .map(|(_, embedding)| embedding.len())
+            .find(|len| *len > 0)
+            .unwrap_or(0);
+        let vectors: Vec<&[f32]> = documents
+            .iter()
+            .map(|(_, embedding)| embedding.as_slice())
+            .filter(|embedding| !embedding.is_empty())
+            .collect();
+        let dimension = u32::try_from(dimension).map_err(|_| MemvidError::InvalidQuery {
+            reason: "vector dimension exceeds u32".to_string(),
+        })?;
+        let mut index = Self::from_parts(
+            ScalarQuantizer::train(kind, &vectors, dimension),
+            Vec::new(),
+        );
+        for (frame_id, embedding) in documents {
+            if !embedding.is_empty() {
+                index.insert(*frame_id, embedding)?;
+            }
+        }
+        Ok(index)
+    }
+
+    pub fn decode(bytes: &[u8]) -> Result<Self> {
+        let payload =
+            bytes
+                .strip_prefix(&SCALAR_SEGMENT_MAGIC)
+                .ok_or_else(|| MemvidError::InvalidToc {
+                    reason: "missing scalar quantized vector index magic".into(),
+                })?;
+        let ((quantizer, documents), read) = bincode::serde::decode_from_slice::<
+            (ScalarQuantizer, Vec<ScalarQuantizedDocument>),
+            _,
+        >(
+            payload,
+            bincode::config::standard()
+                .with_fixed_int_encoding()
+                .with_little_endian()
+                .with_limit::<VEC_DECODE_LIMIT>(),
+        )?;
+        let code_bytes = quantizer.code_bytes();
+        if read != payload.len() || documents.iter().any(|doc| doc.codes.len() != code_bytes) {
+            return Err(MemvidError::InvalidToc {
+                reason: "scalar quantized vector index length mismatch".into(),
+            });
+        }
+        Ok(Self::from_parts(quantizer, documents))
+    }
+
+    fn from_parts(quantizer: ScalarQuantizer, documents: Vec<ScalarQuantizedDocument>) -> Self {
+        Self {
+            deleted: vec![false; documents.len()],
+            quantizer,
+            documents,
+            metric: DistanceMetric::default(),
+        }
+    }
+
+    /// Encode the live codes, compacting tombstones away.
+    pub fn encode(&self) -> Result<VecIndexArtifact> {
+        let live: Vec<&ScalarQuantizedDocument> = self.live_documents().collect();
+        let mut bytes = SCALAR_SEGMENT_MAGIC.to_vec();
+        bytes.extend(bincode::serde::encode_to_vec(
+            (&self.quantizer, live),
+            vec_config(),
+        )?);
+        let vector_count = self.len() as u64;
+        let dimension = self.dimension();
+        Ok(VecIndexArtifact {
+            checksum: *hash(&bytes).as_bytes(),
+            bytes,
+            vector_count,
+            dimension,
+            #[cfg(feature = "parallel_segments")]
+            bytes_uncompressed: vector_count
+                * u64::from(dimension)
+                * std::mem::size_of::<f32>() as u64,
+        })
+    }
+
+    #[must_use]
+    pub fn kind(&self) -> ScalarKind {
+        self.quantizer.kind()
+    }
+
+    /// Number of live (non-removed) vectors.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.deleted.iter().filter(|deleted| !**deleted).count()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    #[must_use]
+    pub fn dimension(&self) -> u32 {
+        self.quantizer.dimension()
+    }
+
+    fn live_documents(&self) -> impl Iterator<Item = &ScalarQuantizedDocument> {
+        self.documents
+            .iter()
+            .zip(&self.deleted)
+            .filter(|(_, deleted)| !**deleted)
+            .map(|(doc, _)| doc)
+    }
+
+    /// Approximate reconstruction of every live vector.
+    pub fn entries(&self) -> impl Iterator<Item = (FrameId, Vec<f32>)> + '_ {
+        self.live_documents()
+            .map(|doc| (doc.frame_id, self.quantizer.decode(&doc.codes)))
+    }
+
+    /// Approximate reconstruction of the vector stored for `frame_id`.
+    #[must_use]
+    pub fn embedding_for(&self, frame_id: FrameId) -> Option<Vec<f32>> {
+        self.live_documents()
+            .find(|doc| doc.frame_id == frame_id)
+            .map(|doc| self.quantizer.decode(&doc.codes))
+    }
+
+    /// Encode `embedding` with the current parameters and add it, replacing any live
+    /// vector already stored for `frame_id`.
+    pub fn insert(&mut self, frame_id: FrameId, embedding: &[f32]) -> Result<()> {
+        let codes = self.quantizer.encode(embedding)?;
+        self.remove(frame_id);
+        self.documents
+            .push(ScalarQuantizedDocument { frame_id, codes });
+        self.deleted.push(false);
+        Ok(())
+    }
+
+    #[must_use]
+    pub fn metric(&self) -> DistanceMetric {
+        self.metric
+    }
+
+    /// Rank with `metric` instead of the default L2.
+    #[must_use]
+    pub fn with_metric(mut self, metric: DistanceMetric) -> Self {
+        self.metric = metric;
+        self
+    }
+
+    #[must_use]
+    pub fn search(&self, query: &[f32], limit: usize) -> Vec<VecSearchHit> {
+        self.search_filtered(query, limit, &|_| true)
+    }
+
+    /// Shortlist accepted frames on their codes, then rescore the shortlist with the
+    /// full-precision query. Queries of the wrong dimension return no hits.
+    pub fn search_filtered(
+        &self,
+        query: &[f32],
+        limit: usize,
+        accept: &dyn Fn(FrameId) -> bool,
+    ) -> Vec<VecSearchHit> {
+        if query.is_empty() || limit == 0 {
+            return Vec::new();
+        }
+        let Ok(query_codes) = self.quantizer.encode_query(query) else {
+            return Vec::new();
+        };
+
+        let by_distance = |a: &(&ScalarQuantizedDocument, f32),
+                           b: &(&ScalarQuantizedDocument, f32)| {
+            a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal)
+        };
+        let mut shortlist: Vec<(&ScalarQuantizedDocument, f32)> = self
+            .live_documents()
+            .filter(|doc| accept(doc.frame_id))
+            .map(|doc| (doc, code_distance(&query_codes, &doc.codes, self.metric)))
+            .collect();
+        let shortlist_len = limit.saturating_mul(self.kind().rescore_factor());
+        if shortlist.len() > shortlist_len {
+            shortlist.select_nth_unstable_by(shortlist_len, by_distance);
+            shortlist.truncate(shortlist_len);
+        }
+
+        let mut hits: Vec<VecSearchHit> = shortlist
+            .into_iter()
+            .map(|(doc, _)| {
+                let embedding = self.quantizer.decode(&doc.codes);
+                let distance = self.metric.distance(query, &embedding);
+                VecSearchHit::new(doc.frame_id, distance, self.metric)
+            })
+            .collect();
+        hits.sort_by(|a, b| {
+            a.distance
+                .partial_cmp(&b.distance)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        hits.truncate(limit);
+        hits
+    }
+
+    /// Tombstone every vector whose frame is rejected by `keep`.
+    pub fn retain(&mut self, keep: impl Fn(FrameId) -> bool) {
+        for (doc, deleted) in self.documents.iter().zip(self.deleted.iter_mut()) {
+            if !keep(doc.frame_id) {
+                *deleted = true;
+            }
+        }
+    }
+
+    /// Tombstone every live vector stored for `frame_id`.
+    pub fn remove(&mut self, frame_id: FrameId) {
+        for (doc, deleted) in self.documents.iter().zip(self.deleted.iter_mut()) {
+            if doc.frame_id == frame_id {
+                *deleted = true;
+            }
+        }
+    }
+
+    /// Sizes of the encoded segment. `compressed_bytes` covers each vector's record
+    /// (frame id, length-prefixed codes) and `codebook_bytes` the magic, quantizer and
+    /// record count, so `total_bytes` matches the artifact length.
+    #[must_use]
+    pub fn compression_stats(&self) -> CompressionStats {
+        let live = self.len();
+        let original_bytes = live * self.dimension() as usize * std::mem::size_of::<f32>();
+        let record_bytes = std::mem::size_of::<FrameId>()
+            + std::mem::size_of::<u64>()
+            + self.quantizer.code_bytes();
+        let compressed_bytes = live * record_bytes;
+        let codebook_bytes = SCALAR_SEGMENT_MAGIC.len()
+            + self.quantizer.parameter_bytes()
+            + std::mem::size_of::<u64>();
+        let total_bytes = compressed_bytes + codebook_bytes;
+
+        // Byte counts stay far below 2^52, where f64 would start rounding.
+        #[allow(clippy::cast_precision_loss)]
+        let compression_ratio = original_bytes as f64 / total_bytes as f64;
+        CompressionStats {
+            mode: self.kind().compression(),
+            vector_count: live as u64,
+            original_bytes: original_bytes as u64,
+            compressed_bytes: compressed_bytes as u64,
+            codebook_bytes: codebook_bytes as u64,
+            total_bytes: total_bytes as u64,
+            compression_ratio,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_documents(count: usize, dimension: usize) -> Vec<(FrameId, Vec<f32>)> {
+        (0..count)
+            .map(|i| {
+                let embedding = (0..dimension)
+                    .map(|d| (((i * 7 + d * 13) % 31) as f32 - 15.0) / 15.0)
+                    .collect();
+                (i as FrameId, embedding)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn int8_roundtrip_and_search() {
+        let docs = sample_documents(50, 32);
+        let index = ScalarQuantizedIndex::build(ScalarKind::Int8, &docs)
+            .unwrap()
+            .with_metric(DistanceMetric::Cosine);
+        assert_eq!(index.len(), 50);
+        assert_eq!(index.dimension(), 32);
+
+        let reconstructed = index.embedding_for(7).unwrap();
+        let error = DistanceMetric::L2.distance(&docs[7].1, &reconstructed);
+        assert!(error < 0.05, "int8 reconstruction error too large: {error}");
+
+        let artifact = index.encode().unwrap();
+        assert!(artifact.bytes.starts_with(&SCALAR_SEGMENT_MAGIC));
+        let decoded = ScalarQuantizedIndex::decode(&artifact.bytes)
+            .unwrap()
+            .with_metric(DistanceMetric::Cosine);
+        let hits = decoded.search(&docs[23].1, 3);
+        assert_eq!(hits[0].frame_id, 23);
+        assert!(hits[0].similarity > 0.99);
+
+        let stats = decoded.compression_stats();
+        assert_eq!(stats.mode, VectorCompression::Int8);
+        assert_eq!(stats.total_bytes, artifact.bytes.len() as u64);
+        assert!(stats.compression_ratio > 2.5);
+    }
+
+    #[test]
+    fn binary_shortlist_rescores_and_tombstones() {
+        let docs = sample_documents(30, 64);
+        let mut index = ScalarQuantizedIndex::build(ScalarKind::Binary, &docs)
+            .unwrap()
+            .with_metric(DistanceMetric::Cosine);
+        assert_eq!(index.quantizer.code_bytes(), 8);
+
+        let hits = index.search(&docs[11].1, 5);
+        assert_eq!(hits[0].frame_id, 11);
+
+        index.remove(11);
+        assert!(index.embedding_for(11).is_none());
+        assert!(
+            index
+                .search(&docs[11].1, 5)
+                .iter()
+                .all(|hit| hit.frame_id != 11)
+        );
+
+        index.insert(11, &docs[12].1).unwrap();
+        assert_eq!(index.len(), 30);
+        let artifact = index.encode().unwrap();
+        assert_eq!(artifact.vector_count, 30);
+        let decoded = ScalarQuantizedIndex::decode(&artifact.bytes).unwrap();
+        assert_eq!(decoded.documents.len(), 30);
+
+        let stats = decoded.compression_stats();
+        assert_eq!(stats.mode, VectorCompression::Binary);
+        assert_eq!(stats.total_bytes, artifact.bytes.len() as u64);
+        assert!(stats.compression_ratio > 7.0);
+
+        assert!(index.insert(99, &[1.0, 2.0]).is_err());
+    }
+}
@@ -1,7 +1,10 @@
 //! Integration tests for doctor recovery functionality.
 //! These tests ensure that doctor can reliably recover corrupted files.
 
-use memvid_core::{DoctorOptions, Memvid, PutOptions, SearchRequest};
+use memvid_core::{
+    DoctorFindingCode, DoctorOptions, Memvid, PutOptions, SKETCH_TRACK_MAGIC, SKETCH_TRACK_VERSION,
+    SearchRequest,
+};
 use tempfile::NamedTempFile;
 
 /// Test that doctor can rebuild a Tantivy-based lex index from scratch.
@@ -51,6 +54,7 @@ fn doctor_rebuilds_tantivy_index() {
                 as_of_frame: None,
                 as_of_ts: None,
                 no_sketch: false,
+                ..Default::default()
             })
             .unwrap();
 
@@ -96,6 +100,7 @@ fn doctor_rebuilds_tantivy_index() {
                 as_of_frame: None,
                 as_of_ts: None,
                 no_sketch: false,
+                ..Default::default()
             })
             .unwrap();
 
@@ -111,6 +116,165 @@ fn doctor_rebuilds_tantivy_index() {
     }
 }
 
+/// Test that doctor accepts a snapshot built with the current Tantivy schema.
+#[test]
+#[cfg(feature = "lex")]
+fn doctor_accepts_current_tantivy_schema() {
+    let temp = NamedTempFile::new().unwrap();
+    let path = temp.path();
+
+    {
+        let mut mem = Memvid::create(path).unwrap();
+        mem.enable_lex().unwrap();
+        let options = PutOptions {
+            uri: Some("mv2://doc".to_string()),
+            title: Some("Titled document".to_string()),
+            search_text: Some("indexed body text".to_string()),
+            ..Default::default()
+        };
+        mem.put_bytes_with_options(b"indexed body text", options)
+            .unwrap();
+        mem.commit().unwrap();
+    }
+
+    let report = Memvid::doctor(
+        path,
+        DoctorOptions {
+            dry_run: true,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    assert!(
+        report
+            .plan
+            .findings
+            .iter()
+            .all(|finding| finding.code != DoctorFindingCode::TantivySnapshotOutdated),
+        "fresh snapshot flagged as outdated: {:?}",
+        report.plan.findings
+    );
+}
+
+/// Test that doctor flags and rebuilds a snapshot whose schema lacks `title`.
+#[test]
+#[cfg(feature = "lex")]
+fn doctor_rebuilds_snapshot_without_title_field() {
+    let temp = NamedTempFile::new().unwrap();
+    let path = temp.path();
+
+    {
+        let mut mem = Memvid::create(path).unwrap();
+        mem.enable_lex().unwrap();
+        let options = PutOptions {
+            uri: Some("mv2://doc".to_string()),
+            title: Some("Quarterly report".to_string()),
+            search_text: Some("revenue grew this quarter".to_string()),
+            ..Default::default()
+        };
+        mem.put_bytes_with_options(b"revenue grew this quarter", options)
+            .unwrap();
+        mem.commit().unwrap();
+    }
+
+    // Rename the field in the embedded meta.json, as if written before titles were indexed.
+    let mut bytes = std::fs::read(path).unwrap();
+    let field = br#""name": "title""#;
+    let position = bytes
+        .windows(field.len())
+        .rposition(|window| window == field)
+        .expect("title field in snapshot schema");
+    bytes[position..position + field.len()].copy_from_slice(br#""name": "tit1e""#);
+    std::fs::write(path, &bytes).unwrap();
+
+    let outdated = |path: &std::path::Path| {
+        let report = Memvid::doctor(
+            path,
+            DoctorOptions {
+                dry_run: true,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        report
+            .plan
+            .findings
+            .iter()
+            .any(|finding| finding.code == DoctorFindingCode::TantivySnapshotOutdated)
+    };
+    assert!(outdated(path));
+
+    Memvid::doctor(path, DoctorOptions::default()).unwrap();
+    assert!(!outdated(path));
+
+    let mut mem = Memvid::open_read_only(path).unwrap();
+    let results = mem
+        .search(SearchRequest {
+            query: "title:report".to_string(),
+            top_k: 10,
+            snippet_chars: 200,
+            ..Default::default()
+        })
+        .unwrap();
+    assert_eq!(results.hits.len(), 1);
+    assert_eq!(results.hits[0].uri, "mv2://doc");
+}
+
+/// Test that doctor rebuilds a sketch track written before CJK bigram terms.
+#[test]
+#[cfg(feature = "lex")]
+fn doctor_rebuilds_outdated_sketch_track() {
+    let temp = NamedTempFile::new().unwrap();
+    let path = temp.path();
+
+    {
+        let mut mem = Memvid::create(path).unwrap();
+        mem.enable_lex().unwrap();
+        let text = "私は東京タワーに行きました";
+        let options = PutOptions {
+            uri: Some("mv2://ja".to_string()),
+            search_text: Some(text.to_string()),
+            ..Default::default()
+        };
+        mem.put_bytes_with_options(text.as_bytes(), options)
+            .unwrap();
+        mem.commit().unwrap();
+        assert!(mem.has_sketches());
+    }
+
+    // Stamp the persisted track with the pre-CJK format version.
+    let mut bytes = std::fs::read(path).unwrap();
+    let header = bytes
+        .windows(4)
+        .rposition(|window| window == SKETCH_TRACK_MAGIC)
+        .expect("sketch track header");
+    bytes[header + 4..header + 6].copy_from_slice(&1u16.to_le_bytes());
+    std::fs::write(path, &bytes).unwrap();
+
+    let report = Memvid::doctor(
+        path,
+        DoctorOptions {
+            dry_run: true,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    assert!(
+        report
+            .plan
+            .findings
+            .iter()
+            .any(|finding| finding.code == DoctorFindingCode::SketchTrackOutdated),
+        "outdated sketch track not reported: {:?}",
+        report.plan.findings
+    );
+
+    Memvid::doctor(path, DoctorOptions::default()).unwrap();
+    let mem = Memvid::open_read_only(path).unwrap();
+    assert_eq!(mem.sketches().version, SKETCH_TRACK_VERSION);
+    assert_eq!(mem.sketches().len(), 1);
+}
+
 /// Test that doctor correctly handles files with 0 frames.
 #[test]
 #[cfg(feature = "lex")]
@@ -234,6 +398,7 @@ fn open_file_with_tantivy_segments_enables_lex() {
             as_of_frame: None,
             as_of_ts: None,
             no_sketch: false,
+            ..Default::default()
         });
 
         assert!(
@@ -317,6 +482,7 @@ fn doctor_rebuild_produces_searchable_index() {
                 as_of_frame: None,
                 as_of_ts: None,
                 no_sketch: false,
+                ..Default::default()
             })
             .unwrap();
 
@@ -344,6 +510,7 @@ fn doctor_rebuild_produces_searchable_index() {
                 as_of_frame: None,
                 as_of_ts: None,
                 no_sketch: false,
+                ..Default::default()
             })
             .unwrap();
 
@@ -2,8 +2,9 @@
 //! Tests: put, put_bytes_with_options, update, delete
 
 use memvid_core::{
-    EmbeddingIdentitySummary, MEMVID_EMBEDDING_MODEL_KEY, MEMVID_EMBEDDING_PROVIDER_KEY, Memvid,
-    MemvidError, PutOptions, TimelineQuery,
+    DistanceMetric, EmbeddingIdentitySummary, EmbeddingProvider, MEMVID_EMBEDDING_MODEL_KEY,
+    MEMVID_EMBEDDING_PROVIDER_KEY, Memvid, MemvidError, PutOptions, TimelineQuery, VecSearchFilter,
+    VectorCompression,
 };
 use std::num::NonZeroU64;
 use tempfile::TempDir;
@@ -255,6 +256,339 @@ fn put_rejects_mixed_embedding_dimensions() {
     }
 }
 
+#[test]
+fn vector_metric_is_recorded_and_locked() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("test.mv2");
+
+    {
+        let mut mem = Memvid::create(&path).unwrap();
+        mem.enable_vec().unwrap();
+        mem.set_vector_metric(DistanceMetric::Cosine).unwrap();
+        mem.put_with_embedding(b"long", vec![10.0, 0.0]).unwrap();
+        mem.put_with_embedding(b"short", vec![0.9, 0.1]).unwrap();
+        mem.commit().unwrap();
+    }
+
+    let mut mem = Memvid::open(&path).unwrap();
+    assert_eq!(mem.vector_metric(), DistanceMetric::Cosine);
+    let hits = mem.search_vec(&[1.0, 0.0], 2).unwrap();
+    assert_eq!(hits[0].frame_id, 0);
+    assert!((hits[0].similarity - 1.0).abs() < 1e-6);
+
+    match mem.set_vector_metric(DistanceMetric::L2).unwrap_err() {
+        MemvidError::VecMetricMismatch { expected, actual } => {
+            assert_eq!(expected, DistanceMetric::Cosine);
+            assert_eq!(actual, DistanceMetric::L2);
+        }
+        other => panic!("expected VecMetricMismatch, got {other:?}"),
+    }
+}
+
+#[test]
+fn scalar_vector_compression_persists_and_rescores() {
+    let embed = |i: u16| -> Vec<f32> {
+        let angle = f32::from(i) * 0.07;
+        (1..=16u16).map(|d| (angle * f32::from(d)).sin()).collect()
+    };
+    for compression in [VectorCompression::Int8, VectorCompression::Binary] {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("test.mv2");
+
+        {
+            let mut mem = Memvid::create(&path).unwrap();
+            mem.enable_vec().unwrap();
+            mem.set_vector_metric(DistanceMetric::Cosine).unwrap();
+            mem.set_vector_compression(compression.clone());
+            for i in 0..40u16 {
+                mem.put_with_embedding(format!("doc {i}").as_bytes(), embed(i))
+                    .unwrap();
+            }
+            mem.commit().unwrap();
+        }
+
+        let mut mem = Memvid::open(&path).unwrap();
+        assert_eq!(mem.vector_compression(), &compression);
+        let query = embed(17);
+        let hits = mem.search_vec(&query, 3).unwrap();
+        assert_eq!(hits[0].frame_id, 17, "{compression:?}");
+
+        let stats = mem.vector_compression_stats().unwrap().unwrap();
+        assert_eq!(stats.mode, compression);
+        assert_eq!(stats.vector_count, 40);
+        // 16-dim vectors: the per-record frame id and length prefix dominate the codes.
+        assert!(stats.total_bytes < stats.original_bytes, "{compression:?}");
+        assert!(stats.compression_ratio > 1.5, "{compression:?}");
+
+        // New vectors are encoded with the persisted parameters.
+        mem.put_with_embedding(b"late", query.clone()).unwrap();
+        mem.commit().unwrap();
+        let stats = mem.vector_compression_stats().unwrap().unwrap();
+        assert_eq!(stats.mode, compression);
+        assert_eq!(stats.vector_count, 41);
+    }
+}
+
+#[test]
+fn read_only_open_searches_mapped_vectors() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("test.mv2");
+
+    {
+        let mut mem = Memvid::create(&path).unwrap();
+        mem.enable_vec().unwrap();
+        mem.put_bytes(b"header shifts the index offset").unwrap();
+        for i in 0..30u8 {
+            mem.put_with_embedding(b"doc", vec![1.0, f32::from(i) * 0.1, 0.5])
+                .unwrap();
+        }
+        mem.commit().unwrap();
+        mem.delete_frame(5).unwrap();
+        mem.commit().unwrap();
+    }
+
+    let query = [1.0, 0.4, 0.5];
+    let expected = Memvid::open(&path).unwrap().search_vec(&query, 5).unwrap();
+    let mut mem = Memvid::open_read_only(&path).unwrap();
+    let hits = mem.search_vec(&query, 5).unwrap();
+    assert_eq!(hits, expected);
+    assert_eq!(hits.len(), 5);
+    assert!(hits.iter().all(|hit| hit.frame_id != 5));
+}
+
+#[test]
+fn filtered_vector_search_fills_limit() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("test.mv2");
+
+    let mut mem = Memvid::create(&path).unwrap();
+    mem.enable_vec().unwrap();
+    // The nearest neighbours all live outside the scope being searched.
+    for i in 0..20u8 {
+        let opts = PutOptions {
+            uri: Some(format!("mv2://noise/{i}")),
+            ..Default::default()
+        };
+        mem.put_with_embedding_and_options(b"noise", vec![1.0, f32::from(i) * 0.01], opts)
+            .unwrap();
+    }
+    for i in 0..3u8 {
+        let opts = PutOptions {
+            uri: Some(format!("mv2://docs/{i}")),
+            tags: vec!["keep".to_string()],
+            ..Default::default()
+        };
+        mem.put_with_embedding_and_options(b"doc", vec![-1.0, f32::from(i)], opts)
+            .unwrap();
+    }
+    mem.commit().unwrap();
+
+    let scoped = VecSearchFilter::scoped(Some("mv2://docs/"));
+    let hits = mem.search_vec_filtered(&[1.0, 0.0], 3, &scoped).unwrap();
+    assert_eq!(hits.len(), 3);
+    assert!(hits.iter().all(|hit| hit.frame_id >= 20));
+
+    let tagged = VecSearchFilter {
+        tags: vec!["KEEP".to_string()],
+        ..Default::default()
+    };
+    let response = mem
+        .vec_search_with_embedding("q", &[1.0, 0.0], 2, 80, &tagged)
+        .unwrap();
+    assert_eq!(response.hits.len(), 2);
+    assert!(
+        response
+            .hits
+            .iter()
+            .all(|hit| hit.uri.starts_with("mv2://docs/"))
+    );
+}
+
+#[test]
+fn named_vector_spaces_coexist() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("test.mv2");
+
+    {
+        let mut mem = Memvid::create(&path).unwrap();
+        mem.put_with_embedding(b"old model", vec![1.0, 0.0])
+            .unwrap();
+        mem.create_vec_space("large", 3, DistanceMetric::Cosine, None)
+            .unwrap();
+        for (payload, embedding) in [
+            (&b"alpha"[..], vec![1.0, 0.0, 0.0]),
+            (&b"beta"[..], vec![0.0, 1.0, 0.0]),
+        ] {
+            let opts = PutOptions::builder().vec_space("large").build();
+            mem.put_with_embedding_and_options(payload, embedding, opts)
+                .unwrap();
+        }
+        mem.commit().unwrap();
+    }
+
+    let mut mem = Memvid::open(&path).unwrap();
+    assert_eq!(mem.vec_spaces().len(), 1);
+    assert_eq!(mem.vec_spaces()[0].index.dimension, 3);
+    assert_eq!(mem.vec_spaces()[0].index.metric, DistanceMetric::Cosine);
+
+    let hits = mem.search_vec_space("large", &[0.0, 2.0, 0.0], 2).unwrap();
+    assert_eq!(hits.len(), 2);
+    assert_eq!(hits[0].frame_id, 2);
+    assert!((hits[0].similarity - 1.0).abs() < 1e-6);
+
+    // The default index only holds the 2-d vector.
+    let hits = mem.search_vec(&[1.0, 0.0], 5).unwrap();
+    assert_eq!(hits.len(), 1);
+    assert_eq!(hits[0].frame_id, 0);
+
+    let opts = PutOptions::builder().vec_space("large").build();
+    assert!(matches!(
+        mem.put_with_embedding_and_options(b"wrong", vec![1.0, 0.0], opts),
+        Err(MemvidError::VecDimensionMismatch { expected: 3, .. })
+    ));
+    assert!(matches!(
+        mem.search_vec_space("missing", &[1.0], 1),
+        Err(MemvidError::VecSpaceNotFound { .. })
+    ));
+
+    // Later commits carry the existing space vectors forward.
+    let opts = PutOptions::builder().vec_space("large").build();
+    mem.put_with_embedding_and_options(b"gamma", vec![0.0, 0.0, 1.0], opts)
+        .unwrap();
+    mem.commit().unwrap();
+    drop(mem);
+
+    let mut mem = Memvid::open(&path).unwrap();
+    let hits = mem.search_vec_space("large", &[1.0, 1.0, 1.0], 10).unwrap();
+    assert_eq!(hits.len(), 3);
+    assert_eq!(mem.vec_spaces()[0].index.vector_count, 3);
+
+    // Deleted frames drop out of named spaces too.
+    mem.delete_frame(2).unwrap();
+    drop(mem);
+
+    let mut mem = Memvid::open(&path).unwrap();
+    let hits = mem.search_vec_space("large", &[0.0, 1.0, 0.0], 10).unwrap();
+    assert_eq!(hits.len(), 2);
+    assert!(hits.iter().all(|hit| hit.frame_id != 2));
+}
+
+/// Deterministic 3-d embedder used to exercise re-embedding.
+struct KeywordEmbedder;
+
+impl EmbeddingProvider for KeywordEmbedder {
+    fn kind(&self) -> &str {
+        "test"
+    }
+
+    fn model(&self) -> &str {
+        "keywords-3"
+    }
+
+    fn dimension(&self) -> usize {
+        3
+    }
+
+    fn embed_text(&self, text: &str) -> memvid_core::Result<Vec<f32>> {
+        Ok(["alpha", "beta", "gamma"]
+            .iter()
+            .map(|keyword| if text.contains(keyword) { 1.0 } else { 0.1 })
+            .collect())
+    }
+}
+
+#[test]
+fn reembed_job_survives_crash_and_swaps_index() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("test.mv2");
+    let provider = KeywordEmbedder;
+
+    {
+        let mut mem = Memvid::create(&path).unwrap();
+        for payload in [&b"alpha"[..], b"beta", b"gamma"] {
+            mem.put_with_embedding(payload, vec![1.0, 0.0]).unwrap();
+        }
+        mem.commit().unwrap();
+        mem.start_reembed(&provider, DistanceMetric::Cosine)
+            .unwrap();
+        mem.commit().unwrap();
+
+        let progress = mem.run_reembed_batch(&provider, 2).unwrap();
+        assert_eq!(progress.next_frame_id, 2);
+        assert_eq!(progress.embedded_frames, 2);
+        assert_eq!(progress.uncommitted_batches, 1);
+        assert!(!progress.is_complete());
+        // Simulate a crash: skip the commit-on-drop so the batch only lives in the WAL.
+        std::mem::forget(mem);
+    }
+
+    let mut mem = Memvid::open(&path).unwrap();
+    let progress = mem.reembed_progress().expect("job survives reopen");
+    assert_eq!(progress.next_frame_id, 2);
+    assert_eq!(progress.embedded_frames, 2);
+    assert_eq!(progress.uncommitted_batches, 0);
+    assert!(matches!(
+        mem.start_reembed(&provider, DistanceMetric::Cosine),
+        Err(MemvidError::ReembedJob { .. })
+    ));
+
+    // The old index keeps serving while the job runs.
+    assert_eq!(mem.search_vec(&[1.0, 0.0], 5).unwrap().len(), 3);
+
+    let progress = mem.run_reembed(&provider, 2).unwrap();
+    assert!(progress.is_complete());
+    assert_eq!(progress.embedded_frames, 3);
+    assert!(mem.reembed_progress().is_none());
+    assert!(mem.vec_spaces().is_empty());
+    drop(mem);
+
+    let mut mem = Memvid::open(&path).unwrap();
+    assert_eq!(mem.vector_metric(), DistanceMetric::Cosine);
+    let hits = mem.search_vec(&[0.0, 0.0, 1.0], 3).unwrap();
+    assert_eq!(hits.len(), 3);
+    assert_eq!(hits[0].frame_id, 2);
+    assert!(matches!(
+        mem.search_vec(&[1.0, 0.0], 3),
+        Err(MemvidError::VecDimensionMismatch { expected: 3, .. })
+    ));
+    match mem.embedding_identity_summary(10) {
+        EmbeddingIdentitySummary::Single(identity) => {
+            assert_eq!(identity.model.as_deref(), Some("keywords-3"));
+            assert_eq!(identity.dimension, Some(3));
+        }
+        other => panic!("expected a single identity, got {other:?}"),
+    }
+}
+
+#[test]
+fn reembed_keeps_configured_vector_compression() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("test.mv2");
+    let provider = KeywordEmbedder;
+
+    {
+        let mut mem = Memvid::create(&path).unwrap();
+        mem.set_vector_compression(VectorCompression::Int8);
+        for payload in [&b"alpha"[..], b"beta", b"gamma"] {
+            mem.put_with_embedding(payload, vec![1.0, 0.0]).unwrap();
+        }
+        mem.commit().unwrap();
+        mem.start_reembed(&provider, DistanceMetric::Cosine)
+            .unwrap();
+        let progress = mem.run_reembed(&provider, 2).unwrap();
+        assert!(progress.is_complete());
+        assert_eq!(mem.vector_compression(), &VectorCompression::Int8);
+    }
+
+    let mut mem = Memvid::open(&path).unwrap();
+    assert_eq!(mem.vector_compression(), &VectorCompression::Int8);
+    let stats = mem.vector_compression_stats().unwrap().unwrap();
+    assert_eq!(stats.mode, VectorCompression::Int8);
+    assert_eq!(stats.vector_count, 3);
+    let hits = mem.search_vec(&[0.0, 0.0, 1.0], 3).unwrap();
+    assert_eq!(hits[0].frame_id, 2);
+}
+
 #[test]
 fn embedding_identity_summary_unknown_when_missing() {
     let dir = TempDir::new().unwrap();
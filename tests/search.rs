@@ -1,8 +1,14 @@
 //! Integration tests for Memvid search operations.
-//! Tests: search (lex), timeline queries
+//! Tests: search (lex), hybrid search, timeline queries
 
-use memvid_core::{Memvid, PutOptions, SearchRequest, TimelineQuery};
+use memvid_core::{
+    ChunkExpansion, CreateOptions, DateInterval, FacetBucket, FacetField, FacetRequest,
+    FusionStrategy, HighlightOptions, HybridRetriever, LexAnalyzerConfig, LexLanguage, Memvid,
+    MemvidError, PutOptions, Reranker, RerankerConfig, RerankerDocument, RerankerResult,
+    SearchEngineKind, SearchRequest, SketchVariant, TimelineQuery, VecSearchFilter,
+};
 use std::num::NonZeroU64;
+use std::sync::Arc;
 use tempfile::TempDir;
 
 /// Helper to create a memory with searchable content.
@@ -76,6 +82,12 @@ fn search_basic_query() {
             as_of_frame: None,
             as_of_ts: None,
             no_sketch: false,
+            mmr_lambda: None,
+            facets: None,
+            highlight: None,
+            auto_correct: false,
+            rerank: false,
+            expand: None,
         })
         .unwrap();
 
@@ -111,6 +123,12 @@ fn search_multiple_results() {
             as_of_frame: None,
             as_of_ts: None,
             no_sketch: false,
+            mmr_lambda: None,
+            facets: None,
+            highlight: None,
+            auto_correct: false,
+            rerank: false,
+            expand: None,
         })
         .unwrap();
 
@@ -163,6 +181,12 @@ fn search_respects_top_k() {
             as_of_frame: None,
             as_of_ts: None,
             no_sketch: false,
+            mmr_lambda: None,
+            facets: None,
+            highlight: None,
+            auto_correct: false,
+            rerank: false,
+            expand: None,
         })
         .unwrap();
 
@@ -194,6 +218,12 @@ fn search_with_scope() {
             as_of_frame: None,
             as_of_ts: None,
             no_sketch: false,
+            mmr_lambda: None,
+            facets: None,
+            highlight: None,
+            auto_correct: false,
+            rerank: false,
+            expand: None,
         })
         .unwrap();
 
@@ -229,6 +259,12 @@ fn search_returns_snippets() {
             as_of_frame: None,
             as_of_ts: None,
             no_sketch: false,
+            mmr_lambda: None,
+            facets: None,
+            highlight: None,
+            auto_correct: false,
+            rerank: false,
+            expand: None,
         })
         .unwrap();
 
@@ -262,6 +298,12 @@ fn search_no_results() {
             as_of_frame: None,
             as_of_ts: None,
             no_sketch: false,
+            mmr_lambda: None,
+            facets: None,
+            highlight: None,
+            auto_correct: false,
+            rerank: false,
+            expand: None,
         })
         .unwrap();
 
@@ -295,6 +337,12 @@ fn search_empty_memory() {
             as_of_frame: None,
             as_of_ts: None,
             no_sketch: false,
+            mmr_lambda: None,
+            facets: None,
+            highlight: None,
+            auto_correct: false,
+            rerank: false,
+            expand: None,
         })
         .unwrap();
 
@@ -305,7 +353,1216 @@ fn search_empty_memory() {
     );
 }
 
+/// Test MMR keeps near-duplicate chunks from filling every slot.
+#[test]
+#[cfg(feature = "lex")]
+fn search_mmr_diversifies_duplicates() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("test.mv2");
+
+    {
+        let mut mem = Memvid::create(&path).unwrap();
+        mem.enable_lex().unwrap();
+        let docs = [
+            (
+                "mv2://solar/a",
+                "solar solar panels convert sunlight into electricity on rooftops",
+            ),
+            (
+                "mv2://solar/b",
+                "solar solar panels convert sunlight into electricity on rooftops",
+            ),
+            (
+                "mv2://solar/c",
+                "solar solar panels convert sunlight into electricity on rooftops",
+            ),
+            (
+                "mv2://storms/flare",
+                "a solar storm disrupts satellites and radio communication",
+            ),
+        ];
+        for (uri, content) in docs {
+            let opts = PutOptions {
+                uri: Some(uri.to_string()),
+                search_text: Some(content.to_string()),
+                timestamp: Some(1700000000),
+                ..Default::default()
+            };
+            mem.put_bytes_with_options(content.as_bytes(), opts)
+                .unwrap();
+        }
+        mem.commit().unwrap();
+    }
+
+    let mut mem = Memvid::open_read_only(&path).unwrap();
+    let request = |mmr_lambda| SearchRequest {
+        query: "solar".to_string(),
+        top_k: 2,
+        snippet_chars: 200,
+        uri: None,
+        scope: None,
+        cursor: None,
+        #[cfg(feature = "temporal_track")]
+        temporal: None,
+        as_of_frame: None,
+        as_of_ts: None,
+        no_sketch: false,
+        mmr_lambda,
+        facets: None,
+        highlight: None,
+        auto_correct: false,
+        rerank: false,
+        expand: None,
+    };
+
+    let plain = mem.search(request(None)).unwrap();
+    assert!(
+        plain
+            .hits
+            .iter()
+            .all(|hit| hit.uri.starts_with("mv2://solar/"))
+    );
+
+    let diverse = mem.search(request(Some(0.3))).unwrap();
+    assert_eq!(diverse.hits.len(), 2);
+    assert_eq!(diverse.params.top_k, 2);
+    assert!(
+        diverse
+            .hits
+            .iter()
+            .any(|hit| hit.uri == "mv2://storms/flare")
+    );
+    assert_eq!(diverse.hits[0].rank, 1);
+    assert_eq!(diverse.hits[1].rank, 2);
+
+    // Later pages continue through the diversified pool without skips or repeats.
+    let mut uris: Vec<String> = Vec::new();
+    let mut page = diverse;
+    loop {
+        uris.extend(page.hits.iter().map(|hit| hit.uri.clone()));
+        let Some(cursor) = page.next_cursor.take() else {
+            break;
+        };
+        let mut next = request(Some(0.3));
+        next.cursor = Some(cursor);
+        page = mem.search(next).unwrap();
+    }
+    uris.sort();
+    assert_eq!(
+        uris,
+        vec![
+            "mv2://solar/a",
+            "mv2://solar/b",
+            "mv2://solar/c",
+            "mv2://storms/flare"
+        ]
+    );
+
+    assert!(mem.search(request(Some(1.5))).is_err());
+}
+
+/// Test hybrid search fuses lexical and vector lists and reports each retriever.
+#[test]
+#[cfg(feature = "lex")]
+fn search_hybrid_reports_retriever_breakdown() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("test.mv2");
+
+    let mut mem = Memvid::create(&path).unwrap();
+    mem.enable_lex().unwrap();
+    mem.enable_vec().unwrap();
+    let docs = [
+        (
+            "mv2://docs/lexical",
+            "orbital mechanics lecture notes",
+            vec![0.0, 1.0],
+        ),
+        (
+            "mv2://docs/both",
+            "orbital transfer between planets",
+            vec![1.0, 0.1],
+        ),
+        (
+            "mv2://docs/semantic",
+            "moving a spacecraft to mars",
+            vec![1.0, 0.0],
+        ),
+    ];
+    for (uri, content, embedding) in docs {
+        let opts = PutOptions {
+            uri: Some(uri.to_string()),
+            search_text: Some(content.to_string()),
+            ..Default::default()
+        };
+        mem.put_with_embedding_and_options(content.as_bytes(), embedding, opts)
+            .unwrap();
+    }
+    mem.commit().unwrap();
+
+    let request = SearchRequest {
+        query: "orbital".to_string(),
+        top_k: 3,
+        snippet_chars: 200,
+        uri: None,
+        scope: None,
+        cursor: None,
+        #[cfg(feature = "temporal_track")]
+        temporal: None,
+        as_of_frame: None,
+        as_of_ts: None,
+        no_sketch: false,
+        mmr_lambda: None,
+        facets: None,
+        highlight: None,
+        auto_correct: false,
+        rerank: false,
+        expand: None,
+    };
+
+    for strategy in [
+        FusionStrategy::default(),
+        FusionStrategy::Linear {
+            lexical: 1.0,
+            semantic: 1.0,
+        },
+        FusionStrategy::Convex {
+            lexical: 0.5,
+            semantic: 0.5,
+        },
+        FusionStrategy::Dbsf,
+    ] {
+        let response = mem
+            .search_hybrid(request.clone(), &[1.0, 0.0], &strategy)
+            .unwrap();
+        assert_eq!(response.engine, SearchEngineKind::Hybrid);
+        assert_eq!(response.hits.len(), 3, "{strategy:?}");
+        let top = &response.hits[0];
+        assert_eq!(top.uri, "mv2://docs/both", "{strategy:?}");
+        let retrievers: Vec<_> = top.retriever_scores.iter().map(|s| s.retriever).collect();
+        assert_eq!(
+            retrievers,
+            vec![HybridRetriever::Lexical, HybridRetriever::Semantic]
+        );
+        let total: f32 = top.retriever_scores.iter().map(|s| s.contribution).sum();
+        assert!((top.score.unwrap() - total).abs() < 1e-6);
+    }
+
+    // A date range in the query bounds the vector hits too.
+    let mut dated = request.clone();
+    dated.query = "orbital date:[2000-01-01 TO 2000-12-31]".to_string();
+    let response = mem
+        .search_hybrid(dated, &[1.0, 0.0], &FusionStrategy::default())
+        .unwrap();
+    assert!(response.hits.is_empty());
+
+    let invalid = FusionStrategy::Linear {
+        lexical: 0.0,
+        semantic: 0.0,
+    };
+    assert!(mem.search_hybrid(request, &[1.0, 0.0], &invalid).is_err());
+}
+
+#[test]
+#[cfg(feature = "lex")]
+fn similar_frames_fuses_signals_and_skips_children() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("test.mv2");
+
+    let mut mem = Memvid::create(&path).unwrap();
+    mem.enable_lex().unwrap();
+    mem.enable_vec().unwrap();
+    // Long enough to be split into child chunks that share the parent's wording.
+    let source = "Volcanic eruptions reshape island coastlines with lava flows. ".repeat(60);
+    let docs = [
+        ("mv2://docs/source", source.as_str(), vec![1.0, 0.0]),
+        (
+            "mv2://docs/near",
+            "lava flows from volcanic eruptions reshape coastlines",
+            vec![0.9, 0.1],
+        ),
+        (
+            "mv2://docs/far",
+            "quarterly budget review for the marketing team",
+            vec![0.0, 1.0],
+        ),
+    ];
+    for (uri, content, embedding) in docs {
+        let opts = PutOptions {
+            uri: Some(uri.to_string()),
+            search_text: Some(content.to_string()),
+            ..Default::default()
+        };
+        mem.put_with_embedding_and_options(content.as_bytes(), embedding, opts)
+            .unwrap();
+    }
+    mem.commit().unwrap();
+    mem.build_all_sketches(SketchVariant::Small);
+    assert_eq!(mem.frame_by_id(1).unwrap().parent_id, Some(0));
+
+    let hits = mem
+        .similar_frames(0, 5, &VecSearchFilter::default())
+        .unwrap();
+    let uris: Vec<_> = hits.iter().map(|hit| hit.uri.as_str()).collect();
+    assert_eq!(uris, vec!["mv2://docs/near", "mv2://docs/far"]);
+    let retrievers: Vec<_> = hits[0]
+        .retriever_scores
+        .iter()
+        .map(|s| s.retriever)
+        .collect();
+    for retriever in [
+        HybridRetriever::Semantic,
+        HybridRetriever::Sketch,
+        HybridRetriever::Lexical,
+    ] {
+        assert!(retrievers.contains(&retriever), "{retrievers:?}");
+    }
+
+    let scoped = VecSearchFilter {
+        uri: Some("mv2://docs/far".to_string()),
+        ..Default::default()
+    };
+    let hits = mem.similar_frames(0, 5, &scoped).unwrap();
+    assert!(hits.iter().all(|hit| hit.uri == "mv2://docs/far"));
+    assert!(
+        mem.similar_frames(99, 5, &VecSearchFilter::default())
+            .is_err()
+    );
+}
+
+#[cfg(feature = "lex")]
+fn lex_request(query: &str) -> SearchRequest {
+    SearchRequest {
+        query: query.to_string(),
+        top_k: 5,
+        snippet_chars: 200,
+        uri: None,
+        scope: None,
+        cursor: None,
+        #[cfg(feature = "temporal_track")]
+        temporal: None,
+        as_of_frame: None,
+        as_of_ts: None,
+        no_sketch: false,
+        mmr_lambda: None,
+        facets: None,
+        highlight: None,
+        auto_correct: false,
+        rerank: false,
+        expand: None,
+    }
+}
+
+/// Test that a per-memory language analyzer stems, folds and survives reopen.
+#[cfg(feature = "lex")]
+#[test]
+fn search_uses_persisted_language_analyzer() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("test.mv2");
+    let mut analyzer = LexAnalyzerConfig::for_language(LexLanguage::German);
+    analyzer.detect_language = true;
+
+    {
+        let options = CreateOptions {
+            lex_analyzer: analyzer.clone(),
+        };
+        let mut mem = Memvid::create_with_options(&path, options).unwrap();
+        mem.enable_lex().unwrap();
+        let docs = [
+            (
+                "mv2://de/haus",
+                "Die alten Häuser stehen am Fluss und sind nicht bewohnt",
+            ),
+            (
+                "mv2://en/run",
+                "The runners are running along the river with the dogs",
+            ),
+        ];
+        for (uri, content) in docs {
+            let opts = PutOptions {
+                uri: Some(uri.to_string()),
+                search_text: Some(content.to_string()),
+                ..Default::default()
+            };
+            mem.put_bytes_with_options(content.as_bytes(), opts)
+                .unwrap();
+        }
+        mem.commit().unwrap();
+    }
+
+    let mut mem = Memvid::open(&path).unwrap();
+    assert_eq!(mem.lex_analyzer(), &analyzer);
+
+    let response = mem.search(lex_request("haus")).unwrap();
+    assert_eq!(response.hits.len(), 1);
+    assert_eq!(response.hits[0].uri, "mv2://de/haus");
+
+    // The English frame was detected and stemmed with the English chain.
+    let response = mem.search(lex_request("run")).unwrap();
+    assert_eq!(response.hits.len(), 1);
+    assert_eq!(response.hits[0].uri, "mv2://en/run");
+}
+
+/// Test that words inside unsegmented CJK sentences are searchable.
+#[cfg(feature = "lex")]
+#[test]
+fn search_finds_words_inside_cjk_text() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("test.mv2");
+
+    let mut mem = Memvid::create(&path).unwrap();
+    mem.enable_lex().unwrap();
+    let docs = [
+        ("mv2://ja/tower", "私は昨日東京タワーに行きました"),
+        ("mv2://zh/campus", "北京大学的校园很漂亮"),
+        ("mv2://ko/food", "서울에서김치찌개를먹었어요"),
+    ];
+    for (uri, content) in docs {
+        let opts = PutOptions {
+            uri: Some(uri.to_string()),
+            search_text: Some(content.to_string()),
+            ..Default::default()
+        };
+        mem.put_bytes_with_options(content.as_bytes(), opts)
+            .unwrap();
+    }
+    mem.commit().unwrap();
+
+    for (query, expected) in [
+        ("東京タワー", "mv2://ja/tower"),
+        ("大学", "mv2://zh/campus"),
+        ("김치찌개", "mv2://ko/food"),
+    ] {
+        let response = mem.search(lex_request(query)).unwrap();
+        assert_eq!(response.hits.len(), 1, "{query}");
+        assert_eq!(response.hits[0].uri, expected, "{query}");
+    }
+}
+
+/// Test that titles are searchable through `title:` and boost free-text matches.
+#[cfg(feature = "lex")]
+#[test]
+fn search_indexes_and_boosts_titles() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("test.mv2");
+
+    let mut mem = Memvid::create(&path).unwrap();
+    mem.enable_lex().unwrap();
+    let docs = [
+        (
+            "mv2://garden/notes",
+            "Gardening notes",
+            "tomatoes love sun, tomatoes love water, tomatoes love warm soil",
+        ),
+        (
+            "mv2://garden/tomatoes",
+            "Tomatoes",
+            "a short note on growing tomatoes in pots on a sunny balcony",
+        ),
+    ];
+    for (uri, title, content) in docs {
+        let opts = PutOptions {
+            uri: Some(uri.to_string()),
+            title: Some(title.to_string()),
+            search_text: Some(content.to_string()),
+            timestamp: Some(1700000000),
+            ..Default::default()
+        };
+        mem.put_bytes_with_options(content.as_bytes(), opts)
+            .unwrap();
+    }
+    mem.commit().unwrap();
+
+    let response = mem.search(lex_request("title:tomato")).unwrap();
+    assert_eq!(response.hits.len(), 1);
+    assert_eq!(response.hits[0].uri, "mv2://garden/tomatoes");
+
+    let response = mem.search(lex_request("tomatoes")).unwrap();
+    assert_eq!(response.hits[0].uri, "mv2://garden/tomatoes");
+
+    mem.set_lex_title_boost(0.0);
+    let response = mem.search(lex_request("tomatoes")).unwrap();
+    assert_eq!(response.hits[0].uri, "mv2://garden/notes");
+}
+
 /// Test timeline query returns ordered results.
+/// Test fuzzy, proximity, boost and required/excluded operators.
+#[cfg(feature = "lex")]
+#[test]
+fn search_supports_fuzzy_proximity_and_boost_operators() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("test.mv2");
+
+    let mut mem = Memvid::create(&path).unwrap();
+    mem.enable_lex().unwrap();
+    let docs = [
+        (
+            "mv2://ops/deploy",
+            "kubernetes rollout finished after the canary stage",
+        ),
+        (
+            "mv2://ops/rollback",
+            "the canary failed so the rollout was reverted",
+        ),
+        (
+            "mv2://ops/billing",
+            "invoice totals for the kubernetes cluster",
+        ),
+    ];
+    for (uri, content) in docs {
+        let opts = PutOptions {
+            uri: Some(uri.to_string()),
+            search_text: Some(content.to_string()),
+            ..Default::default()
+        };
+        mem.put_bytes_with_options(content.as_bytes(), opts)
+            .unwrap();
+    }
+    mem.commit().unwrap();
+
+    let uris = |mem: &mut Memvid, query: &str| -> Vec<String> {
+        let response = mem.search(lex_request(query)).unwrap();
+        response.hits.into_iter().map(|hit| hit.uri).collect()
+    };
+
+    assert!(uris(&mut mem, "kubrenetes").is_empty());
+    let mut fuzzy = uris(&mut mem, "kubrenetes~1");
+    fuzzy.sort();
+    assert_eq!(fuzzy, vec!["mv2://ops/billing", "mv2://ops/deploy"]);
+
+    assert!(uris(&mut mem, "\"rollout reverted\"").is_empty());
+    assert_eq!(
+        uris(&mut mem, "\"rollout reverted\"~1"),
+        vec!["mv2://ops/rollback"]
+    );
+
+    assert_eq!(
+        uris(&mut mem, "+canary -reverted"),
+        vec!["mv2://ops/deploy"]
+    );
+
+    let boosted = uris(&mut mem, "reverted OR kubernetes^10");
+    assert_eq!(boosted.len(), 3);
+    assert_eq!(boosted[2], "mv2://ops/rollback");
+
+    assert!(mem.search(lex_request("kubernetes~3")).is_err());
+}
+
+/// Test `meta.key:value` filters and numeric/date ranges over extra metadata.
+#[cfg(feature = "lex")]
+#[test]
+fn search_filters_on_extra_metadata() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("test.mv2");
+
+    let mut mem = Memvid::create(&path).unwrap();
+    mem.enable_lex().unwrap();
+    // Accounts 2 and 3 round to the same f64; orders "00123" and "123" are distinct ids.
+    let tickets = [
+        ("mv2://tickets/1", "ACME", 1, "2024-03-01", 7_u64, "00123"),
+        (
+            "mv2://tickets/2",
+            "Globex",
+            3,
+            "2024-06-15",
+            9_007_199_254_740_993,
+            "123",
+        ),
+        (
+            "mv2://tickets/3",
+            "ACME",
+            5,
+            "2024-09-30",
+            9_007_199_254_740_992,
+            "456",
+        ),
+    ];
+    for (uri, customer, severity, due, account, order) in tickets {
+        let content = format!("support ticket filed by {customer}");
+        let opts = PutOptions::builder()
+            .uri(uri)
+            .search_text(content.clone())
+            .metadata_entry("customer", serde_json::json!(customer))
+            .metadata_entry("severity", serde_json::json!(severity))
+            .metadata_entry("due", serde_json::json!(due))
+            .metadata_entry("account", serde_json::json!(account))
+            .metadata_entry("order", serde_json::json!(order))
+            .build();
+        mem.put_bytes_with_options(content.as_bytes(), opts)
+            .unwrap();
+    }
+    mem.commit().unwrap();
+
+    let uris = |mem: &mut Memvid, query: &str| -> Vec<String> {
+        let response = mem.search(lex_request(query)).unwrap();
+        let mut uris: Vec<String> = response.hits.into_iter().map(|hit| hit.uri).collect();
+        uris.sort();
+        uris
+    };
+
+    assert_eq!(
+        uris(&mut mem, "ticket meta.customer:acme"),
+        vec!["mv2://tickets/1", "mv2://tickets/3"]
+    );
+    assert_eq!(uris(&mut mem, "meta.severity:3"), vec!["mv2://tickets/2"]);
+    assert_eq!(
+        uris(&mut mem, "meta.severity:[1 TO 3]"),
+        vec!["mv2://tickets/1", "mv2://tickets/2"]
+    );
+    assert_eq!(
+        uris(&mut mem, "ticket meta.severity:[4 TO *]"),
+        vec!["mv2://tickets/3"]
+    );
+    assert_eq!(
+        uris(
+            &mut mem,
+            "ticket meta.due:[2024-05-01 TO 2024-12-31] -globex"
+        ),
+        vec!["mv2://tickets/3"]
+    );
+    assert_eq!(
+        uris(&mut mem, "meta.account:9007199254740993"),
+        vec!["mv2://tickets/2"]
+    );
+    assert_eq!(
+        uris(&mut mem, "meta.account:[9007199254740993 TO *]"),
+        vec!["mv2://tickets/2"]
+    );
+    assert_eq!(uris(&mut mem, "meta.order:00123"), vec!["mv2://tickets/1"]);
+    assert_eq!(uris(&mut mem, "meta.order:123"), vec!["mv2://tickets/2"]);
+    let response = mem.search(lex_request("meta.severity:[2 TO 4]")).unwrap();
+    assert_eq!(response.engine, SearchEngineKind::Tantivy);
+    assert!(mem.search(lex_request("meta.customer:[a TO b]")).is_err());
+}
+
+#[cfg(feature = "lex")]
+#[test]
+fn search_returns_facet_counts() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("test.mv2");
+
+    let mut mem = Memvid::create(&path).unwrap();
+    mem.enable_lex().unwrap();
+    let docs = [
+        ("mv2://docs/api/auth", "Alpha", "note", 1_705_312_800),
+        ("mv2://docs/api/billing", "alpha", "note", 1_705_744_800),
+        ("mv2://docs/guide/setup", "beta", "memo", 1_706_950_800),
+        ("mv2://blog/launch", "beta", "post", 1_709_640_000),
+    ];
+    for (uri, track, kind, timestamp) in docs {
+        let content = format!("release notes for {uri}");
+        let opts = PutOptions::builder()
+            .uri(uri)
+            .search_text(content.clone())
+            .track(track)
+            .kind(kind)
+            .timestamp(timestamp)
+            .build();
+        mem.put_bytes_with_options(content.as_bytes(), opts)
+            .unwrap();
+    }
+    mem.commit().unwrap();
+
+    let facet_request = FacetRequest {
+        fields: vec![
+            FacetField::Track,
+            FacetField::Kind,
+            FacetField::UriPrefix { depth: 2 },
+        ],
+        date_histogram: Some(DateInterval::Month),
+        size: Some(2),
+    };
+    let bucket = |key: &str, count: usize| FacetBucket {
+        key: key.to_string(),
+        count,
+    };
+
+    let mut request = lex_request("release");
+    request.top_k = 1;
+    request.facets = Some(facet_request.clone());
+    let response = mem.search(request).unwrap();
+    assert_eq!(response.engine, SearchEngineKind::Tantivy);
+    assert_eq!(response.hits.len(), 1);
+    assert_eq!(response.facets.len(), 4);
+    assert_eq!(
+        response.facets[0].buckets,
+        vec![bucket("alpha", 2), bucket("beta", 2)]
+    );
+    assert_eq!(
+        response.facets[1].buckets,
+        vec![bucket("note", 2), bucket("memo", 1)]
+    );
+    assert_eq!(
+        response.facets[2].buckets,
+        vec![bucket("mv2://docs/api", 2), bucket("mv2://blog/launch", 1)]
+    );
+    assert_eq!(response.facets[3].interval, Some(DateInterval::Month));
+    assert_eq!(
+        response.facets[3].buckets,
+        vec![
+            bucket("2024-01", 2),
+            bucket("2024-02", 1),
+            bucket("2024-03", 1)
+        ]
+    );
+
+    let mut request = lex_request("release");
+    request.scope = Some("mv2://docs/".to_string());
+    request.facets = Some(facet_request.clone());
+    let response = mem.search(request).unwrap();
+    assert_eq!(
+        response.facets[2].buckets,
+        vec![bucket("mv2://docs/api", 2), bucket("mv2://docs/guide", 1)]
+    );
+
+    // Facets skip matches the hits drop, such as URIs outside the case-sensitive scope.
+    let content = "release notes for the archive";
+    let opts = PutOptions::builder()
+        .uri("mv2://DOCS/archive")
+        .search_text(content)
+        .track("alpha")
+        .build();
+    mem.put_bytes_with_options(content.as_bytes(), opts)
+        .unwrap();
+    mem.commit().unwrap();
+    let mut request = lex_request("release");
+    request.scope = Some("mv2://docs/".to_string());
+    request.facets = Some(facet_request);
+    let response = mem.search(request).unwrap();
+    assert!(
+        response
+            .hits
+            .iter()
+            .all(|hit| hit.uri != "mv2://DOCS/archive")
+    );
+    assert_eq!(
+        response.facets[0].buckets,
+        vec![bucket("alpha", 2), bucket("beta", 1)]
+    );
+
+    let response = mem.search(lex_request("release")).unwrap();
+    assert!(response.facets.is_empty());
+}
+
+#[cfg(feature = "lex")]
+#[test]
+fn search_highlights_stemmed_matches() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("test.mv2");
+
+    let mut mem = Memvid::create(&path).unwrap();
+    mem.enable_lex().unwrap();
+    let content = "Deployments stalled on Monday. Unrelated notes about lunch plans and \
+                   the weather follow here. The team kept deploying fixes until Friday.";
+    let opts = PutOptions::builder()
+        .uri("mv2://ops/week")
+        .search_text(content)
+        .build();
+    mem.put_bytes_with_options(content.as_bytes(), opts)
+        .unwrap();
+    mem.commit().unwrap();
+
+    let mut request = lex_request("deploy");
+    request.highlight = Some(HighlightOptions {
+        fragments: 2,
+        fragment_chars: Some(30),
+    });
+    let response = mem.search(request).unwrap();
+    assert_eq!(response.engine, SearchEngineKind::Tantivy);
+    let hit = &response.hits[0];
+    let chunk_text = hit.chunk_text.as_deref().unwrap();
+    let marked: Vec<&str> = hit
+        .chunk_highlights
+        .iter()
+        .map(|(start, end)| &chunk_text[*start..*end])
+        .collect();
+    // Auto-tag lines appended to the search text can add further matches.
+    assert_eq!(marked[..2], ["Deployments", "deploying"]);
+    assert!(
+        marked
+            .iter()
+            .all(|word| word.to_lowercase().starts_with("deploy"))
+    );
+    assert!(
+        hit.highlights
+            .iter()
+            .all(|(start, end)| hit.text[*start..*end].to_lowercase().starts_with("deploy"))
+    );
+    assert_eq!(hit.fragments.len(), 2);
+    for fragment in &hit.fragments {
+        assert!(fragment.text.chars().count() <= 30);
+        let (start, end) = fragment.highlights[0];
+        assert!(
+            fragment.text[start..end]
+                .to_lowercase()
+                .starts_with("deploy")
+        );
+    }
+
+    let response = mem.search(lex_request("deploy")).unwrap();
+    assert!(response.hits[0].chunk_highlights.is_empty());
+    assert!(response.hits[0].fragments.is_empty());
+}
+
+#[cfg(feature = "lex")]
+#[test]
+fn search_suggests_spellings_for_zero_hit_queries() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("test.mv2");
+
+    let mut mem = Memvid::create(&path).unwrap();
+    mem.enable_lex().unwrap();
+    for (uri, content) in [
+        (
+            "mv2://geo/one",
+            "The eruption reshaped the island coastline.",
+        ),
+        (
+            "mv2://geo/two",
+            "Lava eruptions are monitored from the coastline.",
+        ),
+        (
+            "mv2://ops/budget",
+            "Quarterly budget review for the ops team.",
+        ),
+    ] {
+        let opts = PutOptions::builder().uri(uri).search_text(content).build();
+        mem.put_bytes_with_options(content.as_bytes(), opts)
+            .unwrap();
+    }
+    mem.commit().unwrap();
+
+    let response = mem.search(lex_request("Eruptoin AND coastlnie")).unwrap();
+    assert!(response.hits.is_empty());
+    let corrections: Vec<(&str, &str, u8)> = response
+        .suggestions
+        .iter()
+        .map(|s| (s.word.as_str(), s.suggestion.as_str(), s.distance))
+        .collect();
+    assert_eq!(
+        corrections,
+        vec![("eruptoin", "eruption", 1), ("coastlnie", "coastline", 1)]
+    );
+    assert_eq!(
+        response.corrected_query.as_deref(),
+        Some("eruption AND coastline")
+    );
+
+    let mut request = lex_request("Eruptoin AND coastlnie");
+    request.auto_correct = true;
+    let response = mem.search(request.clone()).unwrap();
+    // Stemming lets the corrected "eruption" match "eruptions" too.
+    let mut uris: Vec<_> = response.hits.iter().map(|hit| hit.uri.as_str()).collect();
+    uris.sort_unstable();
+    assert_eq!(uris, vec!["mv2://geo/one", "mv2://geo/two"]);
+    assert_eq!(
+        response.corrected_query.as_deref(),
+        Some("eruption AND coastline")
+    );
+    assert_eq!(response.suggestions.len(), 2);
+
+    // Later pages are requested with the query as written.
+    request.top_k = 1;
+    let first = mem.search(request.clone()).unwrap();
+    assert_eq!(first.hits.len(), 1);
+    request.cursor = first.next_cursor.clone();
+    let second = mem.search(request).unwrap();
+    assert_eq!(second.hits.len(), 1);
+    assert_ne!(second.hits[0].frame_id, first.hits[0].frame_id);
+    assert!(second.next_cursor.is_none());
+
+    // Known words that simply do not co-occur get no suggestions.
+    let response = mem.search(lex_request("budget AND lava")).unwrap();
+    assert!(response.hits.is_empty());
+    assert!(response.suggestions.is_empty());
+    assert!(response.corrected_query.is_none());
+
+    // Words committed after the vocabulary was first read are suggested too.
+    let content = "The volcano erupted overnight.";
+    let opts = PutOptions::builder()
+        .uri("mv2://geo/three")
+        .search_text(content)
+        .build();
+    mem.put_bytes_with_options(content.as_bytes(), opts)
+        .unwrap();
+    mem.commit().unwrap();
+    let response = mem.search(lex_request("overnigt")).unwrap();
+    assert_eq!(response.corrected_query.as_deref(), Some("overnight"));
+}
+
+/// Scores documents by how often their text and metadata mention `keyword`.
+struct KeywordReranker {
+    keyword: &'static str,
+}
+
+impl Reranker for KeywordReranker {
+    fn kind(&self) -> &str {
+        "keyword"
+    }
+
+    fn rerank(
+        &self,
+        _query: &str,
+        documents: &[RerankerDocument],
+        top_k: usize,
+    ) -> memvid_core::Result<Vec<RerankerResult>> {
+        let mut results: Vec<RerankerResult> = documents
+            .iter()
+            .enumerate()
+            .map(|(idx, doc)| {
+                let haystack = format!("{} {}", doc.text, doc.metadata.as_deref().unwrap_or(""));
+                RerankerResult {
+                    id: doc.id,
+                    score: haystack.matches(self.keyword).count() as f32,
+                    original_rank: idx + 1,
+                    new_rank: 0,
+                }
+            })
+            .collect();
+        results.sort_by(|a, b| b.score.total_cmp(&a.score));
+        results.truncate(top_k);
+        Ok(results)
+    }
+}
+
+#[cfg(feature = "lex")]
+#[test]
+fn search_reranker_reorders_hits() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("test.mv2");
+
+    let mut mem = Memvid::create(&path).unwrap();
+    mem.enable_lex().unwrap();
+    for (uri, content) in [
+        ("mv2://ops/one", "Incident report incident incident."),
+        ("mv2://ops/two", "Incident review with an urgent follow-up."),
+        (
+            "mv2://ops/three",
+            "Incident summary: urgent paging and urgent escalation.",
+        ),
+    ] {
+        let opts = PutOptions::builder().uri(uri).search_text(content).build();
+        mem.put_bytes_with_options(content.as_bytes(), opts)
+            .unwrap();
+    }
+    mem.commit().unwrap();
+
+    let mut request = lex_request("incident");
+    request.rerank = true;
+    assert!(mem.search(request.clone()).is_err());
+
+    let plain = mem.search(lex_request("incident")).unwrap();
+    assert_eq!(plain.hits[0].uri, "mv2://ops/one");
+    assert!(plain.hits.iter().all(|hit| hit.rerank.is_none()));
+
+    mem.set_reranker(
+        Arc::new(KeywordReranker { keyword: "urgent" }),
+        RerankerConfig {
+            min_score: 1.0,
+            ..RerankerConfig::default()
+        },
+    );
+    let response = mem.search(request.clone()).unwrap();
+    // The frame without the keyword falls under `min_score`.
+    let uris: Vec<&str> = response.hits.iter().map(|hit| hit.uri.as_str()).collect();
+    assert_eq!(uris, vec!["mv2://ops/three", "mv2://ops/two"]);
+    for (idx, hit) in response.hits.iter().enumerate() {
+        let rerank = hit.rerank.unwrap();
+        assert_eq!(rerank.new_rank, idx + 1);
+        let before = plain.hits.iter().find(|p| p.uri == hit.uri).unwrap();
+        assert_eq!(rerank.original_rank, before.rank);
+    }
+    assert!(response.hits[0].rerank.unwrap().score > response.hits[1].rerank.unwrap().score);
+
+    request.top_k = 1;
+    let response = mem.search(request.clone()).unwrap();
+    assert_eq!(response.hits.len(), 1);
+    assert_eq!(response.hits[0].uri, "mv2://ops/three");
+    assert_eq!(response.params.top_k, 1);
+
+    // The cursor continues in reranked order.
+    let mut next = request.clone();
+    next.cursor = response.next_cursor;
+    let response = mem.search(next).unwrap();
+    assert_eq!(response.hits.len(), 1);
+    assert_eq!(response.hits[0].uri, "mv2://ops/two");
+    assert!(response.next_cursor.is_none());
+
+    mem.clear_reranker();
+    assert!(mem.search(request).is_err());
+}
+
+#[test]
+fn search_expands_hits_to_neighbouring_chunks_and_sections() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("test.mv2");
+    let section = |heading: &str, topic: &str| {
+        format!("## {heading}\n\nThis part covers {topic}. ")
+            + &"Staff should read every paragraph of this part carefully. ".repeat(25)
+            + "\n\n"
+    };
+    let handbook = format!(
+        "# Handbook\n\n{}{}{}",
+        section("Travel", "booking trains"),
+        section("Expenses", "the zeppelin allowance"),
+        section("Security", "badge access")
+    );
+    {
+        let mut mem = Memvid::create(&path).unwrap();
+        mem.enable_lex().unwrap();
+        let opts = PutOptions::builder().uri("mv2://docs/handbook").build();
+        mem.put_bytes_with_options(handbook.as_bytes(), opts)
+            .unwrap();
+        mem.commit().unwrap();
+    }
+
+    let mut mem = Memvid::open_read_only(&path).unwrap();
+    let request = |expand| SearchRequest {
+        query: "zeppelin".to_string(),
+        top_k: 1,
+        snippet_chars: 200,
+        uri: None,
+        scope: None,
+        cursor: None,
+        #[cfg(feature = "temporal_track")]
+        temporal: None,
+        as_of_frame: None,
+        as_of_ts: None,
+        no_sketch: false,
+        mmr_lambda: None,
+        facets: None,
+        highlight: None,
+        auto_correct: false,
+        rerank: false,
+        expand,
+    };
+
+    let plain = mem.search(request(None)).unwrap();
+    let hit = &plain.hits[0];
+    assert!(hit.expanded.is_none());
+    let chunk_text = hit.chunk_text.clone().unwrap();
+    let chunk_range = hit.chunk_range.unwrap();
+
+    let adjacent = mem
+        .search(request(Some(ChunkExpansion::Adjacent {
+            before: 1,
+            after: 1,
+        })))
+        .unwrap();
+    let window = adjacent.hits[0].expanded.clone().unwrap();
+    assert_ne!(window.parent_id, hit.frame_id);
+    assert!(window.chunk_count > 2);
+    assert!(window.first_chunk < window.last_chunk);
+    assert!(window.text.contains(&chunk_text));
+    assert!(window.range.0 <= chunk_range.0 && chunk_range.1 <= window.range.1);
+    assert_eq!(window.range.1 - window.range.0, window.text.len());
+
+    let own = mem.chunk_window(hit.frame_id, None, 0, 0).unwrap();
+    assert_eq!(own.text, chunk_text);
+    assert_eq!(own.range, chunk_range);
+
+    let sectioned = mem.search(request(Some(ChunkExpansion::Section))).unwrap();
+    let section = sectioned.hits[0].expanded.clone().unwrap();
+    assert!(section.text.starts_with("## Expenses"));
+    assert!(section.text.contains("zeppelin"));
+    assert!(!section.text.contains("## Security"));
+    assert!(!section.text.contains("## Travel"));
+}
+
+#[cfg(feature = "lex")]
+#[test]
+fn reordered_search_pages_past_the_candidate_pool() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("test.mv2");
+
+    let mut mem = Memvid::create(&path).unwrap();
+    mem.enable_lex().unwrap();
+    for i in 0..30 {
+        let parity = if i % 2 == 1 { "odd" } else { "even" };
+        // Repeating the term gives every report its own score, so retrieval order is
+        // the same whatever the pool size.
+        let text =
+            format!("Searchable report number {i}, filed as {parity}") + &" report".repeat(i);
+        let opts = PutOptions::builder()
+            .uri(format!("mv2://reports/{i}"))
+            .search_text(text.clone())
+            .build();
+        mem.put_bytes_with_options(text.as_bytes(), opts).unwrap();
+    }
+    mem.commit().unwrap();
+    mem.set_reranker(
+        Arc::new(KeywordReranker { keyword: "odd" }),
+        RerankerConfig {
+            max_candidates: 6,
+            ..RerankerConfig::default()
+        },
+    );
+
+    let page_all = |mem: &mut Memvid, mut request: SearchRequest| {
+        let mut seen = Vec::new();
+        let mut pages = 0;
+        loop {
+            let page = mem.search(request.clone()).unwrap();
+            pages += 1;
+            seen.extend(page.hits.iter().map(|hit| hit.frame_id));
+            match page.next_cursor {
+                Some(cursor) => request.cursor = Some(cursor),
+                None => break,
+            }
+        }
+        (seen, pages)
+    };
+
+    // Two hits per page walk far beyond the first page's pool of eight candidates.
+    let mut mmr = lex_request("report");
+    mmr.top_k = 2;
+    mmr.mmr_lambda = Some(0.5);
+    let mut reranked = lex_request("report");
+    reranked.top_k = 2;
+    reranked.rerank = true;
+    for request in [mmr, reranked] {
+        let (mut seen, pages) = page_all(&mut mem, request.clone());
+        assert_eq!(pages, 15, "{request:?}");
+        seen.sort_unstable();
+        assert_eq!(
+            seen,
+            (0..30).collect::<Vec<u64>>(),
+            "every document exactly once"
+        );
+    }
+}
+
+#[cfg(feature = "lex")]
+#[test]
+fn search_cursor_survives_commits_and_rejects_vacuum() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("test.mv2");
+
+    let mut mem = Memvid::create(&path).unwrap();
+    mem.enable_lex().unwrap();
+    for i in 0..8 {
+        let text = format!("Searchable report number {i}");
+        let opts = PutOptions::builder()
+            .uri(format!("mv2://reports/{i}"))
+            .search_text(text.clone())
+            .build();
+        mem.put_bytes_with_options(text.as_bytes(), opts).unwrap();
+    }
+    mem.commit().unwrap();
+
+    let mut request = lex_request("report");
+    request.top_k = 3;
+    let first = mem.search(request.clone()).unwrap();
+    assert_eq!(first.hits.len(), 3);
+    let cursor = first.next_cursor.clone().expect("more pages");
+    assert!(cursor.parse::<usize>().is_err(), "cursor should be opaque");
+
+    // A strongly matching frame committed mid-pagination must not shift later pages.
+    let text = "report report report";
+    let opts = PutOptions::builder()
+        .uri("mv2://reports/late")
+        .search_text(text)
+        .build();
+    mem.put_bytes_with_options(text.as_bytes(), opts).unwrap();
+    mem.commit().unwrap();
+    let late = mem.frame_by_uri("mv2://reports/late").unwrap().id;
+
+    // A frame updated mid-pagination is served in its new version.
+    let mut seen: Vec<u64> = first.hits.iter().map(|hit| hit.frame_id).collect();
+    let pending = (0..8).find(|id| !seen.contains(id)).unwrap();
+    let revised = format!("Searchable report number {pending}, revised with a longer summary");
+    let opts = PutOptions::builder().search_text(revised.clone()).build();
+    mem.update_frame(pending, Some(revised.into_bytes()), opts, None)
+        .unwrap();
+    mem.commit().unwrap();
+    let updated = mem
+        .frame_by_uri(&format!("mv2://reports/{pending}"))
+        .unwrap()
+        .id;
+    assert!(updated > late);
+
+    let mut next = Some(cursor);
+    while let Some(cursor) = next {
+        request.cursor = Some(cursor);
+        let page = mem.search(request.clone()).unwrap();
+        seen.extend(page.hits.iter().map(|hit| hit.frame_id));
+        next = page.next_cursor;
+    }
+    assert!(!seen.contains(&late));
+    seen.sort_unstable();
+    let mut expected: Vec<u64> = (0..8).filter(|id| *id != pending).collect();
+    expected.push(updated);
+    assert_eq!(seen, expected, "every document exactly once");
+
+    let mut other = lex_request("number");
+    other.cursor = first.next_cursor.clone();
+    assert!(matches!(
+        mem.search(other),
+        Err(MemvidError::InvalidCursor { .. })
+    ));
+
+    mem.vacuum().unwrap();
+    drop(mem);
+    let mut mem = Memvid::open_read_only(&path).unwrap();
+    request.cursor = first.next_cursor.clone();
+    assert!(matches!(
+        mem.search(request),
+        Err(MemvidError::StaleCursor {
+            cursor_generation: 0,
+            current_generation: 1,
+        })
+    ));
+}
+
+#[test]
+fn timeline_cursor_pages_through_entries() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("test.mv2");
+
+    let mut mem = Memvid::create(&path).unwrap();
+    for i in 0..7 {
+        let opts = PutOptions {
+            uri: Some(format!("mv2://doc{}", i)),
+            timestamp: Some(1700000000 + i as i64 * 1000),
+            ..Default::default()
+        };
+        mem.put_bytes_with_options(format!("Content {}", i).as_bytes(), opts)
+            .unwrap();
+    }
+    mem.commit().unwrap();
+
+    let page_query = |cursor: Option<String>| {
+        let mut builder = TimelineQuery::builder()
+            .limit(NonZeroU64::new(3).unwrap())
+            .reverse(true);
+        if let Some(cursor) = cursor {
+            builder = builder.cursor(cursor);
+        }
+        builder.build()
+    };
+    let first = mem.timeline_page(page_query(None)).unwrap();
+    assert_eq!(first.entries.len(), 3);
+    assert_eq!(first.entries[0].frame_id, 6);
+
+    let opts = PutOptions {
+        uri: Some("mv2://late".into()),
+        timestamp: Some(1800000000),
+        ..Default::default()
+    };
+    mem.put_bytes_with_options(b"Late content", opts).unwrap();
+    mem.commit().unwrap();
+
+    let second = mem.timeline_page(page_query(first.next_cursor)).unwrap();
+    let third = mem.timeline_page(page_query(second.next_cursor)).unwrap();
+    let ids: Vec<u64> = second
+        .entries
+        .iter()
+        .chain(&third.entries)
+        .map(|entry| entry.frame_id)
+        .collect();
+    assert_eq!(ids, vec![3, 2, 1, 0]);
+    assert!(third.next_cursor.is_none());
+}
+
 #[test]
 fn timeline_returns_ordered() {
     let dir = TempDir::new().unwrap();